@@ -0,0 +1,109 @@
+-- Applies the span crop written by hyprfinity (`[recording] obs_region_file`) to OBS.
+--
+-- Add one screen capture source per spanned monitor, named "<prefix><monitor>" (for example
+-- "Hyprfinity DP-1"), set the canvas to the span size, then load this script under
+-- Tools > Scripts. Every scene containing those sources is re-cropped and re-positioned
+-- whenever a new session rewrites the region file.
+
+obs = obslua
+
+local region_file = ""
+local source_prefix = ""
+local last_contents = nil
+
+function script_description()
+	return "Crops and positions Hyprfinity monitor capture sources to the current span."
+end
+
+function script_properties()
+	local props = obs.obs_properties_create()
+	obs.obs_properties_add_path(props, "region_file", "Region file", obs.OBS_PATH_FILE, "*.json", nil)
+	obs.obs_properties_add_text(props, "source_prefix", "Source name prefix", obs.OBS_TEXT_DEFAULT)
+	return props
+end
+
+function script_defaults(settings)
+	local home = os.getenv("HOME") or ""
+	obs.obs_data_set_default_string(settings, "region_file", home .. "/.cache/hyprfinity/obs-region.json")
+	obs.obs_data_set_default_string(settings, "source_prefix", "Hyprfinity ")
+end
+
+function script_update(settings)
+	region_file = obs.obs_data_get_string(settings, "region_file")
+	source_prefix = obs.obs_data_get_string(settings, "source_prefix")
+	last_contents = nil
+end
+
+local function place(name, x, y, crop)
+	local scenes = obs.obs_frontend_get_scenes()
+	if scenes == nil then
+		return
+	end
+	for _, scene_source in ipairs(scenes) do
+		local scene = obs.obs_scene_from_source(scene_source)
+		local item = obs.obs_scene_find_source(scene, name)
+		if item ~= nil then
+			local pos = obs.vec2()
+			pos.x = x
+			pos.y = y
+			obs.obs_sceneitem_set_pos(item, pos)
+			obs.obs_sceneitem_set_crop(item, crop)
+		end
+	end
+	obs.source_list_release(scenes)
+end
+
+local function apply(contents)
+	local data = obs.obs_data_create_from_json(contents)
+	if data == nil then
+		obs.script_log(obs.LOG_WARNING, "Could not parse " .. region_file)
+		return
+	end
+	local sources = obs.obs_data_get_array(data, "sources")
+	for i = 0, obs.obs_data_array_count(sources) - 1 do
+		local entry = obs.obs_data_array_item(sources, i)
+		local crop = obs.obs_sceneitem_crop()
+		crop.left = obs.obs_data_get_int(entry, "crop_left")
+		crop.top = obs.obs_data_get_int(entry, "crop_top")
+		crop.right = obs.obs_data_get_int(entry, "crop_right")
+		crop.bottom = obs.obs_data_get_int(entry, "crop_bottom")
+		place(
+			source_prefix .. obs.obs_data_get_string(entry, "monitor"),
+			obs.obs_data_get_int(entry, "x"),
+			obs.obs_data_get_int(entry, "y"),
+			crop
+		)
+		obs.obs_data_release(entry)
+	end
+	obs.obs_data_array_release(sources)
+	obs.script_log(
+		obs.LOG_INFO,
+		string.format(
+			"Applied Hyprfinity span %dx%d",
+			obs.obs_data_get_int(data, "width"),
+			obs.obs_data_get_int(data, "height")
+		)
+	)
+	obs.obs_data_release(data)
+end
+
+local function poll()
+	local file = io.open(region_file, "r")
+	if file == nil then
+		return
+	end
+	local contents = file:read("*a")
+	file:close()
+	if contents ~= last_contents then
+		last_contents = contents
+		apply(contents)
+	end
+end
+
+function script_load(settings)
+	obs.timer_add(poll, 1000)
+end
+
+function script_unload()
+	obs.timer_remove(poll)
+end
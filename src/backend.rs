@@ -0,0 +1,357 @@
+use crate::debuglog::debug_log_line;
+use crate::flatpak::{FlatpakGamescope, gamescope_flatpak};
+use crate::trace::replay_backend;
+use serde::Deserialize;
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+/// Points at a JSON fixture (`{"monitors": [...]}` in `hyprctl monitors -j` format) and swaps
+/// hyprctl and gamescope for an in-process fake compositor, so sessions run without Hyprland.
+pub(crate) const MOCK_COMPOSITOR_ENV: &str = "HYPRFINITY_MOCK_COMPOSITOR";
+/// Optional file the fake compositor appends every hyprctl call to, one per line.
+pub(crate) const MOCK_LOG_ENV: &str = "HYPRFINITY_MOCK_LOG";
+
+pub(crate) struct HyprctlOutput {
+    pub(crate) success: bool,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+}
+
+pub(crate) trait HyprlandBackend: Sync {
+    fn hyprctl(&self, args: &[&str]) -> std::io::Result<HyprctlOutput>;
+}
+
+pub(crate) trait GamescopeLauncher: Sync {
+    /// Output of `gamescope --help`, used to probe supported flags.
+    fn help(&self) -> Option<String>;
+    fn version(&self) -> Option<String>;
+    fn spawn(&self, cmd: &mut Command) -> std::io::Result<Child>;
+}
+
+struct SystemHyprland;
+
+impl HyprlandBackend for SystemHyprland {
+    fn hyprctl(&self, args: &[&str]) -> std::io::Result<HyprctlOutput> {
+        let output = Command::new("hyprctl").args(args).output()?;
+        Ok(HyprctlOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
+
+/// Runs `gamescope --help` through `cmd`.
+pub(crate) fn gamescope_help_output(cmd: &mut Command) -> Option<String> {
+    let output = cmd.stdin(Stdio::null()).output().ok()?;
+    // gamescope prints usage to stderr on most versions; scan both streams.
+    Some(format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ))
+}
+
+/// First line of `gamescope --version` run through `cmd`.
+pub(crate) fn gamescope_version_line(cmd: &mut Command) -> Option<String> {
+    let output = cmd.stdin(Stdio::null()).output().ok()?;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+struct SystemGamescope;
+
+impl GamescopeLauncher for SystemGamescope {
+    fn help(&self) -> Option<String> {
+        gamescope_help_output(Command::new("gamescope").arg("--help"))
+    }
+
+    fn version(&self) -> Option<String> {
+        gamescope_version_line(Command::new("gamescope").arg("--version"))
+    }
+
+    fn spawn(&self, cmd: &mut Command) -> std::io::Result<Child> {
+        cmd.spawn()
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct MockFixture {
+    #[serde(default)]
+    monitors: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Clone)]
+struct MockClient {
+    pid: u32,
+    address: String,
+    at: (i32, i32),
+    size: (i32, i32),
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    clients: Vec<MockClient>,
+    options: Vec<(String, String)>,
+}
+
+/// A scripted stand-in for Hyprland and gamescope: monitors come from the fixture, the
+/// "gamescope" it launches is just the game command, and move/resize dispatches are applied
+/// to that window so fitting converges.
+struct MockCompositor {
+    monitors: Vec<serde_json::Value>,
+    log: Option<std::path::PathBuf>,
+    state: Mutex<MockState>,
+}
+
+fn parse_exact_pair(arg: &str) -> Option<((i32, i32), &str)> {
+    let (values, selector) = arg.strip_prefix("exact ")?.split_once(',')?;
+    let (a, b) = values.trim().split_once(' ')?;
+    Some((
+        (a.trim().parse().ok()?, b.trim().parse().ok()?),
+        selector.trim(),
+    ))
+}
+
+fn matches_selector(client: &MockClient, selector: &str) -> bool {
+    match selector.split_once(':') {
+        Some(("address", address)) => client.address == address,
+        Some(("pid", pid)) => pid.parse() == Ok(client.pid),
+        _ => false,
+    }
+}
+
+impl MockCompositor {
+    fn load(path: &str) -> Self {
+        let fixture = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<MockFixture>(&contents).ok())
+            .unwrap_or_else(|| {
+                debug_log_line(&format!("mock compositor: unreadable fixture {}", path));
+                MockFixture::default()
+            });
+        MockCompositor {
+            monitors: fixture.monitors,
+            log: std::env::var_os(MOCK_LOG_ENV).map(std::path::PathBuf::from),
+            state: Mutex::new(MockState::default()),
+        }
+    }
+
+    fn record(&self, line: &str) {
+        if let Some(path) = self.log.as_ref()
+            && let Ok(mut file) = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+        {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+
+    fn dispatch(&self, state: &mut MockState, dispatcher: &str, arg: &str) {
+        let Some(((a, b), selector)) = parse_exact_pair(arg) else {
+            return;
+        };
+        for client in state
+            .clients
+            .iter_mut()
+            .filter(|c| matches_selector(c, selector))
+        {
+            match dispatcher {
+                "movewindowpixel" => client.at = (a, b),
+                "resizewindowpixel" => client.size = (a, b),
+                _ => {}
+            }
+        }
+    }
+
+    fn command(&self, state: &mut MockState, command: &str) -> String {
+        let mut parts = command.trim().splitn(3, ' ');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("dispatch"), Some(dispatcher), arg) => {
+                self.dispatch(state, dispatcher, arg.unwrap_or_default());
+                "ok".to_string()
+            }
+            (Some("keyword"), Some(name), value) => {
+                state.options.retain(|(key, _)| key != name);
+                state
+                    .options
+                    .push((name.to_string(), value.unwrap_or_default().to_string()));
+                "ok".to_string()
+            }
+            _ => "ok".to_string(),
+        }
+    }
+}
+
+impl HyprlandBackend for MockCompositor {
+    fn hyprctl(&self, args: &[&str]) -> std::io::Result<HyprctlOutput> {
+        self.record(&args.join(" "));
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        // Drop windows whose process is gone, like a compositor unmapping them.
+        state
+            .clients
+            .retain(|c| std::path::Path::new(&format!("/proc/{}", c.pid)).exists());
+        let stdout = match args {
+            ["monitors", "-j"] => serde_json::to_string(&self.monitors)?,
+            ["clients", "-j"] => serde_json::to_string(
+                &state
+                    .clients
+                    .iter()
+                    .map(|c| {
+                        serde_json::json!({
+                            "pid": c.pid,
+                            "address": c.address,
+                            "at": [c.at.0, c.at.1],
+                            "size": [c.size.0, c.size.1],
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            )?,
+            ["getoption", name, "-j"] => {
+                let value = state
+                    .options
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .and_then(|(_, value)| value.parse::<i64>().ok())
+                    .unwrap_or(0);
+                serde_json::json!({ "option": name, "int": value }).to_string()
+            }
+            ["binds"] => String::new(),
+            ["--batch", batch] => batch
+                .split(" ; ")
+                .map(|command| self.command(&mut state, command))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => self.command(&mut state, &args.join(" ")),
+        };
+        Ok(HyprctlOutput {
+            success: true,
+            stdout,
+            stderr: String::new(),
+        })
+    }
+}
+
+impl GamescopeLauncher for MockCompositor {
+    fn help(&self) -> Option<String> {
+        None
+    }
+
+    fn version(&self) -> Option<String> {
+        Some("mock compositor".to_string())
+    }
+
+    fn spawn(&self, cmd: &mut Command) -> std::io::Result<Child> {
+        let args = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        self.record(&format!("gamescope {}", args.join(" ")));
+        let game = args
+            .iter()
+            .position(|a| a == "--")
+            .map(|i| args[i + 1..].to_vec())
+            .filter(|game| !game.is_empty())
+            .unwrap_or_else(|| vec!["true".to_string()]);
+        let mut game_cmd = Command::new(&game[0]);
+        game_cmd.args(&game[1..]).stdin(Stdio::null());
+        for (key, value) in cmd.get_envs() {
+            match value {
+                Some(value) => game_cmd.env(key, value),
+                None => game_cmd.env_remove(key),
+            };
+        }
+        let child = game_cmd.spawn()?;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let address = format!("0x{:x}", 0x1000 + state.clients.len());
+        state.clients.push(MockClient {
+            pid: child.id(),
+            address,
+            at: (0, 0),
+            size: (1280, 800),
+        });
+        Ok(child)
+    }
+}
+
+static MOCK: OnceLock<Option<MockCompositor>> = OnceLock::new();
+
+fn mock() -> Option<&'static MockCompositor> {
+    MOCK.get_or_init(|| {
+        std::env::var(MOCK_COMPOSITOR_ENV)
+            .ok()
+            .filter(|path| !path.is_empty())
+            .map(|path| MockCompositor::load(&path))
+    })
+    .as_ref()
+}
+
+/// True when hyprctl is served by the mock compositor or a replayed trace.
+pub(crate) fn hyprctl_emulated() -> bool {
+    replay_backend().is_some() || mock().is_some()
+}
+
+pub(crate) fn hyprland() -> &'static dyn HyprlandBackend {
+    if let Some(replay) = replay_backend() {
+        return replay;
+    }
+    match mock() {
+        Some(mock) => mock,
+        None => &SystemHyprland,
+    }
+}
+
+pub(crate) fn gamescope_launcher() -> &'static dyn GamescopeLauncher {
+    match mock() {
+        Some(mock) => mock,
+        None if gamescope_flatpak().is_some() => &FlatpakGamescope,
+        None => &SystemGamescope,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_compositor_applies_fit_dispatches_to_the_window() {
+        let mock = MockCompositor {
+            monitors: Vec::new(),
+            log: None,
+            state: Mutex::new(MockState {
+                clients: vec![MockClient {
+                    pid: std::process::id(),
+                    address: "0x1000".to_string(),
+                    at: (0, 0),
+                    size: (1280, 800),
+                }],
+                options: Vec::new(),
+            }),
+        };
+        mock.hyprctl(&[
+            "--batch",
+            "dispatch setfloating address:0x1000 ; dispatch movewindowpixel exact -1920 0,address:0x1000 ; dispatch resizewindowpixel exact 5760 1080,address:0x1000",
+        ])
+        .unwrap();
+        let clients = mock.hyprctl(&["clients", "-j"]).unwrap().stdout;
+        assert!(clients.contains("\"at\":[-1920,0]"), "{}", clients);
+        assert!(clients.contains("\"size\":[5760,1080]"), "{}", clients);
+
+        mock.hyprctl(&["keyword", "input:follow_mouse", "2"])
+            .unwrap();
+        let option = mock
+            .hyprctl(&["getoption", "input:follow_mouse", "-j"])
+            .unwrap()
+            .stdout;
+        assert!(option.contains("\"int\":2"), "{}", option);
+    }
+}
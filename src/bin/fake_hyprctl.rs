@@ -0,0 +1,143 @@
+//! A `hyprctl` stand-in for integration tests, built with `--features fake-hyprctl`.
+//!
+//! Put it on `PATH` as `hyprctl` and point `FAKE_HYPRCTL_DIR` at a directory holding:
+//! - `monitors.json`, served for `hyprctl monitors -j`;
+//! - `clients.json`, served for `hyprctl clients -j` and updated by move/resize dispatches, so
+//!   fitting converges like it does on Hyprland;
+//! - `options.json`, written by `keyword` and read back by `getoption`;
+//! - `calls.log`, which gets every command appended, one per line, batches split up.
+
+use serde_json::{Value, json};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const DIR_ENV: &str = "FAKE_HYPRCTL_DIR";
+
+fn read_json(path: &Path, default: Value) -> Value {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or(default)
+}
+
+fn write_json(path: &Path, value: &Value) {
+    let _ = std::fs::write(path, value.to_string());
+}
+
+fn record(dir: &Path, line: &str) {
+    if let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join("calls.log"))
+    {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+/// Clients whose process is still running, like a compositor unmapping the windows of exited ones.
+fn live_clients(dir: &Path) -> Value {
+    let clients = read_json(&dir.join("clients.json"), json!([]));
+    Value::Array(
+        clients
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|client| {
+                client["pid"]
+                    .as_i64()
+                    .is_some_and(|pid| Path::new(&format!("/proc/{}", pid)).exists())
+            })
+            .cloned()
+            .collect(),
+    )
+}
+
+/// `exact X Y,SELECTOR` from a move or resize dispatch.
+fn exact_pair(arg: &str) -> Option<((i64, i64), &str)> {
+    let (values, selector) = arg.strip_prefix("exact ")?.split_once(',')?;
+    let (a, b) = values.trim().split_once(' ')?;
+    Some((
+        (a.trim().parse().ok()?, b.trim().parse().ok()?),
+        selector.trim(),
+    ))
+}
+
+fn selects(client: &Value, selector: &str) -> bool {
+    match selector.split_once(':') {
+        Some(("address", address)) => client["address"] == address,
+        Some(("pid", pid)) => pid.parse::<i64>().ok() == client["pid"].as_i64(),
+        _ => false,
+    }
+}
+
+fn dispatch(dir: &Path, dispatcher: &str, arg: &str) {
+    let field = match dispatcher {
+        "movewindowpixel" => "at",
+        "resizewindowpixel" => "size",
+        _ => return,
+    };
+    let Some(((a, b), selector)) = exact_pair(arg) else {
+        return;
+    };
+    let path = dir.join("clients.json");
+    let mut clients = read_json(&path, json!([]));
+    for client in clients
+        .as_array_mut()
+        .into_iter()
+        .flatten()
+        .filter(|client| selects(client, selector))
+    {
+        client[field] = json!([a, b]);
+    }
+    write_json(&path, &clients);
+}
+
+/// Runs one command and returns what hyprctl would print.
+fn command(dir: &Path, words: &[&str]) -> String {
+    record(dir, &words.join(" "));
+    match words {
+        ["monitors", "-j"] => read_json(&dir.join("monitors.json"), json!([])).to_string(),
+        ["clients", "-j"] => live_clients(dir).to_string(),
+        ["plugin", "list", "-j"] => "[]".to_string(),
+        ["binds"] => String::new(),
+        ["getoption", name, "-j"] => {
+            let options = read_json(&dir.join("options.json"), json!({}));
+            json!({ "option": name, "int": options[*name].as_i64().unwrap_or(0) }).to_string()
+        }
+        ["keyword", name, value @ ..] => {
+            let path = dir.join("options.json");
+            let mut options = read_json(&path, json!({}));
+            let value = value.join(" ");
+            options[*name] = value
+                .parse::<i64>()
+                .map(Value::from)
+                .unwrap_or_else(|_| value.into());
+            write_json(&path, &options);
+            "ok".to_string()
+        }
+        ["dispatch", dispatcher, arg @ ..] => {
+            dispatch(dir, dispatcher, &arg.join(" "));
+            "ok".to_string()
+        }
+        _ => "ok".to_string(),
+    }
+}
+
+fn main() {
+    let Some(dir) = std::env::var_os(DIR_ENV).map(PathBuf::from) else {
+        eprintln!("fake-hyprctl: set {} to the fixture directory", DIR_ENV);
+        std::process::exit(2);
+    };
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let stdout = match args.as_slice() {
+        ["--batch", batch] => batch
+            .split(';')
+            .map(|cmd| command(&dir, &cmd.split_whitespace().collect::<Vec<_>>()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        words => command(&dir, words),
+    };
+    println!("{}", stdout);
+}
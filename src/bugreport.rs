@@ -0,0 +1,220 @@
+use crate::autotune::detect_auto_tune_profile;
+use crate::backend::gamescope_launcher;
+use crate::config::resolve_config_path;
+use crate::debuglog::{FALLBACK_DEBUG_LOG_PATH, debug_log_path};
+use crate::gamescope::get_gamescope_state_file_path;
+use crate::hyprland::execute_hyprctl_output;
+use crate::i18n::{Msg, trf};
+use crate::output::info;
+use crate::stats::unix_now;
+use crate::trace::Redactor;
+use std::error::Error;
+use std::path::PathBuf;
+
+const DEBUG_LOG_TAIL_LINES: usize = 200;
+
+/// One `##` section: fenced content, or a note when it could not be collected.
+struct Section {
+    title: &'static str,
+    lang: &'static str,
+    body: Result<String, String>,
+}
+
+fn tail_lines(text: &str, count: usize) -> String {
+    let lines = text.lines().collect::<Vec<_>>();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
+
+fn pretty_json(text: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(text)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| text.to_string())
+}
+
+fn render_report(header: &[(&str, String)], sections: &[Section]) -> String {
+    let mut out = String::from("# Hyprfinity bug report\n\n");
+    for (key, value) in header {
+        out.push_str(&format!("- **{}**: {}\n", key, value));
+    }
+    for section in sections {
+        out.push_str(&format!("\n## {}\n\n", section.title));
+        match &section.body {
+            // Four backticks so fenced content can contain its own code fences.
+            Ok(body) => out.push_str(&format!(
+                "````{}\n{}\n````\n",
+                section.lang,
+                body.trim_end()
+            )),
+            Err(note) => out.push_str(&format!("_{}_\n", note)),
+        }
+    }
+    out
+}
+
+fn os_release() -> Option<String> {
+    let contents = std::fs::read_to_string("/etc/os-release").ok()?;
+    contents.lines().find_map(|line| {
+        line.strip_prefix("PRETTY_NAME=")
+            .map(|value| value.trim_matches('"').to_string())
+    })
+}
+
+fn collect_sections(
+    config_path: &Option<String>,
+    debug_log: &Option<String>,
+    redactor: &Redactor,
+    verbose: bool,
+) -> Vec<Section> {
+    let hyprctl = |args: &[&str]| {
+        execute_hyprctl_output(args, verbose)
+            .map(|stdout| pretty_json(&redactor.output(&stdout)))
+            .map_err(|e| format!("hyprctl {} failed: {}", args.join(" "), e))
+    };
+    let config = resolve_config_path(config_path)
+        .map_err(|e| e.to_string())
+        .and_then(|path| {
+            std::fs::read_to_string(&path).map_err(|_| {
+                format!(
+                    "No config file at {}; defaults are in use.",
+                    redactor.text(&path.display().to_string())
+                )
+            })
+        })
+        .map(|contents| redactor.text(&contents));
+    let autotune = detect_auto_tune_profile();
+    let state = get_gamescope_state_file_path()
+        .map_err(|e| e.to_string())
+        .and_then(|path| {
+            std::fs::read_to_string(path).map_err(|_| "No active session state.".to_string())
+        })
+        .map(|json| pretty_json(&redactor.output(&json)));
+    let log_path = [
+        debug_log_path(debug_log),
+        PathBuf::from(FALLBACK_DEBUG_LOG_PATH),
+    ]
+    .into_iter()
+    .find(|path| path.exists());
+    let log = match log_path {
+        Some(path) => std::fs::read_to_string(&path)
+            .map(|text| redactor.text(&tail_lines(&text, DEBUG_LOG_TAIL_LINES)))
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e)),
+        None => Err("No debug log found; reproduce the issue with --debug first.".to_string()),
+    };
+
+    vec![
+        Section {
+            title: "Config",
+            lang: "toml",
+            body: config,
+        },
+        Section {
+            title: "Hyprland version",
+            lang: "",
+            body: hyprctl(&["version"]),
+        },
+        Section {
+            title: "Monitors",
+            lang: "json",
+            body: hyprctl(&["monitors", "-j"]),
+        },
+        Section {
+            title: "Clients",
+            lang: "json",
+            body: hyprctl(&["clients", "-j"]),
+        },
+        Section {
+            title: "Gamescope",
+            lang: "",
+            body: gamescope_launcher()
+                .version()
+                .ok_or_else(|| "gamescope --version failed (is gamescope installed?)".to_string()),
+        },
+        Section {
+            title: "Autotune profile",
+            lang: "",
+            body: Ok(format!(
+                "render_scale = {}\nreason = {}",
+                autotune.render_scale, autotune.reason
+            )),
+        },
+        Section {
+            title: "Session state",
+            lang: "json",
+            body: state,
+        },
+        Section {
+            title: "Debug log (tail)",
+            lang: "",
+            body: log,
+        },
+    ]
+}
+
+/// Bundles sanitized diagnostics into one markdown file to attach to an issue.
+/// `output` of `-` prints to stdout; by default the file lands in the current directory.
+pub(crate) fn write_bugreport(
+    config_path: &Option<String>,
+    debug_log: &Option<String>,
+    output: Option<&str>,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    let redactor = Redactor::from_env();
+    let header = vec![
+        ("hyprfinity", env!("CARGO_PKG_VERSION").to_string()),
+        ("os", os_release().unwrap_or_else(|| "unknown".to_string())),
+        (
+            "desktop",
+            std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_else(|_| "unknown".to_string()),
+        ),
+        ("generated", format!("unix {}", unix_now())),
+    ];
+    let report = render_report(
+        &header,
+        &collect_sections(config_path, debug_log, &redactor, verbose),
+    );
+
+    if output == Some("-") {
+        print!("{}", report);
+        return Ok(());
+    }
+    let path = output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(format!("hyprfinity-bugreport-{}.md", unix_now())));
+    std::fs::write(&path, report)?;
+    info(&trf(Msg::WroteBugreport, &[&path.display()]));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_lines_keeps_the_last_lines() {
+        assert_eq!(tail_lines("a\nb\nc\n", 2), "b\nc");
+        assert_eq!(tail_lines("a", 5), "a");
+    }
+
+    #[test]
+    fn render_report_fences_bodies_and_notes_failures() {
+        let report = render_report(
+            &[("hyprfinity", "1.0.0".to_string())],
+            &[
+                Section {
+                    title: "Config",
+                    lang: "toml",
+                    body: Ok("render_scale = 0.75\n".to_string()),
+                },
+                Section {
+                    title: "Monitors",
+                    lang: "json",
+                    body: Err("hyprctl monitors -j failed".to_string()),
+                },
+            ],
+        );
+        assert!(report.contains("- **hyprfinity**: 1.0.0\n"));
+        assert!(report.contains("## Config\n\n````toml\nrender_scale = 0.75\n````\n"));
+        assert!(report.contains("## Monitors\n\n_hyprctl monitors -j failed_\n"));
+    }
+}
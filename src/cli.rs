@@ -1,4 +1,5 @@
-use clap::{Parser, Subcommand};
+use crate::types::RenderScale;
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -6,6 +7,14 @@ pub(crate) struct Cli {
     /// Enable verbose debug output.
     #[arg(long, global = true, default_value_t = false)]
     pub(crate) verbose: bool,
+    /// Only print errors; tables and JSON still go to stdout.
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        conflicts_with = "verbose"
+    )]
+    pub(crate) quiet: bool,
     /// Enable diagnostic logging to a file.
     #[arg(long, global = true, default_value_t = false)]
     pub(crate) debug: bool,
@@ -15,56 +24,405 @@ pub(crate) struct Cli {
     /// Path to a config file (TOML). Defaults to $XDG_CONFIG_HOME/hyprfinity/config.toml.
     #[arg(long, global = true)]
     pub(crate) config: Option<String>,
+    /// Layer the named [profiles.NAME] table over the config.
+    #[arg(long, global = true)]
+    pub(crate) profile: Option<String>,
+    /// Override any config key for this run, e.g. `--set output_width=2560` (repeatable).
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE")]
+    pub(crate) set: Vec<String>,
+    /// Record every hyprctl call and the gamescope launch into a redacted JSON trace.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub(crate) record: Option<String>,
+    /// Window-management backend; `auto` detects it from the session environment.
+    #[arg(long, global = true, value_enum, default_value_t = CompositorChoice::Auto)]
+    pub(crate) backend: CompositorChoice,
     #[command(subcommand)]
     pub(crate) command: Option<Commands>,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum CompositorChoice {
+    #[default]
+    Auto,
+    Hyprland,
+    Sway,
+    Niri,
+    /// xrandr for monitors, wmctrl for window placement.
+    X11,
+    /// wlr-randr for monitors, wlrctl (foreign-toplevel) to fullscreen on one output.
+    Wlroots,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ProfileAction {
+    /// Print a profile as a TOML snippet, with hardware-dependent values marked.
+    Export {
+        /// Profile to export.
+        name: String,
+        /// Write the snippet to this file instead of stdout.
+        #[arg(long, value_name = "FILE")]
+        output: Option<String>,
+    },
+    /// Add the profiles from a snippet to the config file.
+    Import {
+        /// Snippet file, `-` for stdin, or an https URL.
+        source: String,
+        /// Import a single-profile snippet under this name.
+        #[arg(long)]
+        name: Option<String>,
+        /// Replace profiles that already exist.
+        #[arg(long, default_value_t = false)]
+        force: bool,
+        /// Import profiles that run commands, use programs or paths, or carry `match` rules
+        /// without asking first.
+        #[arg(long, default_value_t = false)]
+        trust: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ConfigAction {
+    /// Print a JSON Schema of config.toml for editor completion and validation.
+    Schema {
+        /// Write the schema to this file instead of stdout.
+        #[arg(long, value_name = "FILE")]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum PresetsAction {
+    /// Pick a preset from the catalog and add it to the config as a profile.
+    Browse {
+        /// Apply this preset without the picker.
+        #[arg(long)]
+        id: Option<String>,
+        /// Profile name to add the preset as (defaults to the preset id).
+        #[arg(long)]
+        name: Option<String>,
+        /// Print the catalog instead of picking a preset.
+        #[arg(long, default_value_t = false)]
+        list: bool,
+        /// Fetch the catalog even if the cached copy is recent.
+        #[arg(long, default_value_t = false)]
+        refresh: bool,
+        /// Replace a profile that already exists.
+        #[arg(long, default_value_t = false)]
+        force: bool,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StreamServer {
+    /// `sunshine output_name=<output>` for Moonlight clients.
+    Sunshine,
+    /// `wayvnc -o <output>` for VNC viewers.
+    Wayvnc,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum ScreenshotRegion {
+    /// The spanned area (the running session's span, or the computed span).
+    #[default]
+    Span,
+    /// A single monitor (`--monitor`, or the focused one).
+    Monitor,
+}
+
 #[derive(Subcommand, Debug)]
 pub(crate) enum Commands {
     /// Launch and span a Gamescope session across all physical monitors.
+    #[command(alias = "up")]
     GamescopeUp {
-        /// Seconds to wait for the Gamescope window to appear.
-        #[arg(long, default_value_t = 10)]
-        startup_timeout_secs: u64,
+        /// Seconds to wait for the Gamescope window to appear (default 10).
+        #[arg(long)]
+        startup_timeout_secs: Option<u64>,
         /// Do not pin the Gamescope window to all workspaces.
-        #[arg(long, default_value_t = false)]
-        no_pin: bool,
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "pin")]
+        no_pin: Option<bool>,
+        /// Pin the Gamescope window to all workspaces, even if the config sets `no_pin`.
+        #[arg(long, overrides_with = "no_pin")]
+        pin: bool,
         /// Open an interactive picker even if a game/app command is provided.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_pick")]
+        pick: Option<bool>,
+        /// Never open the picker, even if the config enables it.
+        #[arg(long, overrides_with = "pick")]
+        no_pick: bool,
+        /// List every app in the picker, including terminal, hidden and other-desktop entries.
         #[arg(long, default_value_t = false)]
-        pick: bool,
+        show_all: bool,
         /// Inhibit idle/screen blanking while Gamescope is active.
-        #[arg(long, default_value_t = false)]
-        idle_inhibit: bool,
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_idle_inhibit")]
+        idle_inhibit: Option<bool>,
+        /// Do not inhibit idle, even if the config enables it.
+        #[arg(long, overrides_with = "idle_inhibit")]
+        no_idle_inhibit: bool,
         /// Stop Waybar while Gamescope is active, then restore it on exit.
-        #[arg(long, default_value_t = false)]
-        hide_waybar: bool,
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_hide_waybar")]
+        hide_waybar: Option<bool>,
+        /// Keep Waybar running, even if the config hides it.
+        #[arg(long, overrides_with = "hide_waybar")]
+        no_hide_waybar: bool,
         /// Open an interactive picker for internal (virtual) render size.
-        #[arg(long, default_value_t = false)]
-        pick_size: bool,
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_pick_size")]
+        pick_size: Option<bool>,
+        /// Skip the render size picker, even if the config enables it.
+        #[arg(long, overrides_with = "pick_size")]
+        no_pick_size: bool,
         /// Enable overlay support (MangoHud via gamescope --mangoapp).
-        #[arg(long, default_value_t = false)]
-        overlay: bool,
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_overlay")]
+        overlay: Option<bool>,
         /// Disable overlay support (overrides config default).
-        #[arg(long, default_value_t = false)]
+        #[arg(long, overrides_with = "overlay")]
         no_overlay: bool,
         /// Override MangoHud config string (applies when overlay is enabled).
         #[arg(long)]
         mangohud_config: Option<String>,
-        /// Scale internal (virtual) render size relative to monitor span (e.g. 0.75 for 75%).
-        #[arg(long)]
-        render_scale: Option<f32>,
+        /// Scale internal (virtual) render size relative to monitor span (e.g. 0.75 for 75%, or
+        /// 0.75x1.0 per axis; up to 2.0 with allow_supersample).
+        #[arg(long, value_parser = parse_render_scale)]
+        render_scale: Option<RenderScale>,
         /// Internal (virtual) render width for Gamescope (-w).
         #[arg(long)]
         virtual_width: Option<i32>,
         /// Internal (virtual) render height for Gamescope (-h).
         #[arg(long)]
         virtual_height: Option<i32>,
+        /// Output width for Gamescope (-W); defaults to the span width.
+        #[arg(long)]
+        output_width: Option<i32>,
+        /// Output height for Gamescope (-H); defaults to the span height.
+        #[arg(long)]
+        output_height: Option<i32>,
+        /// Output size for Gamescope as WxH (shorthand for --output-width/--output-height).
+        #[arg(long, value_parser = parse_size, conflicts_with_all = ["output_width", "output_height"])]
+        output_size: Option<(i32, i32)>,
+        /// Run the game through umu-run with this GAMEID; ids from umu_games.toml also supply
+        /// the exe, prefix and Proton.
+        #[arg(long, value_name = "GAMEID")]
+        umu: Option<String>,
+        /// Run gamescope with its DRM backend on a spare VT instead of nesting under Hyprland.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        embedded: Option<bool>,
+        /// Pass --immediate-flips to gamescope (allow tearing for lower latency).
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        immediate_flips: Option<bool>,
+        /// Pass --rt to gamescope (realtime scheduling; needs CAP_SYS_NICE on gamescope).
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        rt_priority: Option<bool>,
+        /// Pass --force-grab-cursor to gamescope.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        force_grab_cursor: Option<bool>,
+        /// Pass --expose-wayland to gamescope so Wayland-native clients can connect.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        expose_wayland: Option<bool>,
+        /// Keep the cursor in the game: --force-grab-cursor plus Hyprland follow_mouse off.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        cursor_confine: Option<bool>,
+        /// Move the cursor to the center of the span once gamescope is up.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        cursor_warp: Option<bool>,
+        /// Pause hyprsunset/gammastep for the session and resume it afterwards.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        disable_nightlight: Option<bool>,
+        /// Pixel-perfect mode: snap the internal size to an integer divisor of the output and use
+        /// gamescope's integer scaler with nearest-neighbour filtering.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        integer_scale: Option<bool>,
+        /// Remove the session window's border, rounding and shadow (Hyprland).
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        borderless: Option<bool>,
+        /// Export SDL/gamescope WSI env hints so the game picks the internal size.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        resolution_hints: Option<bool>,
+        /// Run as a Sunshine app: size for the Moonlight client and skip pinning and reflow.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+        stream: Option<bool>,
+        /// End the session when the game quits, even if gamescope keeps running.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_exit_with_game")]
+        exit_with_game: Option<bool>,
+        /// Keep the session running after the game quits, even if the config ends it.
+        #[arg(long, overrides_with = "exit_with_game")]
+        no_exit_with_game: bool,
+        /// Regex for the session window's class, for windows not owned by gamescope's PID.
+        #[arg(long, value_name = "REGEX")]
+        window_class: Option<String>,
+        /// Regex for the session window's title (checked together with --window-class).
+        #[arg(long, value_name = "REGEX")]
+        window_title: Option<String>,
+        /// Run one gamescope per selected monitor, each fitted to its own output, instead of one
+        /// spanning them all. `gamescope-down` stops them together.
+        #[arg(long, default_value_t = false)]
+        per_monitor: bool,
+        /// Command for the next monitor with --per-monitor, e.g. --app "mame pacman" (repeat it
+        /// per monitor). Without it every monitor runs the game command.
+        #[arg(long = "app", value_name = "COMMAND", requires = "per_monitor")]
+        apps: Vec<String>,
+        /// Print the resolved launch (gamescope args, environment, span, window handling and
+        /// hooks) as JSON and exit without starting anything.
+        #[arg(long, default_value_t = false, conflicts_with_all = ["per_monitor", "detach", "plan_from"])]
+        plan_json: bool,
+        /// Launch a plan written by --plan-json (`-` for stdin) as-is instead of resolving the
+        /// args again. Hotkeys, recording and other session settings still come from config.
+        #[arg(long, value_name = "FILE", conflicts_with = "per_monitor")]
+        plan_from: Option<String>,
+        /// Run a plan written by --plan-json exactly as saved, settings included, without reading
+        /// the config or detecting monitors, gamescope features or the plugin.
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["plan_json", "plan_from", "per_monitor", "gamescope_args"])]
+        plan: Option<String>,
+        /// Return right away and run the session in the background (for compositor keybinds).
+        #[arg(long, default_value_t = false)]
+        detach: bool,
+        /// Arguments passed to gamescope. Use `--` to separate gamescope args from the game command.
+        #[arg(trailing_var_arg = true)]
+        gamescope_args: Vec<String>,
+    },
+    /// Launch Steam Big Picture (`steam -gamepadui`) in a spanning Gamescope session.
+    Steam {
+        /// Arguments passed to gamescope (Steam integration `-e` is added automatically).
+        #[arg(trailing_var_arg = true)]
+        gamescope_args: Vec<String>,
+    },
+    /// Run a Steam game in a spanning session; set the launch options to `hyprfinity wrap -- %command%`.
+    Wrap {
+        /// The game command Steam substitutes for `%command%`.
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Create a headless Hyprland output, run a session spanning it, and optionally stream it.
+    /// The output is removed again when the session ends.
+    VirtualUp {
+        /// Size of the headless output, e.g. 3840x1080.
+        #[arg(long, value_parser = parse_size)]
+        size: (i32, i32),
+        /// Refresh rate of the headless output.
+        #[arg(long, default_value_t = 60)]
+        refresh: u32,
+        /// Stream the output with Sunshine or wayvnc for the length of the session.
+        #[arg(long, value_enum)]
+        stream: Option<StreamServer>,
         /// Arguments passed to gamescope. Use `--` to separate gamescope args from the game command.
         #[arg(trailing_var_arg = true)]
         gamescope_args: Vec<String>,
     },
     /// Tear down the active Gamescope session launched by GamescopeUp.
-    GamescopeDown,
+    #[command(alias = "down")]
+    GamescopeDown {
+        /// Wait 3 seconds with a notification first; running it again meanwhile cancels. The
+        /// exit hotkey uses this when exit_hotkey_confirm is set.
+        #[arg(long, default_value_t = false)]
+        confirm: bool,
+    },
+    /// Report whether the companion Hyprland plugin is loaded and used for window placement.
+    Plugin,
+    /// Tear down the active session if there is one, otherwise launch one with config defaults
+    /// (honours `--profile` and `--set`).
+    Toggle,
+    /// List monitors with their capabilities and whether they are part of the span.
+    Monitors {
+        /// Print machine-readable JSON instead of tables.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Print detected monitors, the computed span, and derived output/internal sizes.
+    Span {
+        /// Print machine-readable JSON instead of tables.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Re-run span and sizing decisions against a trace saved with --record.
+    Replay {
+        /// Trace file written by --record.
+        trace: String,
+    },
+    /// Check GitHub for a newer release. Never runs unless asked; `offline = true` disables it.
+    UpgradeCheck {
+        /// Print machine-readable JSON (for status bars); failures go in its `error` field.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Replace this executable with the latest release binary, after checking its checksum and
+    /// its build attestation (needs `gh`).
+    SelfUpdate {
+        /// Reinstall even if up to date, and replace installs under /usr.
+        #[arg(long, default_value_t = false)]
+        force: bool,
+        /// Install without verifying the build attestation, trusting the release's checksum alone.
+        #[arg(long, default_value_t = false)]
+        skip_attestation: bool,
+    },
+    /// Write man pages and a markdown command reference generated from this CLI, for packagers.
+    GenDocs {
+        /// Directory for `man/*.1` and `cli-reference.md`.
+        #[arg(long, value_name = "DIR", default_value = "target/docs")]
+        out_dir: String,
+    },
+    /// Bundle sanitized diagnostics into a markdown file to attach to a GitHub issue.
+    Bugreport {
+        /// Where to write the report (`-` for stdout). Defaults to ./hyprfinity-bugreport-<time>.md.
+        #[arg(long, value_name = "FILE")]
+        output: Option<String>,
+    },
+    /// Capture a screenshot framed to the span (or one monitor) with grim.
+    Screenshot {
+        /// Area to capture.
+        #[arg(long, value_enum, default_value_t = ScreenshotRegion::Span)]
+        region: ScreenshotRegion,
+        /// Monitor to capture with `--region monitor` (defaults to the focused monitor).
+        #[arg(long)]
+        monitor: Option<String>,
+        /// Output file (defaults to ~/Pictures/hyprfinity-<timestamp>.png).
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Internal: tear the session down if the launcher dies without cleaning up.
+    #[command(hide = true)]
+    Watchdog {
+        /// PID of the launching hyprfinity process.
+        #[arg(long)]
+        parent: u32,
+    },
+    /// Save the replay buffer of the active session (bound to the replay save hotkey).
+    ReplaySave,
+    /// Write app launcher entries (~/.local/share/applications) for `--profile NAME`, or all profiles.
+    ExportDesktop,
+    /// Restart gamescope in the active session; the game command is launched again.
+    Restart,
+    /// Restart the active session's gamescope at a new render scale (e.g. 0.8).
+    SetScale {
+        /// Internal size relative to the output size, above 0 and at most 1 (2 with allow_supersample).
+        scale: f32,
+    },
+    /// Pick a session action from a menu (bound to quick_menu_hotkey).
+    QuickMenu,
+    /// Toggle the session status line in Hyprland's overlay (bound to osd_hotkey).
+    Osd {
+        /// Internal: keep the status line updated until the session ends.
+        #[arg(long, hide = true)]
+        run: bool,
+    },
+    /// Show recorded playtime per game.
+    Stats {
+        /// Only count sessions that ended within the last N days.
+        #[arg(long)]
+        days: Option<u64>,
+        /// Print machine-readable JSON instead of tables.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// List past sessions, or relaunch one with the same gamescope args.
+    Sessions {
+        /// Number of most recent sessions to show.
+        #[arg(long, default_value_t = 10)]
+        last: usize,
+        /// Print machine-readable JSON instead of tables.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+        /// Relaunch the session with this id (as shown in the list).
+        #[arg(long)]
+        relaunch: Option<usize>,
+    },
     /// Create a starter config file.
     ConfigInit {
         /// Overwrite existing config if present (skip overwrite prompt).
@@ -72,47 +430,169 @@ pub(crate) enum Commands {
         force: bool,
     },
     /// Interactively configure output and internal render sizes.
-    Config,
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+    /// Browse, duplicate, rename or delete profiles, and launch a session from one.
+    Profiles,
+    /// Share profiles as self-contained TOML snippets.
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+    /// Community presets of per-game render scales and gamescope args.
+    Presets {
+        #[command(subcommand)]
+        action: PresetsAction,
+    },
     /// Print resolved config (path + values).
+    #[command(alias = "show")]
     ConfigShow {
         /// Override no-pin in effective output.
-        #[arg(long, default_value_t = false)]
-        no_pin: bool,
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "pin")]
+        no_pin: Option<bool>,
+        /// Override pin in effective output.
+        #[arg(long, overrides_with = "no_pin")]
+        pin: bool,
         /// Override pick in effective output.
-        #[arg(long, default_value_t = false)]
-        pick: bool,
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_pick")]
+        pick: Option<bool>,
+        /// Override pick off in effective output.
+        #[arg(long, overrides_with = "pick")]
+        no_pick: bool,
         /// Override idle-inhibit in effective output.
-        #[arg(long, default_value_t = false)]
-        idle_inhibit: bool,
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_idle_inhibit")]
+        idle_inhibit: Option<bool>,
+        /// Override idle-inhibit off in effective output.
+        #[arg(long, overrides_with = "idle_inhibit")]
+        no_idle_inhibit: bool,
         /// Override hide-waybar in effective output.
-        #[arg(long, default_value_t = false)]
-        hide_waybar: bool,
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_hide_waybar")]
+        hide_waybar: Option<bool>,
+        /// Override hide-waybar off in effective output.
+        #[arg(long, overrides_with = "hide_waybar")]
+        no_hide_waybar: bool,
         /// Override pick-size in effective output.
-        #[arg(long, default_value_t = false)]
-        pick_size: bool,
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_pick_size")]
+        pick_size: Option<bool>,
+        /// Override pick-size off in effective output.
+        #[arg(long, overrides_with = "pick_size")]
+        no_pick_size: bool,
         /// Override overlay enable in effective output.
-        #[arg(long, default_value_t = false)]
-        overlay: bool,
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_overlay")]
+        overlay: Option<bool>,
         /// Override overlay disable in effective output.
-        #[arg(long, default_value_t = false)]
+        #[arg(long, overrides_with = "overlay")]
         no_overlay: bool,
         /// Override MangoHud config string in effective output.
         #[arg(long)]
         mangohud_config: Option<String>,
         /// Override render scale in effective output.
-        #[arg(long)]
-        render_scale: Option<f32>,
+        #[arg(long, value_parser = parse_render_scale)]
+        render_scale: Option<RenderScale>,
         /// Override virtual width in effective output.
         #[arg(long)]
         virtual_width: Option<i32>,
         /// Override virtual height in effective output.
         #[arg(long)]
         virtual_height: Option<i32>,
+        /// Override output width in effective output.
+        #[arg(long)]
+        output_width: Option<i32>,
+        /// Override output height in effective output.
+        #[arg(long)]
+        output_height: Option<i32>,
+        /// Override output size (WxH) in effective output.
+        #[arg(long, value_parser = parse_size, conflicts_with_all = ["output_width", "output_height"])]
+        output_size: Option<(i32, i32)>,
+        /// Override exit-with-game in effective output.
+        #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", overrides_with = "no_exit_with_game")]
+        exit_with_game: Option<bool>,
+        /// Override exit-with-game off in effective output.
+        #[arg(long, overrides_with = "exit_with_game")]
+        no_exit_with_game: bool,
         /// Override startup timeout in effective output.
-        #[arg(long, default_value_t = 10)]
-        startup_timeout_secs: u64,
+        #[arg(long)]
+        startup_timeout_secs: Option<u64>,
+        /// Show which values differ from the built-in defaults and where each one came from
+        /// (default, config, autotune, profile, --set or cli).
+        #[arg(long, default_value_t = false)]
+        diff: bool,
+        /// Print the `--diff` report as JSON.
+        #[arg(long, default_value_t = false, requires = "diff")]
+        json: bool,
         /// Arguments passed to gamescope (for effective output). Use `--` to separate gamescope args.
         #[arg(trailing_var_arg = true)]
         gamescope_args: Vec<String>,
     },
 }
+
+/// Folds a `--flag[=BOOL]` / `--no-flag` pair into an explicit override, if any.
+pub(crate) fn flag_override(flag: Option<bool>, negated: bool) -> Option<bool> {
+    if negated { Some(false) } else { flag }
+}
+
+/// `0.75` for both axes, or `0.75x1.0` for horizontal and vertical.
+fn parse_render_scale(value: &str) -> Result<RenderScale, String> {
+    let invalid = || format!("expected a scale or XxY (e.g. 0.75x1.0), got `{}`", value);
+    let parse = |v: &str| v.trim().parse::<f32>().ok().filter(|v| *v > 0.0);
+    match value.split_once(['x', 'X']) {
+        Some((x, y)) => match (parse(x), parse(y)) {
+            (Some(x), Some(y)) => Ok(RenderScale { x, y }),
+            _ => Err(invalid()),
+        },
+        None => parse(value).map(RenderScale::uniform).ok_or_else(invalid),
+    }
+}
+
+fn parse_size(value: &str) -> Result<(i32, i32), String> {
+    let invalid = || format!("expected WxH (e.g. 5120x1440), got `{}`", value);
+    let (w, h) = value.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let parse = |v: &str| v.trim().parse::<i32>().ok().filter(|v| *v > 0);
+    match (parse(w), parse(h)) {
+        (Some(w), Some(h)) => Ok((w, h)),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_size_accepts_wxh() {
+        assert_eq!(parse_size("5120x1440"), Ok((5120, 1440)));
+        assert_eq!(parse_size("2560X1080"), Ok((2560, 1080)));
+        assert!(parse_size("5120").is_err());
+        assert!(parse_size("0x1440").is_err());
+        assert!(parse_size("widexhigh").is_err());
+    }
+
+    #[test]
+    fn parse_render_scale_accepts_uniform_and_per_axis() {
+        assert_eq!(parse_render_scale("0.75"), Ok(RenderScale::uniform(0.75)));
+        assert_eq!(
+            parse_render_scale("0.75x1.0"),
+            Ok(RenderScale { x: 0.75, y: 1.0 })
+        );
+        assert!(parse_render_scale("0x1").is_err());
+        assert!(parse_render_scale("half").is_err());
+    }
+
+    #[test]
+    fn pin_and_no_pin_fold_to_the_last_one_given() {
+        let no_pin = |args: &[&str]| {
+            let cli = Cli::try_parse_from(["hyprfinity", "gamescope-up"].iter().chain(args))
+                .expect("parse");
+            match cli.command {
+                Some(Commands::GamescopeUp { no_pin, pin, .. }) => flag_override(no_pin, pin),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(no_pin(&[]), None);
+        assert_eq!(no_pin(&["--pin"]), Some(false));
+        assert_eq!(no_pin(&["--pin", "--no-pin"]), Some(true));
+        assert_eq!(no_pin(&["--no-pin", "--pin"]), Some(false));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::MyError;
+use crate::compositor::{WindowRect, compositor};
+use crate::hyprland::{WindowMatcher, compute_monitor_span, fit_window_to_span, wait_for_client};
+use crate::i18n::{Msg, trf};
+use crate::output::{debug, info};
+use crate::types::{FitSettings, Monitor, WindowProbe};
+use std::error::Error;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+
+fn overlaps(a: WindowRect, b: WindowRect) -> bool {
+    a.0 < b.0 + b.2 && b.0 < a.0 + a.2 && a.1 < b.1 + b.3 && b.1 < a.1 + a.3
+}
+
+/// Where the companion app goes: across all monitors outside the span when their bounding box
+/// stays clear of it, otherwise on the first of them alone. Returns the rectangle and the
+/// monitors it covers.
+pub(crate) fn companion_area(
+    excluded: &[Monitor],
+    span: WindowRect,
+) -> Option<(WindowRect, Vec<String>)> {
+    let first = excluded.first()?;
+    let covered = match compute_monitor_span(excluded) {
+        Ok(rect) if !overlaps(rect, span) => excluded,
+        _ => std::slice::from_ref(first),
+    };
+    let rect = compute_monitor_span(covered).ok()?;
+    Some((
+        rect,
+        covered
+            .iter()
+            .filter_map(|monitor| monitor.name.clone())
+            .collect(),
+    ))
+}
+
+/// Starts `command` in its own process group and lays its window over `area`. The window is
+/// matched by PID, or by `window_class` for apps whose window belongs to a child process.
+pub(crate) fn start_companion(
+    command: &[String],
+    window_class: Option<&str>,
+    area: WindowRect,
+    (timeout_secs, poll_ms): (u64, u64),
+    fit: &FitSettings,
+    verbose: bool,
+) -> Result<u32, Box<dyn Error>> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| MyError("companion_command is empty.".to_string()))?;
+    let mut cmd = Command::new(program);
+    cmd.args(args).stdin(Stdio::null()).process_group(0);
+    if !verbose {
+        cmd.stdout(Stdio::null()).stderr(Stdio::null());
+    }
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| MyError(format!("Failed to start companion `{}`: {}", program, e)))?;
+    let pid = child.id();
+    info(&trf(Msg::CompanionStarted, &[&program, &pid]));
+    let placed = (|| -> Result<(), Box<dyn Error>> {
+        let probe = WindowProbe {
+            class: window_class.map(str::to_string),
+            title: None,
+            poll_ms,
+        };
+        let matcher = WindowMatcher::new(pid, &probe)?;
+        let client = wait_for_client(&matcher, timeout_secs, poll_ms, verbose, || {
+            // Launcher scripts may exit once the app is up; only a class rule can still find it.
+            window_class.is_none() && matches!(child.try_wait(), Ok(Some(_)))
+        })?;
+        let window = compositor().window_selector(client.pid as u32, Some(&client));
+        fit_window_to_span(
+            &matcher, &window, area.0, area.1, area.2, area.3, false, fit, verbose,
+        )
+    })();
+    if let Err(e) = placed {
+        stop_companion(pid, verbose);
+        return Err(MyError(format!("Companion `{}`: {}", program, e)).into());
+    }
+    Ok(pid)
+}
+
+/// Stops the companion and anything it started.
+pub(crate) fn stop_companion(pid: u32, verbose: bool) {
+    match Command::new("kill")
+        .args(["-TERM", "--", &format!("-{}", pid)])
+        .status()
+    {
+        Ok(status) => {
+            if verbose {
+                debug(&format!(
+                    "Stopped companion process group {} (status {}).",
+                    pid, status
+                ));
+            }
+        }
+        Err(e) => {
+            if verbose {
+                debug(&format!("Failed to stop companion PID {}: {}", pid, e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, y: i32) -> Monitor {
+        Monitor {
+            name: Some(name.to_string()),
+            width: 1920,
+            height: 1080,
+            x,
+            y,
+            ..Monitor::default()
+        }
+    }
+
+    #[test]
+    fn companion_area_covers_excluded_monitors_clear_of_the_span() {
+        let span = (1920, 0, 1920, 1080);
+        let right = [monitor("DP-3", 3840, 0), monitor("DP-4", 3840, 1080)];
+        assert_eq!(
+            companion_area(&right, span),
+            Some(((3840, 0, 1920, 2160), vec!["DP-3".into(), "DP-4".into()]))
+        );
+
+        // Monitors on both sides of the span: their bounding box would cover the game.
+        let sides = [monitor("DP-1", 0, 0), monitor("DP-3", 3840, 0)];
+        assert_eq!(
+            companion_area(&sides, span),
+            Some(((0, 0, 1920, 1080), vec!["DP-1".into()]))
+        );
+        assert_eq!(companion_area(&[], span), None);
+    }
+}
@@ -0,0 +1,346 @@
+use crate::MyError;
+use crate::config::{Config, resolve_config_path};
+use crate::types::{CompatSettings, CompatTool, DesktopApp};
+use crate::util::command_in_path;
+use serde::Deserialize;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+// umu-run refuses to start without a GAMEID; this one applies no per-game fixes.
+const UMU_DEFAULT_GAMEID: &str = "umu-default";
+const UMU_GAMES_FILE_NAME: &str = "umu_games.toml";
+
+/// One entry of the user's umu games list (`umu_games.toml` next to the config).
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub(crate) struct UmuGame {
+    pub(crate) name: String,
+    pub(crate) gameid: String,
+    pub(crate) exe: String,
+    #[serde(default)]
+    pub(crate) prefix: Option<String>,
+    #[serde(default)]
+    pub(crate) proton: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct UmuGamesFile {
+    #[serde(default)]
+    games: Vec<UmuGame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CompatLaunch {
+    pub(crate) command: Vec<String>,
+    pub(crate) env: Vec<(String, String)>,
+}
+
+fn expand_home(path: &str, home: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+fn steam_root(home: &Path) -> PathBuf {
+    let legacy = home.join(".steam/steam");
+    if legacy.exists() {
+        legacy
+    } else {
+        home.join(".local/share/Steam")
+    }
+}
+
+// Custom builds (GE-Proton) live in compatibilitytools.d, Valve's builds in steamapps/common.
+fn proton_search_dirs(home: &Path) -> Vec<PathBuf> {
+    vec![
+        home.join(".steam/root/compatibilitytools.d"),
+        home.join(".local/share/Steam/compatibilitytools.d"),
+        home.join(".steam/steam/steamapps/common"),
+        home.join(".local/share/Steam/steamapps/common"),
+    ]
+}
+
+/// Resolves `proton_version` to a Proton install: either a path, or a directory name such as
+/// "GE-Proton9-20" or "Proton 9.0" looked up in the usual Steam locations.
+pub(crate) fn resolve_proton_dir(version: &str, home: &Path) -> Option<PathBuf> {
+    if version.contains('/') {
+        let dir = expand_home(version, home);
+        return dir.join("proton").is_file().then_some(dir);
+    }
+    proton_search_dirs(home)
+        .into_iter()
+        .map(|dir| dir.join(version))
+        .find(|dir| dir.join("proton").is_file())
+}
+
+pub(crate) fn default_wine_prefix(home: &Path) -> PathBuf {
+    home.join(".local/share/hyprfinity/prefixes/default")
+}
+
+/// Wraps a game command so it runs under the configured compatibility tool and returns the
+/// environment gamescope must pass through to it.
+pub(crate) fn wrap_game_command(
+    command: Vec<String>,
+    compat: &CompatSettings,
+    home: &Path,
+) -> Result<CompatLaunch, Box<dyn Error>> {
+    let Some(tool) = compat.tool else {
+        return Ok(CompatLaunch {
+            command,
+            env: Vec::new(),
+        });
+    };
+    let prefix = compat
+        .wine_prefix
+        .as_deref()
+        .map(|p| expand_home(p, home))
+        .unwrap_or_else(|| default_wine_prefix(home));
+    let prefix = prefix.to_string_lossy().to_string();
+
+    let (wrapper, env) = match tool {
+        CompatTool::Proton => {
+            let version = compat.proton_version.as_deref().ok_or_else(|| {
+                MyError("compat_tool = \"proton\" needs proton_version to be set.".to_string())
+            })?;
+            let dir = resolve_proton_dir(version, home).ok_or_else(|| {
+                MyError(format!(
+                    "Proton `{}` not found (looked for a `proton` script in compatibilitytools.d and steamapps/common).",
+                    version
+                ))
+            })?;
+            (
+                vec![
+                    dir.join("proton").to_string_lossy().to_string(),
+                    "run".to_string(),
+                ],
+                vec![
+                    ("STEAM_COMPAT_DATA_PATH".to_string(), prefix),
+                    (
+                        "STEAM_COMPAT_CLIENT_INSTALL_PATH".to_string(),
+                        steam_root(home).to_string_lossy().to_string(),
+                    ),
+                ],
+            )
+        }
+        CompatTool::Umu => {
+            let gameid = compat.umu_gameid.as_deref().unwrap_or(UMU_DEFAULT_GAMEID);
+            let mut env = vec![
+                ("GAMEID".to_string(), gameid.to_string()),
+                ("WINEPREFIX".to_string(), prefix),
+            ];
+            // umu-run also understands names like "GE-Proton" and downloads them itself.
+            if let Some(version) = compat.proton_version.as_deref() {
+                let proton = resolve_proton_dir(version, home)
+                    .map(|dir| dir.to_string_lossy().to_string())
+                    .unwrap_or_else(|| version.to_string());
+                env.push(("PROTONPATH".to_string(), proton));
+            }
+            (vec!["umu-run".to_string()], env)
+        }
+        CompatTool::Wine => (
+            vec!["wine".to_string()],
+            vec![("WINEPREFIX".to_string(), prefix)],
+        ),
+    };
+
+    Ok(CompatLaunch {
+        command: wrapper.into_iter().chain(command).collect(),
+        env,
+    })
+}
+
+fn parse_umu_games(contents: &str) -> Result<Vec<UmuGame>, Box<dyn Error>> {
+    let file: UmuGamesFile = toml::from_str(contents)?;
+    Ok(file.games)
+}
+
+pub(crate) fn umu_games_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(resolve_config_path(&None)?.with_file_name(UMU_GAMES_FILE_NAME))
+}
+
+/// Reads the umu games list; a missing file is an empty list.
+pub(crate) fn load_umu_games() -> Result<Vec<UmuGame>, Box<dyn Error>> {
+    let path = umu_games_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(&path)?;
+    parse_umu_games(&contents)
+        .map_err(|e| MyError(format!("Failed to parse {}: {}", path.display(), e)).into())
+}
+
+fn umu_game_exec(game: &UmuGame, home: &Path) -> Result<String, Box<dyn Error>> {
+    let compat = CompatSettings {
+        tool: Some(CompatTool::Umu),
+        proton_version: game.proton.clone(),
+        wine_prefix: game.prefix.clone(),
+        umu_gameid: Some(game.gameid.clone()),
+    };
+    let launch = wrap_game_command(vec![game.exe.clone()], &compat, home)?;
+    // Picker entries are plain commands, so the umu environment rides along via env(1). Only the
+    // values need quoting: quoting a whole `VAR=value` word is correct but harder to read.
+    let mut words = vec!["env".to_string()];
+    words.extend(
+        launch
+            .env
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, shell_words::quote(v))),
+    );
+    words.push(shell_words::join(launch.command));
+    Ok(words.join(" "))
+}
+
+/// App picker entries for the umu games list, only offered when umu-run is installed.
+pub(crate) fn umu_picker_entries() -> Vec<DesktopApp> {
+    if !command_in_path("umu-run") {
+        return Vec::new();
+    }
+    let Ok(home) = std::env::var("HOME") else {
+        return Vec::new();
+    };
+    load_umu_games()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|game| {
+            Some(DesktopApp {
+                name: format!("{} (umu)", game.name),
+                exec: umu_game_exec(game, Path::new(&home)).ok()?,
+                ..DesktopApp::default()
+            })
+        })
+        .collect()
+}
+
+/// Applies `--umu GAMEID`: runs through umu-run with that GAMEID and, when the id is in the
+/// umu games list, uses its exe as the default command along with its prefix and Proton.
+pub(crate) fn apply_umu_game(config: &Config, gameid: &str) -> Result<Config, Box<dyn Error>> {
+    let mut config = config.clone();
+    config.compat_tool = Some(CompatTool::Umu);
+    config.umu_gameid = Some(gameid.to_string());
+    if let Some(game) = load_umu_games()?.into_iter().find(|g| g.gameid == gameid) {
+        config.default_command = Some(vec![game.exe]);
+        if game.prefix.is_some() {
+            config.wine_prefix = game.prefix;
+        }
+        if game.proton.is_some() {
+            config.proton_version = game.proton;
+        }
+    }
+    Ok(config)
+}
+
+/// The wrapper executable that must be in PATH for `tool` (Proton is run by absolute path).
+pub(crate) fn compat_wrapper_binary(tool: CompatTool) -> Option<&'static str> {
+    match tool {
+        CompatTool::Proton => None,
+        CompatTool::Umu => Some("umu-run"),
+        CompatTool::Wine => Some("wine"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(tool: CompatTool, version: Option<&str>, prefix: Option<&str>) -> CompatSettings {
+        CompatSettings {
+            tool: Some(tool),
+            proton_version: version.map(str::to_string),
+            wine_prefix: prefix.map(str::to_string),
+            umu_gameid: None,
+        }
+    }
+
+    #[test]
+    fn wrap_game_command_composes_wrapper_and_env() {
+        let home = tempfile::tempdir().unwrap();
+        let proton_dir = home
+            .path()
+            .join(".steam/root/compatibilitytools.d/GE-Proton9-20");
+        std::fs::create_dir_all(&proton_dir).unwrap();
+        std::fs::write(proton_dir.join("proton"), "").unwrap();
+        let game = vec!["/games/foo/Game.exe".to_string()];
+
+        let launch = wrap_game_command(
+            game.clone(),
+            &settings(CompatTool::Proton, Some("GE-Proton9-20"), Some("~/pfx/foo")),
+            home.path(),
+        )
+        .unwrap();
+        assert_eq!(
+            launch.command,
+            vec![
+                proton_dir.join("proton").to_string_lossy().to_string(),
+                "run".to_string(),
+                "/games/foo/Game.exe".to_string(),
+            ]
+        );
+        assert_eq!(
+            launch.env[0],
+            (
+                "STEAM_COMPAT_DATA_PATH".to_string(),
+                home.path().join("pfx/foo").to_string_lossy().to_string()
+            )
+        );
+
+        let launch = wrap_game_command(
+            game.clone(),
+            &settings(CompatTool::Umu, Some("GE-Proton"), Some("/pfx")),
+            home.path(),
+        )
+        .unwrap();
+        assert_eq!(launch.command, vec!["umu-run", "/games/foo/Game.exe"]);
+        assert!(
+            launch
+                .env
+                .contains(&("PROTONPATH".to_string(), "GE-Proton".to_string()))
+        );
+        assert!(
+            launch
+                .env
+                .contains(&("WINEPREFIX".to_string(), "/pfx".to_string()))
+        );
+        assert!(
+            launch
+                .env
+                .contains(&("GAMEID".to_string(), "umu-default".to_string()))
+        );
+
+        assert!(
+            wrap_game_command(
+                game.clone(),
+                &settings(CompatTool::Proton, Some("Proton 1.0"), None),
+                home.path(),
+            )
+            .is_err()
+        );
+        assert!(
+            wrap_game_command(game.clone(), &CompatSettings::default(), home.path())
+                .unwrap()
+                .env
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn umu_games_list_parses_and_builds_env_commands() {
+        let games = parse_umu_games(
+            r#"
+[[games]]
+name = "The Witcher 3"
+gameid = "umu-292030"
+exe = "/games/witcher 3/witcher3.exe"
+prefix = "/pfx/witcher"
+proton = "GE-Proton"
+"#,
+        )
+        .unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].gameid, "umu-292030");
+        assert_eq!(
+            umu_game_exec(&games[0], Path::new("/home/me")).unwrap(),
+            "env GAMEID=umu-292030 WINEPREFIX=/pfx/witcher PROTONPATH=GE-Proton umu-run '/games/witcher 3/witcher3.exe'"
+        );
+        assert!(parse_umu_games("").unwrap().is_empty());
+    }
+}
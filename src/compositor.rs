@@ -0,0 +1,157 @@
+use crate::MyError;
+use crate::backend::hyprctl_emulated;
+use crate::cli::CompositorChoice;
+use crate::debuglog::debug_log_line;
+use crate::hyprland::HyprlandCompositor;
+use crate::niri::NiriCompositor;
+use crate::output::debug;
+use crate::sway::SwayCompositor;
+use crate::types::{Client, HotkeyBind, Monitor};
+use crate::util::command_in_path;
+use crate::wlroots::WlrootsCompositor;
+use crate::x11::X11Compositor;
+use std::error::Error;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Window geometry as (x, y, width, height) in global layout coordinates.
+pub(crate) type WindowRect = (i32, i32, i32, i32);
+
+/// Window management the session needs from the compositor. Span and launch logic stay
+/// compositor-agnostic; Hyprland-only extras (cursor confinement, dimming, hyprsunset) still
+/// talk to hyprctl directly and are skipped elsewhere.
+pub(crate) trait Compositor: Sync {
+    fn name(&self) -> &'static str;
+    fn monitors(&self, verbose: bool) -> Result<Vec<Monitor>, Box<dyn Error>>;
+    /// Toplevel windows with pid and global geometry; `address` holds the compositor's window id.
+    fn clients(&self, verbose: bool) -> Result<Vec<Client>, Box<dyn Error>>;
+    /// Selector handed back to `place_window`; `client` is the gamescope window when found.
+    fn window_selector(&self, pid: u32, client: Option<&Client>) -> String;
+    /// Moves and resizes the window. `float` makes it floating first, pinned too when `pin`.
+    fn place_window(
+        &self,
+        window: &str,
+        rect: WindowRect,
+        float: bool,
+        pin: bool,
+        verbose: bool,
+    ) -> Result<(), Box<dyn Error>>;
+    /// Binds MODS+KEY to run `command` when `bind` fires. `Ok(false)` means the combo is
+    /// already taken.
+    fn bind_key(
+        &self,
+        mods: &str,
+        key: &str,
+        bind: HotkeyBind,
+        command: &str,
+        verbose: bool,
+    ) -> Result<bool, Box<dyn Error>>;
+    fn unbind_key(&self, mods: &str, key: &str, verbose: bool) -> Result<(), Box<dyn Error>>;
+    /// Drops border, rounding and shadow for the life of the window, where supported.
+    fn strip_decorations(&self, _window: &str, _verbose: bool) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+    /// Whether `place_window` can cover a span at all.
+    fn window_control(&self) -> bool {
+        true
+    }
+    /// Whether `place_window` decides which output the window lands on.
+    fn chooses_output(&self) -> bool {
+        true
+    }
+}
+
+/// Runs a compositor IPC client (`swaymsg`, `niri msg`) and returns its stdout.
+pub(crate) fn run_ipc(
+    program: &str,
+    args: &[&str],
+    verbose: bool,
+) -> Result<String, Box<dyn Error>> {
+    debug_log_line(&format!("{} {:?}", program, args));
+    if verbose {
+        debug(&format!("Executing {} with args: {:?}", program, args));
+    }
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| MyError(format!("Failed to run {}: {}", program, e)))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() {
+        return Err(MyError(format!(
+            "{} failed for args {:?}: {}{}",
+            program,
+            args,
+            String::from_utf8_lossy(&output.stderr).trim(),
+            stdout.trim()
+        ))
+        .into());
+    }
+    Ok(stdout)
+}
+
+/// Maps wlroots ("flipped-90") and niri ("Flipped90", "_90") output transforms to Hyprland's 0-7.
+pub(crate) fn transform_index(transform: &str) -> i32 {
+    let normalized = transform.to_ascii_lowercase().replace(['_', '-'], "");
+    match normalized.as_str() {
+        "90" => 1,
+        "180" => 2,
+        "270" => 3,
+        "flipped" => 4,
+        "flipped90" => 5,
+        "flipped180" => 6,
+        "flipped270" => 7,
+        _ => 0,
+    }
+}
+
+static COMPOSITOR: OnceLock<&'static dyn Compositor> = OnceLock::new();
+
+fn detect_compositor() -> &'static dyn Compositor {
+    let set = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+    // The mock and replay backends speak hyprctl, whatever session the tests run in.
+    if hyprctl_emulated() || set("HYPRLAND_INSTANCE_SIGNATURE") {
+        &HyprlandCompositor
+    } else if set("SWAYSOCK") {
+        &SwayCompositor
+    } else if set("NIRI_SOCKET") {
+        &NiriCompositor
+    } else if !set("WAYLAND_DISPLAY") && set("DISPLAY") {
+        &X11Compositor
+    } else if command_in_path("wlr-randr") {
+        &WlrootsCompositor
+    } else {
+        &HyprlandCompositor
+    }
+}
+
+/// Applies `--backend`; call before anything asks for the compositor.
+pub(crate) fn init_compositor(choice: CompositorChoice) {
+    let selected: &'static dyn Compositor = match choice {
+        CompositorChoice::Auto => detect_compositor(),
+        CompositorChoice::Hyprland => &HyprlandCompositor,
+        CompositorChoice::Sway => &SwayCompositor,
+        CompositorChoice::Niri => &NiriCompositor,
+        CompositorChoice::X11 => &X11Compositor,
+        CompositorChoice::Wlroots => &WlrootsCompositor,
+    };
+    let _ = COMPOSITOR.set(selected);
+}
+
+pub(crate) fn compositor() -> &'static dyn Compositor {
+    *COMPOSITOR.get_or_init(detect_compositor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transform_index_accepts_sway_and_niri_spellings() {
+        assert_eq!(transform_index("normal"), 0);
+        assert_eq!(transform_index("Normal"), 0);
+        assert_eq!(transform_index("90"), 1);
+        assert_eq!(transform_index("_90"), 1);
+        assert_eq!(transform_index("flipped-270"), 7);
+        assert_eq!(transform_index("Flipped90"), 5);
+    }
+}
@@ -1,49 +1,252 @@
 use crate::MyError;
 use crate::autotune::{detect_auto_tune_profile, detect_span_size};
-use crate::hyprland::{compute_monitor_span, get_monitors};
-use crate::tui_config::{apply_editor_defaults, edit_config_tui};
-use crate::types::AutoTuneProfile;
+use crate::config_migrate::{CONFIG_VERSION, migrate_config_file};
+use crate::hyprland::{
+    DEFAULT_HYPRCTL_ATTEMPTS, DEFAULT_HYPRCTL_BACKOFF_MS, compute_monitor_span, get_monitors,
+};
+use crate::i18n::{Msg, is_yes, tr, trf, yes_no_hint};
+use crate::output::{Tone, info, paint, stdout_color, warn};
+use crate::preset_catalog::DEFAULT_PRESET_CATALOG_URL;
+use crate::streaming::sunshine_client_mode;
+use crate::tui_config::{apply_editor_defaults, edit_config_tui, profiles_tui};
+use crate::types::{
+    AutoTuneProfile, ColorMode, CompatSettings, CompatTool, CursorSettings, DEFAULT_EXIT_HOTKEY,
+    DEFAULT_FIT_ATTEMPTS, DEFAULT_FIT_SETTLE_MS, DEFAULT_FIT_TOLERANCE_PX, DEFAULT_GAMEPAD_HOLD_MS,
+    DEFAULT_RECORDING_FPS, DEFAULT_REPLAY_SAVE_HOTKEY, DEFAULT_REPLAY_SECS,
+    DEFAULT_RESTART_BACKOFF_MS, DEFAULT_RESTART_MAX_RETRIES, DEFAULT_STARTUP_POLL_MS,
+    DEFAULT_STARTUP_TIMEOUT_SECS, DEFAULT_WINDOW_CLASS, ExcludedMonitors, FitSettings,
+    GamepadAction, GamepadSettings, HotkeyBind, MAX_SUPERSAMPLE_SCALE, MetricsSettings,
+    PickerFallback, RecordingBackend, RecordingSettings, RenderScale, RestartPolicy, SpanStrategy,
+    SpanTarget, StreamingSettings, TuningFlags, WindowProbe,
+};
+use crate::util::scaled_dimensions_within;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
 use std::io::Write;
 
 const DEFAULT_CONFIG_REL_PATH: &str = "hyprfinity/config.toml";
 
-#[derive(Debug, Serialize, Deserialize, Default, Clone)]
-pub(crate) struct Config {
+#[derive(Debug, Serialize, Deserialize, Default, Clone, JsonSchema)]
+pub struct Config {
+    pub(crate) config_version: Option<u32>,
+    pub(crate) include: Option<Vec<String>>,
     pub(crate) gamescope_args: Option<Vec<String>>,
     pub(crate) default_command: Option<Vec<String>>,
+    pub(crate) pre_command: Option<Vec<String>>,
     pub(crate) no_pin: Option<bool>,
     pub(crate) pick: Option<bool>,
+    pub(crate) picker_fallback: Option<PickerFallback>,
+    pub(crate) picker_command: Option<Vec<String>>,
+    pub(crate) picker_terminal: Option<Vec<String>>,
     pub(crate) idle_inhibit: Option<bool>,
     pub(crate) hide_waybar: Option<bool>,
     pub(crate) pick_size: Option<bool>,
     pub(crate) overlay_enabled: Option<bool>,
     pub(crate) mangohud_config: Option<String>,
+    #[schemars(range(min = 0.1, max = 2.0))]
     pub(crate) render_scale: Option<f32>,
+    #[schemars(range(min = 0.1, max = 2.0))]
+    pub(crate) render_scale_x: Option<f32>,
+    #[schemars(range(min = 0.1, max = 2.0))]
+    pub(crate) render_scale_y: Option<f32>,
+    pub(crate) allow_supersample: Option<bool>,
+    pub(crate) integer_scale: Option<bool>,
+    #[schemars(range(min = 2))]
     pub(crate) virtual_width: Option<i32>,
+    #[schemars(range(min = 2))]
     pub(crate) virtual_height: Option<i32>,
+    #[schemars(range(min = 2))]
     pub(crate) output_width: Option<i32>,
+    #[schemars(range(min = 2))]
     pub(crate) output_height: Option<i32>,
+    pub(crate) span_target: Option<SpanTarget>,
+    pub(crate) span_strategy: Option<SpanStrategy>,
+    pub(crate) excluded_monitors: Option<ExcludedMonitors>,
+    pub(crate) companion_command: Option<Vec<String>>,
+    pub(crate) companion_window_class: Option<String>,
+    pub(crate) refresh_rate: Option<u32>,
     pub(crate) startup_timeout_secs: Option<u64>,
+    pub(crate) startup_poll_ms: Option<u64>,
+    pub(crate) window_class: Option<String>,
+    pub(crate) window_title: Option<String>,
+    pub(crate) hyprctl_attempts: Option<u32>,
+    pub(crate) hyprctl_backoff_ms: Option<u64>,
+    pub(crate) gamescope_flatpak: Option<String>,
+    pub(crate) fit_attempts: Option<u32>,
+    pub(crate) fit_settle_ms: Option<u64>,
+    #[schemars(range(min = 0))]
+    pub(crate) fit_tolerance_px: Option<i32>,
+    pub(crate) exit_with_game: Option<bool>,
+    pub(crate) restart_on_crash: Option<bool>,
+    pub(crate) restart_max_retries: Option<u32>,
+    pub(crate) restart_backoff_ms: Option<u64>,
+    pub(crate) gamepad_chord: Option<Vec<String>>,
+    pub(crate) gamepad_hold_ms: Option<u64>,
+    pub(crate) gamepad_action: Option<GamepadAction>,
+    pub(crate) metrics_listen: Option<String>,
+    pub(crate) metrics_textfile: Option<String>,
+    pub(crate) color: Option<ColorMode>,
+    pub(crate) exit_hotkey: Option<String>,
+    pub(crate) exit_hotkey_bind: Option<HotkeyBind>,
+    pub(crate) exit_hotkey_confirm: Option<bool>,
+    pub(crate) quick_menu_hotkey: Option<String>,
+    pub(crate) osd_hotkey: Option<String>,
+    pub(crate) compat_tool: Option<CompatTool>,
+    pub(crate) proton_version: Option<String>,
+    pub(crate) wine_prefix: Option<String>,
+    pub(crate) umu_gameid: Option<String>,
+    pub(crate) embedded: Option<bool>,
+    pub(crate) immediate_flips: Option<bool>,
+    pub(crate) rt_priority: Option<bool>,
+    pub(crate) force_grab_cursor: Option<bool>,
+    pub(crate) expose_wayland: Option<bool>,
+    pub(crate) cursor_confine: Option<bool>,
+    pub(crate) cursor_warp: Option<bool>,
+    pub(crate) disable_nightlight: Option<bool>,
+    pub(crate) borderless: Option<bool>,
+    pub(crate) resolution_hints: Option<bool>,
+    pub(crate) engine_hints: Option<bool>,
+    pub(crate) preset_catalog_url: Option<String>,
+    pub(crate) offline: Option<bool>,
+    pub(crate) recording: Option<RecordingConfig>,
+    pub(crate) streaming: Option<StreamingConfig>,
+    // Named partial configs layered over the base config (`--profile NAME`).
+    pub(crate) profiles: Option<BTreeMap<String, Config>>,
+    // Only read inside a profile: game commands that select it automatically.
+    #[serde(rename = "match")]
+    pub(crate) profile_match: Option<ProfileMatch>,
 }
 
-#[derive(Debug, Clone)]
-pub(crate) struct LaunchSettings {
+#[derive(Debug, Serialize, Deserialize, Default, Clone, JsonSchema)]
+pub(crate) struct ProfileMatch {
+    pub(crate) exe: Option<Vec<String>>,
+    pub(crate) steam_appid: Option<Vec<u32>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, JsonSchema)]
+pub(crate) struct RecordingConfig {
+    pub(crate) enabled: Option<bool>,
+    pub(crate) backend: Option<RecordingBackend>,
+    pub(crate) replay_secs: Option<u32>,
+    pub(crate) fps: Option<u32>,
+    pub(crate) output_dir: Option<String>,
+    pub(crate) save_hotkey: Option<String>,
+    pub(crate) obs_region_file: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, JsonSchema)]
+pub(crate) struct StreamingConfig {
+    pub(crate) enabled: Option<bool>,
+    pub(crate) match_client: Option<bool>,
+}
+
+/// Launch values given on the command line. `None` (or no args) leaves the config in charge.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CliOverrides {
+    pub(crate) args: Vec<String>,
+    pub(crate) no_pin: Option<bool>,
+    pub(crate) pick: Option<bool>,
+    pub(crate) idle_inhibit: Option<bool>,
+    pub(crate) hide_waybar: Option<bool>,
+    pub(crate) pick_size: Option<bool>,
+    pub(crate) overlay: Option<bool>,
+    pub(crate) mangohud_config: Option<String>,
+    pub(crate) render_scale: Option<RenderScale>,
+    pub(crate) virtual_width: Option<i32>,
+    pub(crate) virtual_height: Option<i32>,
+    pub(crate) output_width: Option<i32>,
+    pub(crate) output_height: Option<i32>,
+    pub(crate) exit_with_game: Option<bool>,
+    pub(crate) startup_timeout_secs: Option<u64>,
+}
+
+impl CliOverrides {
+    /// Only the gamescope args (and game command) come from the command line.
+    pub(crate) fn args(args: Vec<String>) -> Self {
+        Self {
+            args,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchSettings {
     pub(crate) args: Vec<String>,
+    pub(crate) default_command: Option<Vec<String>>,
+    pub(crate) pre_command: Option<Vec<String>>,
     pub(crate) no_pin: bool,
     pub(crate) pick: bool,
+    pub(crate) picker_fallback: PickerFallback,
+    pub(crate) picker_command: Option<Vec<String>>,
     pub(crate) idle_inhibit: bool,
     pub(crate) hide_waybar: bool,
     pub(crate) pick_size: bool,
     pub(crate) overlay_enabled: bool,
     pub(crate) mangohud_config: Option<String>,
-    pub(crate) render_scale: f32,
+    pub(crate) render_scale: RenderScale,
+    pub(crate) allow_supersample: bool,
+    pub(crate) integer_scale: bool,
     pub(crate) virtual_width: Option<i32>,
     pub(crate) virtual_height: Option<i32>,
     pub(crate) output_width: Option<i32>,
     pub(crate) output_height: Option<i32>,
+    pub(crate) span_target: SpanTarget,
+    pub(crate) span_strategy: SpanStrategy,
+    pub(crate) excluded_monitors: ExcludedMonitors,
+    pub(crate) companion_command: Option<Vec<String>>,
+    pub(crate) companion_window_class: Option<String>,
+    pub(crate) refresh_rate: Option<u32>,
     pub(crate) timeout: u64,
+    pub(crate) probe: WindowProbe,
+    pub(crate) fit: FitSettings,
+    pub(crate) exit_with_game: bool,
+    pub(crate) restart: RestartPolicy,
+    pub(crate) gamepad: GamepadSettings,
+    pub(crate) metrics: MetricsSettings,
+    pub(crate) recording: RecordingSettings,
+    pub(crate) streaming: StreamingSettings,
+    pub(crate) exit_hotkey: String,
+    pub(crate) exit_hotkey_bind: HotkeyBind,
+    pub(crate) exit_hotkey_confirm: bool,
+    pub(crate) quick_menu_hotkey: Option<String>,
+    pub(crate) osd_hotkey: Option<String>,
+    pub(crate) compat: CompatSettings,
+    pub(crate) embedded: bool,
+    pub(crate) tuning: TuningFlags,
+    pub(crate) cursor: CursorSettings,
+    pub(crate) disable_nightlight: bool,
+    pub(crate) borderless: bool,
+    pub(crate) resolution_hints: bool,
+    pub(crate) engine_hints: bool,
+    // Set by launch modes rather than config, e.g. `wrap` clearing Steam's LD_PRELOAD for gamescope.
+    pub(crate) extra_env: Vec<(String, String)>,
+}
+
+impl LaunchSettings {
+    /// Settings for a launch with no CLI overrides, as `hyprfinity` with no subcommand uses.
+    pub fn from_config(config: &Config) -> Self {
+        apply_config(&CliOverrides::default(), config)
+    }
+
+    /// Gamescope args, optionally followed by `--` and the game command.
+    pub fn gamescope_args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn span_target(&self) -> SpanTarget {
+        self.span_target
+    }
+
+    pub fn span_strategy(&self) -> SpanStrategy {
+        self.span_strategy
+    }
+
+    pub fn pin(&self) -> bool {
+        !self.no_pin
+    }
 }
 
 pub(crate) const DEFAULT_MANGOHUD_CONFIG: &str =
@@ -64,7 +267,7 @@ fn resolve_default_config_path() -> Result<std::path::PathBuf, Box<dyn Error>> {
     )
 }
 
-fn resolve_config_path(
+pub(crate) fn resolve_config_path(
     path_override: &Option<String>,
 ) -> Result<std::path::PathBuf, Box<dyn Error>> {
     if let Some(path) = path_override {
@@ -74,40 +277,278 @@ fn resolve_config_path(
     }
 }
 
-pub(crate) fn load_config(path_override: &Option<String>) -> Result<Config, Box<dyn Error>> {
-    let path = if let Some(path) = path_override {
-        std::path::PathBuf::from(path)
-    } else {
-        resolve_default_config_path()?
-    };
-
+/// Loads the config with every `include` merged in: included files in listed order (later
+/// wins), then the including file on top.
+pub fn load_config(path_override: &Option<String>) -> Result<Config, Box<dyn Error>> {
+    let path = resolve_config_path(path_override)?;
     if !path.exists() {
         return Ok(Config::default());
     }
+    let merged = load_config_value(&path, &mut Vec::new())?;
+    let config: Config = merged
+        .try_into()
+        .map_err(|e| MyError(format!("Failed to merge config {}: {}", path.display(), e)))?;
+    Ok(config)
+}
 
-    let contents = std::fs::read_to_string(&path)?;
+/// Loads only the main config file, keeping `include` as written; editors write this back.
+pub(crate) fn load_config_file(path_override: &Option<String>) -> Result<Config, Box<dyn Error>> {
+    let path = resolve_config_path(path_override)?;
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    let contents = migrate_config_file(&path)?;
     let config: Config = toml::from_str(&contents)
         .map_err(|e| MyError(format!("Failed to parse config {}: {}", path.display(), e)))?;
     Ok(config)
 }
 
-fn default_config_values(auto: &AutoTuneProfile) -> Config {
+fn load_config_value(
+    path: &std::path::Path,
+    stack: &mut Vec<std::path::PathBuf>,
+) -> Result<toml::Value, Box<dyn Error>> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if let Some(start) = stack.iter().position(|p| *p == canonical) {
+        let chain = stack[start..]
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        return Err(MyError(format!("Config include cycle: {}", chain)).into());
+    }
+
+    let contents = migrate_config_file(path)
+        .map_err(|e| MyError(format!("Failed to read config {}: {}", path.display(), e)))?;
+    let mut table: toml::Table = toml::from_str(&contents)
+        .map_err(|e| MyError(format!("Failed to parse config {}: {}", path.display(), e)))?;
+    // Check each file on its own so a bad value names the file it came from.
+    Config::deserialize(toml::Value::Table(table.clone()))
+        .map_err(|e| MyError(format!("Failed to parse config {}: {}", path.display(), e)))?;
+    let includes = match table.remove("include") {
+        Some(value) => value.try_into::<Vec<String>>().map_err(|_| {
+            MyError(format!(
+                "`include` in {} must be a list of paths.",
+                path.display()
+            ))
+        })?,
+        None => Vec::new(),
+    };
+
+    let base_dir = path.parent().unwrap_or(std::path::Path::new("."));
+    let mut merged = toml::Value::Table(toml::Table::new());
+    stack.push(canonical);
+    for pattern in &includes {
+        let files = resolve_include(base_dir, pattern).map_err(|e| {
+            MyError(format!(
+                "Bad include `{}` in {}: {}",
+                pattern,
+                path.display(),
+                e
+            ))
+        })?;
+        for file in files {
+            merge_toml(&mut merged, load_config_value(&file, stack)?);
+        }
+    }
+    stack.pop();
+    merge_toml(&mut merged, toml::Value::Table(table));
+    Ok(merged)
+}
+
+fn wildcard_match(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some(('*', rest)), _) => {
+            wildcard_match(rest, name) || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some(('?', rest)), Some((_, name_rest))) => wildcard_match(rest, name_rest),
+        (Some((p, rest)), Some((n, name_rest))) => p == n && wildcard_match(rest, name_rest),
+        _ => false,
+    }
+}
+
+/// Expands an include entry relative to the including file. `*` and `?` are allowed in the
+/// file name; matches are sorted so the merge order does not depend on the filesystem.
+fn resolve_include(
+    base_dir: &std::path::Path,
+    pattern: &str,
+) -> Result<Vec<std::path::PathBuf>, Box<dyn Error>> {
+    let expanded = match (pattern.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => std::path::PathBuf::from(home).join(rest),
+        _ => std::path::PathBuf::from(pattern),
+    };
+    let path = base_dir.join(expanded);
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| MyError("no file name".to_string()))?;
+    if !file_name.contains(['*', '?']) {
+        if !path.is_file() {
+            return Err(MyError(format!("{} not found", path.display())).into());
+        }
+        return Ok(vec![path]);
+    }
+
+    let dir = path.parent().unwrap_or(base_dir);
+    let pattern = file_name.chars().collect::<Vec<_>>();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut files = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|file| file.is_file())
+        .filter(|file| {
+            file.file_name().is_some_and(|name| {
+                wildcard_match(
+                    &pattern,
+                    &name.to_string_lossy().chars().collect::<Vec<_>>(),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+pub(crate) fn default_config_values(auto: &AutoTuneProfile) -> Config {
     Config {
-        gamescope_args: Some(vec!["-r".to_string(), "60".to_string()]),
+        config_version: Some(CONFIG_VERSION),
+        include: None,
+        gamescope_args: Some(Vec::new()),
         default_command: None,
+        pre_command: None,
         no_pin: Some(false),
         pick: Some(false),
+        picker_fallback: Some(PickerFallback::Error),
+        picker_command: None,
+        picker_terminal: None,
         idle_inhibit: Some(true),
         hide_waybar: Some(true),
         pick_size: Some(false),
         overlay_enabled: Some(true),
         mangohud_config: Some(DEFAULT_MANGOHUD_CONFIG.to_string()),
         render_scale: Some(auto.render_scale),
+        render_scale_x: None,
+        render_scale_y: None,
+        allow_supersample: Some(false),
+        integer_scale: Some(false),
         virtual_width: None,
         virtual_height: None,
         output_width: None,
         output_height: None,
-        startup_timeout_secs: Some(10),
+        span_target: Some(SpanTarget::All),
+        span_strategy: Some(SpanStrategy::BoundingBox),
+        excluded_monitors: Some(ExcludedMonitors::Keep),
+        companion_command: None,
+        companion_window_class: None,
+        refresh_rate: None,
+        startup_timeout_secs: Some(DEFAULT_STARTUP_TIMEOUT_SECS),
+        startup_poll_ms: Some(DEFAULT_STARTUP_POLL_MS),
+        window_class: Some(DEFAULT_WINDOW_CLASS.to_string()),
+        window_title: None,
+        hyprctl_attempts: Some(DEFAULT_HYPRCTL_ATTEMPTS),
+        hyprctl_backoff_ms: Some(DEFAULT_HYPRCTL_BACKOFF_MS),
+        gamescope_flatpak: None,
+        fit_attempts: Some(DEFAULT_FIT_ATTEMPTS),
+        fit_settle_ms: Some(DEFAULT_FIT_SETTLE_MS),
+        fit_tolerance_px: Some(DEFAULT_FIT_TOLERANCE_PX),
+        exit_with_game: Some(false),
+        restart_on_crash: Some(false),
+        restart_max_retries: Some(DEFAULT_RESTART_MAX_RETRIES),
+        restart_backoff_ms: Some(DEFAULT_RESTART_BACKOFF_MS),
+        gamepad_chord: None,
+        gamepad_hold_ms: Some(DEFAULT_GAMEPAD_HOLD_MS),
+        gamepad_action: Some(GamepadAction::Exit),
+        metrics_listen: None,
+        metrics_textfile: None,
+        color: Some(ColorMode::Auto),
+        exit_hotkey: Some(DEFAULT_EXIT_HOTKEY.to_string()),
+        exit_hotkey_bind: Some(HotkeyBind::Press),
+        exit_hotkey_confirm: Some(false),
+        quick_menu_hotkey: None,
+        osd_hotkey: None,
+        compat_tool: None,
+        proton_version: None,
+        wine_prefix: None,
+        umu_gameid: None,
+        embedded: Some(false),
+        immediate_flips: Some(false),
+        rt_priority: Some(false),
+        force_grab_cursor: Some(false),
+        expose_wayland: Some(false),
+        cursor_confine: Some(false),
+        cursor_warp: Some(false),
+        disable_nightlight: Some(false),
+        borderless: Some(false),
+        resolution_hints: Some(false),
+        engine_hints: Some(false),
+        preset_catalog_url: None,
+        offline: Some(false),
+        recording: Some(RecordingConfig {
+            enabled: Some(false),
+            backend: Some(RecordingBackend::GpuScreenRecorder),
+            replay_secs: Some(DEFAULT_REPLAY_SECS),
+            fps: Some(DEFAULT_RECORDING_FPS),
+            output_dir: None,
+            save_hotkey: Some(DEFAULT_REPLAY_SAVE_HOTKEY.to_string()),
+            obs_region_file: None,
+        }),
+        streaming: Some(StreamingConfig {
+            enabled: Some(false),
+            match_client: Some(true),
+        }),
+        profiles: None,
+        profile_match: None,
+    }
+}
+
+fn default_recording_dir() -> String {
+    std::env::var("HOME")
+        .map(|home| format!("{}/Videos", home))
+        .unwrap_or_else(|_| "/tmp".to_string())
+}
+
+fn resolve_streaming(config: &Config) -> StreamingSettings {
+    let streaming = config.streaming.clone().unwrap_or_default();
+    StreamingSettings {
+        enabled: streaming.enabled.unwrap_or(false),
+        match_client: streaming.match_client.unwrap_or(true),
+    }
+}
+
+fn expand_home(path: String) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+        _ => path,
+    }
+}
+
+fn resolve_recording(config: &Config) -> RecordingSettings {
+    let recording = config.recording.clone().unwrap_or_default();
+    RecordingSettings {
+        enabled: recording.enabled.unwrap_or(false),
+        backend: recording.backend.unwrap_or_default(),
+        replay_secs: recording.replay_secs.unwrap_or(DEFAULT_REPLAY_SECS).max(1),
+        fps: recording.fps.unwrap_or(DEFAULT_RECORDING_FPS).max(1),
+        output_dir: recording
+            .output_dir
+            .map(expand_home)
+            .unwrap_or_else(default_recording_dir),
+        save_hotkey: recording
+            .save_hotkey
+            .unwrap_or_else(|| DEFAULT_REPLAY_SAVE_HOTKEY.to_string()),
+        obs_region_file: recording.obs_region_file.map(expand_home),
+    }
+}
+
+fn resolve_tuning(config: &Config) -> TuningFlags {
+    TuningFlags {
+        immediate_flips: config.immediate_flips.unwrap_or(false),
+        rt_priority: config.rt_priority.unwrap_or(false),
+        force_grab_cursor: config.force_grab_cursor.unwrap_or(false),
+        expose_wayland: config.expose_wayland.unwrap_or(false),
     }
 }
 
@@ -119,18 +560,31 @@ fn format_toml_string_array(values: &[String]) -> String {
         .join(", ")
 }
 
-fn render_config_template(config: &Config, auto_reason: &str) -> String {
-    let gamescope_args = config
-        .gamescope_args
-        .clone()
-        .unwrap_or_else(|| vec!["-r".to_string(), "60".to_string()]);
+pub(crate) fn render_config_template(config: &Config, auto_reason: &str) -> String {
+    let gamescope_args = config.gamescope_args.clone().unwrap_or_default();
     let default_command_line = config
         .default_command
         .clone()
         .map(|cmd| format!("default_command = [{}]", format_toml_string_array(&cmd)))
         .unwrap_or_else(|| "# default_command = [\"steam\", \"-applaunch\", \"620\"]".to_string());
+    let pre_command_line = config
+        .pre_command
+        .clone()
+        .map(|cmd| format!("pre_command = [{}]", format_toml_string_array(&cmd)))
+        .unwrap_or_else(|| "# pre_command = [\"game-daemon\", \"--quiet\"]".to_string());
+    let picker_command_line = config
+        .picker_command
+        .clone()
+        .map(|cmd| format!("picker_command = [{}]", format_toml_string_array(&cmd)))
+        .unwrap_or_else(|| "# picker_command = [\"fuzzel\", \"--dmenu\"]".to_string());
+    let picker_terminal_line = config
+        .picker_terminal
+        .clone()
+        .map(|cmd| format!("picker_terminal = [{}]", format_toml_string_array(&cmd)))
+        .unwrap_or_else(|| "# picker_terminal = [\"foot\", \"-e\"]".to_string());
     let no_pin = config.no_pin.unwrap_or(false);
     let pick = config.pick.unwrap_or(false);
+    let picker_fallback = config.picker_fallback.unwrap_or_default().as_str();
     let idle_inhibit = config.idle_inhibit.unwrap_or(true);
     let hide_waybar = config.hide_waybar.unwrap_or(true);
     let pick_size = config.pick_size.unwrap_or(false);
@@ -140,7 +594,173 @@ fn render_config_template(config: &Config, auto_reason: &str) -> String {
         .clone()
         .unwrap_or_else(|| DEFAULT_MANGOHUD_CONFIG.to_string());
     let render_scale = config.render_scale.unwrap_or(1.0);
-    let startup_timeout_secs = config.startup_timeout_secs.unwrap_or(10);
+    let render_scale_x_line = config
+        .render_scale_x
+        .map(|v| format!("render_scale_x = {}", v))
+        .unwrap_or_else(|| "# render_scale_x = 0.75".to_string());
+    let render_scale_y_line = config
+        .render_scale_y
+        .map(|v| format!("render_scale_y = {}", v))
+        .unwrap_or_else(|| "# render_scale_y = 1.0".to_string());
+    let allow_supersample = config.allow_supersample.unwrap_or(false);
+    let integer_scale = config.integer_scale.unwrap_or(false);
+    let span_target = config.span_target.unwrap_or_default().as_str();
+    let span_strategy = config.span_strategy.unwrap_or_default().as_str();
+    let excluded_monitors = config.excluded_monitors.unwrap_or_default().as_str();
+    let companion_command_line = config
+        .companion_command
+        .clone()
+        .map(|cmd| format!("companion_command = [{}]", format_toml_string_array(&cmd)))
+        .unwrap_or_else(|| "# companion_command = [\"discord\"]".to_string());
+    let companion_window_class_line = config
+        .companion_window_class
+        .clone()
+        .map(|class| {
+            format!(
+                "companion_window_class = {}",
+                format_toml_string_array(&[class])
+            )
+        })
+        .unwrap_or_else(|| "# companion_window_class = \"^discord$\"".to_string());
+    let refresh_rate_line = config
+        .refresh_rate
+        .map(|v| format!("refresh_rate = {}", v))
+        .unwrap_or_else(|| "# refresh_rate = 144".to_string());
+    let startup_timeout_secs = config
+        .startup_timeout_secs
+        .unwrap_or(DEFAULT_STARTUP_TIMEOUT_SECS);
+    let startup_poll_ms = config.startup_poll_ms.unwrap_or(DEFAULT_STARTUP_POLL_MS);
+    let window_class = config
+        .window_class
+        .clone()
+        .unwrap_or_else(|| DEFAULT_WINDOW_CLASS.to_string());
+    let window_title_line = config
+        .window_title
+        .as_ref()
+        .map(|v| {
+            format!(
+                "window_title = {}",
+                format_toml_string_array(std::slice::from_ref(v))
+            )
+        })
+        .unwrap_or_else(|| "# window_title = \"^Steam Big Picture Mode$\"".to_string());
+    let hyprctl_attempts = config.hyprctl_attempts.unwrap_or(DEFAULT_HYPRCTL_ATTEMPTS);
+    let hyprctl_backoff_ms = config
+        .hyprctl_backoff_ms
+        .unwrap_or(DEFAULT_HYPRCTL_BACKOFF_MS);
+    let gamescope_flatpak_line = config
+        .gamescope_flatpak
+        .as_ref()
+        .map(|app| {
+            format!(
+                "gamescope_flatpak = {}",
+                format_toml_string_array(std::slice::from_ref(app))
+            )
+        })
+        .unwrap_or_else(|| "# gamescope_flatpak = \"com.valvesoftware.Steam\"".to_string());
+    let fit_attempts = config.fit_attempts.unwrap_or(DEFAULT_FIT_ATTEMPTS);
+    let fit_settle_ms = config.fit_settle_ms.unwrap_or(DEFAULT_FIT_SETTLE_MS);
+    let fit_tolerance_px = config.fit_tolerance_px.unwrap_or(DEFAULT_FIT_TOLERANCE_PX);
+    let gamepad_chord_line = config
+        .gamepad_chord
+        .clone()
+        .filter(|chord| !chord.is_empty())
+        .map(|chord| format!("gamepad_chord = [{}]", format_toml_string_array(&chord)))
+        .unwrap_or_else(|| "# gamepad_chord = [\"guide\", \"start\"]".to_string());
+    let exit_with_game = config.exit_with_game.unwrap_or(false);
+    let restart_on_crash = config.restart_on_crash.unwrap_or(false);
+    let restart_max_retries = config
+        .restart_max_retries
+        .unwrap_or(DEFAULT_RESTART_MAX_RETRIES);
+    let restart_backoff_ms = config
+        .restart_backoff_ms
+        .unwrap_or(DEFAULT_RESTART_BACKOFF_MS);
+    let gamepad_hold_ms = config.gamepad_hold_ms.unwrap_or(DEFAULT_GAMEPAD_HOLD_MS);
+    let gamepad_action = config.gamepad_action.unwrap_or_default().as_str();
+    let metrics_listen_line = config
+        .metrics_listen
+        .clone()
+        .map(|addr| format!("metrics_listen = {}", format_toml_string_array(&[addr])))
+        .unwrap_or_else(|| "# metrics_listen = \"127.0.0.1:9977\"".to_string());
+    let metrics_textfile_line = config
+        .metrics_textfile
+        .clone()
+        .map(|path| format!("metrics_textfile = {}", format_toml_string_array(&[path])))
+        .unwrap_or_else(|| {
+            "# metrics_textfile = \"/var/lib/node_exporter/textfile_collector/hyprfinity.prom\""
+                .to_string()
+        });
+    let color = config.color.unwrap_or_default().as_str();
+    let exit_hotkey = config
+        .exit_hotkey
+        .clone()
+        .unwrap_or_else(|| DEFAULT_EXIT_HOTKEY.to_string());
+    let exit_hotkey_bind = config.exit_hotkey_bind.unwrap_or_default().as_str();
+    let exit_hotkey_confirm = config.exit_hotkey_confirm.unwrap_or(false);
+    let quick_menu_hotkey_line = config
+        .quick_menu_hotkey
+        .clone()
+        .map(|hotkey| {
+            format!(
+                "quick_menu_hotkey = {}",
+                format_toml_string_array(&[hotkey])
+            )
+        })
+        .unwrap_or_else(|| "# quick_menu_hotkey = \"SUPER SHIFT, F11\"".to_string());
+    let osd_hotkey_line = config
+        .osd_hotkey
+        .clone()
+        .map(|hotkey| format!("osd_hotkey = {}", format_toml_string_array(&[hotkey])))
+        .unwrap_or_else(|| "# osd_hotkey = \"SUPER SHIFT, F10\"".to_string());
+    let compat_tool_line = config
+        .compat_tool
+        .map(|tool| format!("compat_tool = \"{}\"", tool.as_str()))
+        .unwrap_or_else(|| "# compat_tool = \"umu\"".to_string());
+    let proton_version_line = config
+        .proton_version
+        .clone()
+        .map(|v| format!("proton_version = {}", format_toml_string_array(&[v])))
+        .unwrap_or_else(|| "# proton_version = \"GE-Proton9-20\"".to_string());
+    let wine_prefix_line = config
+        .wine_prefix
+        .clone()
+        .map(|p| format!("wine_prefix = {}", format_toml_string_array(&[p])))
+        .unwrap_or_else(|| "# wine_prefix = \"~/Games/prefixes/default\"".to_string());
+    let umu_gameid_line = config
+        .umu_gameid
+        .clone()
+        .map(|id| format!("umu_gameid = {}", format_toml_string_array(&[id])))
+        .unwrap_or_else(|| "# umu_gameid = \"umu-292030\"".to_string());
+    let embedded = config.embedded.unwrap_or(false);
+    let tuning = resolve_tuning(config);
+    let cursor_confine = config.cursor_confine.unwrap_or(false);
+    let cursor_warp = config.cursor_warp.unwrap_or(false);
+    let disable_nightlight = config.disable_nightlight.unwrap_or(false);
+    let borderless = config.borderless.unwrap_or(false);
+    let resolution_hints = config.resolution_hints.unwrap_or(false);
+    let engine_hints = config.engine_hints.unwrap_or(false);
+    let preset_catalog_url_line = config
+        .preset_catalog_url
+        .clone()
+        .map(|url| format!("preset_catalog_url = {}", format_toml_string_array(&[url])))
+        .unwrap_or_else(|| format!("# preset_catalog_url = \"{}\"", DEFAULT_PRESET_CATALOG_URL));
+    let offline = config.offline.unwrap_or(false);
+    let recording = resolve_recording(config);
+    let streaming = resolve_streaming(config);
+    let recording_output_dir_line = config
+        .recording
+        .as_ref()
+        .and_then(|r| r.output_dir.clone())
+        .map(|dir| format!("output_dir = {}", format_toml_string_array(&[dir])))
+        .unwrap_or_else(|| "# output_dir = \"~/Videos\"".to_string());
+    let recording_obs_region_file_line = config
+        .recording
+        .as_ref()
+        .and_then(|r| r.obs_region_file.clone())
+        .map(|file| format!("obs_region_file = {}", format_toml_string_array(&[file])))
+        .unwrap_or_else(|| {
+            "# obs_region_file = \"~/.cache/hyprfinity/obs-region.json\"".to_string()
+        });
 
     let virtual_width_line = config
         .virtual_width
@@ -158,19 +778,44 @@ fn render_config_template(config: &Config, auto_reason: &str) -> String {
         .output_height
         .map(|v| format!("output_height = {}", v))
         .unwrap_or_else(|| "# output_height = 1440".to_string());
+    let include_line = config
+        .include
+        .as_ref()
+        .map(|files| format!("include = [{}]", format_toml_string_array(files)))
+        .unwrap_or_else(|| "# include = [\"profiles/*.toml\"]".to_string());
 
     format!(
         r#"# Hyprfinity config
 
+# Schema version; older files are upgraded automatically (with a backup) when keys change.
+config_version = {config_version}
+
+# Merge other config files first (paths relative to this file; `*` works in file names).
+# Keys set here win over included ones.
+{include_line}
+
 # Default gamescope args (used when no args are provided on the CLI)
 gamescope_args = [{gamescope_args}]
 
 # Optional default game/app command (appended if no `--` command is provided)
+# Placeholders like {{internal_width}}, {{internal_height}}, {{output_width}}, {{output_height}},
+# {{span_width}}, {{span_height}}, and {{refresh}} are substituted at launch.
 {default_command_line}
+# Started inside the session just before the game and stopped when the game exits.
+{pre_command_line}
 
 # Defaults for CLI flags
 no_pin = {no_pin}
 pick = {pick}
+# What to do when a picker is needed but stdin/stdout is not a terminal:
+# "error" fails fast, "default_command" launches default_command instead,
+# "recent" relaunches the game from the newest recorded session.
+picker_fallback = "{picker_fallback}"
+# Optional external picker (dmenu-style: choices on stdin, selection on stdout).
+# Used for both the app picker and the size picker instead of the terminal UI.
+{picker_command_line}
+# Terminal that runs Terminal=true apps picked from the app picker (they are hidden without it).
+{picker_terminal_line}
 # Inhibit idle/screen blanking while Gamescope runs (uses systemd-inhibit).
 idle_inhibit = {idle_inhibit}
 hide_waybar = {hide_waybar}
@@ -181,18 +826,176 @@ mangohud_config = "{mangohud_config}"
 # Internal render scale relative to output span; 1.0 = native span.
 # {auto_reason}
 render_scale = {render_scale}
+# Optional per-axis scales that replace render_scale on one axis, e.g. to render a triple-wide
+# span at 75% width and full height.
+{render_scale_x_line}
+{render_scale_y_line}
+# Allow render_scale (and explicit sizes) above the output size, up to 2.0x, to supersample.
+allow_supersample = {allow_supersample}
+# Pixel-perfect scaling for emulators and pixel art: snap the internal size to the output divided
+# by a whole number and use gamescope's integer scaler with nearest-neighbour filtering.
+integer_scale = {integer_scale}
 # Optional explicit internal render size (when set, these take precedence over render_scale).
 {virtual_width_line}
 {virtual_height_line}
 # Optional explicit output size for Gamescope (-W/-H). Default is full monitor span.
 {output_width_line}
 {output_height_line}
+# Which monitors to span: "all" (bounding box of every monitor) or "focused" (focused monitor only).
+span_target = "{span_target}"
+# How the span is cut from those monitors: "bounding-box" (everything, including areas no monitor
+# shows on L-shaped or incomplete grid layouts) or "largest-rect" (the biggest rectangle fully
+# covered by monitors).
+span_strategy = "{span_strategy}"
+# Monitors outside the span when it covers only some of them: "keep", "blank" (DPMS off for the
+# session) or "dim" (Hyprland dim_inactive). They are restored when the session ends.
+excluded_monitors = "{excluded_monitors}"
+# App shown on the excluded monitors (chat, maps, OBS): started once the game window is placed,
+# laid over them (they are then not blanked) and stopped with the session. Set
+# companion_window_class when the app's window belongs to another process than the one started.
+{companion_command_line}
+{companion_window_class_line}
+# Gamescope refresh rate (-r). Defaults to the lowest refresh rate among spanned monitors;
+# an explicit -r in gamescope_args wins over both.
+{refresh_rate_line}
 startup_timeout_secs = {startup_timeout_secs}
+# How often to look for the gamescope window while it starts.
+startup_poll_ms = {startup_poll_ms}
+# Some setups report the window under another PID (gamescope's XWayland child, flatpak).
+# Then a window whose class/title (or initial class/title) matches these regexes counts;
+# an empty window_class turns the class check off.
+window_class = {window_class}
+{window_title_line}
+# Retry failed hyprctl calls (e.g. socket busy during a Hyprland reload).
+# Backoff doubles after each failed attempt.
+hyprctl_attempts = {hyprctl_attempts}
+hyprctl_backoff_ms = {hyprctl_backoff_ms}
+# Run gamescope inside this Flatpak app's sandbox, from the
+# org.freedesktop.Platform.VulkanLayer.gamescope extension. Detected automatically
+# when gamescope is not in PATH; "" turns detection off.
+{gamescope_flatpak_line}
+# Window fitting: move/resize attempts, delay before checking geometry, and
+# how many pixels off the span still counts as a fit. Raise these on slow setups.
+fit_attempts = {fit_attempts}
+fit_settle_ms = {fit_settle_ms}
+fit_tolerance_px = {fit_tolerance_px}
+# End the session when the game quits, even if gamescope itself keeps running (e.g. with --steam).
+exit_with_game = {exit_with_game}
+# Relaunch gamescope with the same args if it crashes mid-session. The delay doubles
+# after each restart; the session ends once restart_max_retries is used up.
+restart_on_crash = {restart_on_crash}
+restart_max_retries = {restart_max_retries}
+restart_backoff_ms = {restart_backoff_ms}
+# Optional controller chord for couch sessions (buttons: a, b, x, y, lb, rb, lt, rt,
+# select, start, guide, l3, r3). Held for gamepad_hold_ms, it runs gamepad_action:
+# "exit" tears the session down, "restart" relaunches gamescope.
+{gamepad_chord_line}
+gamepad_hold_ms = {gamepad_hold_ms}
+gamepad_action = "{gamepad_action}"
+# Session telemetry in Prometheus format while a session runs: an HTTP endpoint and/or a
+# node_exporter textfile collector file (session_up, reflows, fit failures, hyprctl errors,
+# gamescope restarts, uptime).
+{metrics_listen_line}
+{metrics_textfile_line}
+# Colored tables and messages: "auto" colors terminals unless NO_COLOR is set; "always"
+# or "never" force it.
+color = "{color}"
+# Hyprland bind ("MODS, KEY") that runs `hyprfinity gamescope-down` while a session is up.
+exit_hotkey = {exit_hotkey}
+# What fires it: "press", or "hold" so only a long press ends the session (Hyprland bindo; the
+# key must stay down longer than input:repeat_delay).
+exit_hotkey_bind = "{exit_hotkey_bind}"
+# Ask before tearing down: the exit hotkey shows a notification and waits 3 seconds, and
+# pressing it again within them cancels the exit.
+exit_hotkey_confirm = {exit_hotkey_confirm}
+# Bind that opens a menu (wofi, or picker_command) with screenshot, replay save, restart and quit.
+{quick_menu_hotkey_line}
+# Bind that toggles a status line (game, render scale, FPS from MangoHud's log, uptime) in
+# Hyprland's notification overlay.
+{osd_hotkey_line}
+# Run the game command through a compatibility tool for non-Steam Windows games:
+# "proton" (proton run, STEAM_COMPAT_DATA_PATH), "umu" (umu-run) or "wine".
+# proton_version is a directory name in compatibilitytools.d/steamapps/common or a path;
+# wine_prefix defaults to ~/.local/share/hyprfinity/prefixes/default. umu_gameid picks
+# umu's per-game fixes (GAMEID); `--umu GAMEID` sets it for one run.
+{compat_tool_line}
+{proton_version_line}
+{wine_prefix_line}
+{umu_gameid_line}
+# Run gamescope with its DRM backend on a spare VT instead of nesting it under Hyprland
+# (needs openvt/fgconsole/chvt and console access); Hyprland gets its VT back afterwards.
+embedded = {embedded}
+# Latency tuning switches, added to the gamescope args when true: --immediate-flips (tearing,
+# lower latency), --rt (realtime scheduling; gamescope needs CAP_SYS_NICE), --force-grab-cursor
+# and --expose-wayland (let Wayland-native clients connect to gamescope).
+immediate_flips = {immediate_flips}
+rt_priority = {rt_priority}
+force_grab_cursor = {force_grab_cursor}
+expose_wayland = {expose_wayland}
+# Keep the cursor inside the game: cursor_confine adds --force-grab-cursor and turns off
+# Hyprland's input:follow_mouse for the session; cursor_warp moves the cursor to the span center.
+cursor_confine = {cursor_confine}
+cursor_warp = {cursor_warp}
+# Pause hyprsunset/gammastep while the game runs (warm gamma makes games look wrong) and
+# resume it afterwards, including after a crash via gamescope-down.
+disable_nightlight = {disable_nightlight}
+# Drop the window's border, rounding and shadow so the span is edge-to-edge; Hyprland only.
+borderless = {borderless}
+# Export ENABLE_GAMESCOPE_WSI, SDL_VIDEODRIVER and HYPRFINITY_INTERNAL_WIDTH/HEIGHT to the game so
+# it takes the internal size as its desktop instead of opening a 1080p window.
+resolution_hints = {resolution_hints}
+# Append resolution arguments derived from the internal size for engines that take them (Source:
+# -w/-h, Unity: -screen-width/-screen-height). Arguments already in the game command win.
+engine_hints = {engine_hints}
+# Index fetched by `hyprfinity presets browse`; cached for a day and used offline. A local path
+# works too.
+{preset_catalog_url_line}
+# Never use the network: `hyprfinity upgrade-check` and imports from URLs refuse to run, and the
+# preset catalog is only read from its cache.
+offline = {offline}
+
+# Optional replay buffer while the session runs. "gpu_screen_recorder" captures exactly the
+# span; "obs" drives OBS's replay buffer through obs-cli. save_hotkey ("MODS, KEY") writes
+# the last replay_secs seconds to output_dir. obs_region_file is rewritten with the span's
+# per-monitor crop on every session start for packaging/linux/hyprfinity-obs.lua to apply.
+[recording]
+enabled = {recording_enabled}
+backend = "{recording_backend}"
+replay_secs = {recording_replay_secs}
+fps = {recording_fps}
+{recording_output_dir_line}
+save_hotkey = "{recording_save_hotkey}"
+{recording_obs_region_file_line}
+
+# Sunshine/Moonlight game streaming (`gamescope-up --stream`). With match_client, output and
+# internal sizes and the refresh rate follow what the Moonlight client asked for. The window
+# is not pinned or re-fitted, so it does not fight the stream's capture.
+[streaming]
+enabled = {streaming_enabled}
+match_client = {streaming_match_client}
+
+# Named profiles override any of the keys above. Pick one with `--profile NAME`, or manage
+# and launch them from `hyprfinity profiles`.
+# [profiles.couch]
+# render_scale = 0.6
+# span_target = "focused"
+# default_command = ["steam", "-gamepadui"]
+# A profile with [profiles.NAME.match] is also picked automatically when the game command
+# contains one of these executables or launches one of these Steam app ids.
+# [profiles.witcher.match]
+# exe = ["witcher3.exe"]
+# steam_appid = [292030]
 "#,
         gamescope_args = format_toml_string_array(&gamescope_args),
+        config_version = CONFIG_VERSION,
+        include_line = include_line,
         default_command_line = default_command_line,
+        pre_command_line = pre_command_line,
         no_pin = no_pin,
         pick = pick,
+        picker_fallback = picker_fallback,
+        picker_command_line = picker_command_line,
+        picker_terminal_line = picker_terminal_line,
         idle_inhibit = idle_inhibit,
         hide_waybar = hide_waybar,
         pick_size = pick_size,
@@ -200,17 +1003,77 @@ startup_timeout_secs = {startup_timeout_secs}
         mangohud_config = mangohud_config,
         auto_reason = auto_reason,
         render_scale = render_scale,
+        render_scale_x_line = render_scale_x_line,
+        render_scale_y_line = render_scale_y_line,
+        allow_supersample = allow_supersample,
+        integer_scale = integer_scale,
         virtual_width_line = virtual_width_line,
         virtual_height_line = virtual_height_line,
         output_width_line = output_width_line,
         output_height_line = output_height_line,
+        span_target = span_target,
+        span_strategy = span_strategy,
+        excluded_monitors = excluded_monitors,
+        companion_command_line = companion_command_line,
+        companion_window_class_line = companion_window_class_line,
+        refresh_rate_line = refresh_rate_line,
         startup_timeout_secs = startup_timeout_secs,
+        startup_poll_ms = startup_poll_ms,
+        window_class = format_toml_string_array(&[window_class]),
+        window_title_line = window_title_line,
+        hyprctl_attempts = hyprctl_attempts,
+        hyprctl_backoff_ms = hyprctl_backoff_ms,
+        gamescope_flatpak_line = gamescope_flatpak_line,
+        fit_attempts = fit_attempts,
+        fit_settle_ms = fit_settle_ms,
+        fit_tolerance_px = fit_tolerance_px,
+        exit_with_game = exit_with_game,
+        restart_on_crash = restart_on_crash,
+        restart_max_retries = restart_max_retries,
+        restart_backoff_ms = restart_backoff_ms,
+        gamepad_chord_line = gamepad_chord_line,
+        gamepad_hold_ms = gamepad_hold_ms,
+        gamepad_action = gamepad_action,
+        metrics_listen_line = metrics_listen_line,
+        metrics_textfile_line = metrics_textfile_line,
+        color = color,
+        exit_hotkey = format_toml_string_array(&[exit_hotkey]),
+        exit_hotkey_bind = exit_hotkey_bind,
+        exit_hotkey_confirm = exit_hotkey_confirm,
+        quick_menu_hotkey_line = quick_menu_hotkey_line,
+        osd_hotkey_line = osd_hotkey_line,
+        compat_tool_line = compat_tool_line,
+        proton_version_line = proton_version_line,
+        wine_prefix_line = wine_prefix_line,
+        umu_gameid_line = umu_gameid_line,
+        embedded = embedded,
+        immediate_flips = tuning.immediate_flips,
+        rt_priority = tuning.rt_priority,
+        force_grab_cursor = tuning.force_grab_cursor,
+        expose_wayland = tuning.expose_wayland,
+        cursor_confine = cursor_confine,
+        cursor_warp = cursor_warp,
+        disable_nightlight = disable_nightlight,
+        borderless = borderless,
+        resolution_hints = resolution_hints,
+        engine_hints = engine_hints,
+        preset_catalog_url_line = preset_catalog_url_line,
+        offline = offline,
+        recording_enabled = recording.enabled,
+        recording_backend = recording.backend.as_str(),
+        recording_replay_secs = recording.replay_secs,
+        recording_fps = recording.fps,
+        recording_output_dir_line = recording_output_dir_line,
+        recording_save_hotkey = recording.save_hotkey,
+        recording_obs_region_file_line = recording_obs_region_file_line,
+        streaming_enabled = streaming.enabled,
+        streaming_match_client = streaming.match_client,
     )
 }
 
-fn prompt_yes_no(prompt: &str, default: bool) -> Result<bool, Box<dyn Error>> {
+pub(crate) fn prompt_yes_no(prompt: &str, default: bool) -> Result<bool, Box<dyn Error>> {
     loop {
-        let hint = if default { "Y/n" } else { "y/N" };
+        let hint = yes_no_hint(default);
         print!("{} [{}]: ", prompt, hint);
         std::io::stdout().flush()?;
 
@@ -221,14 +1084,14 @@ fn prompt_yes_no(prompt: &str, default: bool) -> Result<bool, Box<dyn Error>> {
         if normalized.is_empty() {
             return Ok(default);
         }
-        if normalized == "y" || normalized == "yes" {
+        if is_yes(&normalized) {
             return Ok(true);
         }
         if normalized == "n" || normalized == "no" {
             return Ok(false);
         }
 
-        println!("Please answer y/yes or n/no.");
+        println!("{}", tr(Msg::YesNoRetry));
     }
 }
 
@@ -241,44 +1104,86 @@ fn format_optional_size(width: Option<i32>, height: Option<i32>) -> String {
     }
 }
 
-fn print_kv_table(title: &str, rows: Vec<(&str, String)>) {
-    println!("Hyprfinity: {}", title);
-    let key_width = rows
-        .iter()
-        .map(|(k, _)| k.len())
-        .max()
-        .unwrap_or(3)
-        .max("Key".len());
-    let val_width = rows
+pub(crate) fn print_kv_table(title: &str, rows: Vec<(&str, String)>) {
+    print_table(
+        title,
+        &[tr(Msg::TableKey), tr(Msg::TableValue)],
+        rows.into_iter()
+            .map(|(k, v)| (vec![k.to_string(), v], None))
+            .collect(),
+    );
+}
+
+/// Prints a boxed table; the first column is the key, `tone` paints the rest of a row.
+fn print_table(title: &str, headers: &[&str], rows: Vec<(Vec<String>, Option<Tone>)>) {
+    let color = stdout_color();
+    println!(
+        "{} {}",
+        paint("Hyprfinity:", Tone::Accent, color),
+        paint(title, Tone::Bold, color)
+    );
+    let widths = headers
         .iter()
-        .map(|(_, v)| v.len())
-        .max()
-        .unwrap_or(5)
-        .max("Value".len());
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .filter_map(|(cells, _)| cells.get(i))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(header.chars().count())
+        })
+        .collect::<Vec<_>>();
 
-    let sep = format!("+-{}-+-{}-+", "-".repeat(key_width), "-".repeat(val_width));
+    // Pad before painting so escape codes do not count towards column widths.
+    let sep = paint(
+        &format!(
+            "+-{}-+",
+            widths
+                .iter()
+                .map(|w| "-".repeat(*w))
+                .collect::<Vec<_>>()
+                .join("-+-")
+        ),
+        Tone::Dim,
+        color,
+    );
+    let bar = paint("|", Tone::Dim, color);
+    let line = |cells: Vec<String>| format!("{bar} {} {bar}", cells.join(&format!(" {bar} ")));
     println!("{}", sep);
     println!(
-        "| {:<key_width$} | {:<val_width$} |",
-        "Key",
-        "Value",
-        key_width = key_width,
-        val_width = val_width
+        "{}",
+        line(
+            headers
+                .iter()
+                .zip(&widths)
+                .map(|(header, width)| paint(&format!("{:<width$}", header), Tone::Bold, color))
+                .collect()
+        )
     );
     println!("{}", sep);
-    for (k, v) in rows {
-        println!(
-            "| {:<key_width$} | {:<val_width$} |",
-            k,
-            v,
-            key_width = key_width,
-            val_width = val_width
-        );
+    for (cells, tone) in rows {
+        let painted = cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, width))| {
+                let padded = format!("{:<width$}", cell);
+                match (i, tone) {
+                    (0, _) => paint(&padded, Tone::Accent, color),
+                    (_, Some(tone)) => paint(&padded, tone, color),
+                    (_, None) => padded,
+                }
+            })
+            .collect();
+        println!("{}", line(painted));
     }
     println!("{}", sep);
 }
 
-fn print_config_table(title: &str, config: &Config) {
+pub(crate) fn print_config_table(title: &str, config: &Config) {
+    let recording = config.recording.clone().unwrap_or_default();
+    let streaming = config.streaming.clone().unwrap_or_default();
     print_kv_table(
         title,
         vec![
@@ -292,6 +1197,26 @@ fn print_config_table(title: &str, config: &Config) {
             ),
             ("no_pin", config.no_pin.unwrap_or(false).to_string()),
             ("pick", config.pick.unwrap_or(false).to_string()),
+            (
+                "picker_fallback",
+                config
+                    .picker_fallback
+                    .unwrap_or_default()
+                    .as_str()
+                    .to_string(),
+            ),
+            (
+                "pre_command",
+                format!("{:?}", config.pre_command.clone().unwrap_or_default()),
+            ),
+            (
+                "picker_command",
+                format!("{:?}", config.picker_command.clone().unwrap_or_default()),
+            ),
+            (
+                "picker_terminal",
+                format!("{:?}", config.picker_terminal.clone().unwrap_or_default()),
+            ),
             (
                 "idle_inhibit",
                 config.idle_inhibit.unwrap_or(true).to_string(),
@@ -316,6 +1241,26 @@ fn print_config_table(title: &str, config: &Config) {
                 "render_scale",
                 config.render_scale.unwrap_or(1.0).to_string(),
             ),
+            (
+                "render_scale_x",
+                config
+                    .render_scale_x
+                    .map_or_else(|| "-".to_string(), |v| v.to_string()),
+            ),
+            (
+                "render_scale_y",
+                config
+                    .render_scale_y
+                    .map_or_else(|| "-".to_string(), |v| v.to_string()),
+            ),
+            (
+                "allow_supersample",
+                config.allow_supersample.unwrap_or(false).to_string(),
+            ),
+            (
+                "integer_scale",
+                config.integer_scale.unwrap_or(false).to_string(),
+            ),
             (
                 "virtual_size",
                 format_optional_size(config.virtual_width, config.virtual_height),
@@ -324,233 +1269,1081 @@ fn print_config_table(title: &str, config: &Config) {
                 "output_size",
                 format_optional_size(config.output_width, config.output_height),
             ),
+            (
+                "span_target",
+                config.span_target.unwrap_or_default().as_str().to_string(),
+            ),
+            (
+                "span_strategy",
+                config
+                    .span_strategy
+                    .unwrap_or_default()
+                    .as_str()
+                    .to_string(),
+            ),
+            (
+                "excluded_monitors",
+                config
+                    .excluded_monitors
+                    .unwrap_or_default()
+                    .as_str()
+                    .to_string(),
+            ),
+            (
+                "companion_command",
+                format!("{:?}", config.companion_command.clone().unwrap_or_default()),
+            ),
+            (
+                "companion_window_class",
+                config.companion_window_class.clone().unwrap_or_default(),
+            ),
+            (
+                "refresh_rate",
+                config
+                    .refresh_rate
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "auto".to_string()),
+            ),
             (
                 "startup_timeout_secs",
-                config.startup_timeout_secs.unwrap_or(10).to_string(),
+                config
+                    .startup_timeout_secs
+                    .unwrap_or(DEFAULT_STARTUP_TIMEOUT_SECS)
+                    .to_string(),
             ),
-        ],
-    );
-}
-
-fn print_effective_launch_table(title: &str, launch: &LaunchSettings) {
-    print_kv_table(
-        title,
-        vec![
-            ("gamescope_args", format!("{:?}", launch.args)),
-            ("no_pin", launch.no_pin.to_string()),
-            ("pick", launch.pick.to_string()),
-            ("idle_inhibit", launch.idle_inhibit.to_string()),
-            ("hide_waybar", launch.hide_waybar.to_string()),
-            ("pick_size", launch.pick_size.to_string()),
-            ("overlay_enabled", launch.overlay_enabled.to_string()),
             (
-                "mangohud_config",
-                launch.mangohud_config.clone().unwrap_or_default(),
+                "startup_poll_ms",
+                config
+                    .startup_poll_ms
+                    .unwrap_or(DEFAULT_STARTUP_POLL_MS)
+                    .to_string(),
             ),
-            ("render_scale", launch.render_scale.to_string()),
             (
-                "virtual_size",
-                format_optional_size(launch.virtual_width, launch.virtual_height),
+                "window_class",
+                config
+                    .window_class
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_WINDOW_CLASS.to_string()),
             ),
             (
-                "output_size",
-                format_optional_size(launch.output_width, launch.output_height),
+                "window_title",
+                config
+                    .window_title
+                    .clone()
+                    .unwrap_or_else(|| "-".to_string()),
             ),
-            ("startup_timeout_secs", launch.timeout.to_string()),
-        ],
-    );
-}
-
-fn write_config(path_override: &Option<String>, config: &Config) -> Result<(), Box<dyn Error>> {
-    let path = resolve_config_path(path_override)?;
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-    let toml_str = toml::to_string_pretty(config)
-        .map_err(|e| MyError(format!("Failed to serialize config: {}", e)))?;
-    std::fs::write(&path, toml_str)?;
-    println!("Hyprfinity: Wrote config to {}", path.display());
-    Ok(())
-}
-
-pub(crate) fn write_default_config(
-    path_override: &Option<String>,
-    force: bool,
-) -> Result<(), Box<dyn Error>> {
-    let path = resolve_config_path(path_override)?;
-
-    if path.exists() && !force {
-        let should_overwrite = prompt_yes_no(
-            &format!("Config already exists at {}. Overwrite it?", path.display()),
-            false,
-        )?;
-        if !should_overwrite {
-            println!("Hyprfinity: Keeping existing config unchanged.");
-            return Ok(());
-        }
-    }
-
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-
-    let auto = detect_auto_tune_profile();
-    let mut config = apply_editor_defaults(default_config_values(&auto), auto.render_scale);
-    let span = detect_span_size();
-
-    if !force {
-        match edit_config_tui("Config Init", config.clone(), &auto.reason, span)? {
-            Some(edited) => config = apply_editor_defaults(edited, auto.render_scale),
-            None => {
-                println!("Hyprfinity: Config init cancelled.");
-                return Ok(());
-            }
-        }
-    }
-
-    let contents = render_config_template(&config, &auto.reason);
-
-    std::fs::write(&path, contents)?;
-    println!("Hyprfinity: Wrote config to {}", path.display());
-    print_config_table("Final Config Defaults", &config);
-    Ok(())
-}
-
-#[allow(clippy::too_many_arguments)]
-pub(crate) fn show_config(
-    path_override: &Option<String>,
-    cli_args: &[String],
-    cli_no_pin: bool,
-    cli_pick: bool,
-    cli_idle_inhibit: bool,
-    cli_hide_waybar: bool,
-    cli_pick_size: bool,
-    cli_overlay: bool,
-    cli_no_overlay: bool,
-    cli_mangohud_config: Option<String>,
-    cli_render_scale: Option<f32>,
-    cli_virtual_width: Option<i32>,
-    cli_virtual_height: Option<i32>,
-    cli_timeout: u64,
-) -> Result<(), Box<dyn Error>> {
-    let path = resolve_config_path(path_override)?;
-    let config = load_config(path_override)?;
-
-    let launch = apply_config(
-        cli_args,
-        cli_no_pin,
-        cli_pick,
-        cli_idle_inhibit,
-        cli_hide_waybar,
-        cli_pick_size,
-        cli_overlay,
-        cli_no_overlay,
-        cli_mangohud_config,
-        cli_render_scale,
-        cli_virtual_width,
-        cli_virtual_height,
-        cli_timeout,
-        &config,
-    );
-
-    println!("Hyprfinity: Config path: {}", path.display());
-    print_effective_launch_table("Effective Values (after CLI overrides)", &launch);
-    print_config_table("Raw Config Values", &config);
-    Ok(())
-}
-
-pub(crate) fn interactive_config(
-    path_override: &Option<String>,
-    verbose: bool,
-) -> Result<(), Box<dyn Error>> {
-    let path = resolve_config_path(path_override)?;
-    println!("Hyprfinity: Interactive config at {}", path.display());
-    let auto = detect_auto_tune_profile();
-    let config = apply_editor_defaults(load_config(path_override)?, auto.render_scale);
-
-    let span = match get_monitors(verbose) {
-        Ok(monitors) => compute_monitor_span(&monitors)
-            .ok()
-            .map(|(_, _, w, h)| (w, h)),
-        Err(_) => None,
-    };
-
-    match edit_config_tui("Config Editor", config, &auto.reason, span)? {
-        Some(edited) => {
-            write_config(path_override, &edited)?;
-            println!("Hyprfinity: Done. Use `hyprfinity config-show` to inspect effective values.");
-        }
-        None => println!("Hyprfinity: Config update cancelled."),
-    }
-    Ok(())
-}
-
-#[allow(clippy::too_many_arguments)]
-pub(crate) fn apply_config(
-    cli_args: &[String],
-    cli_no_pin: bool,
-    cli_pick: bool,
-    cli_idle_inhibit: bool,
-    cli_hide_waybar: bool,
-    cli_pick_size: bool,
-    cli_overlay: bool,
-    cli_no_overlay: bool,
-    cli_mangohud_config: Option<String>,
-    cli_render_scale: Option<f32>,
-    cli_virtual_width: Option<i32>,
-    cli_virtual_height: Option<i32>,
-    cli_timeout: u64,
-    config: &Config,
-) -> LaunchSettings {
-    let mut args = if cli_args.is_empty() {
-        config.gamescope_args.clone().unwrap_or_default()
-    } else {
-        cli_args.to_vec()
-    };
-
-    if args.is_empty() {
-        args = Vec::new();
-    }
-
-    let no_pin = if cli_no_pin {
-        true
-    } else {
-        config.no_pin.unwrap_or(false)
-    };
+            (
+                "hyprctl_attempts",
+                config
+                    .hyprctl_attempts
+                    .unwrap_or(DEFAULT_HYPRCTL_ATTEMPTS)
+                    .to_string(),
+            ),
+            (
+                "hyprctl_backoff_ms",
+                config
+                    .hyprctl_backoff_ms
+                    .unwrap_or(DEFAULT_HYPRCTL_BACKOFF_MS)
+                    .to_string(),
+            ),
+            (
+                "gamescope_flatpak",
+                config
+                    .gamescope_flatpak
+                    .clone()
+                    .unwrap_or_else(|| "auto".to_string()),
+            ),
+            (
+                "fit_attempts",
+                config
+                    .fit_attempts
+                    .unwrap_or(DEFAULT_FIT_ATTEMPTS)
+                    .to_string(),
+            ),
+            (
+                "fit_settle_ms",
+                config
+                    .fit_settle_ms
+                    .unwrap_or(DEFAULT_FIT_SETTLE_MS)
+                    .to_string(),
+            ),
+            (
+                "fit_tolerance_px",
+                config
+                    .fit_tolerance_px
+                    .unwrap_or(DEFAULT_FIT_TOLERANCE_PX)
+                    .to_string(),
+            ),
+            (
+                "exit_with_game",
+                config.exit_with_game.unwrap_or(false).to_string(),
+            ),
+            (
+                "restart_on_crash",
+                config.restart_on_crash.unwrap_or(false).to_string(),
+            ),
+            (
+                "restart_max_retries",
+                config
+                    .restart_max_retries
+                    .unwrap_or(DEFAULT_RESTART_MAX_RETRIES)
+                    .to_string(),
+            ),
+            (
+                "restart_backoff_ms",
+                config
+                    .restart_backoff_ms
+                    .unwrap_or(DEFAULT_RESTART_BACKOFF_MS)
+                    .to_string(),
+            ),
+            (
+                "gamepad_chord",
+                format!("{:?}", config.gamepad_chord.clone().unwrap_or_default()),
+            ),
+            (
+                "gamepad_hold_ms",
+                config
+                    .gamepad_hold_ms
+                    .unwrap_or(DEFAULT_GAMEPAD_HOLD_MS)
+                    .to_string(),
+            ),
+            (
+                "gamepad_action",
+                config
+                    .gamepad_action
+                    .unwrap_or_default()
+                    .as_str()
+                    .to_string(),
+            ),
+            (
+                "metrics_listen",
+                config.metrics_listen.clone().unwrap_or_default(),
+            ),
+            (
+                "metrics_textfile",
+                config.metrics_textfile.clone().unwrap_or_default(),
+            ),
+            (
+                "color",
+                config.color.unwrap_or_default().as_str().to_string(),
+            ),
+            (
+                "exit_hotkey",
+                config
+                    .exit_hotkey
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_EXIT_HOTKEY.to_string()),
+            ),
+            (
+                "exit_hotkey_bind",
+                config
+                    .exit_hotkey_bind
+                    .unwrap_or_default()
+                    .as_str()
+                    .to_string(),
+            ),
+            (
+                "exit_hotkey_confirm",
+                config.exit_hotkey_confirm.unwrap_or(false).to_string(),
+            ),
+            (
+                "quick_menu_hotkey",
+                config
+                    .quick_menu_hotkey
+                    .clone()
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            (
+                "osd_hotkey",
+                config.osd_hotkey.clone().unwrap_or_else(|| "-".to_string()),
+            ),
+            (
+                "compat_tool",
+                config
+                    .compat_tool
+                    .map(|tool| tool.as_str().to_string())
+                    .unwrap_or_else(|| "none".to_string()),
+            ),
+            (
+                "proton_version",
+                config.proton_version.clone().unwrap_or_default(),
+            ),
+            (
+                "wine_prefix",
+                config.wine_prefix.clone().unwrap_or_default(),
+            ),
+            ("umu_gameid", config.umu_gameid.clone().unwrap_or_default()),
+            ("embedded", config.embedded.unwrap_or(false).to_string()),
+            (
+                "immediate_flips",
+                config.immediate_flips.unwrap_or(false).to_string(),
+            ),
+            (
+                "rt_priority",
+                config.rt_priority.unwrap_or(false).to_string(),
+            ),
+            (
+                "force_grab_cursor",
+                config.force_grab_cursor.unwrap_or(false).to_string(),
+            ),
+            (
+                "expose_wayland",
+                config.expose_wayland.unwrap_or(false).to_string(),
+            ),
+            (
+                "cursor_confine",
+                config.cursor_confine.unwrap_or(false).to_string(),
+            ),
+            (
+                "cursor_warp",
+                config.cursor_warp.unwrap_or(false).to_string(),
+            ),
+            (
+                "disable_nightlight",
+                config.disable_nightlight.unwrap_or(false).to_string(),
+            ),
+            ("borderless", config.borderless.unwrap_or(false).to_string()),
+            (
+                "resolution_hints",
+                config.resolution_hints.unwrap_or(false).to_string(),
+            ),
+            (
+                "engine_hints",
+                config.engine_hints.unwrap_or(false).to_string(),
+            ),
+            (
+                "preset_catalog_url",
+                config
+                    .preset_catalog_url
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_PRESET_CATALOG_URL.to_string()),
+            ),
+            ("offline", config.offline.unwrap_or(false).to_string()),
+            (
+                "recording.enabled",
+                recording.enabled.unwrap_or(false).to_string(),
+            ),
+            (
+                "recording.backend",
+                recording.backend.unwrap_or_default().as_str().to_string(),
+            ),
+            (
+                "recording.replay_secs",
+                recording
+                    .replay_secs
+                    .unwrap_or(DEFAULT_REPLAY_SECS)
+                    .to_string(),
+            ),
+            (
+                "recording.output_dir",
+                recording
+                    .output_dir
+                    .clone()
+                    .unwrap_or_else(default_recording_dir),
+            ),
+            (
+                "recording.save_hotkey",
+                recording
+                    .save_hotkey
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_REPLAY_SAVE_HOTKEY.to_string()),
+            ),
+            (
+                "recording.obs_region_file",
+                recording.obs_region_file.clone().unwrap_or_default(),
+            ),
+            (
+                "streaming.enabled",
+                streaming.enabled.unwrap_or(false).to_string(),
+            ),
+            (
+                "streaming.match_client",
+                streaming.match_client.unwrap_or(true).to_string(),
+            ),
+        ],
+    );
+}
 
-    let pick = if cli_pick {
-        true
-    } else {
-        config.pick.unwrap_or(false)
+fn print_effective_launch_table(title: &str, launch: &LaunchSettings) {
+    print_kv_table(title, launch_rows(launch));
+}
+
+fn launch_rows(launch: &LaunchSettings) -> Vec<(&'static str, String)> {
+    vec![
+        ("gamescope_args", format!("{:?}", launch.args)),
+        ("no_pin", launch.no_pin.to_string()),
+        ("pick", launch.pick.to_string()),
+        (
+            "picker_fallback",
+            launch.picker_fallback.as_str().to_string(),
+        ),
+        (
+            "pre_command",
+            format!("{:?}", launch.pre_command.clone().unwrap_or_default()),
+        ),
+        (
+            "picker_command",
+            format!("{:?}", launch.picker_command.clone().unwrap_or_default()),
+        ),
+        ("idle_inhibit", launch.idle_inhibit.to_string()),
+        ("hide_waybar", launch.hide_waybar.to_string()),
+        ("pick_size", launch.pick_size.to_string()),
+        ("overlay_enabled", launch.overlay_enabled.to_string()),
+        (
+            "mangohud_config",
+            launch.mangohud_config.clone().unwrap_or_default(),
+        ),
+        ("render_scale", launch.render_scale.to_string()),
+        ("allow_supersample", launch.allow_supersample.to_string()),
+        ("integer_scale", launch.integer_scale.to_string()),
+        (
+            "virtual_size",
+            format_optional_size(launch.virtual_width, launch.virtual_height),
+        ),
+        (
+            "output_size",
+            format_optional_size(launch.output_width, launch.output_height),
+        ),
+        ("span_target", launch.span_target.as_str().to_string()),
+        ("span_strategy", launch.span_strategy.as_str().to_string()),
+        (
+            "excluded_monitors",
+            launch.excluded_monitors.as_str().to_string(),
+        ),
+        (
+            "companion_command",
+            format!("{:?}", launch.companion_command.clone().unwrap_or_default()),
+        ),
+        (
+            "companion_window_class",
+            launch.companion_window_class.clone().unwrap_or_default(),
+        ),
+        (
+            "refresh_rate",
+            launch
+                .refresh_rate
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "auto".to_string()),
+        ),
+        ("startup_timeout_secs", launch.timeout.to_string()),
+        ("startup_poll_ms", launch.probe.poll_ms.to_string()),
+        (
+            "window_class",
+            launch
+                .probe
+                .class
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        (
+            "window_title",
+            launch
+                .probe
+                .title
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        ("fit_attempts", launch.fit.attempts.to_string()),
+        ("fit_settle_ms", launch.fit.settle_ms.to_string()),
+        ("fit_tolerance_px", launch.fit.tolerance_px.to_string()),
+        ("exit_with_game", launch.exit_with_game.to_string()),
+        ("restart_on_crash", launch.restart.enabled.to_string()),
+        (
+            "restart_max_retries",
+            launch.restart.max_retries.to_string(),
+        ),
+        ("restart_backoff_ms", launch.restart.backoff_ms.to_string()),
+        ("gamepad_chord", format!("{:?}", launch.gamepad.chord)),
+        ("gamepad_hold_ms", launch.gamepad.hold_ms.to_string()),
+        ("gamepad_action", launch.gamepad.action.as_str().to_string()),
+        (
+            "metrics_listen",
+            launch.metrics.listen.clone().unwrap_or_default(),
+        ),
+        (
+            "metrics_textfile",
+            launch.metrics.textfile.clone().unwrap_or_default(),
+        ),
+        ("exit_hotkey", launch.exit_hotkey.clone()),
+        (
+            "exit_hotkey_bind",
+            launch.exit_hotkey_bind.as_str().to_string(),
+        ),
+        (
+            "exit_hotkey_confirm",
+            launch.exit_hotkey_confirm.to_string(),
+        ),
+        (
+            "quick_menu_hotkey",
+            launch
+                .quick_menu_hotkey
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        (
+            "osd_hotkey",
+            launch.osd_hotkey.clone().unwrap_or_else(|| "-".to_string()),
+        ),
+        (
+            "compat_tool",
+            launch
+                .compat
+                .tool
+                .map(|tool| tool.as_str().to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+        (
+            "proton_version",
+            launch.compat.proton_version.clone().unwrap_or_default(),
+        ),
+        (
+            "wine_prefix",
+            launch.compat.wine_prefix.clone().unwrap_or_default(),
+        ),
+        (
+            "umu_gameid",
+            launch.compat.umu_gameid.clone().unwrap_or_default(),
+        ),
+        ("embedded", launch.embedded.to_string()),
+        ("immediate_flips", launch.tuning.immediate_flips.to_string()),
+        ("rt_priority", launch.tuning.rt_priority.to_string()),
+        (
+            "force_grab_cursor",
+            launch.tuning.force_grab_cursor.to_string(),
+        ),
+        ("expose_wayland", launch.tuning.expose_wayland.to_string()),
+        ("cursor_confine", launch.cursor.confine.to_string()),
+        ("cursor_warp", launch.cursor.warp.to_string()),
+        ("disable_nightlight", launch.disable_nightlight.to_string()),
+        ("borderless", launch.borderless.to_string()),
+        ("resolution_hints", launch.resolution_hints.to_string()),
+        ("engine_hints", launch.engine_hints.to_string()),
+        ("recording.enabled", launch.recording.enabled.to_string()),
+        (
+            "recording.backend",
+            launch.recording.backend.as_str().to_string(),
+        ),
+        (
+            "recording.replay_secs",
+            launch.recording.replay_secs.to_string(),
+        ),
+        ("recording.fps", launch.recording.fps.to_string()),
+        ("recording.output_dir", launch.recording.output_dir.clone()),
+        (
+            "recording.save_hotkey",
+            launch.recording.save_hotkey.clone(),
+        ),
+        (
+            "recording.obs_region_file",
+            launch.recording.obs_region_file.clone().unwrap_or_default(),
+        ),
+        ("streaming.enabled", launch.streaming.enabled.to_string()),
+        (
+            "streaming.match_client",
+            launch.streaming.match_client.to_string(),
+        ),
+    ]
+}
+
+/// Where an effective value came from: the last layer that set it.
+#[derive(Debug, Serialize, PartialEq)]
+struct ValueOrigin<'a> {
+    key: &'static str,
+    value: String,
+    default: String,
+    source: &'a str,
+}
+
+/// Dotted paths of every value `value` sets, e.g. `recording.fps`.
+fn set_keys(prefix: &str, value: &toml::Value, keys: &mut BTreeSet<String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                set_keys(&path, value, keys);
+            }
+        }
+        _ => {
+            keys.insert(prefix.to_string());
+        }
+    }
+}
+
+/// Config keys a file or profile sets itself, leaving out its profiles.
+pub(crate) fn config_set_keys(config: &Config) -> Result<BTreeSet<String>, Box<dyn Error>> {
+    let mut value = toml::Value::try_from(config)?;
+    if let Some(table) = value.as_table_mut() {
+        table.remove("profiles");
+    }
+    let mut keys = BTreeSet::new();
+    set_keys("", &value, &mut keys);
+    Ok(keys)
+}
+
+/// Whether a layer setting `keys` sets the value shown in the `row` of [`launch_rows`].
+fn layer_sets_row(row: &str, keys: &BTreeSet<String>) -> bool {
+    let config_keys: &[&str] = match row {
+        "render_scale" => &["render_scale", "render_scale_x", "render_scale_y"],
+        "virtual_size" => &["virtual_width", "virtual_height"],
+        "output_size" => &["output_width", "output_height"],
+        _ => &[],
     };
+    keys.contains(row) || config_keys.iter().any(|key| keys.contains(*key))
+}
+
+// Layers are ordered from the config file to CLI flags; each lists the keys it sets itself, so a
+// key set to its default value still counts as set.
+fn value_origins<'a>(
+    defaults: &[(&'static str, String)],
+    effective: &[(&'static str, String)],
+    layers: &'a [(&'a str, BTreeSet<String>)],
+    autotuned: &[&str],
+) -> Vec<ValueOrigin<'a>> {
+    defaults
+        .iter()
+        .zip(effective)
+        .map(|((key, default), (_, value))| {
+            let mut source = layers
+                .iter()
+                .rev()
+                .find(|(_, keys)| layer_sets_row(key, keys))
+                .map_or("default", |(name, _)| name);
+            if source == "config" && autotuned.contains(key) {
+                source = "autotune";
+            }
+            ValueOrigin {
+                key,
+                value: value.clone(),
+                default: default.clone(),
+                source,
+            }
+        })
+        .collect()
+}
 
-    let idle_inhibit = if cli_idle_inhibit {
-        true
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) if existing.is_table() && value.is_table() => {
+                        merge_toml(existing, value)
+                    }
+                    _ => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+pub(crate) fn apply_profile(config: &Config, name: &str) -> Result<Config, Box<dyn Error>> {
+    let profiles = config.profiles.as_ref();
+    let profile = profiles.and_then(|p| p.get(name)).ok_or_else(|| {
+        let known = profiles
+            .map(|p| p.keys().cloned().collect::<Vec<_>>().join(", "))
+            .filter(|names| !names.is_empty())
+            .unwrap_or_else(|| "none".to_string());
+        MyError(format!(
+            "Unknown profile `{}` (configured: {}).",
+            name, known
+        ))
+    })?;
+    // Merge through TOML values so every key (including [recording]) can be overridden.
+    let mut merged = toml::Value::try_from(config)?;
+    // A chosen profile ends automatic profile matching for this run.
+    if let Some(table) = merged.as_table_mut() {
+        table.remove("profiles");
+    }
+    let mut overlay = toml::Value::try_from(profile)?;
+    if let Some(table) = overlay.as_table_mut() {
+        table.remove("profiles");
+    }
+    merge_toml(&mut merged, overlay);
+    Ok(merged.try_into()?)
+}
+
+/// Steam app id a command launches: `-applaunch N`, `steam://rungameid/N` or reaper's `AppId=N`.
+pub(crate) fn steam_appid(command: &[String]) -> Option<u32> {
+    command.iter().enumerate().find_map(|(i, word)| {
+        if word == "-applaunch" {
+            return command.get(i + 1)?.parse().ok();
+        }
+        word.strip_prefix("steam://rungameid/")
+            .or_else(|| word.strip_prefix("AppId="))
+            .and_then(|id| id.parse().ok())
+    })
+}
+
+fn match_reason(rule: &ProfileMatch, command: &[String]) -> Option<String> {
+    if let Some(appid) = steam_appid(command)
+        && rule
+            .steam_appid
+            .as_ref()
+            .is_some_and(|ids| ids.contains(&appid))
+    {
+        return Some(trf(Msg::MatchSteamAppId, &[&appid]));
+    }
+    let exes = rule.exe.as_deref().unwrap_or_default();
+    command.iter().find_map(|word| {
+        // Windows paths from Proton or Wine commands use backslashes.
+        let name = word.rsplit(['/', '\\']).next()?;
+        exes.iter()
+            .any(|exe| exe.eq_ignore_ascii_case(name))
+            .then(|| trf(Msg::MatchExecutable, &[&name]))
+    })
+}
+
+/// First profile (by name) whose `match` rules fit the game command, and why it matched.
+pub(crate) fn matching_profile(config: &Config, command: &[String]) -> Option<(String, String)> {
+    config
+        .profiles
+        .as_ref()?
+        .iter()
+        .find_map(|(name, profile)| {
+            let reason = match_reason(profile.profile_match.as_ref()?, command)?;
+            Some((name.clone(), reason))
+        })
+}
+
+/// The game command a launch with these args would run, before the picker is consulted.
+pub(crate) fn configured_game_command(cli_args: &[String], config: &Config) -> Vec<String> {
+    let args = if cli_args.is_empty() {
+        config.gamescope_args.as_deref().unwrap_or_default()
     } else {
-        config.idle_inhibit.unwrap_or(true)
+        cli_args
     };
+    match args.iter().position(|a| a == "--") {
+        Some(idx) => args[idx + 1..].to_vec(),
+        None => config.default_command.clone().unwrap_or_default(),
+    }
+}
 
-    let hide_waybar = if cli_hide_waybar {
-        true
-    } else {
-        config.hide_waybar.unwrap_or(true)
+/// Rebuilds the config for launching `game` with the profile its `match` rules pick. `base` is
+/// the config before `--set`; the profile goes under those overrides and the CLI flags the
+/// caller folds in afterwards, so both still win over it. `None` when no profile matches.
+pub(crate) fn apply_game_profile(
+    base: &Config,
+    overrides: &[String],
+    game: &[String],
+) -> Result<Option<(String, Config)>, Box<dyn Error>> {
+    let Some((name, reason)) = matching_profile(base, game) else {
+        return Ok(None);
     };
+    match apply_profile(base, &name) {
+        Ok(profiled) => {
+            info(&trf(Msg::UsingProfile, &[&name, &reason]));
+            Ok(Some((name, apply_overrides(&profiled, overrides)?)))
+        }
+        Err(e) => {
+            warn(&trf(Msg::MatchedProfileFailed, &[&name, &e]));
+            Ok(None)
+        }
+    }
+}
 
-    let pick_size = if cli_pick_size {
-        true
-    } else {
-        config.pick_size.unwrap_or(false)
+fn parse_override(entry: &str) -> Result<(Vec<String>, toml::Value), Box<dyn Error>> {
+    let (key, raw) = entry
+        .split_once('=')
+        .ok_or_else(|| MyError(format!("Invalid --set `{}` (expected KEY=VALUE).", entry)))?;
+    let path = key
+        .trim()
+        .split('.')
+        .map(|part| part.trim().to_string())
+        .collect::<Vec<_>>();
+    if path.iter().any(|part| part.is_empty()) {
+        return Err(MyError(format!("Invalid --set key `{}`.", key)).into());
+    }
+    // Values are TOML (0.7, false, ["-r", "60"]); anything that does not parse is a string.
+    let value = toml::from_str::<toml::Table>(&format!("v = {}", raw.trim()))
+        .ok()
+        .and_then(|mut table| table.remove("v"))
+        .unwrap_or_else(|| toml::Value::String(raw.trim().to_string()));
+    Ok((path, value))
+}
+
+pub(crate) fn apply_overrides(
+    config: &Config,
+    entries: &[String],
+) -> Result<Config, Box<dyn Error>> {
+    if entries.is_empty() {
+        return Ok(config.clone());
+    }
+    let mut overlay = toml::Table::new();
+    let mut paths = Vec::new();
+    for entry in entries {
+        let (path, value) = parse_override(entry)?;
+        let (last, parents) = path.split_last().expect("non-empty override path");
+        let mut table = &mut overlay;
+        for part in parents {
+            table = table
+                .entry(part.clone())
+                .or_insert(toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .ok_or_else(|| {
+                    MyError(format!("--set `{}` conflicts with another --set.", entry))
+                })?;
+        }
+        table.insert(last.clone(), value);
+        paths.push(path);
+    }
+
+    let mut merged = toml::Value::try_from(config)?;
+    merge_toml(&mut merged, toml::Value::Table(overlay));
+    let updated: Config = merged
+        .try_into()
+        .map_err(|e| MyError(format!("Invalid --set value: {}", e)))?;
+
+    // Unknown keys are dropped by deserialization; catch them by looking for the value again.
+    let check = toml::Value::try_from(&updated)?;
+    for path in paths {
+        let found = path
+            .iter()
+            .try_fold(&check, |value, part| value.get(part.as_str()))
+            .is_some();
+        if !found {
+            return Err(
+                MyError(format!("Unknown config key `{}` in --set.", path.join("."))).into(),
+            );
+        }
+    }
+    Ok(updated)
+}
+
+pub(crate) fn profile_summary(profile: &Config) -> String {
+    let Ok(toml::Value::Table(table)) = toml::Value::try_from(profile) else {
+        return String::new();
     };
+    table
+        .iter()
+        .filter(|(key, _)| key.as_str() != "profiles")
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+pub(crate) fn manage_profiles(
+    path_override: &Option<String>,
+) -> Result<Option<Config>, Box<dyn Error>> {
+    // Edit the main file only, so profiles from included files are not copied into it.
+    let mut config = load_config_file(path_override)?;
+    let outcome = profiles_tui(config.profiles.clone().unwrap_or_default())?;
+    if outcome.changed {
+        config.profiles = (!outcome.profiles.is_empty()).then_some(outcome.profiles);
+        write_config(path_override, &config)?;
+    }
+    outcome
+        .launch
+        .map(|name| apply_profile(&load_config(path_override)?, &name))
+        .transpose()
+}
+
+pub(crate) fn write_config(
+    path_override: &Option<String>,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    let path = resolve_config_path(path_override)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // An existing file is only ever edited in place; rewriting it from scratch would drop its
+    // comments and any keys this version does not know about.
+    let toml_str = match std::fs::read_to_string(&path) {
+        Ok(contents) => update_config_document(&contents, config).map_err(|e| {
+            MyError(format!(
+                "Failed to update {} in place ({}); it was left unchanged.",
+                path.display(),
+                e
+            ))
+        })?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => toml::to_string_pretty(config)
+            .map_err(|e| MyError(format!("Failed to serialize config: {}", e)))?,
+        Err(e) => return Err(e.into()),
+    };
+    std::fs::write(&path, toml_str)?;
+    info(&trf(Msg::WroteConfig, &[&path.display()]));
+    Ok(())
+}
+
+/// Applies `config` to an existing config file in place: only keys whose value changed are
+/// rewritten, so comments, ordering and keys Hyprfinity does not know about survive.
+fn update_config_document(contents: &str, config: &Config) -> Result<String, Box<dyn Error>> {
+    let mut doc = contents.parse::<toml_edit::DocumentMut>()?;
+    let old = toml::Value::try_from(toml::from_str::<Config>(contents)?)?;
+    let new = toml::Value::try_from(config)?;
+    let fresh = toml::to_string(config)?.parse::<toml_edit::DocumentMut>()?;
+    if let (Some(old), Some(new)) = (old.as_table(), new.as_table()) {
+        sync_document_table(doc.as_table_mut(), old, new, fresh.as_table());
+    }
+    Ok(doc.to_string())
+}
+
+// `old` is what the file deserialized to, so keys missing from it are unknown to Config and
+// are left alone; keys present in `old` but not in `new` were cleared and get removed.
+fn sync_document_table(
+    doc: &mut toml_edit::Table,
+    old: &toml::Table,
+    new: &toml::Table,
+    fresh: &toml_edit::Table,
+) {
+    for (key, new_value) in new {
+        if old.get(key) == Some(new_value) {
+            continue;
+        }
+        let Some(fresh_item) = fresh.get(key) else {
+            continue;
+        };
+        if let (Some(toml_edit::Item::Table(doc_table)), Some(toml::Value::Table(old_table))) =
+            (doc.get_mut(key), old.get(key))
+            && let (toml::Value::Table(new_table), Some(fresh_table)) =
+                (new_value, fresh_item.as_table())
+        {
+            sync_document_table(doc_table, old_table, new_table, fresh_table);
+            continue;
+        }
+        if let Some(toml_edit::Item::Value(doc_value)) = doc.get_mut(key)
+            && let Some(fresh_value) = fresh_item.as_value()
+        {
+            // Keep the surrounding whitespace and any trailing comment.
+            let decor = doc_value.decor().clone();
+            *doc_value = fresh_value.clone();
+            *doc_value.decor_mut() = decor;
+            continue;
+        }
+        doc.insert(key, fresh_item.clone());
+    }
+    for key in old.keys() {
+        if !new.contains_key(key) {
+            doc.remove(key);
+        }
+    }
+}
 
-    let overlay_enabled = if cli_no_overlay {
-        false
-    } else if cli_overlay {
-        true
+pub(crate) fn write_default_config(
+    path_override: &Option<String>,
+    force: bool,
+) -> Result<(), Box<dyn Error>> {
+    let path = resolve_config_path(path_override)?;
+
+    if path.exists() && !force {
+        let should_overwrite =
+            prompt_yes_no(&trf(Msg::ConfigExistsOverwrite, &[&path.display()]), false)?;
+        if !should_overwrite {
+            info(tr(Msg::KeepingConfig));
+            return Ok(());
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let auto = detect_auto_tune_profile();
+    let mut config = apply_editor_defaults(default_config_values(&auto), auto.render_scale);
+    let span = detect_span_size();
+
+    if !force {
+        match edit_config_tui(tr(Msg::TuiConfigInit), config.clone(), &auto.reason, span)? {
+            Some(edited) => config = apply_editor_defaults(edited, auto.render_scale),
+            None => {
+                info(tr(Msg::ConfigInitCancelled));
+                return Ok(());
+            }
+        }
+    }
+
+    let contents = render_config_template(&config, &auto.reason);
+
+    std::fs::write(&path, contents)?;
+    info(&trf(Msg::WroteConfig, &[&path.display()]));
+    print_config_table("Final Config Defaults", &config);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn show_config(
+    path_override: &Option<String>,
+    profile: Option<&str>,
+    overrides: &[String],
+    cli: &CliOverrides,
+    diff: bool,
+    json: bool,
+) -> Result<(), Box<dyn Error>> {
+    let path = resolve_config_path(path_override)?;
+    let file_config = load_config(path_override)?;
+    let profile_config = match profile {
+        Some(name) => apply_profile(&file_config, name)?,
+        None => file_config.clone(),
+    };
+    let config = apply_overrides(&profile_config, overrides)?;
+    // Without --profile, the game command's `match` rules may pick one, as a launch would.
+    let matched = match profile {
+        Some(_) => None,
+        None => apply_game_profile(
+            &file_config,
+            overrides,
+            &configured_game_command(&cli.args, &config),
+        )?,
+    };
+    let config = matched
+        .as_ref()
+        .map_or(config, |(_, matched)| matched.clone());
+
+    let cli_keys = [
+        (!cli.args.is_empty(), "gamescope_args"),
+        (cli.no_pin.is_some(), "no_pin"),
+        (cli.pick.is_some(), "pick"),
+        (cli.idle_inhibit.is_some(), "idle_inhibit"),
+        (cli.hide_waybar.is_some(), "hide_waybar"),
+        (cli.pick_size.is_some(), "pick_size"),
+        (cli.overlay.is_some(), "overlay_enabled"),
+        (cli.mangohud_config.is_some(), "mangohud_config"),
+        (cli.render_scale.is_some(), "render_scale"),
+        (cli.virtual_width.is_some(), "virtual_width"),
+        (cli.virtual_height.is_some(), "virtual_height"),
+        (cli.output_width.is_some(), "output_width"),
+        (cli.output_height.is_some(), "output_height"),
+        (cli.exit_with_game.is_some(), "exit_with_game"),
+        (cli.startup_timeout_secs.is_some(), "startup_timeout_secs"),
+    ]
+    .into_iter()
+    .filter(|(set, _)| *set)
+    .map(|(_, key)| key.to_string())
+    .collect::<BTreeSet<_>>();
+
+    let launch = apply_config(cli, &config);
+
+    if diff {
+        let auto = detect_auto_tune_profile();
+        let autotuned: &[&str] = if file_config.render_scale == Some(auto.render_scale) {
+            &["render_scale"]
+        } else {
+            &[]
+        };
+        let profile_name = profile.or(matched.as_ref().map(|(name, _)| name.as_str()));
+        let profile_keys =
+            match profile_name.and_then(|name| file_config.profiles.as_ref()?.get(name)) {
+                Some(profile) => config_set_keys(profile)?,
+                None => BTreeSet::new(),
+            };
+        let profile_source = match &matched {
+            Some((name, _)) => format!("profile {} (matched)", name),
+            None => "profile".to_string(),
+        };
+        let mut set_override_keys = BTreeSet::new();
+        for entry in overrides {
+            let (path, value) = parse_override(entry)?;
+            set_keys(&path.join("."), &value, &mut set_override_keys);
+        }
+        let layers = [
+            ("config", config_set_keys(&file_config)?),
+            (profile_source.as_str(), profile_keys),
+            ("--set", set_override_keys),
+            ("cli", cli_keys),
+        ];
+        let origins = value_origins(
+            &launch_rows(&LaunchSettings::from_config(&Config::default())),
+            &launch_rows(&launch),
+            &layers,
+            autotuned,
+        );
+        if json {
+            println!("{}", serde_json::to_string_pretty(&origins)?);
+            return Ok(());
+        }
+        info(&trf(Msg::ConfigPath, &[&path.display()]));
+        let changed = origins.iter().filter(|o| o.value != o.default).count();
+        print_table(
+            "Effective Values vs Defaults",
+            &[tr(Msg::TableKey), tr(Msg::TableValue), tr(Msg::TableSource)],
+            origins
+                .into_iter()
+                .map(|o| {
+                    let tone = (o.value != o.default).then_some(Tone::Warn);
+                    let value = if tone.is_some() {
+                        format!("{} (default {})", o.value, o.default)
+                    } else {
+                        o.value
+                    };
+                    (vec![o.key.to_string(), value, o.source.to_string()], tone)
+                })
+                .collect(),
+        );
+        info(&trf(Msg::ConfigDiffSummary, &[&changed]));
+        return Ok(());
+    }
+
+    info(&trf(Msg::ConfigPath, &[&path.display()]));
+    print_effective_launch_table("Effective Values (after CLI overrides)", &launch);
+    print_config_table("Raw Config Values", &config);
+    Ok(())
+}
+
+pub(crate) fn interactive_config(
+    path_override: &Option<String>,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    let path = resolve_config_path(path_override)?;
+    info(&trf(Msg::InteractiveConfigAt, &[&path.display()]));
+    let auto = detect_auto_tune_profile();
+    let config = apply_editor_defaults(load_config_file(path_override)?, auto.render_scale);
+
+    let span = match get_monitors(verbose) {
+        Ok(monitors) => compute_monitor_span(&monitors)
+            .ok()
+            .map(|(_, _, w, h)| (w, h)),
+        Err(_) => None,
+    };
+
+    match edit_config_tui(tr(Msg::TuiConfigEditor), config, &auto.reason, span)? {
+        Some(edited) => {
+            write_config(path_override, &edited)?;
+            info(tr(Msg::ConfigDone));
+        }
+        None => info(tr(Msg::ConfigUpdateCancelled)),
+    }
+    Ok(())
+}
+
+pub(crate) fn apply_config(cli: &CliOverrides, config: &Config) -> LaunchSettings {
+    let mut args = if cli.args.is_empty() {
+        config.gamescope_args.clone().unwrap_or_default()
     } else {
-        config.overlay_enabled.unwrap_or(true)
+        cli.args.clone()
     };
 
-    let mangohud_config = if let Some(value) = cli_mangohud_config {
+    if args.is_empty() {
+        args = Vec::new();
+    }
+
+    let streaming = resolve_streaming(config);
+    let client = (streaming.enabled && streaming.match_client)
+        .then(sunshine_client_mode)
+        .flatten();
+
+    // Explicit CLI values (including `--flag=false`) win over the config, which wins over defaults.
+    // A pinned, re-fitted window fights the stream's capture, so streaming defaults to no pin.
+    let no_pin = cli
+        .no_pin
+        .or(streaming.enabled.then_some(true))
+        .or(config.no_pin)
+        .unwrap_or(false);
+    let pick = cli.pick.or(config.pick).unwrap_or(false);
+
+    let picker_fallback = config.picker_fallback.unwrap_or_default();
+
+    let idle_inhibit = cli.idle_inhibit.or(config.idle_inhibit).unwrap_or(true);
+    let hide_waybar = cli.hide_waybar.or(config.hide_waybar).unwrap_or(true);
+    let pick_size = cli.pick_size.or(config.pick_size).unwrap_or(false);
+    let overlay_enabled = cli.overlay.or(config.overlay_enabled).unwrap_or(true);
+
+    let mangohud_config = if let Some(value) = cli.mangohud_config.clone() {
         Some(value)
     } else if overlay_enabled {
         Some(
@@ -563,46 +2356,182 @@ pub(crate) fn apply_config(
         None
     };
 
-    let mut render_scale = cli_render_scale.or(config.render_scale).unwrap_or(1.0);
-    if !(0.1..=1.0).contains(&render_scale) {
-        eprintln!(
-            "Hyprfinity: render_scale {} is out of range; clamping to [0.1, 1.0].",
-            render_scale
-        );
-        render_scale = render_scale.clamp(0.1, 1.0);
+    let allow_supersample = config.allow_supersample.unwrap_or(false);
+    let max_render_scale = if allow_supersample {
+        MAX_SUPERSAMPLE_SCALE
+    } else {
+        1.0
+    };
+    let mut render_scale = cli.render_scale.unwrap_or_else(|| {
+        let scale = config.render_scale.unwrap_or(1.0);
+        RenderScale {
+            x: config.render_scale_x.unwrap_or(scale),
+            y: config.render_scale_y.unwrap_or(scale),
+        }
+    });
+    if !render_scale.is_within(max_render_scale) {
+        let msg = if allow_supersample || render_scale.x.min(render_scale.y) < 0.1 {
+            Msg::RenderScaleClamped
+        } else {
+            Msg::RenderScaleClampedSupersample
+        };
+        let max = format!("{:.1}", max_render_scale);
+        warn(&trf(msg, &[&render_scale, &max]));
+        render_scale = render_scale.clamp(max_render_scale);
     }
 
-    let virtual_width = cli_virtual_width.or(config.virtual_width);
-    let virtual_height = cli_virtual_height.or(config.virtual_height);
-    let output_width = config.output_width;
-    let output_height = config.output_height;
+    // Moonlight's requested mode replaces the configured sizes; explicit CLI sizes still win.
+    let client_internal = client.map(|c| {
+        scaled_dimensions_within(
+            c.width,
+            c.height,
+            (render_scale.x, render_scale.y),
+            (c.width, c.height),
+        )
+    });
+    let virtual_width = cli
+        .virtual_width
+        .or(client_internal.map(|(w, _)| w))
+        .or(config.virtual_width);
+    let virtual_height = cli
+        .virtual_height
+        .or(client_internal.map(|(_, h)| h))
+        .or(config.virtual_height);
+    let output_width = cli
+        .output_width
+        .or(client.map(|c| c.width))
+        .or(config.output_width);
+    let output_height = cli
+        .output_height
+        .or(client.map(|c| c.height))
+        .or(config.output_height);
 
-    let timeout = if cli_timeout != 10 {
-        cli_timeout
-    } else {
-        config.startup_timeout_secs.unwrap_or(10)
-    };
+    let exit_with_game = cli
+        .exit_with_game
+        .or(config.exit_with_game)
+        .unwrap_or(false);
+
+    let timeout = cli
+        .startup_timeout_secs
+        .or(config.startup_timeout_secs)
+        .unwrap_or(DEFAULT_STARTUP_TIMEOUT_SECS);
 
     if config.default_command.is_some() && !args.iter().any(|a| a == "--") {
         args.push("--".to_string());
         args.extend(config.default_command.clone().unwrap_or_default());
     }
 
+    let probe = WindowProbe {
+        class: Some(
+            config
+                .window_class
+                .clone()
+                .unwrap_or_else(|| DEFAULT_WINDOW_CLASS.to_string()),
+        )
+        .filter(|pattern| !pattern.is_empty()),
+        title: config
+            .window_title
+            .clone()
+            .filter(|pattern| !pattern.is_empty()),
+        poll_ms: config
+            .startup_poll_ms
+            .unwrap_or(DEFAULT_STARTUP_POLL_MS)
+            .max(10),
+    };
+
+    let fit = FitSettings {
+        attempts: config.fit_attempts.unwrap_or(DEFAULT_FIT_ATTEMPTS).max(1),
+        settle_ms: config.fit_settle_ms.unwrap_or(DEFAULT_FIT_SETTLE_MS),
+        tolerance_px: config
+            .fit_tolerance_px
+            .unwrap_or(DEFAULT_FIT_TOLERANCE_PX)
+            .max(0),
+    };
+
+    let restart = RestartPolicy {
+        enabled: config.restart_on_crash.unwrap_or(false),
+        max_retries: config
+            .restart_max_retries
+            .unwrap_or(DEFAULT_RESTART_MAX_RETRIES),
+        backoff_ms: config
+            .restart_backoff_ms
+            .unwrap_or(DEFAULT_RESTART_BACKOFF_MS),
+    };
+
+    let gamepad = GamepadSettings {
+        chord: config.gamepad_chord.clone().unwrap_or_default(),
+        hold_ms: config.gamepad_hold_ms.unwrap_or(DEFAULT_GAMEPAD_HOLD_MS),
+        action: config.gamepad_action.unwrap_or_default(),
+    };
+
     LaunchSettings {
         args,
+        default_command: config.default_command.clone(),
+        pre_command: config.pre_command.clone(),
         no_pin,
         pick,
+        picker_fallback,
+        picker_command: config.picker_command.clone(),
         idle_inhibit,
         hide_waybar,
         pick_size,
         overlay_enabled,
         mangohud_config,
         render_scale,
+        allow_supersample,
+        integer_scale: config.integer_scale.unwrap_or(false),
         virtual_width,
         virtual_height,
         output_width,
         output_height,
+        span_target: config.span_target.unwrap_or_default(),
+        span_strategy: config.span_strategy.unwrap_or_default(),
+        excluded_monitors: config.excluded_monitors.unwrap_or_default(),
+        companion_command: config.companion_command.clone(),
+        companion_window_class: config.companion_window_class.clone(),
+        refresh_rate: client.and_then(|c| c.fps).or(config.refresh_rate),
         timeout,
+        probe,
+        fit,
+        exit_with_game,
+        restart,
+        gamepad,
+        metrics: MetricsSettings {
+            listen: config.metrics_listen.clone(),
+            textfile: config.metrics_textfile.clone(),
+        },
+        recording: resolve_recording(config),
+        streaming,
+        exit_hotkey: config
+            .exit_hotkey
+            .clone()
+            .unwrap_or_else(|| DEFAULT_EXIT_HOTKEY.to_string()),
+        exit_hotkey_bind: config.exit_hotkey_bind.unwrap_or_default(),
+        exit_hotkey_confirm: config.exit_hotkey_confirm.unwrap_or(false),
+        quick_menu_hotkey: config.quick_menu_hotkey.clone(),
+        osd_hotkey: config.osd_hotkey.clone(),
+        compat: CompatSettings {
+            tool: config.compat_tool,
+            proton_version: config.proton_version.clone(),
+            wine_prefix: config.wine_prefix.clone(),
+            umu_gameid: config.umu_gameid.clone(),
+        },
+        embedded: config.embedded.unwrap_or(false),
+        tuning: TuningFlags {
+            // Confinement relies on gamescope grabbing the pointer; the config keeps them apart.
+            force_grab_cursor: config.force_grab_cursor.unwrap_or(false)
+                || config.cursor_confine.unwrap_or(false),
+            ..resolve_tuning(config)
+        },
+        cursor: CursorSettings {
+            confine: config.cursor_confine.unwrap_or(false),
+            warp: config.cursor_warp.unwrap_or(false),
+        },
+        disable_nightlight: config.disable_nightlight.unwrap_or(false),
+        borderless: config.borderless.unwrap_or(false),
+        resolution_hints: config.resolution_hints.unwrap_or(false),
+        engine_hints: config.engine_hints.unwrap_or(false),
+        extra_env: Vec::new(),
     }
 }
 
@@ -612,47 +2541,116 @@ mod tests {
 
     fn base_config() -> Config {
         Config {
+            config_version: None,
+            include: None,
             gamescope_args: Some(vec!["-r".to_string(), "60".to_string()]),
             default_command: Some(vec![
                 "steam".to_string(),
                 "-applaunch".to_string(),
                 "620".to_string(),
             ]),
+            pre_command: Some(vec!["game-daemon".to_string()]),
             no_pin: Some(false),
             pick: Some(false),
+            picker_fallback: Some(PickerFallback::DefaultCommand),
+            picker_command: None,
+            picker_terminal: None,
             idle_inhibit: Some(true),
             hide_waybar: Some(true),
             pick_size: Some(false),
             overlay_enabled: Some(true),
             mangohud_config: Some(DEFAULT_MANGOHUD_CONFIG.to_string()),
             render_scale: Some(0.9),
+            render_scale_x: None,
+            render_scale_y: None,
+            allow_supersample: None,
+            integer_scale: None,
             virtual_width: Some(1280),
             virtual_height: Some(720),
             output_width: Some(3840),
             output_height: Some(1080),
+            span_target: None,
+            span_strategy: Some(SpanStrategy::LargestRect),
+            excluded_monitors: Some(ExcludedMonitors::Blank),
+            companion_command: Some(vec!["discord".to_string()]),
+            companion_window_class: None,
+            refresh_rate: Some(144),
             startup_timeout_secs: Some(15),
+            startup_poll_ms: Some(100),
+            window_class: Some(String::new()),
+            window_title: Some("^Steam$".to_string()),
+            hyprctl_attempts: Some(3),
+            hyprctl_backoff_ms: Some(100),
+            gamescope_flatpak: None,
+            fit_attempts: Some(6),
+            fit_settle_ms: None,
+            fit_tolerance_px: Some(-3),
+            exit_with_game: None,
+            restart_on_crash: Some(true),
+            restart_max_retries: Some(5),
+            restart_backoff_ms: None,
+            gamepad_chord: Some(vec!["guide".to_string(), "start".to_string()]),
+            gamepad_hold_ms: None,
+            gamepad_action: Some(GamepadAction::Restart),
+            metrics_listen: Some("127.0.0.1:9977".to_string()),
+            metrics_textfile: None,
+            color: Some(ColorMode::Never),
+            exit_hotkey: Some("SUPER, Escape".to_string()),
+            exit_hotkey_bind: Some(HotkeyBind::Hold),
+            exit_hotkey_confirm: Some(true),
+            quick_menu_hotkey: Some("SUPER, F11".to_string()),
+            osd_hotkey: Some("SUPER, F10".to_string()),
+            compat_tool: Some(CompatTool::Umu),
+            proton_version: Some("GE-Proton".to_string()),
+            wine_prefix: None,
+            umu_gameid: Some("umu-292030".to_string()),
+            embedded: Some(true),
+            immediate_flips: Some(true),
+            rt_priority: None,
+            force_grab_cursor: Some(true),
+            expose_wayland: Some(false),
+            cursor_confine: None,
+            cursor_warp: Some(true),
+            disable_nightlight: Some(true),
+            borderless: Some(true),
+            resolution_hints: Some(true),
+            engine_hints: Some(true),
+            preset_catalog_url: None,
+            offline: Some(true),
+            recording: Some(RecordingConfig {
+                enabled: Some(true),
+                backend: Some(RecordingBackend::Obs),
+                replay_secs: Some(0),
+                fps: None,
+                output_dir: Some("/tmp/clips".to_string()),
+                save_hotkey: None,
+                obs_region_file: Some("/tmp/obs-region.json".to_string()),
+            }),
+            streaming: None,
+            profiles: Some(BTreeMap::from([(
+                "couch".to_string(),
+                Config {
+                    render_scale: Some(0.6),
+                    span_target: Some(SpanTarget::Focused),
+                    recording: Some(RecordingConfig {
+                        fps: Some(30),
+                        ..Default::default()
+                    }),
+                    profile_match: Some(ProfileMatch {
+                        exe: Some(vec!["witcher3.exe".to_string()]),
+                        steam_appid: Some(vec![292030]),
+                    }),
+                    ..Default::default()
+                },
+            )])),
+            profile_match: None,
         }
     }
 
     #[test]
     fn apply_config_uses_config_defaults_and_appends_default_command() {
         let config = base_config();
-        let launch = apply_config(
-            &[],
-            false,
-            false,
-            false,
-            false,
-            false,
-            false,
-            false,
-            None,
-            None,
-            None,
-            None,
-            10,
-            &config,
-        );
+        let launch = LaunchSettings::from_config(&config);
 
         assert_eq!(
             launch.args,
@@ -660,6 +2658,16 @@ mod tests {
         );
         assert!(!launch.no_pin);
         assert!(!launch.pick);
+        assert_eq!(launch.picker_fallback, PickerFallback::DefaultCommand);
+        assert_eq!(
+            launch.default_command,
+            Some(vec![
+                "steam".to_string(),
+                "-applaunch".to_string(),
+                "620".to_string()
+            ])
+        );
+        assert_eq!(launch.pre_command, Some(vec!["game-daemon".to_string()]));
         assert!(launch.idle_inhibit);
         assert!(launch.hide_waybar);
         assert!(!launch.pick_size);
@@ -668,31 +2676,104 @@ mod tests {
             launch.mangohud_config,
             Some(DEFAULT_MANGOHUD_CONFIG.to_string())
         );
-        assert_eq!(launch.render_scale, 0.9);
+        assert_eq!(launch.render_scale, RenderScale::uniform(0.9));
         assert_eq!(launch.virtual_width, Some(1280));
         assert_eq!(launch.virtual_height, Some(720));
         assert_eq!(launch.output_width, Some(3840));
         assert_eq!(launch.output_height, Some(1080));
         assert_eq!(launch.timeout, 15);
+        assert_eq!(
+            launch.probe,
+            WindowProbe {
+                class: None,
+                title: Some("^Steam$".to_string()),
+                poll_ms: 100,
+            }
+        );
+        assert_eq!(launch.refresh_rate, Some(144));
+        assert_eq!(
+            launch.fit,
+            FitSettings {
+                attempts: 6,
+                settle_ms: DEFAULT_FIT_SETTLE_MS,
+                tolerance_px: 0,
+            }
+        );
+        assert!(!launch.exit_with_game);
+        assert_eq!(
+            launch.restart,
+            RestartPolicy {
+                enabled: true,
+                max_retries: 5,
+                backoff_ms: DEFAULT_RESTART_BACKOFF_MS,
+            }
+        );
+        assert_eq!(launch.gamepad.chord, vec!["guide", "start"]);
+        assert_eq!(launch.gamepad.hold_ms, DEFAULT_GAMEPAD_HOLD_MS);
+        assert_eq!(launch.gamepad.action, GamepadAction::Restart);
+        assert_eq!(launch.metrics.listen.as_deref(), Some("127.0.0.1:9977"));
+        assert_eq!(launch.metrics.textfile, None);
+        assert_eq!(launch.exit_hotkey, "SUPER, Escape");
+        assert!(launch.exit_hotkey_confirm);
+        assert_eq!(launch.exit_hotkey_bind, HotkeyBind::Hold);
+        assert_eq!(launch.quick_menu_hotkey.as_deref(), Some("SUPER, F11"));
+        assert_eq!(launch.osd_hotkey.as_deref(), Some("SUPER, F10"));
+        assert_eq!(launch.compat.tool, Some(CompatTool::Umu));
+        assert_eq!(launch.compat.proton_version.as_deref(), Some("GE-Proton"));
+        assert_eq!(launch.compat.umu_gameid.as_deref(), Some("umu-292030"));
+        assert!(launch.embedded);
+        assert_eq!(launch.excluded_monitors, ExcludedMonitors::Blank);
+        assert_eq!(launch.span_strategy, SpanStrategy::LargestRect);
+        assert_eq!(launch.companion_command, Some(vec!["discord".to_string()]));
+        assert!(launch.disable_nightlight);
+        assert!(launch.borderless);
+        assert!(launch.resolution_hints);
+        assert!(launch.engine_hints);
+        assert_eq!(
+            launch.tuning.gamescope_flags(),
+            vec!["--immediate-flips", "--force-grab-cursor"]
+        );
+        assert_eq!(
+            launch.cursor,
+            CursorSettings {
+                confine: false,
+                warp: true
+            }
+        );
+        assert_eq!(
+            launch.recording,
+            RecordingSettings {
+                enabled: true,
+                backend: RecordingBackend::Obs,
+                replay_secs: 1,
+                fps: DEFAULT_RECORDING_FPS,
+                output_dir: "/tmp/clips".to_string(),
+                save_hotkey: DEFAULT_REPLAY_SAVE_HOTKEY.to_string(),
+                obs_region_file: Some("/tmp/obs-region.json".to_string()),
+            }
+        );
     }
 
     #[test]
     fn apply_config_cli_overrides_and_clamps_render_scale() {
         let config = base_config();
         let launch = apply_config(
-            &["-r".to_string(), "120".to_string()],
-            true,
-            true,
-            true,
-            true,
-            true,
-            false,
-            true,
-            Some("custom_text=Exit".to_string()),
-            Some(2.0),
-            Some(1600),
-            None,
-            25,
+            &CliOverrides {
+                args: vec!["-r".to_string(), "120".to_string()],
+                no_pin: Some(true),
+                pick: Some(true),
+                idle_inhibit: Some(true),
+                hide_waybar: Some(true),
+                pick_size: Some(true),
+                overlay: Some(false),
+                mangohud_config: Some("custom_text=Exit".to_string()),
+                render_scale: Some(RenderScale::uniform(2.0)),
+                virtual_width: Some(1600),
+                output_width: Some(2560),
+                exit_with_game: Some(true),
+                startup_timeout_secs: Some(25),
+                ..CliOverrides::default()
+            },
             &config,
         );
 
@@ -707,9 +2788,361 @@ mod tests {
         assert!(launch.pick_size);
         assert!(!launch.overlay_enabled);
         assert_eq!(launch.mangohud_config, Some("custom_text=Exit".to_string()));
-        assert_eq!(launch.render_scale, 1.0);
+        assert_eq!(launch.render_scale, RenderScale::uniform(1.0));
         assert_eq!(launch.virtual_width, Some(1600));
         assert_eq!(launch.virtual_height, Some(720));
+        assert_eq!(launch.output_width, Some(2560));
+        assert_eq!(launch.output_height, Some(1080));
+        assert!(launch.exit_with_game);
         assert_eq!(launch.timeout, 25);
+
+        let mut supersampled = base_config();
+        supersampled.allow_supersample = Some(true);
+        supersampled.render_scale = Some(1.5);
+        let launch = LaunchSettings::from_config(&supersampled);
+        assert!(launch.allow_supersample);
+        assert_eq!(launch.render_scale, RenderScale::uniform(1.5));
+
+        supersampled.render_scale_x = Some(0.75);
+        let launch = LaunchSettings::from_config(&supersampled);
+        assert_eq!(launch.render_scale, RenderScale { x: 0.75, y: 1.5 });
+    }
+
+    #[test]
+    fn apply_config_explicit_false_overrides_config_true() {
+        let mut config = base_config();
+        config.exit_with_game = Some(true);
+        let launch = apply_config(
+            &CliOverrides {
+                idle_inhibit: Some(false),
+                hide_waybar: Some(false),
+                overlay: Some(false),
+                exit_with_game: Some(false),
+                ..CliOverrides::default()
+            },
+            &config,
+        );
+        assert!(!launch.idle_inhibit);
+        assert!(!launch.hide_waybar);
+        assert!(!launch.overlay_enabled);
+        assert!(!launch.exit_with_game);
+
+        let launch = LaunchSettings::from_config(&config);
+        assert!(launch.idle_inhibit);
+        assert!(launch.hide_waybar);
+        assert!(launch.overlay_enabled);
+        assert!(launch.exit_with_game);
+    }
+
+    #[test]
+    fn apply_config_streaming_defaults_to_no_pin() {
+        let mut config = base_config();
+        config.streaming = Some(StreamingConfig {
+            enabled: Some(true),
+            match_client: None,
+        });
+        let launch = LaunchSettings::from_config(&config);
+        assert!(launch.no_pin);
+        assert_eq!(
+            launch.streaming,
+            StreamingSettings {
+                enabled: true,
+                match_client: true
+            }
+        );
+
+        let launch = apply_config(
+            &CliOverrides {
+                no_pin: Some(false),
+                ..CliOverrides::default()
+            },
+            &config,
+        );
+        assert!(!launch.no_pin);
+    }
+
+    #[test]
+    fn apply_config_explicit_timeout_always_wins() {
+        let config = base_config();
+        let launch = apply_config(
+            &CliOverrides {
+                startup_timeout_secs: Some(10),
+                ..CliOverrides::default()
+            },
+            &config,
+        );
+        assert_eq!(launch.timeout, 10);
+
+        let launch = LaunchSettings::from_config(&config);
+        assert_eq!(launch.timeout, 15);
+
+        let mut config = base_config();
+        config.startup_timeout_secs = None;
+        let launch = LaunchSettings::from_config(&config);
+        assert_eq!(launch.timeout, DEFAULT_STARTUP_TIMEOUT_SECS);
+    }
+
+    #[test]
+    fn config_template_keeps_cursor_confine_and_grab_apart() {
+        let config = Config {
+            cursor_confine: Some(true),
+            force_grab_cursor: Some(false),
+            ..base_config()
+        };
+        let written: Config = toml::from_str(&render_config_template(&config, "test")).unwrap();
+        assert_eq!(written.cursor_confine, Some(true));
+        assert_eq!(written.force_grab_cursor, Some(false));
+        // The launch still grabs the cursor while confinement is on.
+        let launch = LaunchSettings::from_config(&written);
+        assert!(launch.tuning.force_grab_cursor);
+
+        let unconfined = Config {
+            cursor_confine: Some(false),
+            ..written
+        };
+        assert!(
+            !LaunchSettings::from_config(&unconfined)
+                .tuning
+                .force_grab_cursor
+        );
+    }
+
+    #[test]
+    fn apply_profile_overrides_only_set_keys() {
+        let config = base_config();
+        let merged = apply_profile(&config, "couch").unwrap();
+        assert_eq!(merged.render_scale, Some(0.6));
+        assert_eq!(merged.span_target, Some(SpanTarget::Focused));
+        assert_eq!(merged.startup_timeout_secs, Some(15));
+        let recording = merged.recording.unwrap();
+        assert_eq!(recording.fps, Some(30));
+        assert_eq!(recording.backend, Some(RecordingBackend::Obs));
+        assert_eq!(recording.output_dir.as_deref(), Some("/tmp/clips"));
+
+        let err = apply_profile(&config, "desk").unwrap_err().to_string();
+        assert!(err.contains("configured: couch"));
+    }
+
+    #[test]
+    fn profiles_are_matched_by_executable_or_steam_appid() {
+        let config = base_config();
+        let words = |s: &str| s.split(' ').map(str::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            matching_profile(&config, &words("proton run C:\\Games\\Witcher3.exe")),
+            Some(("couch".to_string(), "executable Witcher3.exe".to_string()))
+        );
+        assert_eq!(
+            matching_profile(&config, &words("steam steam://rungameid/292030")),
+            Some(("couch".to_string(), "Steam app id 292030".to_string()))
+        );
+        assert_eq!(
+            matching_profile(&config, &words("steam -applaunch 620")),
+            None
+        );
+
+        let (name, matched) = apply_game_profile(&config, &[], &words("/games/witcher3.exe"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(name, "couch");
+        let launch = LaunchSettings::from_config(&matched);
+        assert_eq!(launch.render_scale, RenderScale::uniform(0.6));
+        // An explicitly chosen profile turns matching off.
+        let chosen = apply_profile(&config, "couch").unwrap();
+        assert!(chosen.profiles.is_none());
+    }
+
+    #[test]
+    fn set_overrides_and_cli_flags_win_over_a_matched_profile() {
+        let config = base_config();
+        let game = vec!["/games/witcher3.exe".to_string()];
+        let (_, matched) = apply_game_profile(&config, &["span_target=all".to_string()], &game)
+            .unwrap()
+            .unwrap();
+        let launch = apply_config(
+            &CliOverrides {
+                render_scale: Some(RenderScale::uniform(0.8)),
+                ..CliOverrides::default()
+            },
+            &matched,
+        );
+        // The profile sets render_scale 0.6, span_target "focused" and recording.fps 30.
+        assert_eq!(launch.render_scale, RenderScale::uniform(0.8));
+        assert_eq!(launch.span_target, SpanTarget::All);
+        assert_eq!(launch.recording.fps, 30);
+    }
+
+    #[test]
+    fn apply_overrides_parses_toml_values_and_rejects_unknown_keys() {
+        let config = base_config();
+        let updated = apply_overrides(
+            &config,
+            &[
+                "render_scale=0.7".to_string(),
+                "hide_waybar=false".to_string(),
+                "output_width=2560".to_string(),
+                "span_target=focused".to_string(),
+                "gamescope_args=[\"-r\", \"90\"]".to_string(),
+                "recording.fps=30".to_string(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(updated.render_scale, Some(0.7));
+        assert_eq!(updated.hide_waybar, Some(false));
+        assert_eq!(updated.output_width, Some(2560));
+        assert_eq!(updated.span_target, Some(SpanTarget::Focused));
+        assert_eq!(
+            updated.gamescope_args,
+            Some(vec!["-r".to_string(), "90".to_string()])
+        );
+        let recording = updated.recording.unwrap();
+        assert_eq!(recording.fps, Some(30));
+        assert_eq!(recording.output_dir.as_deref(), Some("/tmp/clips"));
+
+        assert!(apply_overrides(&config, &["render_scal=0.7".to_string()]).is_err());
+        assert!(apply_overrides(&config, &["hide_waybar=maybe".to_string()]).is_err());
+        assert!(apply_overrides(&config, &["render_scale".to_string()]).is_err());
+    }
+
+    #[test]
+    fn load_config_merges_includes_under_the_main_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("config.toml");
+        std::fs::create_dir(dir.path().join("profiles")).unwrap();
+        std::fs::write(
+            &main,
+            "include = [\"profiles/*.toml\"]\nrender_scale = 0.8\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("profiles/b.toml"),
+            "render_scale = 0.5\nhide_waybar = false\n[profiles.desk]\nrender_scale = 1.0\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("profiles/a.toml"),
+            "hide_waybar = true\n[profiles.couch]\nrender_scale = 0.6\n",
+        )
+        .unwrap();
+
+        let config = load_config(&Some(main.display().to_string())).unwrap();
+        assert_eq!(config.render_scale, Some(0.8));
+        assert_eq!(config.hide_waybar, Some(false));
+        assert!(config.include.is_none());
+        let profiles = config.profiles.unwrap();
+        assert_eq!(profiles.keys().collect::<Vec<_>>(), vec!["couch", "desk"]);
+    }
+
+    #[test]
+    fn load_config_reports_include_cycles_and_bad_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.toml");
+        std::fs::write(&a, "include = [\"b.toml\"]\n").unwrap();
+        std::fs::write(dir.path().join("b.toml"), "include = [\"a.toml\"]\n").unwrap();
+        let err = load_config(&Some(a.display().to_string()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("include cycle"), "{}", err);
+
+        std::fs::write(&a, "include = [\"c.toml\"]\n").unwrap();
+        std::fs::write(dir.path().join("c.toml"), "render_scale = \"big\"\n").unwrap();
+        let err = load_config(&Some(a.display().to_string()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("c.toml"), "{}", err);
+
+        std::fs::write(&a, "include = [\"missing.toml\"]\n").unwrap();
+        assert!(load_config(&Some(a.display().to_string())).is_err());
+    }
+
+    #[test]
+    fn wildcard_match_handles_star_and_question_mark() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert!(wildcard_match(&chars("*.toml"), &chars("witcher.toml")));
+        assert!(wildcard_match(&chars("game-?.toml"), &chars("game-1.toml")));
+        assert!(!wildcard_match(&chars("*.toml"), &chars("notes.txt")));
+    }
+
+    #[test]
+    fn update_config_document_keeps_comments_and_unknown_keys() {
+        let contents = "# my notes\nrender_scale = 0.75 # tuned for the 3060\nhide_waybar = true\nfuture_key = 1\n\n[profiles.couch]\n# living room\nrender_scale = 0.6\n";
+        let mut config: Config = toml::from_str(contents).unwrap();
+        config.render_scale = Some(0.5);
+        config.hide_waybar = None;
+        config.pick = Some(true);
+
+        let updated = update_config_document(contents, &config).unwrap();
+        assert!(updated.starts_with("# my notes\n"));
+        assert!(updated.contains("render_scale = 0.5 # tuned for the 3060"));
+        assert!(!updated.contains("hide_waybar"));
+        assert!(updated.contains("future_key = 1"));
+        assert!(updated.contains("pick = true"));
+        assert!(updated.contains("# living room\nrender_scale = 0.6"));
+    }
+
+    #[test]
+    fn write_config_refuses_to_clobber_a_file_it_cannot_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let contents = "# my notes\nrender_scale = \"three quarters\"\n";
+        std::fs::write(&path, contents).unwrap();
+
+        let path_override = Some(path.to_string_lossy().into_owned());
+        assert!(write_config(&path_override, &Config::default()).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+    }
+
+    #[test]
+    fn value_origins_credit_the_last_layer_that_set_a_value() {
+        let rows = |scale: &str, pick: &str, waybar: &str| {
+            vec![
+                ("render_scale", scale.to_string()),
+                ("pick", pick.to_string()),
+                ("hide_waybar", waybar.to_string()),
+                ("no_pin", "false".to_string()),
+            ]
+        };
+        let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<BTreeSet<_>>();
+        let layers = [
+            ("config", keys(&["render_scale_x", "pick", "hide_waybar"])),
+            ("profile", keys(&[])),
+            ("--set", keys(&[])),
+            ("cli", keys(&["pick"])),
+        ];
+        let origins = value_origins(
+            &rows("1", "false", "true"),
+            &rows("0.67", "true", "true"),
+            &layers,
+            &["render_scale"],
+        );
+        assert_eq!(
+            origins,
+            vec![
+                ValueOrigin {
+                    key: "render_scale",
+                    value: "0.67".to_string(),
+                    default: "1".to_string(),
+                    source: "autotune",
+                },
+                ValueOrigin {
+                    key: "pick",
+                    value: "true".to_string(),
+                    default: "false".to_string(),
+                    source: "cli",
+                },
+                // Set to its default value, but set all the same.
+                ValueOrigin {
+                    key: "hide_waybar",
+                    value: "true".to_string(),
+                    default: "true".to_string(),
+                    source: "config",
+                },
+                ValueOrigin {
+                    key: "no_pin",
+                    value: "false".to_string(),
+                    default: "false".to_string(),
+                    source: "default",
+                },
+            ]
+        );
     }
 }
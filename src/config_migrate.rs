@@ -0,0 +1,274 @@
+use crate::i18n::{Msg, trf};
+use crate::output::{info, warn};
+use std::error::Error;
+use std::path::Path;
+
+/// Current config schema. Files without `config_version` are version 1.
+pub(crate) const CONFIG_VERSION: u32 = 2;
+
+/// Key changes made in a schema version; applied to the top level and to every profile.
+struct ConfigMigration {
+    version: u32,
+    renamed: &'static [(&'static str, &'static str)],
+    removed: &'static [&'static str],
+    /// Keys removed only while they still hold a former default (a TOML value), so the new
+    /// default applies; values the user changed are kept.
+    old_defaults: &'static [(&'static str, &'static str)],
+}
+
+// Append a step (and bump CONFIG_VERSION) whenever a key is renamed or dropped.
+const CONFIG_MIGRATIONS: &[ConfigMigration] = &[ConfigMigration {
+    // Version 1 `config-init` wrote `-r 60`, which beats the refresh rate derived from the span.
+    version: 2,
+    renamed: &[],
+    removed: &[],
+    old_defaults: &[("gamescope_args", r#"["-r", "60"]"#)],
+}];
+
+fn file_version(doc: &toml_edit::DocumentMut) -> u32 {
+    doc.get("config_version")
+        .and_then(|item| item.as_integer())
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(1)
+}
+
+fn holds_value(item: &toml_edit::Item, literal: &str) -> bool {
+    let parse = |value: &str| {
+        format!("v = {}", value)
+            .parse::<toml::Table>()
+            .ok()
+            .and_then(|mut table| table.remove("v"))
+    };
+    item.as_value()
+        .and_then(|value| parse(&value.to_string()))
+        .is_some_and(|value| Some(value) == parse(literal))
+}
+
+fn migrate_table(
+    table: &mut toml_edit::Table,
+    step: &ConfigMigration,
+    scope: &str,
+    notes: &mut Vec<String>,
+) {
+    for (old, new) in step.renamed {
+        if !table.contains_key(old) {
+            continue;
+        }
+        if table.contains_key(new) {
+            table.remove(old);
+            notes.push(trf(Msg::MigrationDropped, &[&scope, old, new]));
+            continue;
+        }
+        // Renamed in place: the new key keeps the old one's comments and position.
+        let order = table
+            .iter()
+            .map(|(key, _)| if key == *old { *new } else { key }.to_string())
+            .collect::<Vec<_>>();
+        let Some((old_key, item)) = table.remove_entry(old) else {
+            continue;
+        };
+        let key = toml_edit::Key::new(*new).with_leaf_decor(old_key.leaf_decor().clone());
+        table.insert_formatted(&key, item);
+        let position = |key: &toml_edit::Key| order.iter().position(|k| k == key.get());
+        table.sort_values_by(|a, _, b, _| position(a).cmp(&position(b)));
+        notes.push(trf(Msg::MigrationRenamed, &[&scope, old, new]));
+    }
+    for key in step.removed {
+        if table.remove(key).is_some() {
+            notes.push(trf(Msg::MigrationRemoved, &[&scope, key]));
+        }
+    }
+    for (key, old_default) in step.old_defaults {
+        if table
+            .get(key)
+            .is_some_and(|item| holds_value(item, old_default))
+        {
+            table.remove(key);
+            notes.push(trf(Msg::MigrationOldDefault, &[&scope, key, old_default]));
+        }
+    }
+}
+
+fn apply_migrations(
+    doc: &mut toml_edit::DocumentMut,
+    migrations: &[ConfigMigration],
+) -> Vec<String> {
+    let version = file_version(doc);
+    let mut notes = Vec::new();
+    for step in migrations.iter().filter(|step| step.version > version) {
+        migrate_table(doc.as_table_mut(), step, "", &mut notes);
+        if let Some(profiles) = doc.get_mut("profiles").and_then(|p| p.as_table_mut()) {
+            for (name, profile) in profiles.iter_mut() {
+                if let Some(profile) = profile.as_table_mut() {
+                    let scope = format!("[profiles.{}] ", name.get());
+                    migrate_table(profile, step, &scope, &mut notes);
+                }
+            }
+        }
+    }
+    notes
+}
+
+/// Deprecated keys still present, e.g. re-added after the file was stamped with a newer version.
+fn deprecated_keys(doc: &toml_edit::DocumentMut, migrations: &[ConfigMigration]) -> Vec<String> {
+    let mut tables = vec![(String::new(), doc.as_table())];
+    if let Some(profiles) = doc.get("profiles").and_then(|p| p.as_table()) {
+        tables.extend(profiles.iter().filter_map(|(name, profile)| {
+            profile
+                .as_table()
+                .map(|table| (format!("[profiles.{}] ", name), table))
+        }));
+    }
+    let mut found = Vec::new();
+    for step in migrations {
+        for (scope, table) in &tables {
+            for (old, new) in step.renamed {
+                if table.contains_key(old) {
+                    found.push(trf(Msg::KeyDeprecated, &[scope, old, new]));
+                }
+            }
+            for key in step.removed {
+                if table.contains_key(key) {
+                    found.push(trf(Msg::KeyUnused, &[scope, key]));
+                }
+            }
+        }
+    }
+    found
+}
+
+fn migrate_config_file_with(
+    path: &Path,
+    migrations: &[ConfigMigration],
+) -> Result<String, Box<dyn Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    let Ok(mut doc) = contents.parse::<toml_edit::DocumentMut>() else {
+        // Leave syntax errors to the regular parser, which reports them with the path.
+        return Ok(contents);
+    };
+    let version = file_version(&doc);
+    if version > CONFIG_VERSION {
+        warn(&trf(
+            Msg::ConfigVersionNewer,
+            &[&path.display(), &version, &CONFIG_VERSION],
+        ));
+    }
+
+    let notes = apply_migrations(&mut doc, migrations);
+    for note in deprecated_keys(&doc, migrations) {
+        warn(&trf(Msg::ConfigNote, &[&path.display(), &note]));
+    }
+    if notes.is_empty() {
+        return Ok(contents);
+    }
+    let target = migrations
+        .iter()
+        .map(|step| step.version)
+        .fold(CONFIG_VERSION, u32::max);
+    doc["config_version"] = toml_edit::value(i64::from(target));
+    let migrated = doc.to_string();
+    let backup = path.with_extension(format!("toml.v{}.bak", version));
+    let written = std::fs::copy(path, &backup).and_then(|_| std::fs::write(path, &migrated));
+    match written {
+        Ok(()) => info(&trf(
+            Msg::ConfigMigrated,
+            &[
+                &path.display(),
+                &target,
+                &notes.join(", "),
+                &backup.display(),
+            ],
+        )),
+        Err(e) => warn(&trf(
+            Msg::ConfigMigrateWriteFailed,
+            &[&path.display(), &e, &notes.join(", ")],
+        )),
+    }
+    Ok(migrated)
+}
+
+/// Reads a config file, upgrading it in place (with a backup) when it predates a key change.
+pub(crate) fn migrate_config_file(path: &Path) -> Result<String, Box<dyn Error>> {
+    migrate_config_file_with(path, CONFIG_MIGRATIONS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_MIGRATIONS: &[ConfigMigration] = &[ConfigMigration {
+        version: 2,
+        renamed: &[("overlay", "overlay_enabled")],
+        removed: &["legacy_fit"],
+        old_defaults: &[],
+    }];
+
+    #[test]
+    fn apply_migrations_renames_and_removes_keys_in_profiles_too() {
+        let mut doc =
+            "# keep me\noverlay = false\nlegacy_fit = true\n\n[profiles.couch]\noverlay = true\n"
+                .parse::<toml_edit::DocumentMut>()
+                .unwrap();
+        let notes = apply_migrations(&mut doc, TEST_MIGRATIONS);
+        assert_eq!(notes.len(), 3);
+        let out = doc.to_string();
+        // The renamed key keeps its comment and its place at the top.
+        assert!(
+            out.starts_with("# keep me\noverlay_enabled = false\n"),
+            "{}",
+            out
+        );
+        assert!(!out.contains("legacy_fit"));
+        assert_eq!(
+            doc["profiles"]["couch"]["overlay_enabled"].as_bool(),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn old_default_gamescope_args_are_dropped_but_edited_values_kept() {
+        let mut doc = concat!(
+            "gamescope_args = [\"-r\", \"60\"] # old default\n\n",
+            "[profiles.fast]\ngamescope_args = [\"-r\",\"120\"]\n\n",
+            "[profiles.capped]\ngamescope_args = [\"-r\", \"60\", \"-f\"]\n",
+        )
+        .parse::<toml_edit::DocumentMut>()
+        .unwrap();
+        let notes = apply_migrations(&mut doc, CONFIG_MIGRATIONS);
+        assert_eq!(
+            notes,
+            vec!["removed the old default `gamescope_args = [\"-r\", \"60\"]`"]
+        );
+        assert!(doc.get("gamescope_args").is_none());
+        assert!(doc["profiles"]["fast"].get("gamescope_args").is_some());
+        assert!(doc["profiles"]["capped"].get("gamescope_args").is_some());
+    }
+
+    #[test]
+    fn stamped_files_are_not_migrated_but_deprecated_keys_are_reported() {
+        let mut doc = "config_version = 2\noverlay = false\n"
+            .parse::<toml_edit::DocumentMut>()
+            .unwrap();
+        assert!(apply_migrations(&mut doc, TEST_MIGRATIONS).is_empty());
+        assert_eq!(
+            deprecated_keys(&doc, TEST_MIGRATIONS),
+            vec!["`overlay` is deprecated; use `overlay_enabled`"]
+        );
+    }
+
+    #[test]
+    fn migrate_config_file_writes_backup_and_stamps_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "overlay = false\n").unwrap();
+
+        let migrated = migrate_config_file_with(&path, TEST_MIGRATIONS).unwrap();
+        assert!(migrated.contains("overlay_enabled = false"));
+        assert!(migrated.contains("config_version = 2"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), migrated);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("config.toml.v1.bak")).unwrap(),
+            "overlay = false\n"
+        );
+    }
+}
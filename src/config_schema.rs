@@ -0,0 +1,173 @@
+use crate::config::{Config, default_config_values, render_config_template};
+use crate::i18n::{Msg, trf};
+use crate::output::info;
+use crate::types::AutoTuneProfile;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// Template tables whose keys live in a nested struct of the schema.
+const SECTION_DEFINITIONS: &[(&str, &str)] = &[
+    ("recording", "RecordingConfig"),
+    ("streaming", "StreamingConfig"),
+];
+
+/// The key a template line sets, and whether it is only a commented-out example.
+fn template_key(line: &str) -> Option<(&str, bool)> {
+    let (body, commented) = match line.strip_prefix("# ") {
+        Some(rest) => (rest, true),
+        None => (line, false),
+    };
+    let (key, _) = body.split_once(" = ")?;
+    (!key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'))
+    .then_some((key, commented))
+}
+
+/// Docs for each key from the comment block above it in the config template, keyed by dotted
+/// path. Keys listed together (all set, or all commented-out examples) share one block; a table
+/// takes the block above its header.
+fn template_key_docs(template: &str) -> BTreeMap<String, String> {
+    let mut docs = BTreeMap::new();
+    let mut section = String::new();
+    let mut comment: Vec<&str> = Vec::new();
+    let mut previous_key: Option<bool> = None;
+    for line in template.lines().map(str::trim) {
+        if line.is_empty() {
+            comment.clear();
+            previous_key = None;
+            continue;
+        }
+        let unquoted = line.strip_prefix("# ").unwrap_or(line);
+        if unquoted.starts_with('[') {
+            section = unquoted.trim_matches(['[', ']']).to_string();
+            if !comment.is_empty() {
+                docs.entry(section.clone())
+                    .or_insert_with(|| comment.join(" "));
+            }
+            comment.clear();
+            previous_key = None;
+            continue;
+        }
+        if let Some((key, commented)) = template_key(line) {
+            if previous_key.is_some_and(|previous| previous != commented) {
+                comment.clear();
+            }
+            if !comment.is_empty() {
+                let path = if section.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", section, key)
+                };
+                docs.entry(path).or_insert_with(|| comment.join(" "));
+            }
+            previous_key = Some(commented);
+        } else if let Some(text) = line.strip_prefix('#') {
+            if previous_key.take().is_some() {
+                comment.clear();
+            }
+            if !text.trim().is_empty() {
+                comment.push(text.trim());
+            }
+        }
+    }
+    docs
+}
+
+/// JSON Pointer to the schema of a dotted config key.
+fn schema_pointer(path: &str) -> Option<String> {
+    match path.split_once('.') {
+        None => Some(format!("/properties/{}", path)),
+        Some((section, key)) => SECTION_DEFINITIONS
+            .iter()
+            .find(|(name, _)| *name == section)
+            .map(|(_, definition)| format!("/definitions/{}/properties/{}", definition, key)),
+    }
+}
+
+/// JSON Schema for config.toml, with the config template's comments as descriptions and the
+/// built-in defaults.
+pub(crate) fn config_schema() -> Result<serde_json::Value, Box<dyn Error>> {
+    let mut schema = serde_json::to_value(schemars::schema_for!(Config))?;
+    schema["title"] = "Hyprfinity config".into();
+    // The stock defaults, without hardware auto-tuning or its note in the template.
+    let defaults = default_config_values(&AutoTuneProfile {
+        render_scale: 1.0,
+        reason: String::new(),
+    });
+    let docs = template_key_docs(&render_config_template(&defaults, ""));
+    for (path, doc) in docs {
+        if let Some(property) = schema_pointer(&path)
+            .and_then(|pointer| schema.pointer_mut(&pointer))
+            .and_then(|property| property.as_object_mut())
+        {
+            property.insert("description".to_string(), doc.into());
+        }
+    }
+    if let serde_json::Value::Object(defaults) = serde_json::to_value(&defaults)? {
+        for (key, value) in defaults.into_iter().filter(|(_, value)| !value.is_null()) {
+            if let Some(property) = schema
+                .pointer_mut(&format!("/properties/{}", key))
+                .and_then(|property| property.as_object_mut())
+            {
+                property.insert("default".to_string(), value);
+            }
+        }
+    }
+    Ok(schema)
+}
+
+pub(crate) fn print_config_schema(output: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let json = serde_json::to_string_pretty(&config_schema()?)?;
+    match output {
+        Some(path) if path != "-" => {
+            std::fs::write(path, format!("{}\n", json))?;
+            info(&trf(Msg::WroteFile, &[&path]));
+        }
+        _ => println!("{}", json),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_comments_become_schema_descriptions() {
+        let docs = template_key_docs(
+            "# Size of the thing.\n# virtual_width = 5760\n# virtual_height = 1080\nhide_waybar = true\n\n\
+             # Replay buffer.\n[recording]\nfps = 60\n",
+        );
+        assert_eq!(
+            docs.get("virtual_width").map(String::as_str),
+            Some("Size of the thing.")
+        );
+        assert_eq!(
+            docs.get("virtual_height").map(String::as_str),
+            Some("Size of the thing.")
+        );
+        assert_eq!(docs.get("hide_waybar"), None);
+        assert_eq!(
+            docs.get("recording").map(String::as_str),
+            Some("Replay buffer.")
+        );
+
+        let schema = config_schema().unwrap();
+        let render_scale = &schema["properties"]["render_scale"];
+        assert!(
+            render_scale["description"]
+                .as_str()
+                .is_some_and(|doc| doc.starts_with("Internal render scale"))
+        );
+        assert_eq!(render_scale["default"], 1.0);
+        assert_eq!(render_scale["maximum"], 2.0);
+        assert!(schema["definitions"]["RecordingConfig"]["properties"]["fps"].is_object());
+        assert!(
+            schema["definitions"]["SpanTarget"]
+                .to_string()
+                .contains("focused")
+        );
+    }
+}
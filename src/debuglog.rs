@@ -1,3 +1,5 @@
+use crate::i18n::{Msg, trf};
+use crate::output::{info, warn};
 use std::io::Write;
 use std::path::PathBuf;
 use std::sync::{Mutex, OnceLock};
@@ -9,6 +11,17 @@ pub(crate) const FALLBACK_DEBUG_LOG_PATH: &str = "/tmp/hyprfinity-debug.log";
 
 static DEBUG_LOGGER: OnceLock<Mutex<std::fs::File>> = OnceLock::new();
 
+/// Where `--debug` writes: the override, then HYPRFINITY_DEBUG_LOG, then the default path.
+pub(crate) fn debug_log_path(path_override: &Option<String>) -> PathBuf {
+    if let Some(p) = path_override.as_ref() {
+        PathBuf::from(p)
+    } else if let Ok(p) = std::env::var(DEBUG_LOG_ENV_VAR) {
+        PathBuf::from(p)
+    } else {
+        PathBuf::from(DEFAULT_DEBUG_LOG_PATH)
+    }
+}
+
 pub(crate) fn init_debug_logging(
     enabled: bool,
     path_override: &Option<String>,
@@ -16,13 +29,7 @@ pub(crate) fn init_debug_logging(
     if !enabled {
         return Ok(());
     }
-    let chosen_path = if let Some(p) = path_override.as_ref() {
-        PathBuf::from(p)
-    } else if let Ok(p) = std::env::var(DEBUG_LOG_ENV_VAR) {
-        PathBuf::from(p)
-    } else {
-        PathBuf::from(DEFAULT_DEBUG_LOG_PATH)
-    };
+    let chosen_path = debug_log_path(path_override);
 
     let open_file = |path: &PathBuf| -> Result<std::fs::File, Box<dyn std::error::Error>> {
         if let Some(parent) = path.parent() {
@@ -39,19 +46,17 @@ pub(crate) fn init_debug_logging(
         Ok(f) => (chosen_path, f),
         Err(e) => {
             let fallback = PathBuf::from(FALLBACK_DEBUG_LOG_PATH);
-            eprintln!(
-                "Hyprfinity: Failed to open debug log at {} ({}), falling back to {}",
-                chosen_path.display(),
-                e,
-                fallback.display()
-            );
+            warn(&trf(
+                Msg::DebugLogFallback,
+                &[&chosen_path.display(), &e, &fallback.display()],
+            ));
             let f = open_file(&fallback)?;
             (fallback, f)
         }
     };
 
     let _ = DEBUG_LOGGER.set(Mutex::new(file));
-    println!("Hyprfinity: Debug log enabled at {}", path.display());
+    info(&trf(Msg::DebugLogEnabled, &[&path.display()]));
     debug_log_line("debug logging initialized");
     Ok(())
 }
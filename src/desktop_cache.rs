@@ -0,0 +1,133 @@
+use crate::debuglog::debug_log_line;
+use crate::types::DesktopApp;
+use crate::util::xdg_cache_path;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::Metadata;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+const CACHE_REL_PATH: &str = "hyprfinity/desktop-apps.json";
+/// Bumped whenever `DesktopApp` gains fields, so entries parsed by an older build are re-read.
+const CACHE_VERSION: u32 = 2;
+
+/// Parse result for one .desktop file; `app` is `None` for hidden or incomplete entries, which
+/// are cached too so they are not re-read on every scan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CachedDesktopFile {
+    pub(crate) mtime_ns: u64,
+    pub(crate) app: Option<DesktopApp>,
+}
+
+/// Parsed .desktop files keyed by path, reused while a file's mtime is unchanged.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct DesktopAppCache {
+    version: u32,
+    files: BTreeMap<String, CachedDesktopFile>,
+}
+
+/// Modification time in nanoseconds; `None` when the filesystem does not report one.
+pub(crate) fn mtime_ns(meta: &Metadata) -> Option<u64> {
+    let modified = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    u64::try_from(modified.as_nanos()).ok()
+}
+
+impl DesktopAppCache {
+    pub(crate) fn load() -> Self {
+        xdg_cache_path(CACHE_REL_PATH)
+            .map(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<DesktopAppCache>(&contents).ok())
+            .filter(|cache| cache.version == CACHE_VERSION)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn get(&self, path: &str, mtime_ns: u64) -> Option<&CachedDesktopFile> {
+        self.files
+            .get(path)
+            .filter(|cached| cached.mtime_ns == mtime_ns)
+    }
+
+    /// Replaces the cached files with the latest scan and writes them out if anything was added,
+    /// changed or removed. The cache is best-effort: write failures only go to the debug log.
+    pub(crate) fn update(&mut self, files: BTreeMap<String, CachedDesktopFile>) {
+        let changed = files.len() != self.files.len()
+            || files
+                .iter()
+                .any(|(path, file)| self.get(path, file.mtime_ns).is_none());
+        self.version = CACHE_VERSION;
+        self.files = files;
+        if !changed {
+            return;
+        }
+        if let Some(path) = xdg_cache_path(CACHE_REL_PATH)
+            && let Err(e) = self.save_to(&path)
+        {
+            debug_log_line(&format!(
+                "desktop app cache: failed to write {}: {}",
+                path.display(),
+                e
+            ));
+        }
+    }
+
+    fn save_to(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write then rename, so a concurrent picker never reads a half-written cache.
+        let tmp = path.with_extension(format!("json.{}", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_string(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_round_trips_and_misses_on_changed_mtime_or_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("desktop-apps.json");
+        let mut cache = DesktopAppCache {
+            version: CACHE_VERSION,
+            files: BTreeMap::new(),
+        };
+        cache.files.insert(
+            "/usr/share/applications/steam.desktop".to_string(),
+            CachedDesktopFile {
+                mtime_ns: 42,
+                app: Some(DesktopApp {
+                    name: "Steam".to_string(),
+                    exec: "steam %U".to_string(),
+                    icon: Some("steam".to_string()),
+                    ..DesktopApp::default()
+                }),
+            },
+        );
+        cache.save_to(&path).unwrap();
+
+        let loaded = DesktopAppCache::load_from(&path);
+        let hit = loaded
+            .get("/usr/share/applications/steam.desktop", 42)
+            .and_then(|file| file.app.as_ref())
+            .unwrap();
+        assert_eq!(hit.exec, "steam %U");
+        assert!(
+            loaded
+                .get("/usr/share/applications/steam.desktop", 43)
+                .is_none()
+        );
+
+        cache.version = CACHE_VERSION + 1;
+        cache.save_to(&path).unwrap();
+        assert!(DesktopAppCache::load_from(&path).files.is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::MyError;
+use crate::config::{apply_profile, configured_game_command, load_config, steam_appid};
+use crate::i18n::{Msg, trf};
+use crate::output::info;
+use crate::picker::{list_desktop_apps, sanitize_exec};
+use crate::types::DesktopApp;
+use std::error::Error;
+use std::path::PathBuf;
+
+/// Used when the profile's game has no desktop entry of its own.
+const FALLBACK_ICON: &str = "applications-games";
+
+fn applications_dir() -> Result<PathBuf, Box<dyn Error>> {
+    if let Ok(data) = std::env::var("XDG_DATA_HOME")
+        && !data.is_empty()
+    {
+        return Ok(PathBuf::from(data).join("applications"));
+    }
+    let home = std::env::var("HOME").map_err(|_| MyError("HOME is not set.".to_string()))?;
+    Ok(PathBuf::from(home).join(".local/share/applications"))
+}
+
+fn desktop_file_name(profile: &str) -> String {
+    let slug = profile
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    format!("hyprfinity-{}.desktop", slug.trim_matches('-'))
+}
+
+/// Quotes an Exec argument per the desktop entry spec when it needs it.
+fn exec_arg(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./~=".contains(c))
+    {
+        return arg.to_string();
+    }
+    let escaped = arg
+        .chars()
+        .flat_map(|c| match c {
+            '"' | '`' | '$' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect::<String>();
+    format!("\"{}\"", escaped)
+}
+
+/// Icon of the installed app that runs `command`: Steam's per-game icon for app ids, otherwise
+/// the desktop entry whose Exec resolves to the same program.
+fn game_icon(command: &[String], apps: &[DesktopApp]) -> Option<String> {
+    if let Some(appid) = steam_appid(command) {
+        return Some(format!("steam_icon_{}", appid));
+    }
+    let program = command.first()?.rsplit('/').next()?;
+    apps.iter().find_map(|app| {
+        let words = shell_words::split(&sanitize_exec(&app.exec)).ok()?;
+        let exec_program = words.first()?.rsplit('/').next()?;
+        if exec_program == program {
+            app.icon.clone()
+        } else {
+            None
+        }
+    })
+}
+
+fn desktop_entry(profile: &str, config_path: Option<&str>, icon: &str) -> String {
+    let mut exec = vec!["hyprfinity".to_string()];
+    if let Some(path) = config_path {
+        exec.extend(["--config".to_string(), exec_arg(path)]);
+    }
+    exec.extend(["up".to_string(), "--profile".to_string(), exec_arg(profile)]);
+    format!(
+        "[Desktop Entry]
+Type=Application
+Version=1.0
+Name={profile} (Hyprfinity)
+Comment=Launch the {profile} profile spanned across monitors
+Exec={exec}
+Icon={icon}
+Terminal=false
+Categories=Game;
+",
+        exec = exec.join(" ")
+    )
+}
+
+/// Writes a launcher entry for `profile`, or for every configured profile.
+pub(crate) fn export_desktop(
+    config_path: &Option<String>,
+    profile: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let config = load_config(config_path)?;
+    let names = match profile {
+        Some(name) => vec![name.to_string()],
+        None => config
+            .profiles
+            .as_ref()
+            .map(|profiles| profiles.keys().cloned().collect())
+            .unwrap_or_default(),
+    };
+    if names.is_empty() {
+        return Err(MyError(
+            "No profiles configured; add a [profiles.NAME] table or pass --profile.".to_string(),
+        )
+        .into());
+    }
+    // Launchers run Exec from their own working directory.
+    let config_arg = config_path.as_deref().map(|path| {
+        std::fs::canonicalize(path)
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|_| path.to_string())
+    });
+    let apps = list_desktop_apps().unwrap_or_default();
+    let dir = applications_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    for name in names {
+        let profiled = apply_profile(&config, &name)?;
+        let icon = game_icon(&configured_game_command(&[], &profiled), &apps)
+            .unwrap_or_else(|| FALLBACK_ICON.to_string());
+        let path = dir.join(desktop_file_name(&name));
+        std::fs::write(&path, desktop_entry(&name, config_arg.as_deref(), &icon))?;
+        info(&trf(Msg::WroteFile, &[&path.display()]));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desktop_entry_launches_the_profile_with_the_game_icon() {
+        let apps = vec![DesktopApp {
+            name: "The Witcher 3".to_string(),
+            exec: "/usr/bin/witcher3 %U".to_string(),
+            icon: Some("witcher3".to_string()),
+            ..DesktopApp::default()
+        }];
+        let words = |s: &str| s.split(' ').map(str::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            game_icon(&words("/opt/games/witcher3 --fast"), &apps).as_deref(),
+            Some("witcher3")
+        );
+        assert_eq!(
+            game_icon(&words("steam -applaunch 620"), &apps).as_deref(),
+            Some("steam_icon_620")
+        );
+        assert_eq!(game_icon(&words("other"), &apps), None);
+
+        assert_eq!(
+            desktop_file_name("Couch Co-op"),
+            "hyprfinity-couch-co-op.desktop"
+        );
+        let entry = desktop_entry("Couch Co-op", Some("/home/me/hf.toml"), "witcher3");
+        assert!(
+            entry.contains(
+                "Exec=hyprfinity --config /home/me/hf.toml up --profile \"Couch Co-op\"\n"
+            )
+        );
+        assert!(entry.contains("Icon=witcher3\n"));
+    }
+}
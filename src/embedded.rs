@@ -0,0 +1,216 @@
+use crate::MyError;
+use crate::config::LaunchSettings;
+use crate::debuglog::debug_log_line;
+use crate::game_db::{append_engine_hints, report_wide_support};
+use crate::gamescope::{
+    apply_compat_tool, apply_refresh_rate, apply_tuning_flags, build_gamescope_args_with_internal,
+    chain_pre_command, derive_internal_size, derive_output_size, ensure_game_command,
+    ensure_gamescope_flag_value, game_command_label, refresh_placeholder, resolution_hint_env,
+    snap_to_integer_scale, substitute_command_placeholders,
+};
+use crate::hooks::{HookContext, HookStage, run_hooks};
+use crate::hyprland::get_monitors;
+use crate::i18n::{Msg, trf};
+use crate::output::{error, info};
+use crate::stats::{SessionRecord, record_session, unix_now};
+use crate::util::command_in_path;
+use std::error::Error;
+use std::process::Command;
+
+// openvt/fgconsole/chvt come from kbd; gamescope takes the displays over with its DRM backend.
+const EMBEDDED_TOOLS: &[&str] = &["openvt", "fgconsole", "chvt", "gamescope"];
+
+fn parse_vt(output: &str) -> Option<u32> {
+    output.trim().parse::<u32>().ok().filter(|vt| *vt > 0)
+}
+
+fn query_vt(args: &[&str]) -> Result<u32, Box<dyn Error>> {
+    let output = Command::new("fgconsole").args(args).output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_vt(&stdout).ok_or_else(|| {
+        MyError(format!(
+            "fgconsole {} failed ({}); run from a user session with access to /dev/tty0.",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .into()
+    })
+}
+
+fn switch_vt(vt: u32) {
+    match Command::new("chvt").arg(vt.to_string()).status() {
+        Ok(status) if status.success() => info(&trf(Msg::BackOnVt, &[&vt])),
+        Ok(status) => error(&trf(Msg::ChvtFailed, &[&vt, &status, &vt])),
+        Err(e) => error(&trf(Msg::ChvtFailed, &[&vt, &e, &vt])),
+    }
+}
+
+fn openvt_args(vt: u32, gamescope_args: &[String]) -> Vec<String> {
+    // -s switches to the new VT, -w waits for gamescope to exit before returning.
+    let mut args = vec![
+        "-c".to_string(),
+        vt.to_string(),
+        "-s".to_string(),
+        "-w".to_string(),
+        "--".to_string(),
+        "gamescope".to_string(),
+    ];
+    args.extend(gamescope_args.iter().cloned());
+    args
+}
+
+// Nested sizing follows the Hyprland span; on the DRM backend gamescope drives one display,
+// so default to the focused monitor.
+fn embedded_output_size(
+    launch: &LaunchSettings,
+    verbose: bool,
+) -> Result<(i32, i32), Box<dyn Error>> {
+    if let (Some(w), Some(h)) = (launch.output_width, launch.output_height) {
+        return Ok((w, h));
+    }
+    let monitors = get_monitors(verbose)?;
+    let monitor = monitors
+        .iter()
+        .find(|m| m.focused)
+        .or_else(|| monitors.first())
+        .ok_or_else(|| MyError("No monitors reported by Hyprland.".to_string()))?;
+    Ok(derive_output_size(
+        monitor.width,
+        monitor.height,
+        launch.output_width,
+        launch.output_height,
+    ))
+}
+
+/// Runs gamescope with its DRM backend on a spare VT instead of nesting it under Hyprland,
+/// then switches back to Hyprland's VT once gamescope exits.
+pub(crate) fn run_embedded_session(
+    launch: &LaunchSettings,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(missing) = EMBEDDED_TOOLS.iter().find(|tool| !command_in_path(tool)) {
+        return Err(MyError(format!("Embedded mode needs `{}` in PATH.", missing)).into());
+    }
+
+    let output = embedded_output_size(launch, verbose)?;
+    let args = ensure_game_command(
+        launch.args.clone(),
+        launch.pick,
+        launch.picker_fallback,
+        launch.picker_command.as_deref(),
+        launch.default_command.clone(),
+    )?;
+    report_wide_support(&args, output);
+    let (args, compat_env) = apply_compat_tool(args, &launch.compat)?;
+    let args = chain_pre_command(args, launch.pre_command.as_deref());
+    let args = apply_refresh_rate(args, launch.refresh_rate.map(|r| r as i32));
+    let args = apply_tuning_flags(args, &launch.tuning);
+    let mut internal = derive_internal_size(
+        output.0,
+        output.1,
+        launch.render_scale,
+        launch.virtual_width,
+        launch.virtual_height,
+        launch.allow_supersample,
+    );
+    if launch.integer_scale {
+        internal = snap_to_integer_scale(output, internal);
+    }
+    let placeholders = [
+        ("span_width", output.0.to_string()),
+        ("span_height", output.1.to_string()),
+        ("output_width", output.0.to_string()),
+        ("output_height", output.1.to_string()),
+        ("internal_width", internal.0.to_string()),
+        ("internal_height", internal.1.to_string()),
+        ("refresh", refresh_placeholder(&args)),
+    ];
+    let args = substitute_command_placeholders(args, &placeholders);
+    let args = if launch.engine_hints {
+        append_engine_hints(args, internal)
+    } else {
+        args
+    };
+    let final_args = build_gamescope_args_with_internal(
+        &args,
+        output.0,
+        output.1,
+        internal.0,
+        internal.1,
+        launch.allow_supersample,
+        launch.integer_scale,
+    );
+    let final_args = ensure_gamescope_flag_value(final_args, "--backend", "drm");
+
+    let home_vt = query_vt(&[])?;
+    let target_vt = query_vt(&["--next-available"])?;
+    info(&trf(
+        Msg::StartingEmbedded,
+        &[&target_vt, &home_vt, &format!("{:?}", final_args)],
+    ));
+    debug_log_line(&format!(
+        "embedded session vt={} home_vt={} args={:?}",
+        target_vt, home_vt, final_args
+    ));
+
+    let hook_context = HookContext {
+        span: (0, 0, output.0, output.1),
+        pid: None,
+        game: Some(game_command_label(&final_args)),
+    };
+    run_hooks(HookStage::PreUp, &hook_context, verbose);
+    let started_at = unix_now();
+    let mut cmd = Command::new("openvt");
+    cmd.args(openvt_args(target_vt, &final_args));
+    if launch.resolution_hints {
+        cmd.envs(resolution_hint_env(internal, launch.tuning.expose_wayland));
+    }
+    cmd.envs(compat_env.iter().cloned());
+    cmd.envs(launch.extra_env.iter().cloned());
+    if launch.overlay_enabled
+        && let Some(config) = launch.mangohud_config.clone()
+    {
+        cmd.env("MANGOHUD_CONFIG", config);
+    }
+    let status = cmd.status();
+    // openvt -w only returns once gamescope is gone, so Hyprland's VT is free to take back.
+    switch_vt(home_vt);
+    run_hooks(HookStage::PostDown, &hook_context, verbose);
+
+    let status = status?;
+    let record = SessionRecord {
+        game: game_command_label(&final_args),
+        started_at,
+        ended_at: unix_now().max(started_at),
+        args: final_args,
+        span: Some((0, 0, output.0, output.1)),
+        exit: Some(if status.success() {
+            "exited".to_string()
+        } else {
+            status.to_string()
+        }),
+    };
+    if let Err(e) = record_session(&record) {
+        error(&trf(Msg::RecordPlaytimeFailed, &[&e]));
+    }
+    if !status.success() {
+        return Err(MyError(format!("Embedded gamescope session ended with {}.", status)).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openvt_args_switch_and_wait_for_gamescope() {
+        assert_eq!(parse_vt("3\n"), Some(3));
+        assert_eq!(parse_vt("0"), None);
+        assert_eq!(parse_vt("Couldn't get a file descriptor"), None);
+        assert_eq!(
+            openvt_args(4, &["--backend".to_string(), "drm".to_string()]),
+            vec!["-c", "4", "-s", "-w", "--", "gamescope", "--backend", "drm"]
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use crate::MyError;
+use std::error::Error;
+use std::io::Read;
+use std::process::Command;
+use std::sync::OnceLock;
+
+static OFFLINE: OnceLock<bool> = OnceLock::new();
+
+/// Set from the `offline` config key; every network fetch goes through `curl`.
+pub(crate) fn init_offline(offline: bool) {
+    let _ = OFFLINE.set(offline);
+}
+
+/// Runs curl for `url` with `args`, unless network use is disabled.
+fn curl(url: &str, args: &[&str]) -> Result<Vec<u8>, Box<dyn Error>> {
+    if OFFLINE.get().copied().unwrap_or(false) {
+        return Err(MyError(format!(
+            "Not fetching {}: network use is disabled (offline = true).",
+            url
+        ))
+        .into());
+    }
+    let output = Command::new("curl")
+        .arg("-fsSL")
+        .args(args)
+        .arg(url)
+        .output()
+        .map_err(|e| MyError(format!("Failed to run curl to fetch {}: {}", url, e)))?;
+    if !output.status.success() {
+        return Err(MyError(format!(
+            "Failed to fetch {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .into());
+    }
+    Ok(output.stdout)
+}
+
+/// Downloads `url` into `dest`.
+pub(crate) fn download(url: &str, dest: &std::path::Path) -> Result<(), Box<dyn Error>> {
+    let dest = dest.to_string_lossy();
+    curl(url, &["--max-time", "300", "-o", &dest])?;
+    Ok(())
+}
+
+/// Reads a file, `-` for stdin, or an https URL (fetched with curl). Plain http is refused.
+pub(crate) fn read_source(source: &str) -> Result<String, Box<dyn Error>> {
+    if source == "-" {
+        let mut contents = String::new();
+        std::io::stdin().read_to_string(&mut contents)?;
+        return Ok(contents);
+    }
+    if source.starts_with("http://") {
+        return Err(MyError(format!(
+            "Refusing to fetch {} over plain http; use an https URL.",
+            source
+        ))
+        .into());
+    }
+    if !source.starts_with("https://") {
+        return std::fs::read_to_string(source)
+            .map_err(|e| MyError(format!("Failed to read {}: {}", source, e)).into());
+    }
+    Ok(String::from_utf8(curl(source, &["--max-time", "30"])?)?)
+}
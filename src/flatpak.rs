@@ -0,0 +1,195 @@
+use crate::backend::{GamescopeLauncher, gamescope_help_output, gamescope_version_line};
+use crate::debuglog::debug_log_line;
+use crate::util::command_in_path;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::OnceLock;
+
+/// Flathub's gamescope build, available to apps on the freedesktop runtime.
+const GAMESCOPE_EXTENSION: &str = "org.freedesktop.Platform.VulkanLayer.gamescope";
+/// App whose sandbox runs gamescope when it is only installed as the Flatpak extension.
+const DEFAULT_FLATPAK_APP: &str = "com.valvesoftware.Steam";
+
+static GAMESCOPE_FLATPAK: OnceLock<Option<String>> = OnceLock::new();
+
+fn flatpak_installed(id: &str) -> bool {
+    Command::new("flatpak")
+        .args(["info", id])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Without a host gamescope, the Steam Flatpak with the gamescope extension can run it.
+fn detect_gamescope_flatpak() -> Option<String> {
+    if command_in_path("gamescope")
+        || !command_in_path("flatpak")
+        || !flatpak_installed(GAMESCOPE_EXTENSION)
+        || !flatpak_installed(DEFAULT_FLATPAK_APP)
+    {
+        return None;
+    }
+    debug_log_line(&format!(
+        "gamescope not in PATH; using {} from {}",
+        GAMESCOPE_EXTENSION, DEFAULT_FLATPAK_APP
+    ));
+    Some(DEFAULT_FLATPAK_APP.to_string())
+}
+
+/// Sets the `gamescope_flatpak` app from config; empty disables detection.
+pub(crate) fn init_gamescope_flatpak(app: Option<String>) {
+    if let Some(app) = app {
+        let _ = GAMESCOPE_FLATPAK.set(Some(app).filter(|a| !a.is_empty()));
+    }
+}
+
+/// App id whose sandbox runs gamescope, if gamescope is not a host binary.
+pub(crate) fn gamescope_flatpak() -> Option<&'static str> {
+    GAMESCOPE_FLATPAK
+        .get_or_init(detect_gamescope_flatpak)
+        .as_deref()
+}
+
+/// Whether the game command runs in a Flatpak sandbox, where its windows and processes do not
+/// carry host PIDs that hyprfinity can follow.
+pub(crate) fn game_is_sandboxed(args: &[String]) -> bool {
+    let game = match args.iter().position(|a| a == "--") {
+        Some(i) => &args[i + 1..],
+        None => return false,
+    };
+    game.first()
+        .and_then(|program| program.rsplit('/').next())
+        .is_some_and(|program| program == "flatpak")
+}
+
+/// Host directory a path argument needs exposed inside the sandbox, if any. The runtime brings
+/// its own /usr, so only paths elsewhere (home, game libraries, mounts) are shared.
+fn shared_dir(arg: &str) -> Option<String> {
+    let path = Path::new(arg);
+    if !path.is_absolute()
+        || ["/usr", "/bin", "/lib", "/etc", "/proc", "/dev", "/run"]
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+    {
+        return None;
+    }
+    let dir = if path.is_dir() { path } else { path.parent()? };
+    (dir != Path::new("/") && dir.exists()).then(|| dir.display().to_string())
+}
+
+/// `flatpak run` arguments for `gamescope <args>` inside `app`: the environment becomes
+/// `--env=` options, since flatpak resets parts of it, and host paths in the game command are
+/// shared with `--filesystem=`.
+fn flatpak_run_args(app: &str, env: &[(String, String)], args: &[String]) -> Vec<String> {
+    let mut run = vec!["run".to_string(), "--command=gamescope".to_string()];
+    run.extend(
+        env.iter()
+            .map(|(key, value)| format!("--env={}={}", key, value)),
+    );
+    let game_start = args
+        .iter()
+        .position(|a| a == "--")
+        .map_or(args.len(), |i| i + 1);
+    let mut shared = Vec::new();
+    for dir in args[game_start..].iter().filter_map(|arg| shared_dir(arg)) {
+        if !shared.contains(&dir) {
+            shared.push(dir);
+        }
+    }
+    run.extend(shared.iter().map(|dir| format!("--filesystem={}", dir)));
+    run.push(app.to_string());
+    run.extend(args.iter().cloned());
+    run
+}
+
+/// Runs gamescope from the Flatpak extension inside `gamescope_flatpak`'s sandbox.
+pub(crate) struct FlatpakGamescope;
+
+impl FlatpakGamescope {
+    fn command(&self, args: &[&str]) -> Command {
+        let mut cmd = Command::new("flatpak");
+        cmd.args([
+            "run",
+            "--command=gamescope",
+            gamescope_flatpak().unwrap_or_default(),
+        ])
+        .args(args);
+        cmd
+    }
+}
+
+impl GamescopeLauncher for FlatpakGamescope {
+    fn help(&self) -> Option<String> {
+        gamescope_help_output(&mut self.command(&["--help"]))
+    }
+
+    fn version(&self) -> Option<String> {
+        gamescope_version_line(&mut self.command(&["--version"]))
+    }
+
+    fn spawn(&self, cmd: &mut Command) -> std::io::Result<Child> {
+        let args = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        let env = cmd
+            .get_envs()
+            .filter_map(|(key, value)| {
+                Some((
+                    key.to_string_lossy().to_string(),
+                    value?.to_string_lossy().to_string(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let run = flatpak_run_args(gamescope_flatpak().unwrap_or_default(), &env, &args);
+        debug_log_line(&format!("flatpak gamescope: flatpak {:?}", run));
+        // The session reads gamescope's stderr tail for failure hints.
+        Command::new("flatpak")
+            .args(&run)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatpak_run_args_pass_env_and_share_game_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let game = dir.path().join("game.x86_64");
+        std::fs::write(&game, "").unwrap();
+        let args = vec![
+            "-W".to_string(),
+            "5760".to_string(),
+            "--".to_string(),
+            game.display().to_string(),
+            "/usr/bin/true".to_string(),
+        ];
+        let run = flatpak_run_args(
+            "com.valvesoftware.Steam",
+            &[("MANGOHUD_CONFIG".to_string(), "fps".to_string())],
+            &args,
+        );
+        let mut expected = vec![
+            "run".to_string(),
+            "--command=gamescope".to_string(),
+            "--env=MANGOHUD_CONFIG=fps".to_string(),
+            format!("--filesystem={}", dir.path().display()),
+            "com.valvesoftware.Steam".to_string(),
+        ];
+        expected.extend(args);
+        assert_eq!(run, expected);
+
+        let words = |s: &str| s.split(' ').map(str::to_string).collect::<Vec<_>>();
+        assert!(game_is_sandboxed(&words(
+            "-r 60 -- /usr/bin/flatpak run com.heroicgameslauncher.hgl"
+        )));
+        assert!(!game_is_sandboxed(&words("-r 60 -- steam -applaunch 620")));
+        assert!(!game_is_sandboxed(&words("flatpak")));
+    }
+}
@@ -0,0 +1,268 @@
+use crate::config::steam_appid;
+use crate::debuglog::debug_log_line;
+use crate::i18n::{Msg, tr, trf};
+use crate::output::{info, warn};
+use std::path::Path;
+
+/// How a game copes with a render size wider than 16:9.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WideSupport {
+    /// Renders the full width; the note says how to get the best of it.
+    Native,
+    /// Needs a community patch or specific settings for spans wider than 16:9.
+    NeedsFix,
+    /// Renders 16:9 and pillarboxes the rest of the span.
+    Locked,
+}
+
+/// A game with known behaviour on wide spans, recognised by Steam app id or executable.
+#[derive(Debug)]
+struct KnownGame {
+    name: &'static str,
+    steam_appids: &'static [u32],
+    exes: &'static [&'static str],
+    support: WideSupport,
+    note: &'static str,
+}
+
+/// Community notes for games whose wide-span behaviour is well known. Kept small on purpose:
+/// entries should say what the game does, not which settings to prefer.
+const KNOWN_GAMES: &[KnownGame] = &[
+    KnownGame {
+        name: "Elden Ring",
+        steam_appids: &[1245620],
+        exes: &["eldenring.exe"],
+        support: WideSupport::Locked,
+        note: "renders at 16:9 and pillarboxes wider spans. Community ultrawide mods patch the \
+               executable and need Easy Anti-Cheat off, so they are for offline play only",
+    },
+    KnownGame {
+        name: "Sekiro: Shadows Die Twice",
+        steam_appids: &[814380],
+        exes: &["sekiro.exe"],
+        support: WideSupport::NeedsFix,
+        note: "is locked to 16:9; wider aspect ratios need a community patch of the executable",
+    },
+    KnownGame {
+        name: "Dark Souls III",
+        steam_appids: &[374320],
+        exes: &["DarkSoulsIII.exe"],
+        support: WideSupport::Locked,
+        note: "renders at 16:9 and pillarboxes wider spans; widescreen fixes are community hex \
+               patches",
+    },
+    KnownGame {
+        name: "Assetto Corsa",
+        steam_appids: &[244210],
+        exes: &["acs.exe", "AssettoCorsa.exe"],
+        support: WideSupport::Native,
+        note: "has a triple-screen mode in its video settings for correct side-monitor perspective",
+    },
+    KnownGame {
+        name: "Euro Truck Simulator 2",
+        steam_appids: &[227300],
+        exes: &["eurotrucks2.exe", "eurotrucks2"],
+        support: WideSupport::Native,
+        note: "supports multi-monitor setups natively; set the side screens' angles in its \
+               multi-monitor configuration for correct perspective",
+    },
+];
+
+/// The known game a command launches, by Steam app id or executable file name.
+fn detect_game(command: &[String]) -> Option<&'static KnownGame> {
+    if let Some(appid) = steam_appid(command)
+        && let Some(game) = KNOWN_GAMES.iter().find(|g| g.steam_appids.contains(&appid))
+    {
+        return Some(game);
+    }
+    command.iter().find_map(|word| {
+        // Windows paths from Proton or Wine commands use backslashes.
+        let name = word.rsplit(['/', '\\']).next()?;
+        KNOWN_GAMES
+            .iter()
+            .find(|g| g.exes.iter().any(|exe| exe.eq_ignore_ascii_case(name)))
+    })
+}
+
+/// Engines that take the render size as command-line arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Engine {
+    Source,
+    Unity,
+}
+
+impl Engine {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Source => "Source",
+            Self::Unity => "Unity",
+        }
+    }
+
+    /// Width and height flags, in the order they are appended.
+    fn size_flags(self) -> [&'static str; 2] {
+        match self {
+            Self::Source => ["-w", "-h"],
+            Self::Unity => ["-screen-width", "-screen-height"],
+        }
+    }
+}
+
+/// Valve's own Source engine games, which are often launched through Steam.
+const SOURCE_APPIDS: &[u32] = &[220, 240, 320, 340, 380, 400, 420, 440, 500, 550, 620, 4000];
+const SOURCE_EXES: &[&str] = &[
+    "hl2.exe",
+    "hl2_linux",
+    "hl2.sh",
+    "portal2.exe",
+    "portal2_linux",
+    "left4dead2.exe",
+    "left4dead2_linux",
+];
+
+/// A Unity player ships `UnityPlayer.dll`/`.so` or a `<name>_Data` folder next to the
+/// executable. Only local paths can be checked, so Unity games launched through Steam are missed.
+fn is_unity_player(word: &str) -> bool {
+    let path = Path::new(word);
+    let (Some(dir), Some(stem)) = (path.parent(), path.file_stem()) else {
+        return false;
+    };
+    path.is_file()
+        && (dir.join("UnityPlayer.dll").is_file()
+            || dir.join("UnityPlayer.so").is_file()
+            || dir
+                .join(format!("{}_Data", stem.to_string_lossy()))
+                .is_dir())
+}
+
+fn detect_engine(command: &[String]) -> Option<Engine> {
+    if steam_appid(command).is_some_and(|appid| SOURCE_APPIDS.contains(&appid)) {
+        return Some(Engine::Source);
+    }
+    command.iter().find_map(|word| {
+        let name = word.rsplit(['/', '\\']).next()?;
+        if SOURCE_EXES.iter().any(|exe| exe.eq_ignore_ascii_case(name)) {
+            Some(Engine::Source)
+        } else {
+            is_unity_player(word).then_some(Engine::Unity)
+        }
+    })
+}
+
+/// Appends the detected engine's resolution arguments for `internal` to the game command after
+/// `--`, unless the command already passes either of them.
+pub(crate) fn append_engine_hints(
+    args: Vec<String>,
+    (internal_width, internal_height): (i32, i32),
+) -> Vec<String> {
+    let Some(idx) = args.iter().position(|a| a == "--") else {
+        return args;
+    };
+    let Some(engine) = detect_engine(&args[idx + 1..]) else {
+        info(tr(Msg::NoEngineDetected));
+        return args;
+    };
+    let flags = engine.size_flags();
+    if args[idx + 1..]
+        .iter()
+        .any(|arg| flags.contains(&arg.as_str()))
+    {
+        debug_log_line(&format!(
+            "engine_hints: {} size arguments already set",
+            engine.as_str()
+        ));
+        return args;
+    }
+    info(&trf(
+        Msg::PassingEngineSize,
+        &[
+            &engine.as_str(),
+            &flags[0],
+            &internal_width,
+            &flags[1],
+            &internal_height,
+        ],
+    ));
+    let mut args = args;
+    args.extend([
+        flags[0].to_string(),
+        internal_width.to_string(),
+        flags[1].to_string(),
+        internal_height.to_string(),
+    ]);
+    args
+}
+
+/// What is known about running `command` on a span of `width`x`height`; `None` for unknown games
+/// and for spans no wider than 16:9.
+pub(crate) fn wide_support_note(
+    command: &[String],
+    (width, height): (i32, i32),
+) -> Option<(WideSupport, String)> {
+    if height <= 0 || i64::from(width) * 9 <= i64::from(height) * 16 {
+        return None;
+    }
+    let game = detect_game(command)?;
+    let aspect = (i64::from(width) * 9 + i64::from(height) / 2) / i64::from(height);
+    Some((
+        game.support,
+        format!("{} {} (this span is {}:9).", game.name, game.note, aspect),
+    ))
+}
+
+/// Surfaces the community note for the launched game, warning when it needs a fix or will not
+/// fill the span.
+pub(crate) fn report_wide_support(command: &[String], span: (i32, i32)) {
+    match wide_support_note(command, span) {
+        Some((WideSupport::Native, note)) => info(&note),
+        Some((_, note)) => warn(&note),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(s: &str) -> Vec<String> {
+        s.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn wide_support_note_matches_appid_or_exe_on_wide_spans_only() {
+        let (support, note) =
+            wide_support_note(&words("steam -applaunch 1245620"), (5760, 1080)).unwrap();
+        assert_eq!(support, WideSupport::Locked);
+        assert!(note.starts_with("Elden Ring renders at 16:9"));
+        assert!(note.ends_with("(this span is 48:9)."));
+
+        let proton = words("proton run C:\\Games\\AC\\ACS.EXE");
+        assert_eq!(
+            wide_support_note(&proton, (7680, 1440)).map(|(s, _)| s),
+            Some(WideSupport::Native)
+        );
+        assert!(wide_support_note(&proton, (1920, 1080)).is_none());
+        assert!(wide_support_note(&words("steam -applaunch 620"), (5760, 1080)).is_none());
+    }
+
+    #[test]
+    fn append_engine_hints_detects_source_and_unity_and_keeps_user_sizes() {
+        assert_eq!(
+            append_engine_hints(words("-f -- steam -applaunch 620"), (4320, 810)),
+            words("-f -- steam -applaunch 620 -w 4320 -h 810")
+        );
+        let set = words("-- steam -applaunch 620 -w 1920");
+        assert_eq!(append_engine_hints(set.clone(), (4320, 810)), set);
+
+        let dir = tempfile::tempdir().unwrap();
+        let game = dir.path().join("Game.x86_64");
+        std::fs::write(&game, "").unwrap();
+        let unity = vec!["--".to_string(), game.to_string_lossy().to_string()];
+        assert_eq!(append_engine_hints(unity.clone(), (4320, 810)), unity);
+        std::fs::create_dir(dir.path().join("Game_Data")).unwrap();
+        assert_eq!(
+            append_engine_hints(unity.clone(), (4320, 810))[2..],
+            words("-screen-width 4320 -screen-height 810")
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use crate::debuglog::debug_log_line;
+use crate::i18n::{Msg, tr, trf};
+use crate::output::{debug, error, info};
+use crate::types::{GamepadAction, GamepadSettings};
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const EV_KEY: u16 = 0x01;
+const JOYSTICK_BY_ID_DIR: &str = "/dev/input/by-id";
+
+// struct input_event is a timeval followed by type/code/value; timeval is two longs.
+#[cfg(target_pointer_width = "64")]
+const INPUT_EVENT_SIZE: usize = 24;
+#[cfg(not(target_pointer_width = "64"))]
+const INPUT_EVENT_SIZE: usize = 16;
+
+pub(crate) fn button_code(name: &str) -> Option<u16> {
+    let name = name.trim().to_lowercase();
+    let name = name.strip_prefix("btn_").unwrap_or(&name);
+    let code = match name {
+        "a" | "south" => 0x130,
+        "b" | "east" => 0x131,
+        "x" | "north" => 0x133,
+        "y" | "west" => 0x134,
+        "tl" | "lb" => 0x136,
+        "tr" | "rb" => 0x137,
+        "tl2" | "lt" => 0x138,
+        "tr2" | "rt" => 0x139,
+        "select" | "back" => 0x13a,
+        "start" => 0x13b,
+        "mode" | "guide" => 0x13c,
+        "thumbl" | "l3" => 0x13d,
+        "thumbr" | "r3" => 0x13e,
+        _ => return None,
+    };
+    Some(code)
+}
+
+fn parse_key_event(buf: &[u8]) -> Option<(u16, i32)> {
+    if buf.len() < INPUT_EVENT_SIZE {
+        return None;
+    }
+    let off = INPUT_EVENT_SIZE - 8;
+    let ev_type = u16::from_ne_bytes([buf[off], buf[off + 1]]);
+    if ev_type != EV_KEY {
+        return None;
+    }
+    let code = u16::from_ne_bytes([buf[off + 2], buf[off + 3]]);
+    let value = i32::from_ne_bytes([buf[off + 4], buf[off + 5], buf[off + 6], buf[off + 7]]);
+    Some((code, value))
+}
+
+#[derive(Debug, Default)]
+struct ChordTracker {
+    chord: BTreeSet<u16>,
+    pressed: BTreeSet<u16>,
+    held_since: Option<Instant>,
+}
+
+impl ChordTracker {
+    fn new(chord: BTreeSet<u16>) -> Self {
+        ChordTracker {
+            chord,
+            ..Default::default()
+        }
+    }
+
+    fn handle(&mut self, code: u16, value: i32, now: Instant) {
+        match value {
+            0 => {
+                self.pressed.remove(&code);
+            }
+            1 => {
+                self.pressed.insert(code);
+            }
+            _ => return,
+        }
+        let complete = !self.chord.is_empty() && self.chord.is_subset(&self.pressed);
+        self.held_since = match (complete, self.held_since) {
+            (true, Some(since)) => Some(since),
+            (true, None) => Some(now),
+            (false, _) => None,
+        };
+    }
+}
+
+pub(crate) struct GamepadWatcher {
+    hold: Duration,
+    slots: Vec<Arc<Mutex<Option<Instant>>>>,
+    last_fired: Option<Instant>,
+}
+
+impl GamepadWatcher {
+    // Fires once per hold; the chord has to be released and held again to fire twice.
+    pub(crate) fn triggered(&mut self) -> bool {
+        let held = self
+            .slots
+            .iter()
+            .filter_map(|slot| slot.lock().ok().and_then(|since| *since))
+            .find(|since| since.elapsed() >= self.hold && Some(*since) != self.last_fired);
+        if held.is_some() {
+            self.last_fired = held;
+        }
+        held.is_some()
+    }
+}
+
+fn joystick_devices() -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir(JOYSTICK_BY_ID_DIR) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with("-event-joystick"))
+        })
+        .collect()
+}
+
+pub(crate) fn start_gamepad_watcher(
+    settings: &GamepadSettings,
+    verbose: bool,
+) -> Option<GamepadWatcher> {
+    if settings.chord.is_empty() {
+        return None;
+    }
+    let mut chord = BTreeSet::new();
+    for name in &settings.chord {
+        match button_code(name) {
+            Some(code) => {
+                chord.insert(code);
+            }
+            None => {
+                error(&trf(Msg::UnknownGamepadButton, &[&name]));
+                return None;
+            }
+        }
+    }
+
+    let devices = joystick_devices();
+    if devices.is_empty() {
+        info(tr(Msg::NoGamepads));
+        return None;
+    }
+
+    let mut slots = Vec::new();
+    for path in devices {
+        let mut file = match std::fs::File::open(&path) {
+            Ok(f) => f,
+            Err(e) => {
+                if verbose {
+                    debug(&format!("Cannot read gamepad {}: {}", path.display(), e));
+                }
+                continue;
+            }
+        };
+        debug_log_line(&format!("watching gamepad {}", path.display()));
+        let slot = Arc::new(Mutex::new(None));
+        slots.push(Arc::clone(&slot));
+        let mut tracker = ChordTracker::new(chord.clone());
+        thread::spawn(move || {
+            let mut buf = [0_u8; INPUT_EVENT_SIZE];
+            while file.read_exact(&mut buf).is_ok() {
+                if let Some((code, value)) = parse_key_event(&buf) {
+                    tracker.handle(code, value, Instant::now());
+                    if let Ok(mut since) = slot.lock() {
+                        *since = tracker.held_since;
+                    }
+                }
+            }
+        });
+    }
+
+    if slots.is_empty() {
+        error(tr(Msg::GamepadsUnreadable));
+        return None;
+    }
+    let msg = match settings.action {
+        GamepadAction::Exit => Msg::GamepadChordExit,
+        GamepadAction::Restart => Msg::GamepadChordRestart,
+    };
+    let hold = format!("{:.1}", settings.hold_ms as f32 / 1000.0);
+    info(&trf(msg, &[&settings.chord.join("+"), &hold]));
+    Some(GamepadWatcher {
+        hold: Duration::from_millis(settings.hold_ms),
+        slots,
+        last_fired: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_event(code: u16, value: i32) -> [u8; INPUT_EVENT_SIZE] {
+        let mut buf = [0_u8; INPUT_EVENT_SIZE];
+        let off = INPUT_EVENT_SIZE - 8;
+        buf[off..off + 2].copy_from_slice(&EV_KEY.to_ne_bytes());
+        buf[off + 2..off + 4].copy_from_slice(&code.to_ne_bytes());
+        buf[off + 4..off + 8].copy_from_slice(&value.to_ne_bytes());
+        buf
+    }
+
+    #[test]
+    fn chord_tracker_requires_all_buttons_held() {
+        let chord = ["guide", "start"]
+            .iter()
+            .filter_map(|n| button_code(n))
+            .collect::<BTreeSet<_>>();
+        let mut tracker = ChordTracker::new(chord);
+        let t0 = Instant::now();
+
+        let (code, value) = parse_key_event(&key_event(0x13c, 1)).unwrap();
+        tracker.handle(code, value, t0);
+        assert!(tracker.held_since.is_none());
+
+        tracker.handle(0x13b, 1, t0);
+        assert_eq!(tracker.held_since, Some(t0));
+        tracker.handle(0x13b, 2, t0 + Duration::from_millis(500));
+        assert_eq!(tracker.held_since, Some(t0));
+
+        tracker.handle(0x13c, 0, t0);
+        assert!(tracker.held_since.is_none());
+    }
+
+    #[test]
+    fn button_code_accepts_aliases() {
+        assert_eq!(button_code("BTN_MODE"), button_code("guide"));
+        assert_eq!(button_code("Start"), Some(0x13b));
+        assert_eq!(button_code("turbo"), None);
+    }
+}
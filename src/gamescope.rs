@@ -1,21 +1,68 @@
 use crate::MyError;
+use crate::autotune::detect_auto_tune_profile;
+use crate::backend::gamescope_launcher;
+use crate::companion::{companion_area, start_companion, stop_companion};
+use crate::compat::{compat_wrapper_binary, wrap_game_command};
+use crate::compositor::{WindowRect, compositor};
+use crate::config::{Config, LaunchSettings, configured_game_command};
 use crate::debuglog::debug_log_line;
+use crate::embedded::run_embedded_session;
+use crate::flatpak::{game_is_sandboxed, gamescope_flatpak};
+use crate::game_db::{append_engine_hints, report_wide_support};
+use crate::gamepad::start_gamepad_watcher;
+use crate::gamescope_args::GamescopeArgs;
+use crate::gamescope_caps::{adapt_gamescope_args, gamescope_has_sys_nice, probe_gamescope_flags};
+use crate::hooks::{HookContext, HookStage, planned_hooks, run_hooks};
+use crate::hypr_events::start_reload_watcher;
 use crate::hyprland::{
-    bind_exists, compute_monitor_span, execute_hyprctl, fit_window_to_span, get_monitors,
-    get_primary_window_selector, wait_for_client_pid,
+    WindowMatcher, excluded_monitor_names, execute_hyprctl, fit_window_to_span, get_monitors,
+    get_option_int, get_primary_window_selector, reassert_pin, retry_delay, select_span_monitors,
+    span_layout_warnings, span_rect, span_refresh_rate, wait_for_client,
+};
+use crate::i18n::{Msg, tr, trf};
+use crate::metrics::{
+    count_fit_failure, count_gamescope_restart, count_reflow, mark_session_up,
+    start_metrics_server, write_metrics_textfile,
+};
+use crate::nightlight::{NightlightRestore, pause_nightlight, resume_nightlight};
+use crate::output::{debug, error, info, warn};
+use crate::per_monitor::{
+    per_monitor_launcher_pid, per_monitor_session_running, stop_per_monitor_session,
 };
 use crate::picker::{pick_desktop_app_command, pick_internal_size};
-use crate::util::{clamp_i32, command_in_path, even_floor, scaled_dimensions};
+use crate::plugin::{lock_window_geometry, plugin_available};
+use crate::proctree::game_processes;
+use crate::progress::{DETACHED_ENV_VAR, Progress};
+use crate::recording::{
+    RecorderState, parse_hotkey, save_replay, start_recording, stop_recording, write_obs_region,
+};
+use crate::stats::{SessionRecord, latest_game_command, record_session, unix_now};
+use crate::suspend::start_sleep_watcher;
+use crate::trace::{finish_trace, record_gamescope};
+use crate::types::{
+    CompatSettings, DEFAULT_WINDOW_CLASS, ExcludedMonitors, FitSettings, GamepadAction, HotkeyBind,
+    MAX_SUPERSAMPLE_SCALE, Monitor, PickerFallback, RenderScale, SpanStrategy, SpanTarget,
+    TuningFlags, WindowProbe,
+};
+use crate::util::{
+    clamp_i32, command_in_path, even_floor, scaled_dimensions_within, stdio_is_interactive,
+};
+use crate::virtual_output::teardown_virtual_output;
 use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
 use std::error::Error;
-use std::process::{Command, Stdio};
-use std::sync::Arc;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, ChildStderr, Command, ExitStatus, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct GamescopeState {
+    #[serde(default)]
+    version: u32,
     gamescope_pid: u32,
     span_x: i32,
     span_y: i32,
@@ -27,137 +74,450 @@ struct GamescopeState {
     idle_inhibit_pid: Option<u32>,
     #[serde(default)]
     exit_hotkey: Option<ExitHotkey>,
+    #[serde(default)]
+    game: String,
+    #[serde(default)]
+    started_at: u64,
+    #[serde(default)]
+    recorder: Option<RecorderState>,
+    #[serde(default)]
+    replay_hotkey: Option<ExitHotkey>,
+    #[serde(default)]
+    menu_hotkey: Option<ExitHotkey>,
+    #[serde(default)]
+    osd_hotkey: Option<ExitHotkey>,
+    #[serde(default)]
+    launcher_pid: Option<u32>,
+    #[serde(default)]
+    follow_mouse_restore: Option<i64>,
+    #[serde(default)]
+    blanked_monitors: Vec<String>,
+    #[serde(default)]
+    dim_inactive_restore: Option<i64>,
+    #[serde(default)]
+    nightlight: Option<NightlightRestore>,
+    #[serde(default)]
+    companion_pid: Option<u32>,
+}
+
+/// What `hyprfinity restart` / `set-scale` asks the running session to do.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RestartRequest {
+    #[serde(default)]
+    render_scale: Option<f32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-struct ExitHotkey {
+pub(crate) struct ExitHotkey {
     mods: String,
     key: String,
 }
 
 const GAMESCOPE_STATE_FILE_NAME: &str = "hyprfinity_gamescope_state.json";
-const DEFAULT_EXIT_HOTKEY_MODS: &str = "SUPER SHIFT";
-const DEFAULT_EXIT_HOTKEY_KEY: &str = "F12";
+const RESTART_REQUEST_FILE_NAME: &str = "hyprfinity_restart_request";
+const EXIT_PENDING_FILE_NAME: &str = "hyprfinity_exit_pending";
+const EXIT_CONFIRM_GRACE: Duration = Duration::from_secs(3);
+const DETACHED_LOG_FILE_NAME: &str = "hyprfinity_detached.log";
+// Bump when GamescopeState changes shape and add a step to migrate_gamescope_state.
+const GAMESCOPE_STATE_VERSION: u32 = 2;
+const STDERR_TAIL_LINES: usize = 40;
+pub(crate) const GAME_EXIT_GRACE_TICKS: u32 = 3;
+// Outputs reconnect a few seconds after resume; how long to wait for them, one poll a second.
+const RESUME_MONITOR_POLLS: u32 = 5;
 
-fn get_gamescope_state_file_path() -> Result<std::path::PathBuf, Box<dyn Error>> {
+pub(crate) fn get_gamescope_state_file_path() -> Result<std::path::PathBuf, Box<dyn Error>> {
     let temp_dir = std::env::temp_dir();
     Ok(temp_dir.join(GAMESCOPE_STATE_FILE_NAME))
 }
 
 fn save_gamescope_state(state: &GamescopeState) -> Result<(), Box<dyn Error>> {
     let path = get_gamescope_state_file_path()?;
-    let json = serde_json::to_string_pretty(state)?;
-    std::fs::write(&path, json)?;
-    println!("Hyprfinity: Saved Gamescope state to {:?}", path);
+    let mut state = state.clone();
+    state.version = GAMESCOPE_STATE_VERSION;
+    let json = serde_json::to_string_pretty(&state)?;
+    // Write to a sibling temp file and rename so readers never see a partial file.
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, &path)?;
+    info(&trf(Msg::SavedState, &[&path.display()]));
     Ok(())
 }
 
+fn migrate_gamescope_state(mut value: serde_json::Value) -> serde_json::Value {
+    // Files written before versioning carry no `version` field.
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(1);
+    if let Some(obj) = value.as_object_mut() {
+        if version < 2 {
+            obj.entry("waybar_was_stopped")
+                .or_insert(serde_json::Value::Bool(false));
+            obj.entry("gamescope_args")
+                .or_insert(serde_json::Value::Array(Vec::new()));
+        }
+        obj.insert(
+            "version".to_string(),
+            serde_json::Value::from(version.max(u64::from(GAMESCOPE_STATE_VERSION))),
+        );
+    }
+    value
+}
+
+fn parse_gamescope_state(json: &str) -> Result<GamescopeState, Box<dyn Error>> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| {
+        MyError(format!(
+            "Gamescope state file is corrupt or incomplete: {}",
+            e
+        ))
+    })?;
+    let file_version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(1);
+    if file_version > u64::from(GAMESCOPE_STATE_VERSION) {
+        // Newer launcher wrote it; unknown fields are ignored and known ones still apply.
+        debug_log_line(&format!(
+            "state file version {} is newer than {}; reading known fields",
+            file_version, GAMESCOPE_STATE_VERSION
+        ));
+    }
+    let state = serde_json::from_value(migrate_gamescope_state(value)).map_err(|e| {
+        MyError(format!(
+            "Gamescope state file is missing required fields: {}",
+            e
+        ))
+    })?;
+    Ok(state)
+}
+
+pub(crate) fn pid_alive(pid: u32) -> bool {
+    std::path::Path::new(&format!("/proc/{}", pid)).exists()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SessionStatus {
+    /// No state file: nothing is running.
+    Inactive,
+    /// A state file is left behind but its gamescope is gone (or the file is unreadable).
+    Stale,
+    /// Gamescope from the state file is still running.
+    Active(u32),
+}
+
+fn classify_session(
+    state: Option<Result<GamescopeState, Box<dyn Error>>>,
+    alive: impl Fn(u32) -> bool,
+) -> SessionStatus {
+    match state {
+        None => SessionStatus::Inactive,
+        Some(Ok(state)) if alive(state.gamescope_pid) => SessionStatus::Active(state.gamescope_pid),
+        Some(_) => SessionStatus::Stale,
+    }
+}
+
+pub(crate) fn session_status() -> SessionStatus {
+    let state = get_gamescope_state_file_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|json| parse_gamescope_state(&json));
+    classify_session(state, pid_alive)
+}
+
+pub(crate) fn active_session_span() -> Option<(i32, i32, i32, i32)> {
+    let path = get_gamescope_state_file_path().ok()?;
+    let json = std::fs::read_to_string(path).ok()?;
+    let state = parse_gamescope_state(&json).ok()?;
+    if !pid_alive(state.gamescope_pid) {
+        return None;
+    }
+    Some((
+        state.span_x,
+        state.span_y,
+        state.span_width,
+        state.span_height,
+    ))
+}
+
+fn record_finished_session(state: &GamescopeState, exit: &str) {
+    if state.started_at == 0 {
+        return;
+    }
+    let record = SessionRecord {
+        game: state.game.clone(),
+        started_at: state.started_at,
+        ended_at: unix_now().max(state.started_at),
+        args: state.gamescope_args.clone(),
+        span: Some((
+            state.span_x,
+            state.span_y,
+            state.span_width,
+            state.span_height,
+        )),
+        exit: Some(exit.to_string()),
+    };
+    if let Err(e) = record_session(&record) {
+        error(&trf(Msg::RecordPlaytimeFailed, &[&e]));
+    }
+}
+
+fn hook_context(state: &GamescopeState) -> HookContext {
+    HookContext {
+        span: (
+            state.span_x,
+            state.span_y,
+            state.span_width,
+            state.span_height,
+        ),
+        pid: Some(state.gamescope_pid),
+        game: Some(state.game.clone()),
+    }
+}
+
+pub(crate) fn game_command_label(args: &[String]) -> String {
+    match args.iter().position(|a| a == "--") {
+        Some(idx) if idx + 1 < args.len() => {
+            let command = &args[idx + 1..];
+            // Label chained sessions by the game, not the wrapper.
+            match command {
+                [sh, flag, script, _, _, game @ ..]
+                    if sh == "sh" && flag == "-c" && script == CHAIN_SCRIPT && !game.is_empty() =>
+                {
+                    game.join(" ")
+                }
+                _ => command.join(" "),
+            }
+        }
+        _ => "gamescope".to_string(),
+    }
+}
+
 fn load_gamescope_state() -> Result<GamescopeState, Box<dyn Error>> {
     let path = get_gamescope_state_file_path()?;
-    let json = std::fs::read_to_string(&path)?;
-    let state: GamescopeState = serde_json::from_str(&json)?;
-    println!("Hyprfinity: Loaded Gamescope state from {:?}", path);
+    let json = match std::fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(MyError(format!(
+                "No active Gamescope session (state file {:?} not found).",
+                path
+            ))
+            .into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let state = parse_gamescope_state(&json)?;
+    info(&trf(Msg::LoadedState, &[&path.display()]));
     Ok(state)
 }
 
-fn has_arg(args: &[String], flag: &str) -> bool {
-    args.iter().any(|arg| {
-        arg == flag
-            || arg.starts_with(&format!("{flag}="))
-            || (flag.len() == 2 && arg.starts_with(flag) && arg.len() > 2)
+pub(crate) fn arg_value(args: &[String], flag: &str) -> Option<String> {
+    GamescopeArgs::parse(args).value(flag).map(str::to_string)
+}
+
+pub(crate) fn substitute_command_placeholders(
+    args: Vec<String>,
+    values: &[(&str, String)],
+) -> Vec<String> {
+    let Some(idx) = args.iter().position(|a| a == "--") else {
+        return args;
+    };
+    args.into_iter()
+        .enumerate()
+        .map(|(i, arg)| {
+            if i <= idx || !arg.contains('{') {
+                return arg;
+            }
+            values.iter().fold(arg, |acc, (name, value)| {
+                acc.replace(&format!("{{{name}}}"), value)
+            })
+        })
+        .collect()
+}
+
+/// Passes the configured or derived rate as `-r`. An explicit `-r` wins, with a warning when it
+/// caps the session below that rate.
+pub(crate) fn apply_refresh_rate(args: Vec<String>, refresh: Option<i32>) -> Vec<String> {
+    match (arg_value(&args, "-r"), refresh) {
+        (None, Some(refresh)) => {
+            debug_log_line(&format!("using refresh rate {} for -r", refresh));
+            ensure_gamescope_flag_value(args, "-r", &refresh.to_string())
+        }
+        (Some(explicit), Some(refresh)) => {
+            if explicit.parse::<i32>().is_ok_and(|r| r < refresh) {
+                warn(&trf(Msg::RefreshCapped, &[&explicit, &refresh]));
+            }
+            args
+        }
+        (_, None) => args,
+    }
+}
+
+/// Value for `{refresh}`: the gamescope `-r` rate, or an empty string (with a warning when the
+/// game command uses it) so the literal token never reaches the game.
+pub(crate) fn refresh_placeholder(args: &[String]) -> String {
+    arg_value(args, "-r").unwrap_or_else(|| {
+        let in_command = args
+            .iter()
+            .skip_while(|a| *a != "--")
+            .any(|a| a.contains("{refresh}"));
+        if in_command {
+            warn(tr(Msg::RefreshUnknown));
+        }
+        String::new()
     })
 }
 
-fn build_gamescope_args_with_internal(
+pub(crate) fn build_gamescope_args_with_internal(
     args: &[String],
     span_width: i32,
     span_height: i32,
     internal_width: i32,
     internal_height: i32,
+    allow_supersample: bool,
+    integer_scale: bool,
 ) -> Vec<String> {
-    let mut pre: Vec<String> = Vec::new();
-    let mut post: Vec<String> = Vec::new();
-
-    if let Some(idx) = args.iter().position(|a| a == "--") {
-        pre.extend(args[..idx].iter().cloned());
-        post.extend(args[idx..].iter().cloned());
-    } else {
-        pre.extend(args.iter().cloned());
+    let mut parsed = GamescopeArgs::parse(args);
+    // Config and command-line gamescope_args are concatenated, so a flag can appear twice.
+    for conflict in parsed.dedup() {
+        warn(&trf(Msg::ConflictingArgs, &[&conflict]));
     }
-
-    let has_output_w = has_arg(&pre, "-W") || has_arg(&pre, "--output-width");
-    let has_output_h = has_arg(&pre, "-H") || has_arg(&pre, "--output-height");
-    let has_nested_w = has_arg(&pre, "-w") || has_arg(&pre, "--nested-width");
-    let has_nested_h = has_arg(&pre, "-h") || has_arg(&pre, "--nested-height");
-
-    if !has_output_w {
-        pre.push("-W".to_string());
-        pre.push(span_width.to_string());
-    }
-    if !has_output_h {
-        pre.push("-H".to_string());
-        pre.push(span_height.to_string());
+    for (flag, derived) in [
+        ("-W", span_width),
+        ("-H", span_height),
+        ("-w", internal_width),
+        ("-h", internal_height),
+    ] {
+        if let Some(explicit) = parsed.set_default(flag, &derived.to_string()) {
+            debug_log_line(&format!(
+                "gamescope {} {} from args overrides derived {}",
+                flag, explicit, derived
+            ));
+        }
     }
-    if !has_nested_w {
-        pre.push("-w".to_string());
-        pre.push(internal_width.to_string());
+    if integer_scale {
+        for (flag, value) in [("-S", "integer"), ("-F", "nearest")] {
+            if let Some(explicit) = parsed.set_default(flag, value) {
+                warn(&trf(
+                    Msg::IntegerScaleArgConflict,
+                    &[&flag, &value, &explicit, &explicit],
+                ));
+            }
+        }
     }
-    if !has_nested_h {
-        pre.push("-h".to_string());
-        pre.push(internal_height.to_string());
+    for correction in parsed.correct_sizes(
+        (span_width, span_height),
+        (internal_width, internal_height),
+        allow_supersample,
+    ) {
+        warn(&trf(Msg::AdjustedSize, &[&correction]));
     }
+    parsed.into_args()
+}
 
-    pre.extend(post);
-    pre
+/// Adds the switch `flag` before the game command unless it is already set.
+pub(crate) fn ensure_gamescope_flag(args: Vec<String>, flag: &str) -> Vec<String> {
+    let mut parsed = GamescopeArgs::parse(&args);
+    parsed.set_default_switch(flag);
+    parsed.into_args()
 }
 
-fn ensure_gamescope_flag(args: Vec<String>, flag: &str) -> Vec<String> {
-    if has_arg(&args, flag) {
-        return args;
+pub(crate) fn apply_tuning_flags(args: Vec<String>, tuning: &TuningFlags) -> Vec<String> {
+    if tuning.rt_priority && gamescope_has_sys_nice() == Some(false) {
+        warn(tr(Msg::RtPriorityNoCap));
     }
+    tuning
+        .gamescope_flags()
+        .into_iter()
+        .fold(args, ensure_gamescope_flag)
+}
 
-    let mut pre: Vec<String> = Vec::new();
-    let mut post: Vec<String> = Vec::new();
+/// Replaces any nested size flags with `-w`/`-h` for `internal`.
+fn with_internal_size(args: Vec<String>, (width, height): (i32, i32)) -> Vec<String> {
+    let mut parsed = GamescopeArgs::parse(&args);
+    parsed.remove("-w");
+    parsed.remove("-h");
+    parsed.set_default("-w", &width.to_string());
+    parsed.set_default("-h", &height.to_string());
+    parsed.into_args()
+}
 
-    if let Some(idx) = args.iter().position(|a| a == "--") {
-        pre.extend(args[..idx].iter().cloned());
-        post.extend(args[idx..].iter().cloned());
-    } else {
-        pre.extend(args.iter().cloned());
+/// Adds `flag value` before the game command unless the flag is already set.
+pub(crate) fn ensure_gamescope_flag_value(
+    args: Vec<String>,
+    flag: &str,
+    value: &str,
+) -> Vec<String> {
+    let mut parsed = GamescopeArgs::parse(&args);
+    parsed.set_default(flag, value);
+    parsed.into_args()
+}
+
+/// Largest internal size for an output: the output itself, or `MAX_SUPERSAMPLE_SCALE` times it
+/// when supersampling is allowed.
+pub(crate) fn max_internal_size(
+    output_width: i32,
+    output_height: i32,
+    allow_supersample: bool,
+) -> (i32, i32) {
+    if !allow_supersample {
+        return (output_width, output_height);
     }
+    (
+        even_floor((output_width as f32 * MAX_SUPERSAMPLE_SCALE).round() as i32),
+        even_floor((output_height as f32 * MAX_SUPERSAMPLE_SCALE).round() as i32),
+    )
+}
 
-    pre.push(flag.to_string());
-    pre.extend(post);
-    pre
+/// Internal sizes gamescope can integer-scale to the output without borders: the output divided
+/// by a whole number on both axes, largest first. Only even results are kept, as for every size.
+pub(crate) fn integer_scale_sizes(output_width: i32, output_height: i32) -> Vec<(i32, i32)> {
+    (1..=output_width.min(output_height) / 2)
+        .filter(|n| output_width % n == 0 && output_height % n == 0)
+        .map(|n| (output_width / n, output_height / n))
+        .filter(|(w, h)| w % 2 == 0 && h % 2 == 0)
+        .collect()
 }
 
-fn derive_internal_size(
+/// The largest integer-scaling size that fits within `internal`, or the smallest one when
+/// `internal` is smaller than all of them. `internal` is kept when the output has none.
+pub(crate) fn snap_to_integer_scale(output: (i32, i32), internal: (i32, i32)) -> (i32, i32) {
+    let sizes = integer_scale_sizes(output.0, output.1);
+    sizes
+        .iter()
+        .find(|(w, h)| *w <= internal.0 && *h <= internal.1)
+        .or(sizes.last())
+        .copied()
+        .unwrap_or(internal)
+}
+
+pub(crate) fn derive_internal_size(
     span_width: i32,
     span_height: i32,
-    render_scale: f32,
+    render_scale: RenderScale,
     virtual_width: Option<i32>,
     virtual_height: Option<i32>,
+    allow_supersample: bool,
 ) -> (i32, i32) {
+    let (max_width, max_height) = max_internal_size(span_width, span_height, allow_supersample);
     match (virtual_width, virtual_height) {
         (Some(w), Some(h)) => (
-            even_floor(clamp_i32(w, 2, span_width)),
-            even_floor(clamp_i32(h, 2, span_height)),
+            even_floor(clamp_i32(w, 2, max_width)),
+            even_floor(clamp_i32(h, 2, max_height)),
         ),
         (Some(w), None) => {
-            let w = even_floor(clamp_i32(w, 2, span_width));
+            let w = even_floor(clamp_i32(w, 2, max_width));
             let h = ((w as f32 * span_height as f32) / span_width as f32).round() as i32;
-            (w, even_floor(clamp_i32(h, 2, span_height)))
+            (w, even_floor(clamp_i32(h, 2, max_height)))
         }
         (None, Some(h)) => {
-            let h = even_floor(clamp_i32(h, 2, span_height));
+            let h = even_floor(clamp_i32(h, 2, max_height));
             let w = ((h as f32 * span_width as f32) / span_height as f32).round() as i32;
-            (even_floor(clamp_i32(w, 2, span_width)), h)
+            (even_floor(clamp_i32(w, 2, max_width)), h)
         }
-        (None, None) => scaled_dimensions(span_width, span_height, render_scale),
+        (None, None) => scaled_dimensions_within(
+            span_width,
+            span_height,
+            (render_scale.x, render_scale.y),
+            (max_width, max_height),
+        ),
     }
 }
 
-fn derive_output_size(
+pub(crate) fn derive_output_size(
     span_width: i32,
     span_height: i32,
     output_width: Option<i32>,
@@ -178,13 +538,40 @@ fn derive_output_size(
             let w = ((h as f32 * span_width as f32) / span_height as f32).round() as i32;
             (even_floor(clamp_i32(w, 2, span_width)), h)
         }
-        (None, None) => (span_width, span_height),
+        (None, None) => (span_width.max(2), span_height.max(2)),
+    }
+}
+
+fn non_interactive_game_command(
+    picker_fallback: PickerFallback,
+    default_command: Option<Vec<String>>,
+    recent_command: Option<Vec<String>>,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    match (picker_fallback, default_command) {
+        (PickerFallback::DefaultCommand, Some(cmd)) if !cmd.is_empty() => Ok(cmd),
+        (PickerFallback::DefaultCommand, _) => Err(MyError(
+            "No terminal available for the app picker and picker_fallback = \"default_command\", but default_command is not set.".to_string(),
+        )
+        .into()),
+        (PickerFallback::Recent, _) => recent_command.ok_or_else(|| {
+            MyError(
+                "No terminal available for the app picker and picker_fallback = \"recent\", but no recorded session has a game command.".to_string(),
+            )
+            .into()
+        }),
+        (PickerFallback::Error, _) => Err(MyError(
+            "No terminal available for the app picker. Pass a command after `--`, or set picker_fallback = \"default_command\" in config.".to_string(),
+        )
+        .into()),
     }
 }
 
-fn ensure_game_command(
+pub(crate) fn ensure_game_command(
     mut gamescope_args: Vec<String>,
     pick: bool,
+    picker_fallback: PickerFallback,
+    picker_command: Option<&[String]>,
+    default_command: Option<Vec<String>>,
 ) -> Result<Vec<String>, Box<dyn Error>> {
     let mut need_pick = pick;
     if let Some(idx) = gamescope_args.iter().position(|a| a == "--") {
@@ -196,7 +583,23 @@ fn ensure_game_command(
     }
 
     if need_pick {
-        let cmd = pick_desktop_app_command()?;
+        let cmd = if picker_command.is_some() || stdio_is_interactive() {
+            pick_desktop_app_command(picker_command)?
+        } else {
+            debug_log_line(&format!(
+                "app picker skipped: no terminal (picker_fallback={})",
+                picker_fallback.as_str()
+            ));
+            let recent_command = if picker_fallback == PickerFallback::Recent {
+                latest_game_command()?
+            } else {
+                None
+            };
+            non_interactive_game_command(picker_fallback, default_command, recent_command)?
+        };
+        if let Some(idx) = gamescope_args.iter().position(|a| a == "--") {
+            gamescope_args.truncate(idx);
+        }
         gamescope_args.push("--".to_string());
         gamescope_args.extend(cmd);
     }
@@ -204,7 +607,145 @@ fn ensure_game_command(
     Ok(gamescope_args)
 }
 
-fn maybe_stop_waybar(verbose: bool) -> Result<bool, Box<dyn Error>> {
+/// Environment that makes games treat gamescope's nested display, sized to `internal`, as their
+/// desktop. Variables already set by the user are left alone.
+pub(crate) fn resolution_hint_env(
+    (internal_width, internal_height): (i32, i32),
+    expose_wayland: bool,
+) -> Vec<(String, String)> {
+    // Without --expose-wayland only Xwayland exists inside gamescope; SDL must not try Wayland.
+    let video_driver = if expose_wayland { "wayland" } else { "x11" };
+    [
+        ("ENABLE_GAMESCOPE_WSI", "1".to_string()),
+        ("SDL_VIDEODRIVER", video_driver.to_string()),
+        ("SDL_VIDEO_MINIMIZE_ON_FOCUS_LOSS", "0".to_string()),
+        ("HYPRFINITY_INTERNAL_WIDTH", internal_width.to_string()),
+        ("HYPRFINITY_INTERNAL_HEIGHT", internal_height.to_string()),
+    ]
+    .into_iter()
+    .filter(|(key, _)| std::env::var_os(key).is_none())
+    .map(|(key, value)| (key.to_string(), value))
+    .collect()
+}
+
+// Runs $1 in its own session next to the game ($2...), then takes that whole process group
+// down once the game exits, so gamescope (and exit_with_game) still end with the game.
+const CHAIN_SCRIPT: &str = "setsid sh -c \"$1\" & pre=$!; shift; \"$@\"; status=$?; \
+     kill -TERM -\"$pre\" 2>/dev/null; exit $status";
+
+/// Wraps the game command after `--` so `pre_command` starts first in the same session.
+pub(crate) fn chain_pre_command(args: Vec<String>, pre_command: Option<&[String]>) -> Vec<String> {
+    let Some(pre) = pre_command.filter(|cmd| !cmd.is_empty()) else {
+        return args;
+    };
+    let Some(idx) = args.iter().position(|a| a == "--") else {
+        return args;
+    };
+    let mut args = args;
+    let game = args.split_off(idx + 1);
+    debug_log_line(&format!("chaining pre_command {:?} before {:?}", pre, game));
+    args.extend([
+        "sh".to_string(),
+        "-c".to_string(),
+        CHAIN_SCRIPT.to_string(),
+        "hyprfinity-chain".to_string(),
+        shell_words::join(pre),
+    ]);
+    args.extend(game);
+    args
+}
+
+/// Runs the app picker before settings are resolved when a profile has `match` rules, so the
+/// picked game can select its profile. Returns the picked command, if the picker ran.
+pub(crate) fn pick_game_for_profiles(
+    cli_args: &[String],
+    cli_pick: Option<bool>,
+    config: &Config,
+) -> Result<Option<Vec<String>>, Box<dyn Error>> {
+    let has_rules = config
+        .profiles
+        .iter()
+        .flat_map(|profiles| profiles.values())
+        .any(|profile| profile.profile_match.is_some());
+    let pick = cli_pick.or(config.pick).unwrap_or(false);
+    if !has_rules || (!pick && !configured_game_command(cli_args, config).is_empty()) {
+        return Ok(None);
+    }
+    let args = ensure_game_command(
+        vec!["--".to_string()],
+        true,
+        config.picker_fallback.unwrap_or_default(),
+        config.picker_command.as_deref(),
+        config.default_command.clone(),
+    )?;
+    Ok(Some(args[1..].to_vec()))
+}
+
+/// Makes a game from `pick_game_for_profiles` this launch's default command.
+pub(crate) fn use_picked_game(
+    game: Vec<String>,
+    cli_args: &mut Vec<String>,
+    cli_pick: &mut Option<bool>,
+    config: &mut Config,
+) {
+    // apply_config appends default_command only when no `--` is left in the args.
+    for args in [Some(&mut *cli_args), config.gamescope_args.as_mut()]
+        .into_iter()
+        .flatten()
+    {
+        if let Some(idx) = args.iter().position(|a| a == "--") {
+            args.truncate(idx);
+        }
+    }
+    config.default_command = Some(game);
+    config.pick = Some(false);
+    *cli_pick = Some(false);
+}
+
+/// Gamescope args with the game command wrapped, plus the environment the wrapper needs.
+pub(crate) type CompatLaunch = (Vec<String>, Vec<(String, String)>);
+
+pub(crate) fn apply_compat_tool(
+    args: Vec<String>,
+    compat: &CompatSettings,
+) -> Result<CompatLaunch, Box<dyn Error>> {
+    let Some(tool) = compat.tool else {
+        return Ok((args, Vec::new()));
+    };
+    if let Some(binary) = compat_wrapper_binary(tool)
+        && !command_in_path(binary)
+    {
+        return Err(MyError(format!(
+            "compat_tool = \"{}\" needs `{}` in PATH.",
+            tool.as_str(),
+            binary
+        ))
+        .into());
+    }
+    let Some(idx) = args.iter().position(|a| a == "--") else {
+        return Ok((args, Vec::new()));
+    };
+    let home = std::env::var("HOME").map_err(|_| MyError("HOME is not set.".to_string()))?;
+    let mut args = args;
+    let command = args.split_off(idx + 1);
+    let launch = wrap_game_command(command, compat, std::path::Path::new(&home))?;
+    // Proton refuses to start unless STEAM_COMPAT_DATA_PATH exists; Wine and umu accept an empty prefix.
+    for (key, value) in &launch.env {
+        if key == "STEAM_COMPAT_DATA_PATH" || key == "WINEPREFIX" {
+            std::fs::create_dir_all(value)?;
+        }
+    }
+    debug_log_line(&format!(
+        "compat tool {} wraps game command: {:?} env={:?}",
+        tool.as_str(),
+        launch.command,
+        launch.env
+    ));
+    args.extend(launch.command);
+    Ok((args, launch.env))
+}
+
+pub(crate) fn maybe_stop_waybar(verbose: bool) -> Result<bool, Box<dyn Error>> {
     let status = Command::new("pgrep").args(["-x", "waybar"]).status()?;
     if !status.success() {
         return Ok(false);
@@ -214,12 +755,12 @@ fn maybe_stop_waybar(verbose: bool) -> Result<bool, Box<dyn Error>> {
         return Err(MyError("Failed to stop waybar with pkill -x waybar.".to_string()).into());
     }
     if verbose {
-        println!("Hyprfinity (DEBUG): Stopped waybar for fullscreen coverage.");
+        debug("Stopped waybar for fullscreen coverage.");
     }
     Ok(true)
 }
 
-fn maybe_start_waybar(verbose: bool) -> Result<(), Box<dyn Error>> {
+pub(crate) fn maybe_start_waybar(verbose: bool) -> Result<(), Box<dyn Error>> {
     let status = Command::new("pgrep").args(["-x", "waybar"]).status()?;
     if status.success() {
         return Ok(());
@@ -230,12 +771,12 @@ fn maybe_start_waybar(verbose: bool) -> Result<(), Box<dyn Error>> {
         .spawn()
         .map_err(|e| MyError(format!("Failed to start waybar: {}", e)))?;
     if verbose {
-        println!("Hyprfinity (DEBUG): Restarted waybar.");
+        debug("Restarted waybar.");
     }
     Ok(())
 }
 
-fn maybe_start_idle_inhibit(verbose: bool) -> Result<Option<u32>, Box<dyn Error>> {
+pub(crate) fn maybe_start_idle_inhibit(verbose: bool) -> Result<Option<u32>, Box<dyn Error>> {
     let mut cmd = Command::new("systemd-inhibit");
     cmd.args([
         "--what=idle",
@@ -253,216 +794,1245 @@ fn maybe_start_idle_inhibit(verbose: bool) -> Result<Option<u32>, Box<dyn Error>
     match cmd.spawn() {
         Ok(child) => {
             if verbose {
-                println!(
-                    "Hyprfinity (DEBUG): Started idle inhibitor (systemd-inhibit PID {}).",
+                debug(&format!(
+                    "Started idle inhibitor (systemd-inhibit PID {}).",
                     child.id()
-                );
+                ));
             }
             Ok(Some(child.id()))
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            eprintln!(
-                "Hyprfinity: idle inhibit requested, but systemd-inhibit was not found in PATH."
-            );
+            error(tr(Msg::NoSystemdInhibit));
             Ok(None)
         }
         Err(e) => Err(MyError(format!("Failed to start idle inhibitor: {}", e)).into()),
     }
 }
 
-fn maybe_stop_idle_inhibit(pid: u32, verbose: bool) {
+pub(crate) fn maybe_stop_idle_inhibit(pid: u32, verbose: bool) {
     match Command::new("kill").arg(pid.to_string()).status() {
         Ok(status) => {
             if verbose {
-                println!(
-                    "Hyprfinity (DEBUG): Stopped idle inhibitor PID {} (status {}).",
+                debug(&format!(
+                    "Stopped idle inhibitor PID {} (status {}).",
                     pid, status
-                );
+                ));
             }
         }
         Err(e) => {
             if verbose {
-                eprintln!(
-                    "Hyprfinity (DEBUG): Failed to stop idle inhibitor PID {}: {}",
-                    pid, e
-                );
+                debug(&format!("Failed to stop idle inhibitor PID {}: {}", pid, e));
             }
         }
     }
 }
 
-fn register_exit_hotkey(verbose: bool) -> Result<Option<ExitHotkey>, Box<dyn Error>> {
-    let mods = DEFAULT_EXIT_HOTKEY_MODS;
-    let key = DEFAULT_EXIT_HOTKEY_KEY;
-    if bind_exists(mods, key, verbose)? {
-        println!(
-            "Hyprfinity: Exit hotkey {}+{} is already bound; skipping.",
-            mods, key
-        );
-        return Ok(None);
+fn capture_stderr_tail(stderr: ChildStderr, echo: bool) -> Arc<Mutex<VecDeque<String>>> {
+    let tail = Arc::new(Mutex::new(VecDeque::with_capacity(STDERR_TAIL_LINES)));
+    let sink = Arc::clone(&tail);
+    thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            if echo {
+                debug(&format!("gamescope: {}", line));
+            }
+            if let Ok(mut buf) = sink.lock() {
+                if buf.len() == STDERR_TAIL_LINES {
+                    buf.pop_front();
+                }
+                buf.push_back(line);
+            }
+        }
+    });
+    tail
+}
+
+fn gamescope_failure_hint(stderr: &str) -> Option<&'static str> {
+    let lc = stderr.to_lowercase();
+    if lc.contains("unrecognized option") || lc.contains("invalid option") {
+        Some("gamescope rejected an argument; compare gamescope_args with `gamescope --help`.")
+    } else if lc.contains("cap_sys_nice") {
+        Some(
+            "gamescope lacks CAP_SYS_NICE; drop --rt or run `sudo setcap 'CAP_SYS_NICE=eip' $(command -v gamescope)`.",
+        )
+    } else if lc.contains("drm") && (lc.contains("failed") || lc.contains("no such")) {
+        Some("No usable DRM node; check that your user can access /dev/dri (video/render groups).")
+    } else if lc.contains("vulkan") || lc.contains("vkcreate") {
+        Some(
+            "Vulkan initialization failed; check that your GPU's Vulkan driver (ICD) is installed.",
+        )
+    } else if lc.contains("wayland") && lc.contains("connect") {
+        Some(
+            "gamescope could not connect to the Wayland display; run Hyprfinity from inside Hyprland.",
+        )
+    } else {
+        None
     }
+}
 
-    let binding = format!("{mods}, {key}, exec, hyprfinity gamescope-down");
-    execute_hyprctl(&["keyword", "bind", &binding], verbose)?;
-    println!(
-        "Hyprfinity: Exit hotkey bound: {}+{} (runs `hyprfinity gamescope-down`).",
-        mods, key
-    );
+// A clean exit, or a SIGINT/SIGTERM (Ctrl+C, `gamescope-down`), is a deliberate stop.
+pub(crate) fn gamescope_crashed(status: ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    const SIGINT: i32 = 2;
+    const SIGTERM: i32 = 15;
+    !status.success() && !matches!(status.signal(), Some(SIGINT | SIGTERM))
+}
+
+fn report_startup_failure(status: ExitStatus, stderr_tail: Option<&Arc<Mutex<VecDeque<String>>>>) {
+    // Give the reader thread a moment to drain whatever gamescope wrote before exiting.
+    thread::sleep(Duration::from_millis(100));
+    let lines = stderr_tail
+        .and_then(|tail| {
+            tail.lock()
+                .ok()
+                .map(|buf| buf.iter().cloned().collect::<Vec<_>>())
+        })
+        .unwrap_or_default();
+    error(&trf(Msg::GamescopeStartupExit, &[&status]));
+    debug_log_line(&format!(
+        "gamescope exited during startup status={} stderr_tail={:?}",
+        status, lines
+    ));
+    if lines.is_empty() {
+        error(tr(Msg::GamescopeNoStderr));
+        return;
+    }
+    error(tr(Msg::GamescopeStderrTail));
+    for line in &lines {
+        error(&format!("  {}", line));
+    }
+    if let Some(hint) = gamescope_failure_hint(&lines.join("\n")) {
+        error(&trf(Msg::Hint, &[&hint]));
+    }
+}
+
+fn register_session_hotkey(
+    label: Msg,
+    mods: &str,
+    key: &str,
+    bind: HotkeyBind,
+    subcommand: &str,
+    verbose: bool,
+) -> Result<Option<ExitHotkey>, Box<dyn Error>> {
+    if !compositor().bind_key(
+        mods,
+        key,
+        bind,
+        &format!("hyprfinity {subcommand}"),
+        verbose,
+    )? {
+        info(&trf(Msg::HotkeyAlreadyBound, &[&tr(label), &mods, &key]));
+        return Ok(None);
+    }
+    let bound = if bind == HotkeyBind::Hold {
+        Msg::HotkeyBoundHeld
+    } else {
+        Msg::HotkeyBound
+    };
+    info(&trf(bound, &[&tr(label), &mods, &key, &subcommand]));
     Ok(Some(ExitHotkey {
         mods: mods.to_string(),
         key: key.to_string(),
     }))
 }
 
-fn unregister_exit_hotkey(hotkey: &ExitHotkey, verbose: bool) {
-    let binding = format!("{}, {}", hotkey.mods, hotkey.key);
-    let _ = execute_hyprctl(&["keyword", "unbind", &binding], verbose);
+fn register_menu_hotkey(hotkey: &str, verbose: bool) -> Result<Option<ExitHotkey>, Box<dyn Error>> {
+    let (mods, key) = parse_hotkey(hotkey).ok_or_else(|| {
+        MyError(format!(
+            "Invalid quick_menu_hotkey `{}` (expected \"MODS, KEY\").",
+            hotkey
+        ))
+    })?;
+    register_session_hotkey(
+        Msg::HotkeyQuickMenu,
+        &mods,
+        &key,
+        HotkeyBind::Press,
+        "quick-menu",
+        verbose,
+    )
 }
 
-#[allow(clippy::too_many_arguments)]
-pub(crate) fn gamescope_up(
-    gamescope_args: &[String],
-    startup_timeout_secs: u64,
-    no_pin: bool,
-    pick: bool,
-    idle_inhibit: bool,
-    hide_waybar: bool,
-    pick_size: bool,
-    overlay_enabled: bool,
-    mangohud_config: Option<String>,
-    render_scale: f32,
-    virtual_width: Option<i32>,
-    virtual_height: Option<i32>,
-    output_width: Option<i32>,
-    output_height: Option<i32>,
-    verbose: bool,
-) -> Result<(), Box<dyn Error>> {
-    debug_log_line("gamescope_up begin");
-    let mut waybar_was_stopped = false;
-    let mut exit_hotkey: Option<ExitHotkey> = None;
-    let mut idle_inhibit_pid: Option<u32> = None;
-    let mut overlay_enabled = overlay_enabled;
+fn register_osd_hotkey(hotkey: &str, verbose: bool) -> Result<Option<ExitHotkey>, Box<dyn Error>> {
+    let (mods, key) = parse_hotkey(hotkey).ok_or_else(|| {
+        MyError(format!(
+            "Invalid osd_hotkey `{}` (expected \"MODS, KEY\").",
+            hotkey
+        ))
+    })?;
+    register_session_hotkey(
+        Msg::HotkeyOsd,
+        &mods,
+        &key,
+        HotkeyBind::Press,
+        "osd",
+        verbose,
+    )
+}
 
-    let result = (|| -> Result<(), Box<dyn Error>> {
-        let monitors = get_monitors(verbose)?;
-        let (span_x, span_y, span_width, span_height) = compute_monitor_span(&monitors)?;
+pub(crate) fn register_exit_hotkey(
+    hotkey: &str,
+    bind: HotkeyBind,
+    confirm: bool,
+    verbose: bool,
+) -> Result<Option<ExitHotkey>, Box<dyn Error>> {
+    let (mods, key) = parse_hotkey(hotkey).ok_or_else(|| {
+        MyError(format!(
+            "Invalid exit_hotkey `{}` (expected \"MODS, KEY\").",
+            hotkey
+        ))
+    })?;
+    register_session_hotkey(
+        Msg::HotkeyExit,
+        &mods,
+        &key,
+        bind,
+        exit_hotkey_command(confirm),
+        verbose,
+    )
+}
 
-        println!(
-            "Hyprfinity: Computed monitor span: origin=({}, {}), size={}x{}",
-            span_x, span_y, span_width, span_height
-        );
-        debug_log_line(&format!(
-            "computed span origin=({}, {}), size={}x{}",
-            span_x, span_y, span_width, span_height
-        ));
+fn exit_hotkey_command(confirm: bool) -> &'static str {
+    if confirm {
+        "gamescope-down --confirm"
+    } else {
+        "gamescope-down"
+    }
+}
 
-        let gamescope_args = ensure_game_command(gamescope_args.to_vec(), pick)?;
-        if overlay_enabled && !command_in_path("mangoapp") {
-            debug_log_line("overlay disabled: mangoapp not found in PATH");
-            overlay_enabled = false;
-        }
-        let gamescope_args = if overlay_enabled {
-            ensure_gamescope_flag(gamescope_args, "--mangoapp")
-        } else {
-            gamescope_args
-        };
-        let output = derive_output_size(span_width, span_height, output_width, output_height);
-        debug_log_line(&format!(
-            "derived output size={}x{} from span={}x{} with config output={:?}x{:?}",
-            output.0, output.1, span_width, span_height, output_width, output_height
-        ));
-        let mut internal = derive_internal_size(
-            output.0,
-            output.1,
-            render_scale,
-            virtual_width,
-            virtual_height,
-        );
-        if pick_size {
-            if let Some(selected) = pick_internal_size(&monitors, span_width, span_height)? {
-                internal = selected;
-            } else {
-                println!(
-                    "Hyprfinity: Internal size picker cancelled, using configured/default size."
-                );
-            }
+/// Binds a session hotkey again after a Hyprland config reload dropped it. Left unset if the
+/// reloaded config now binds the same keys itself.
+fn rebind_hotkey(
+    hotkey: &mut Option<ExitHotkey>,
+    label: Msg,
+    bind: HotkeyBind,
+    subcommand: &str,
+    verbose: bool,
+) {
+    let Some(current) = hotkey.take() else {
+        return;
+    };
+    match register_session_hotkey(
+        label,
+        &current.mods,
+        &current.key,
+        bind,
+        subcommand,
+        verbose,
+    ) {
+        Ok(rebound) => *hotkey = rebound,
+        Err(e) => {
+            error(&trf(Msg::HotkeyRestoreFailed, &[&tr(label), &e]));
+            *hotkey = Some(current);
         }
+    }
+}
 
-        println!(
-            "Hyprfinity: Internal render size: {}x{} (output span {}x{})",
-            internal.0, internal.1, output.0, output.1
-        );
+fn exit_pending_file_path() -> std::path::PathBuf {
+    std::env::temp_dir().join(EXIT_PENDING_FILE_NAME)
+}
 
-        if hide_waybar {
-            waybar_was_stopped = maybe_stop_waybar(verbose)?;
-        }
-        if idle_inhibit {
-            idle_inhibit_pid = maybe_start_idle_inhibit(verbose)?;
-        }
+/// Whether an exit countdown is running: its marker is younger than the grace period, so one
+/// left behind by a killed countdown does not swallow the next press.
+fn exit_pending(marker: &std::path::Path) -> bool {
+    std::fs::metadata(marker)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < EXIT_CONFIRM_GRACE + Duration::from_secs(1))
+}
 
-        let final_args = build_gamescope_args_with_internal(
-            &gamescope_args,
-            output.0,
-            output.1,
-            internal.0,
-            internal.1,
-        );
-        println!(
-            "Hyprfinity: Launching gamescope with args: {:?}",
-            final_args
+fn notify_exit(icon: &str, duration: Duration, text: &str, verbose: bool) {
+    let ms = duration.as_millis().to_string();
+    if let Err(e) = execute_hyprctl(&["notify", icon, &ms, "0", text], verbose) {
+        debug_log_line(&format!("exit confirm: notify failed: {}", e));
+    }
+}
+
+/// `gamescope-down --confirm`, which the exit hotkey runs with exit_hotkey_confirm: the first
+/// press announces the exit and waits out the grace period, a second press within it cancels.
+pub(crate) fn confirm_gamescope_down(verbose: bool) -> Result<(), Box<dyn Error>> {
+    if !matches!(session_status(), SessionStatus::Active(_)) && !per_monitor_session_running() {
+        return gamescope_down();
+    }
+    let marker = exit_pending_file_path();
+    if exit_pending(&marker) {
+        let _ = std::fs::remove_file(&marker);
+        notify_exit(
+            "5",
+            Duration::from_secs(2),
+            tr(Msg::ExitCancelledNotice),
+            verbose,
         );
-        debug_log_line(&format!("gamescope final args: {:?}", final_args));
+        info(tr(Msg::ExitCancelled));
+        return Ok(());
+    }
+    let token = std::process::id().to_string();
+    std::fs::write(&marker, &token)?;
+    notify_exit(
+        "0",
+        EXIT_CONFIRM_GRACE,
+        &trf(Msg::ExitCountdownNotice, &[&EXIT_CONFIRM_GRACE.as_secs()]),
+        verbose,
+    );
+    let deadline = std::time::Instant::now() + EXIT_CONFIRM_GRACE;
+    while std::time::Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(100));
+        // A second press removed the marker (and may already have started a new countdown).
+        if std::fs::read_to_string(&marker).ok().as_deref() != Some(token.as_str()) {
+            debug_log_line("exit confirm: cancelled");
+            return Ok(());
+        }
+    }
+    let _ = std::fs::remove_file(&marker);
+    gamescope_down()
+}
 
-        let mut cmd = Command::new("gamescope");
-        cmd.args(&final_args);
-        if overlay_enabled && let Some(config) = mangohud_config.clone() {
-            cmd.env("MANGOHUD_CONFIG", config);
+// --force-grab-cursor keeps the pointer locked while gamescope has focus; with follow_mouse
+// off, a cursor that still slips onto another monitor cannot pull focus away from it.
+fn confine_cursor(verbose: bool) -> Option<i64> {
+    let previous = match get_option_int("input:follow_mouse", verbose) {
+        Ok(value) => value,
+        Err(e) => {
+            error(&trf(Msg::ReadOptionFailed, &[&"input:follow_mouse", &e]));
+            return None;
         }
-        if !verbose {
-            cmd.stdout(Stdio::null()).stderr(Stdio::null());
+    };
+    match execute_hyprctl(&["keyword", "input:follow_mouse", "0"], verbose) {
+        Ok(()) => Some(previous),
+        Err(e) => {
+            error(&trf(Msg::ConfineCursorFailed, &[&e]));
+            None
         }
-        let mut child = cmd.spawn()?;
+    }
+}
+
+fn restore_cursor_focus(previous: i64, verbose: bool) {
+    if let Err(e) = execute_hyprctl(
+        &["keyword", "input:follow_mouse", &previous.to_string()],
+        verbose,
+    ) {
+        error(&trf(Msg::RestoreOptionFailed, &[&"input:follow_mouse", &e]));
+    }
+}
 
-        let gamescope_pid = child.id();
-        println!("Hyprfinity: gamescope started with PID {}.", gamescope_pid);
+// Monitors outside a partial span keep showing the desktop; blanking turns them off with DPMS,
+// dimming leans on Hyprland's dim_inactive since gamescope holds focus for the session.
+fn blank_monitors(names: &[String], verbose: bool) -> Vec<String> {
+    names
+        .iter()
+        .filter(|name| {
+            match execute_hyprctl(&["dispatch", "dpms", "off", name.as_str()], verbose) {
+                Ok(()) => true,
+                Err(e) => {
+                    error(&trf(Msg::BlankMonitorFailed, &[&name, &e]));
+                    false
+                }
+            }
+        })
+        .cloned()
+        .collect()
+}
 
-        wait_for_client_pid(gamescope_pid, startup_timeout_secs, verbose)?;
+fn dim_inactive(verbose: bool) -> Option<i64> {
+    let previous = match get_option_int("decoration:dim_inactive", verbose) {
+        Ok(value) => value,
+        Err(e) => {
+            error(&trf(
+                Msg::ReadOptionFailed,
+                &[&"decoration:dim_inactive", &e],
+            ));
+            return None;
+        }
+    };
+    match execute_hyprctl(&["keyword", "decoration:dim_inactive", "1"], verbose) {
+        Ok(()) => Some(previous),
+        Err(e) => {
+            error(&trf(Msg::DimMonitorsFailed, &[&e]));
+            None
+        }
+    }
+}
 
-        let window = get_primary_window_selector(gamescope_pid, verbose)
-            .unwrap_or_else(|_| format!("pid:{}", gamescope_pid));
-        debug_log_line(&format!("initial window selector: {}", window));
-        execute_hyprctl(&["dispatch", "setfloating", &window], verbose)?;
-        fit_window_to_span(
-            gamescope_pid,
-            &window,
-            span_x,
-            span_y,
-            span_width,
-            span_height,
+fn restore_excluded_monitors(blanked: &[String], dim_restore: Option<i64>, verbose: bool) {
+    for name in blanked {
+        if let Err(e) = execute_hyprctl(&["dispatch", "dpms", "on", name.as_str()], verbose) {
+            error(&trf(Msg::WakeMonitorFailed, &[&name, &e]));
+        }
+    }
+    if let Some(previous) = dim_restore
+        && let Err(e) = execute_hyprctl(
+            &["keyword", "decoration:dim_inactive", &previous.to_string()],
             verbose,
-        )?;
+        )
+    {
+        error(&trf(
+            Msg::RestoreOptionFailed,
+            &[&"decoration:dim_inactive", &e],
+        ));
+    }
+}
 
-        if !no_pin {
-            execute_hyprctl(&["dispatch", "pin", &window], verbose)?;
-        }
+fn span_center(span_x: i32, span_y: i32, span_width: i32, span_height: i32) -> (i32, i32) {
+    (span_x + span_width / 2, span_y + span_height / 2)
+}
 
-        match register_exit_hotkey(verbose) {
-            Ok(hotkey) => exit_hotkey = hotkey,
-            Err(e) => eprintln!("Hyprfinity: Failed to register exit hotkey: {}", e),
+/// The span the session's monitors cover now, once all of them are back. `None` if some are
+/// still missing after [`RESUME_MONITOR_POLLS`].
+fn redetect_span(
+    spanned: &[Monitor],
+    strategy: SpanStrategy,
+    verbose: bool,
+) -> Option<(i32, i32, i32, i32)> {
+    for attempt in 0..RESUME_MONITOR_POLLS {
+        if attempt > 0 {
+            thread::sleep(Duration::from_secs(1));
+        }
+        let Ok(current) = get_monitors(verbose) else {
+            continue;
+        };
+        let back = spanned
+            .iter()
+            .filter_map(|m| {
+                current
+                    .iter()
+                    .find(|c| c.name.is_some() && c.name == m.name && c.contributes_to_span())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if back.len() == spanned.len()
+            && let Ok(span) = span_rect(&back, strategy)
+        {
+            return Some(span);
         }
+    }
+    None
+}
 
-        let state = GamescopeState {
+/// Fits the window to the span again and restores its pin and decorations, after suspend or a
+/// config reload may have moved or reset them.
+fn restore_window_placement(
+    matcher: &WindowMatcher,
+    (x, y, width, height): WindowRect,
+    pin: bool,
+    fit: &FitSettings,
+    borderless: bool,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    let window = get_primary_window_selector(matcher, verbose)?;
+    count_reflow();
+    fit_window_to_span(matcher, &window, x, y, width, height, pin, fit, verbose)
+        .inspect_err(|_| count_fit_failure())?;
+    if pin {
+        reassert_pin(matcher, &window, verbose)?;
+    }
+    if borderless {
+        compositor().strip_decorations(&window, verbose)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn unregister_exit_hotkey(hotkey: &ExitHotkey, verbose: bool) {
+    let _ = compositor().unbind_key(&hotkey.mods, &hotkey.key, verbose);
+}
+
+/// One session resolved against the current layout: the settings plus the monitors and span it
+/// covers. Build it with [`LaunchPlan::builder`] and run it with [`LaunchPlan::launch`].
+#[derive(Debug, Clone)]
+pub struct LaunchPlan {
+    pub(crate) settings: LaunchSettings,
+    pub(crate) all_monitors: Vec<Monitor>,
+    pub(crate) monitors: Vec<Monitor>,
+    pub(crate) span: WindowRect,
+    /// A saved plan to launch instead of resolving the settings again.
+    pub(crate) resolved: Option<ResolvedLaunch>,
+}
+
+/// Adjusts [`LaunchSettings`] for one launch, then detects the span in [`LaunchPlanBuilder::build`].
+#[derive(Debug, Clone)]
+pub struct LaunchPlanBuilder {
+    settings: LaunchSettings,
+    monitors: Option<Vec<Monitor>>,
+    resolved: Option<ResolvedLaunch>,
+}
+
+impl LaunchPlan {
+    pub fn builder(settings: LaunchSettings) -> LaunchPlanBuilder {
+        LaunchPlanBuilder {
+            settings,
+            monitors: None,
+            resolved: None,
+        }
+    }
+
+    /// Span rectangle as (x, y, width, height) in layout coordinates.
+    pub fn span(&self) -> (i32, i32, i32, i32) {
+        self.span
+    }
+
+    pub fn settings(&self) -> &LaunchSettings {
+        &self.settings
+    }
+
+    /// Monitors the span covers.
+    pub fn monitors(&self) -> &[Monitor] {
+        &self.monitors
+    }
+
+    /// Runs the session in the foreground until it ends, then restores the desktop.
+    pub fn launch(&self, verbose: bool) -> Result<(), Box<dyn Error>> {
+        if self.settings.embedded && self.resolved.is_some() {
+            return Err(MyError(
+                "Launch plans describe nested sessions; --embedded builds its own gamescope \
+                 command."
+                    .to_string(),
+            )
+            .into());
+        }
+        if self.settings.embedded {
+            return run_embedded_session(&self.settings, verbose);
+        }
+        gamescope_up(self, verbose)
+    }
+}
+
+impl LaunchPlanBuilder {
+    /// Gamescope args, optionally followed by `--` and the game command.
+    pub fn gamescope_args(mut self, args: Vec<String>) -> Self {
+        self.settings.args = args;
+        self
+    }
+
+    pub fn span_target(mut self, target: SpanTarget) -> Self {
+        self.settings.span_target = target;
+        self
+    }
+
+    pub fn span_strategy(mut self, strategy: SpanStrategy) -> Self {
+        self.settings.span_strategy = strategy;
+        self
+    }
+
+    /// Gamescope output size (-W/-H); defaults to the span.
+    pub fn output_size(mut self, width: i32, height: i32) -> Self {
+        self.settings.output_width = Some(width);
+        self.settings.output_height = Some(height);
+        self
+    }
+
+    /// Internal render size (-w/-h); defaults to the span scaled by the render scale.
+    pub fn internal_size(mut self, width: i32, height: i32) -> Self {
+        self.settings.virtual_width = Some(width);
+        self.settings.virtual_height = Some(height);
+        self
+    }
+
+    /// Scales above 1.0 supersample and take effect only with [`Self::supersample`].
+    pub fn render_scale(mut self, scale: f32) -> Self {
+        self.settings.render_scale = RenderScale::uniform(scale).clamp(MAX_SUPERSAMPLE_SCALE);
+        self
+    }
+
+    /// Allows internal sizes above the output size (up to 2x per axis).
+    pub fn supersample(mut self, allow: bool) -> Self {
+        self.settings.allow_supersample = allow;
+        self
+    }
+
+    /// Pixel-perfect scaling: internal sizes snap to integer divisors of the output and gamescope
+    /// uses its integer scaler with nearest-neighbour filtering.
+    pub fn integer_scale(mut self, enabled: bool) -> Self {
+        self.settings.integer_scale = enabled;
+        self
+    }
+
+    pub fn pin(mut self, pin: bool) -> Self {
+        self.settings.no_pin = !pin;
+        self
+    }
+
+    /// Plans against these monitors instead of asking the compositor.
+    pub fn monitors(mut self, monitors: Vec<Monitor>) -> Self {
+        self.monitors = Some(monitors);
+        self
+    }
+
+    /// Launches a plan saved with `--plan-json` as-is: its span, gamescope args, environment and
+    /// window handling replace the ones the settings would resolve to.
+    pub(crate) fn resolved(mut self, resolved: ResolvedLaunch) -> Self {
+        self.resolved = Some(resolved);
+        self
+    }
+
+    pub fn build(self, verbose: bool) -> Result<LaunchPlan, Box<dyn Error>> {
+        let all_monitors = match self.monitors {
+            Some(monitors) => monitors,
+            None => get_monitors(verbose)?,
+        };
+        let (monitors, span) = match self.resolved.as_ref() {
+            Some(resolved) => (
+                all_monitors
+                    .iter()
+                    .filter(|m| {
+                        m.name
+                            .as_ref()
+                            .is_some_and(|name| resolved.monitors.contains(name))
+                    })
+                    .cloned()
+                    .collect(),
+                resolved.span,
+            ),
+            None => {
+                let monitors = select_span_monitors(&all_monitors, self.settings.span_target);
+                let span = span_rect(&monitors, self.settings.span_strategy)?;
+                if self.settings.span_strategy == SpanStrategy::BoundingBox {
+                    for warning in span_layout_warnings(&monitors) {
+                        warn(&warning);
+                    }
+                }
+                (monitors, span)
+            }
+        };
+        Ok(LaunchPlan {
+            settings: self.settings,
+            all_monitors,
+            monitors,
+            span,
+            resolved: self.resolved,
+        })
+    }
+}
+
+/// How the session window is kept on the span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum WindowPlacement {
+    /// The companion plugin holds the geometry; reflow takes over if locking it fails.
+    Plugin,
+    /// Refit every other second, for games that resize their window.
+    Reflow,
+    /// Fit once at startup (streaming sessions).
+    Once,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct WindowStrategy {
+    pub(crate) placement: WindowPlacement,
+    pub(crate) pin: bool,
+    /// Regexes matching the session window when it is not owned by gamescope's PID.
+    pub(crate) class: Option<String>,
+    pub(crate) title: Option<String>,
+}
+
+pub(crate) const LAUNCH_PLAN_VERSION: u32 = 1;
+
+/// A launch resolved down to the gamescope command: printed by `gamescope-up --plan-json` and
+/// launched as-is with `--plan-from`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ResolvedLaunch {
+    pub(crate) version: u32,
+    /// Span rectangle as (x, y, width, height) in layout coordinates.
+    pub(crate) span: WindowRect,
+    /// Monitors the span covers.
+    pub(crate) monitors: Vec<String>,
+    pub(crate) output: (i32, i32),
+    pub(crate) internal: (i32, i32),
+    /// Final gamescope args, including `--` and the game command.
+    pub(crate) args: Vec<String>,
+    /// Variables set for gamescope on top of the inherited environment.
+    pub(crate) env: Vec<(String, String)>,
+    pub(crate) window: WindowStrategy,
+    /// hooks.d executables per stage. For reference only: each stage reads hooks.d again.
+    pub(crate) hooks: BTreeMap<String, Vec<String>>,
+    /// Every monitor at resolve time, for excluded monitors and the companion app.
+    pub(crate) all_monitors: Vec<Monitor>,
+    /// Session settings the plan was resolved from; `up --plan` runs with these instead of the
+    /// config.
+    pub(crate) settings: LaunchSettings,
+}
+
+/// Resolves `plan` down to what gamescope is started with. Runs the game and size pickers when
+/// they are enabled, but starts nothing and leaves the desktop untouched.
+pub(crate) fn resolve_launch(
+    plan: &LaunchPlan,
+    verbose: bool,
+) -> Result<ResolvedLaunch, Box<dyn Error>> {
+    let LaunchSettings {
+        args: gamescope_args,
+        probe,
+        default_command,
+        pre_command,
+        no_pin,
+        pick,
+        picker_fallback,
+        picker_command,
+        pick_size,
+        mut overlay_enabled,
+        mangohud_config,
+        render_scale,
+        allow_supersample,
+        integer_scale,
+        virtual_width,
+        virtual_height,
+        output_width,
+        output_height,
+        refresh_rate,
+        streaming,
+        compat,
+        tuning,
+        resolution_hints,
+        engine_hints,
+        extra_env,
+        ..
+    } = plan.settings.clone();
+    let monitors = &plan.monitors;
+    let (span_x, span_y, span_width, span_height) = plan.span;
+
+    info(&trf(
+        Msg::ComputedSpan,
+        &[&span_x, &span_y, &span_width, &span_height],
+    ));
+    debug_log_line(&format!(
+        "computed span origin=({}, {}), size={}x{}",
+        span_x, span_y, span_width, span_height
+    ));
+
+    let gamescope_args = ensure_game_command(
+        gamescope_args,
+        pick,
+        picker_fallback,
+        picker_command.as_deref(),
+        default_command,
+    )?;
+    report_wide_support(&gamescope_args, (span_width, span_height));
+    let (gamescope_args, compat_env) = apply_compat_tool(gamescope_args, &compat)?;
+    let gamescope_args = chain_pre_command(gamescope_args, pre_command.as_deref());
+    let refresh = refresh_rate
+        .map(|r| r as i32)
+        .or_else(|| span_refresh_rate(monitors));
+    let gamescope_args = apply_refresh_rate(gamescope_args, refresh);
+    if overlay_enabled && !command_in_path("mangoapp") {
+        debug_log_line("overlay disabled: mangoapp not found in PATH");
+        overlay_enabled = false;
+    }
+    let gamescope_args = if overlay_enabled {
+        ensure_gamescope_flag(gamescope_args, "--mangoapp")
+    } else {
+        gamescope_args
+    };
+    let gamescope_args = apply_tuning_flags(gamescope_args, &tuning);
+    let output = derive_output_size(span_width, span_height, output_width, output_height);
+    debug_log_line(&format!(
+        "derived output size={}x{} from span={}x{} with config output={:?}x{:?}",
+        output.0, output.1, span_width, span_height, output_width, output_height
+    ));
+    let mut internal = derive_internal_size(
+        output.0,
+        output.1,
+        render_scale,
+        virtual_width,
+        virtual_height,
+        allow_supersample,
+    );
+    if pick_size && picker_command.is_none() && !stdio_is_interactive() {
+        info(tr(Msg::NoTerminalSizePicker));
+    } else if pick_size {
+        if let Some(selected) = pick_internal_size(
+            monitors,
+            span_width,
+            span_height,
+            Some(detect_auto_tune_profile().render_scale),
+            allow_supersample,
+            integer_scale,
+            picker_command.as_deref(),
+        )? {
+            internal = selected;
+        } else {
+            info(tr(Msg::SizePickerCancelled));
+        }
+    }
+
+    if integer_scale {
+        let snapped = snap_to_integer_scale(output, internal);
+        if snapped != internal {
+            info(&trf(
+                Msg::IntegerScaling,
+                &[&snapped.0, &snapped.1, &internal.0, &internal.1],
+            ));
+            internal = snapped;
+        }
+    }
+
+    info(&trf(
+        Msg::InternalRenderSize,
+        &[&internal.0, &internal.1, &output.0, &output.1],
+    ));
+
+    let mut placeholders = vec![
+        ("span_width", span_width.to_string()),
+        ("span_height", span_height.to_string()),
+        ("output_width", output.0.to_string()),
+        ("output_height", output.1.to_string()),
+        ("internal_width", internal.0.to_string()),
+        ("internal_height", internal.1.to_string()),
+    ];
+    placeholders.push(("refresh", refresh_placeholder(&gamescope_args)));
+    let gamescope_args = substitute_command_placeholders(gamescope_args, &placeholders);
+    let gamescope_args = if engine_hints {
+        append_engine_hints(gamescope_args, internal)
+    } else {
+        gamescope_args
+    };
+
+    let final_args = build_gamescope_args_with_internal(
+        &gamescope_args,
+        output.0,
+        output.1,
+        internal.0,
+        internal.1,
+        allow_supersample,
+        integer_scale,
+    );
+    // Sandboxed processes show up under PIDs we did not spawn, so the class has to match.
+    let probe = if (gamescope_flatpak().is_some() || game_is_sandboxed(&final_args))
+        && probe.class.is_none()
+        && probe.title.is_none()
+    {
+        info(&trf(Msg::FlatpakMatchByClass, &[&DEFAULT_WINDOW_CLASS]));
+        WindowProbe {
+            class: Some(DEFAULT_WINDOW_CLASS.to_string()),
+            ..probe
+        }
+    } else {
+        probe
+    };
+    // Rejects invalid class/title regexes before anything is printed or started.
+    WindowMatcher::new(0, &probe)?;
+    let progress = Progress::begin(tr(Msg::StageChecking));
+    let final_args = match probe_gamescope_flags(verbose) {
+        Some(supported) => {
+            let (adapted, warnings) = adapt_gamescope_args(final_args, &supported);
+            for warning in warnings {
+                warn(&warning);
+                debug_log_line(&format!("gamescope arg check: {}", warning));
+            }
+            adapted
+        }
+        None => {
+            debug_log_line("gamescope --help probe failed; skipping arg validation");
+            final_args
+        }
+    };
+    progress.done();
+
+    let mut env = Vec::new();
+    if overlay_enabled && let Some(config) = mangohud_config {
+        env.push(("MANGOHUD_CONFIG".to_string(), config));
+    }
+    if resolution_hints {
+        env.extend(resolution_hint_env(internal, tuning.expose_wayland));
+    }
+    env.extend(compat_env);
+    env.extend(extra_env);
+
+    let placement = if streaming.enabled {
+        WindowPlacement::Once
+    } else if plugin_available(verbose) {
+        WindowPlacement::Plugin
+    } else {
+        WindowPlacement::Reflow
+    };
+    Ok(ResolvedLaunch {
+        version: LAUNCH_PLAN_VERSION,
+        span: plan.span,
+        monitors: monitors.iter().filter_map(|m| m.name.clone()).collect(),
+        output,
+        internal,
+        args: final_args,
+        env,
+        window: WindowStrategy {
+            placement,
+            pin: !no_pin,
+            class: probe.class,
+            title: probe.title,
+        },
+        hooks: planned_hooks(),
+        all_monitors: plan.all_monitors.clone(),
+        settings: plan.settings.clone(),
+    })
+}
+
+fn gamescope_up(plan: &LaunchPlan, verbose: bool) -> Result<(), Box<dyn Error>> {
+    let LaunchSettings {
+        args: _,
+        timeout: startup_timeout_secs,
+        probe,
+        default_command: _,
+        pre_command: _,
+        no_pin: _,
+        pick: _,
+        picker_fallback: _,
+        picker_command: _,
+        idle_inhibit,
+        hide_waybar,
+        pick_size: _,
+        overlay_enabled: _,
+        mangohud_config: _,
+        render_scale: _,
+        allow_supersample,
+        integer_scale: _,
+        virtual_width: _,
+        virtual_height: _,
+        output_width: _,
+        output_height: _,
+        span_target: _,
+        span_strategy,
+        excluded_monitors,
+        companion_command,
+        companion_window_class,
+        refresh_rate: _,
+        fit,
+        exit_with_game,
+        restart,
+        gamepad,
+        metrics,
+        recording,
+        streaming: _,
+        exit_hotkey: exit_hotkey_binding,
+        exit_hotkey_bind,
+        exit_hotkey_confirm,
+        quick_menu_hotkey,
+        osd_hotkey: osd_hotkey_binding,
+        compat: _,
+        embedded: _,
+        tuning,
+        cursor,
+        disable_nightlight,
+        borderless,
+        resolution_hints,
+        engine_hints: _,
+        extra_env: _,
+    } = plan.settings.clone();
+    debug_log_line("gamescope_up begin");
+    if !compositor().window_control() {
+        return Err(MyError(
+            "Placing a nested gamescope window on this wlroots compositor needs `wlrctl` in \
+             PATH; install it, or use --embedded."
+                .to_string(),
+        )
+        .into());
+    }
+    let mut follow_mouse_restore: Option<i64> = None;
+    let mut companion_pid: Option<u32> = None;
+    let mut blanked_monitors: Vec<String> = Vec::new();
+    let mut dim_inactive_restore: Option<i64> = None;
+    let mut nightlight: Option<NightlightRestore> = None;
+    let mut waybar_was_stopped = false;
+    let mut exit_hotkey: Option<ExitHotkey> = None;
+    let mut idle_inhibit_pid: Option<u32> = None;
+    let mut recorder: Option<RecorderState> = None;
+    let mut replay_hotkey: Option<ExitHotkey> = None;
+    let mut menu_hotkey: Option<ExitHotkey> = None;
+    let mut osd_hotkey: Option<ExitHotkey> = None;
+
+    let result = (|| -> Result<(), Box<dyn Error>> {
+        let all_monitors = plan.all_monitors.clone();
+        let resolved = match plan.resolved.clone() {
+            Some(resolved) => {
+                info(tr(Msg::LaunchingSavedPlan));
+                resolved
+            }
+            None => resolve_launch(plan, verbose)?,
+        };
+        let startup_progress = Cell::new(Some(Progress::begin(tr(Msg::StageStarting))));
+        let monitors = plan.monitors.clone();
+        if !compositor().chooses_output() && monitors.len() > 1 {
+            return Err(MyError(format!(
+                "{} can only fullscreen gamescope on one output, but the span covers {} \
+                 monitors; set span_target = \"focused\" or use --embedded.",
+                compositor().name(),
+                monitors.len()
+            ))
+            .into());
+        }
+        let (span_x, span_y, span_width, span_height) = resolved.span;
+        let (output, internal) = (resolved.output, resolved.internal);
+        let final_args = resolved.args.clone();
+        let pin = resolved.window.pin;
+        info(&trf(
+            Msg::LaunchingGamescopeArgs,
+            &[&format!("{:?}", final_args)],
+        ));
+        debug_log_line(&format!("gamescope final args: {:?}", final_args));
+
+        if hide_waybar {
+            waybar_was_stopped = maybe_stop_waybar(verbose)?;
+        }
+        if idle_inhibit {
+            idle_inhibit_pid = maybe_start_idle_inhibit(verbose)?;
+        }
+        if disable_nightlight {
+            nightlight = pause_nightlight(verbose);
+        }
+
+        // PID is filled in for each gamescope started; class/title rules stay the same.
+        let probe = WindowProbe {
+            class: resolved.window.class.clone(),
+            title: resolved.window.title.clone(),
+            ..probe
+        };
+        let window_matcher = WindowMatcher::new(0, &probe)?;
+        // With the companion plugin, Hyprland holds the geometry itself: no reflow needed.
+        let use_plugin = resolved.window.placement == WindowPlacement::Plugin;
+        let plugin_locked = AtomicBool::new(false);
+        // `set-scale` swaps the args and internal size used by later restarts.
+        let launch = RefCell::new((final_args.clone(), internal));
+        let start_gamescope = || -> Result<(Child, u32), Box<dyn Error>> {
+            let (final_args, internal) = launch.borrow().clone();
+            let mut progress = match startup_progress.take() {
+                Some(mut progress) => {
+                    progress.stage(tr(Msg::StageStarting));
+                    progress
+                }
+                None => Progress::begin(tr(Msg::StageRestarting)),
+            };
+            let mut cmd = Command::new("gamescope");
+            cmd.args(&final_args);
+            cmd.envs(resolved.env.iter().cloned());
+            if resolution_hints && internal != resolved.internal {
+                cmd.envs(resolution_hint_env(internal, tuning.expose_wayland));
+            }
+            if !verbose {
+                cmd.stdout(Stdio::null());
+            }
+            cmd.stderr(Stdio::piped());
+            let mut child = gamescope_launcher().spawn(&mut cmd)?;
+            let stderr_tail = child
+                .stderr
+                .take()
+                .map(|stderr| capture_stderr_tail(stderr, verbose));
+
+            let gamescope_pid = child.id();
+            info(&trf(Msg::GamescopeStarted, &[&gamescope_pid]));
+            record_gamescope(
+                &final_args,
+                &cmd.get_envs()
+                    .filter_map(|(key, value)| {
+                        Some((
+                            key.to_string_lossy().to_string(),
+                            value?.to_string_lossy().to_string(),
+                        ))
+                    })
+                    .collect::<Vec<_>>(),
+                gamescope_pid,
+            );
+            if use_plugin {
+                match lock_window_geometry(
+                    gamescope_pid,
+                    (span_x, span_y, span_width, span_height),
+                    pin,
+                    verbose,
+                ) {
+                    Ok(()) => plugin_locked.store(true, Ordering::SeqCst),
+                    Err(e) => warn(&trf(Msg::PluginLockFailed, &[&e])),
+                }
+            }
+
+            progress.stage(tr(Msg::StageWaitingWindow));
+            let matcher = window_matcher.for_pid(gamescope_pid);
+            let client = match wait_for_client(
+                &matcher,
+                startup_timeout_secs,
+                probe.poll_ms,
+                verbose,
+                || matches!(child.try_wait(), Ok(Some(_))),
+            ) {
+                Ok(client) => client,
+                Err(e) => {
+                    if let Ok(Some(status)) = child.try_wait() {
+                        report_startup_failure(status, stderr_tail.as_ref());
+                    }
+                    return Err(e);
+                }
+            };
+
+            let window = compositor().window_selector(client.pid as u32, Some(&client));
+            debug_log_line(&format!("initial window selector: {}", window));
+            progress.stage(tr(Msg::StageFitting));
+            fit_window_to_span(
+                &matcher,
+                &window,
+                span_x,
+                span_y,
+                span_width,
+                span_height,
+                pin,
+                &fit,
+                verbose,
+            )?;
+            if borderless && let Err(e) = compositor().strip_decorations(&window, verbose) {
+                warn(&trf(Msg::RemoveDecorationsFailed, &[&e]));
+            }
+            progress.done();
+            Ok((child, gamescope_pid))
+        };
+        run_hooks(
+            HookStage::PreUp,
+            &HookContext {
+                span: (span_x, span_y, span_width, span_height),
+                pid: None,
+                game: Some(game_command_label(&final_args)),
+            },
+            verbose,
+        );
+        let (mut child, mut gamescope_pid) = start_gamescope()?;
+        if cursor.confine {
+            follow_mouse_restore = confine_cursor(verbose);
+        }
+        let excluded = excluded_monitor_names(&all_monitors, &monitors);
+        let mut companion_monitors = Vec::new();
+        if let Some(command) = companion_command.as_deref().filter(|cmd| !cmd.is_empty()) {
+            let outside = all_monitors
+                .iter()
+                .filter(|m| m.name.as_ref().is_some_and(|name| excluded.contains(name)))
+                .cloned()
+                .collect::<Vec<_>>();
+            match companion_area(&outside, (span_x, span_y, span_width, span_height)) {
+                Some((area, covered)) => match start_companion(
+                    command,
+                    companion_window_class.as_deref(),
+                    area,
+                    (startup_timeout_secs, probe.poll_ms),
+                    &fit,
+                    verbose,
+                ) {
+                    Ok(pid) => {
+                        companion_pid = Some(pid);
+                        companion_monitors = covered;
+                    }
+                    Err(e) => error(&trf(Msg::CompanionStartFailed, &[&e])),
+                },
+                None => info(tr(Msg::CompanionNoRoom)),
+            }
+        }
+        if !excluded.is_empty() {
+            // Monitors showing the companion stay on.
+            let blank = excluded
+                .iter()
+                .filter(|name| !companion_monitors.contains(name))
+                .cloned()
+                .collect::<Vec<_>>();
+            match excluded_monitors {
+                ExcludedMonitors::Keep => {}
+                ExcludedMonitors::Blank => blanked_monitors = blank_monitors(&blank, verbose),
+                ExcludedMonitors::Dim => dim_inactive_restore = dim_inactive(verbose),
+            }
+        }
+        if cursor.warp {
+            let (x, y) = span_center(span_x, span_y, span_width, span_height);
+            if let Err(e) = execute_hyprctl(
+                &["dispatch", "movecursor", &x.to_string(), &y.to_string()],
+                verbose,
+            ) {
+                error(&trf(Msg::WarpCursorFailed, &[&e]));
+            }
+        }
+
+        match register_exit_hotkey(
+            &exit_hotkey_binding,
+            exit_hotkey_bind,
+            exit_hotkey_confirm,
+            verbose,
+        ) {
+            Ok(hotkey) => exit_hotkey = hotkey,
+            Err(e) => error(&trf(Msg::HotkeyRegisterFailed, &[&tr(Msg::HotkeyExit), &e])),
+        }
+        if let Some(binding) = quick_menu_hotkey.as_deref() {
+            match register_menu_hotkey(binding, verbose) {
+                Ok(hotkey) => menu_hotkey = hotkey,
+                Err(e) => error(&trf(
+                    Msg::HotkeyRegisterFailed,
+                    &[&tr(Msg::HotkeyQuickMenu), &e],
+                )),
+            }
+        }
+        if let Some(binding) = osd_hotkey_binding.as_deref() {
+            match register_osd_hotkey(binding, verbose) {
+                Ok(hotkey) => osd_hotkey = hotkey,
+                Err(e) => error(&trf(Msg::HotkeyRegisterFailed, &[&tr(Msg::HotkeyOsd), &e])),
+            }
+        }
+
+        match start_recording(
+            &recording,
+            (span_x, span_y, span_width, span_height),
+            verbose,
+        ) {
+            Ok(started) => recorder = started,
+            Err(e) => error(&trf(Msg::ReplayStartFailed, &[&e])),
+        }
+        if let Some(path) = recording.obs_region_file.as_deref()
+            && let Err(e) = write_obs_region(
+                path,
+                &monitors,
+                (span_x, span_y, span_width, span_height),
+                verbose,
+            )
+        {
+            error(&trf(Msg::ObsRegionFailed, &[&e]));
+        }
+        if recorder.is_some() {
+            match parse_hotkey(&recording.save_hotkey) {
+                Some((mods, key)) => {
+                    match register_session_hotkey(
+                        Msg::HotkeyReplaySave,
+                        &mods,
+                        &key,
+                        HotkeyBind::Press,
+                        "replay-save",
+                        verbose,
+                    ) {
+                        Ok(hotkey) => replay_hotkey = hotkey,
+                        Err(e) => error(&trf(
+                            Msg::HotkeyRegisterFailed,
+                            &[&tr(Msg::HotkeyReplaySave), &e],
+                        )),
+                    }
+                }
+                None => error(&trf(Msg::InvalidSaveHotkey, &[&recording.save_hotkey])),
+            }
+        }
+
+        let mut state = GamescopeState {
             gamescope_pid,
             span_x,
             span_y,
             span_width,
             span_height,
-            gamescope_args: final_args,
+            gamescope_args: final_args.clone(),
             waybar_was_stopped,
             idle_inhibit_pid,
             exit_hotkey: exit_hotkey.clone(),
+            game: game_command_label(&final_args),
+            started_at: unix_now(),
+            recorder: recorder.clone(),
+            replay_hotkey: replay_hotkey.clone(),
+            menu_hotkey: menu_hotkey.clone(),
+            osd_hotkey: osd_hotkey.clone(),
+            version: GAMESCOPE_STATE_VERSION,
+            launcher_pid: Some(std::process::id()),
+            follow_mouse_restore,
+            blanked_monitors: blanked_monitors.clone(),
+            dim_inactive_restore,
+            nightlight: nightlight.clone(),
+            companion_pid,
         };
         save_gamescope_state(&state)?;
+        run_hooks(HookStage::PostUp, &hook_context(&state), verbose);
+        mark_session_up(true);
+        spawn_watchdog(verbose);
+        if let Some(listen) = metrics.listen.as_deref() {
+            start_metrics_server(listen);
+        }
 
         let shutting_down = Arc::new(AtomicBool::new(false));
         {
@@ -471,51 +2041,285 @@ pub(crate) fn gamescope_up(
                 if shutting_down.swap(true, Ordering::SeqCst) {
                     return;
                 }
-                println!("\nHyprfinity: Ctrl+C received, tearing down Gamescope session...");
+                info(tr(Msg::CtrlCTearingDown));
                 if let Err(e) = gamescope_down() {
-                    eprintln!("Hyprfinity: Failed to tear down Gamescope session: {}", e);
+                    error(&trf(Msg::TeardownFailed, &[&e]));
                 }
+                finish_trace();
                 std::process::exit(130);
             })?;
         }
 
-        println!("Hyprfinity: Gamescope is running. Press Ctrl+C to stop.");
+        let mut gamepad_watcher = start_gamepad_watcher(&gamepad, verbose);
+        let sleep_watcher = start_sleep_watcher(verbose);
+        let reload_watcher = start_reload_watcher(verbose);
+        // Drop a request left over from a session that ended before picking it up.
+        take_restart_request();
+
+        info(tr(Msg::GamescopeRunning));
         let mut reflow_tick: u64 = 0;
+        let mut game_seen = false;
+        let mut game_gone_ticks: u32 = 0;
+        let mut crash_restarts: u32 = 0;
         loop {
-            if let Ok(Some(status)) = child.try_wait() {
-                println!("Hyprfinity: Gamescope exited with status {}.", status);
-                if let Some(pid) = idle_inhibit_pid {
-                    maybe_stop_idle_inhibit(pid, verbose);
+            let mut relaunch = false;
+            let mut ended_by_us = false;
+            if let Some(watcher) = gamepad_watcher.as_mut()
+                && watcher.triggered()
+            {
+                debug_log_line(&format!("gamepad chord fired: {}", gamepad.action.as_str()));
+                let _ = child.kill();
+                let _ = child.wait();
+                match gamepad.action {
+                    GamepadAction::Exit => {
+                        info(tr(Msg::ChordEnding));
+                        ended_by_us = true;
+                    }
+                    GamepadAction::Restart => {
+                        info(tr(Msg::ChordRestarting));
+                        relaunch = true;
+                    }
+                }
+            }
+            if !relaunch && let Some(request) = take_restart_request() {
+                match request.render_scale {
+                    Some(scale) => {
+                        let internal = scaled_dimensions_within(
+                            output.0,
+                            output.1,
+                            (scale, scale),
+                            max_internal_size(output.0, output.1, allow_supersample),
+                        );
+                        let args = with_internal_size(state.gamescope_args.clone(), internal);
+                        state.gamescope_args = args.clone();
+                        *launch.borrow_mut() = (args, internal);
+                        info(&trf(
+                            Msg::ScaleRestarting,
+                            &[&scale, &internal.0, &internal.1],
+                        ));
+                    }
+                    None => info(tr(Msg::RestartRequested)),
+                }
+                let _ = child.kill();
+                let _ = child.wait();
+                relaunch = true;
+            }
+
+            if exit_with_game && !relaunch && !ended_by_us {
+                let games = game_processes(gamescope_pid);
+                if !games.is_empty() {
+                    if !game_seen {
+                        debug_log_line(&format!("tracking game processes: {:?}", games));
+                    }
+                    game_seen = true;
+                    game_gone_ticks = 0;
+                } else if game_seen {
+                    // Launchers often exec or hand off to a new process; allow a short gap.
+                    game_gone_ticks += 1;
+                    if game_gone_ticks >= GAME_EXIT_GRACE_TICKS {
+                        info(tr(Msg::GameExited));
+                        debug_log_line("no game processes left under gamescope; tearing down");
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        ended_by_us = true;
+                    }
                 }
-                if waybar_was_stopped {
-                    maybe_start_waybar(verbose)?;
+            }
+
+            if !relaunch && let Ok(Some(status)) = child.try_wait() {
+                if restart.enabled && !ended_by_us && gamescope_crashed(status) {
+                    if crash_restarts < restart.max_retries {
+                        crash_restarts += 1;
+                        let delay = retry_delay(restart.backoff_ms, crash_restarts);
+                        error(&trf(
+                            Msg::CrashRestarting,
+                            &[
+                                &status,
+                                &delay.as_millis(),
+                                &crash_restarts,
+                                &restart.max_retries,
+                            ],
+                        ));
+                        debug_log_line(&format!(
+                            "gamescope crashed with {}; restart {}/{}",
+                            status, crash_restarts, restart.max_retries
+                        ));
+                        thread::sleep(delay);
+                        relaunch = true;
+                    } else {
+                        error(&trf(Msg::CrashGivingUp, &[&restart.max_retries]));
+                    }
                 }
-                if let Some(hotkey) = exit_hotkey.as_ref() {
-                    unregister_exit_hotkey(hotkey, verbose);
+                if !relaunch {
+                    info(&trf(Msg::GamescopeExitStatus, &[&status]));
+                    // `gamescope-down` takes the state file before stopping gamescope and runs
+                    // the down hooks itself; they run here only while this launcher owns it.
+                    let owns_state =
+                        load_gamescope_state().is_ok_and(|s| s.gamescope_pid == gamescope_pid);
+                    if owns_state {
+                        run_hooks(HookStage::PreDown, &hook_context(&state), verbose);
+                    }
+                    if let Some(pid) = idle_inhibit_pid {
+                        maybe_stop_idle_inhibit(pid, verbose);
+                    }
+                    if waybar_was_stopped {
+                        maybe_start_waybar(verbose)?;
+                    }
+                    if let Some(hotkey) = exit_hotkey.as_ref() {
+                        unregister_exit_hotkey(hotkey, verbose);
+                    }
+                    if let Some(rec) = recorder.as_ref() {
+                        stop_recording(rec, verbose);
+                    }
+                    if let Some(hotkey) = replay_hotkey.as_ref() {
+                        unregister_exit_hotkey(hotkey, verbose);
+                    }
+                    if let Some(hotkey) = menu_hotkey.as_ref() {
+                        unregister_exit_hotkey(hotkey, verbose);
+                    }
+                    if let Some(hotkey) = osd_hotkey.as_ref() {
+                        unregister_exit_hotkey(hotkey, verbose);
+                    }
+                    if let Some(previous) = follow_mouse_restore {
+                        restore_cursor_focus(previous, verbose);
+                    }
+                    restore_excluded_monitors(&blanked_monitors, dim_inactive_restore, verbose);
+                    if let Some(restore) = nightlight.as_ref() {
+                        resume_nightlight(restore, verbose);
+                    }
+                    if let Some(pid) = companion_pid {
+                        stop_companion(pid, verbose);
+                    }
+                    let state_file_path = get_gamescope_state_file_path()?;
+                    // Whoever removes the state file records the session, so a
+                    // concurrent `gamescope-down` does not count it twice.
+                    if owns_state && std::fs::remove_file(&state_file_path).is_ok() {
+                        let exit = if ended_by_us || status.success() {
+                            "exited".to_string()
+                        } else {
+                            status.to_string()
+                        };
+                        record_finished_session(&state, &exit);
+                        run_hooks(HookStage::PostDown, &hook_context(&state), verbose);
+                    }
+                    break;
                 }
-                let state_file_path = get_gamescope_state_file_path()?;
-                let _ = std::fs::remove_file(&state_file_path);
-                break;
             }
 
-            if reflow_tick.is_multiple_of(2)
-                && let Ok(window) = get_primary_window_selector(gamescope_pid, verbose)
+            if relaunch {
+                (child, gamescope_pid) = match start_gamescope() {
+                    Ok(started) => started,
+                    Err(e) => {
+                        let _ = std::fs::remove_file(get_gamescope_state_file_path()?);
+                        return Err(e);
+                    }
+                };
+                state.gamescope_pid = gamescope_pid;
+                save_gamescope_state(&state)?;
+                count_gamescope_restart();
+                game_seen = false;
+                game_gone_ticks = 0;
+                continue;
+            }
+
+            let asleep = sleep_watcher.as_ref().is_some_and(|w| w.sleeping());
+            if sleep_watcher.as_ref().is_some_and(|w| w.take_resumed()) {
+                info(tr(Msg::ResumeRefitting));
+                match redetect_span(&monitors, span_strategy, verbose) {
+                    Some(span) if span != (span_x, span_y, span_width, span_height) => {
+                        warn(&trf(
+                            Msg::SpanMovedAcrossSuspend,
+                            &[&span.2, &span.3, &span.0, &span.1],
+                        ));
+                    }
+                    Some(_) => {}
+                    None => debug_log_line("spanned monitors not all back after resume"),
+                }
+                if let Err(e) = restore_window_placement(
+                    &window_matcher.for_pid(gamescope_pid),
+                    (span_x, span_y, span_width, span_height),
+                    pin,
+                    &fit,
+                    borderless,
+                    verbose,
+                ) {
+                    warn(&trf(Msg::ResumeRefitFailed, &[&e]));
+                }
+            }
+            if reload_watcher.as_ref().is_some_and(|w| w.take_reloaded()) {
+                info(tr(Msg::ReloadRestoring));
+                rebind_hotkey(
+                    &mut exit_hotkey,
+                    Msg::HotkeyExit,
+                    exit_hotkey_bind,
+                    exit_hotkey_command(exit_hotkey_confirm),
+                    verbose,
+                );
+                rebind_hotkey(
+                    &mut menu_hotkey,
+                    Msg::HotkeyQuickMenu,
+                    HotkeyBind::Press,
+                    "quick-menu",
+                    verbose,
+                );
+                rebind_hotkey(
+                    &mut osd_hotkey,
+                    Msg::HotkeyOsd,
+                    HotkeyBind::Press,
+                    "osd",
+                    verbose,
+                );
+                rebind_hotkey(
+                    &mut replay_hotkey,
+                    Msg::HotkeyReplaySave,
+                    HotkeyBind::Press,
+                    "replay-save",
+                    verbose,
+                );
+                // `gamescope-down` unbinds what the state file lists, so keep it current.
+                state.exit_hotkey = exit_hotkey.clone();
+                state.menu_hotkey = menu_hotkey.clone();
+                state.osd_hotkey = osd_hotkey.clone();
+                state.replay_hotkey = replay_hotkey.clone();
+                save_gamescope_state(&state)?;
+                if let Err(e) = restore_window_placement(
+                    &window_matcher.for_pid(gamescope_pid),
+                    (span_x, span_y, span_width, span_height),
+                    pin,
+                    &fit,
+                    borderless,
+                    verbose,
+                ) {
+                    warn(&trf(Msg::ReloadRefitFailed, &[&e]));
+                }
+            }
+            if resolved.window.placement != WindowPlacement::Once
+                && !asleep
+                && !plugin_locked.load(Ordering::SeqCst)
+                && reflow_tick.is_multiple_of(2)
+                && let Ok(window) =
+                    get_primary_window_selector(&window_matcher.for_pid(gamescope_pid), verbose)
             {
                 debug_log_line(&format!("reflow window selector: {}", window));
-                let _ = execute_hyprctl(&["dispatch", "setfloating", &window], verbose);
-                let _ = fit_window_to_span(
-                    gamescope_pid,
+                count_reflow();
+                if let Err(e) = fit_window_to_span(
+                    &window_matcher.for_pid(gamescope_pid),
                     &window,
                     span_x,
                     span_y,
                     span_width,
                     span_height,
+                    pin,
+                    &fit,
                     verbose,
-                );
-                if !no_pin {
-                    let _ = execute_hyprctl(&["dispatch", "pin", &window], verbose);
+                ) {
+                    count_fit_failure();
+                    debug_log_line(&format!("reflow fit failed: {}", e));
                 }
             }
+            if let Some(path) = metrics.textfile.as_deref() {
+                write_metrics_textfile(path);
+            }
             reflow_tick = reflow_tick.wrapping_add(1);
             thread::sleep(Duration::from_secs(1));
         }
@@ -523,6 +2327,10 @@ pub(crate) fn gamescope_up(
         Ok(())
     })();
 
+    mark_session_up(false);
+    if let Some(path) = metrics.textfile.as_deref() {
+        write_metrics_textfile(path);
+    }
     if result.is_err() && waybar_was_stopped {
         let _ = maybe_start_waybar(verbose);
     }
@@ -536,39 +2344,182 @@ pub(crate) fn gamescope_up(
     {
         unregister_exit_hotkey(hotkey, verbose);
     }
+    if result.is_err()
+        && let Some(rec) = recorder.as_ref()
+    {
+        stop_recording(rec, verbose);
+    }
+    if result.is_err()
+        && let Some(hotkey) = replay_hotkey.as_ref()
+    {
+        unregister_exit_hotkey(hotkey, verbose);
+    }
+    if result.is_err()
+        && let Some(hotkey) = menu_hotkey.as_ref()
+    {
+        unregister_exit_hotkey(hotkey, verbose);
+    }
+    if result.is_err()
+        && let Some(hotkey) = osd_hotkey.as_ref()
+    {
+        unregister_exit_hotkey(hotkey, verbose);
+    }
+    if result.is_err()
+        && let Some(previous) = follow_mouse_restore
+    {
+        restore_cursor_focus(previous, verbose);
+    }
+    if result.is_err() {
+        restore_excluded_monitors(&blanked_monitors, dim_inactive_restore, verbose);
+    }
+    if result.is_err()
+        && let Some(pid) = companion_pid
+    {
+        stop_companion(pid, verbose);
+    }
+    if result.is_err()
+        && let Some(restore) = nightlight.as_ref()
+    {
+        resume_nightlight(restore, verbose);
+    }
 
     result
 }
 
-pub(crate) fn gamescope_down() -> Result<(), Box<dyn Error>> {
-    let state = load_gamescope_state()?;
+pub(crate) fn launch_session(launch: &LaunchSettings, verbose: bool) -> Result<(), Box<dyn Error>> {
+    if launch.embedded {
+        return run_embedded_session(launch, verbose);
+    }
+    LaunchPlan::builder(launch.clone())
+        .build(verbose)?
+        .launch(verbose)
+}
+
+/// Prints what `gamescope-up` would launch as JSON, without starting anything.
+pub(crate) fn print_launch_plan(
+    launch: &LaunchSettings,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    if launch.embedded {
+        return Err(MyError(
+            "Launch plans describe nested sessions; --embedded builds its own gamescope command."
+                .to_string(),
+        )
+        .into());
+    }
+    let plan = LaunchPlan::builder(launch.clone()).build(verbose)?;
     println!(
-        "Hyprfinity: Stopping gamescope PID {}...",
-        state.gamescope_pid
+        "{}",
+        serde_json::to_string_pretty(&resolve_launch(&plan, verbose)?)?
     );
+    Ok(())
+}
+
+/// Runs a plan written by `--plan-json` exactly as saved, with its own settings and monitors and
+/// no config or detection.
+pub(crate) fn launch_plan_file(path: &str, verbose: bool) -> Result<(), Box<dyn Error>> {
+    let resolved = read_launch_plan(path)?;
+    LaunchPlan::builder(resolved.settings.clone())
+        .monitors(resolved.all_monitors.clone())
+        .resolved(resolved)
+        .build(verbose)?
+        .launch(verbose)
+}
+
+/// Reads a plan written by `--plan-json` from a file, or stdin for `-`.
+pub(crate) fn read_launch_plan(path: &str) -> Result<ResolvedLaunch, Box<dyn Error>> {
+    let contents = if path == "-" {
+        let mut contents = String::new();
+        std::io::stdin().read_to_string(&mut contents)?;
+        contents
+    } else {
+        std::fs::read_to_string(path)
+            .map_err(|e| MyError(format!("Failed to read launch plan {}: {}", path, e)))?
+    };
+    let plan: ResolvedLaunch = serde_json::from_str(&contents)
+        .map_err(|e| MyError(format!("Invalid launch plan {}: {}", path, e)))?;
+    if plan.version != LAUNCH_PLAN_VERSION {
+        return Err(MyError(format!(
+            "Launch plan {} is version {}; this Hyprfinity reads version {}. Write it again with \
+             --plan-json.",
+            path, plan.version, LAUNCH_PLAN_VERSION
+        ))
+        .into());
+    }
+    Ok(plan)
+}
+
+pub(crate) fn gamescope_down() -> Result<(), Box<dyn Error>> {
+    // Per-monitor sessions keep their own state file; stop them together with any spanning one.
+    if stop_per_monitor_session()? && !get_gamescope_state_file_path()?.exists() {
+        return Ok(());
+    }
+    let state = match load_gamescope_state() {
+        Ok(state) => state,
+        Err(e) => {
+            let path = get_gamescope_state_file_path()?;
+            if path.exists() {
+                // An unreadable file would otherwise block every future teardown.
+                error(&trf(Msg::StateUnreadableRemoving, &[&e, &path.display()]));
+                let _ = std::fs::remove_file(&path);
+            }
+            return Err(e);
+        }
+    };
+    run_hooks(HookStage::PreDown, &hook_context(&state), false);
+    // Taken before the kill, so the launcher sees the session is not its own to wind down.
+    remove_state_file()?;
+    info(&trf(Msg::StoppingGamescope, &[&state.gamescope_pid]));
     match Command::new("kill")
         .arg(state.gamescope_pid.to_string())
         .status()
     {
         Ok(status) => {
             if status.success() {
-                println!("Hyprfinity: Gamescope process killed.");
+                info(tr(Msg::GamescopeKilled));
             } else {
-                eprintln!(
-                    "Hyprfinity: Failed to kill gamescope process. Status: {}",
-                    status
-                );
+                error(&trf(Msg::KillFailedStatus, &[&status]));
             }
         }
-        Err(e) => eprintln!("Hyprfinity: Error killing gamescope process: {}", e),
+        Err(e) => error(&trf(Msg::KillError, &[&e])),
     }
 
+    record_finished_session(&state, "stopped");
+    release_session_state(&state)?;
+    teardown_virtual_output(false);
+    run_hooks(HookStage::PostDown, &hook_context(&state), false);
+    Ok(())
+}
+
+/// Cleans up after a session whose gamescope already died without a teardown, without
+/// signalling its (possibly reused) PID.
+pub(crate) fn clear_stale_session() -> Result<(), Box<dyn Error>> {
+    match load_gamescope_state() {
+        Ok(state) => release_session_state(&state),
+        Err(e) => {
+            let path = get_gamescope_state_file_path()?;
+            if path.exists() {
+                warn(&trf(Msg::StateRemoving, &[&e, &path.display()]));
+                std::fs::remove_file(&path)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Removes the session state file; already gone is fine.
+fn remove_state_file() -> Result<(), Box<dyn Error>> {
     let state_file_path = get_gamescope_state_file_path()?;
-    std::fs::remove_file(&state_file_path)?;
-    println!(
-        "Hyprfinity: Cleaned up Gamescope state file {:?}",
-        state_file_path
-    );
+    match std::fs::remove_file(&state_file_path) {
+        Ok(()) => info(&trf(Msg::CleanedState, &[&state_file_path.display()])),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
+
+fn release_session_state(state: &GamescopeState) -> Result<(), Box<dyn Error>> {
+    remove_state_file()?;
     if state.waybar_was_stopped {
         maybe_start_waybar(false)?;
     }
@@ -578,5 +2529,637 @@ pub(crate) fn gamescope_down() -> Result<(), Box<dyn Error>> {
     if let Some(hotkey) = state.exit_hotkey.as_ref() {
         unregister_exit_hotkey(hotkey, false);
     }
+    if let Some(rec) = state.recorder.as_ref() {
+        stop_recording(rec, false);
+    }
+    if let Some(hotkey) = state.replay_hotkey.as_ref() {
+        unregister_exit_hotkey(hotkey, false);
+    }
+    if let Some(hotkey) = state.menu_hotkey.as_ref() {
+        unregister_exit_hotkey(hotkey, false);
+    }
+    if let Some(hotkey) = state.osd_hotkey.as_ref() {
+        unregister_exit_hotkey(hotkey, false);
+    }
+    if let Some(previous) = state.follow_mouse_restore {
+        restore_cursor_focus(previous, false);
+    }
+    restore_excluded_monitors(&state.blanked_monitors, state.dim_inactive_restore, false);
+    if let Some(restore) = state.nightlight.as_ref() {
+        resume_nightlight(restore, false);
+    }
+    if let Some(pid) = state.companion_pid {
+        stop_companion(pid, false);
+    }
     Ok(())
 }
+
+pub(crate) fn spawn_watchdog(verbose: bool) {
+    use std::os::unix::process::CommandExt;
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            error(&trf(Msg::WatchdogFailed, &[&e]));
+            return;
+        }
+    };
+    // Own process group: Ctrl+C and the terminal's SIGHUP go to the launcher, not the watchdog.
+    // The backend is pinned so teardown talks to the same compositor as the session.
+    let spawned = Command::new(exe)
+        .args(["--backend", compositor().name()])
+        .args(["watchdog", "--parent", &std::process::id().to_string()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn();
+    match spawned {
+        Ok(child) => {
+            debug_log_line(&format!("teardown watchdog started pid={}", child.id()));
+            if verbose {
+                debug(&format!("Teardown watchdog started (PID {}).", child.id()));
+            }
+        }
+        Err(e) => error(&trf(Msg::WatchdogFailed, &[&e])),
+    }
+}
+
+/// The launcher's own arguments with `--detach` removed from the hyprfinity/gamescope part, so
+/// the background copy runs the session itself.
+fn detached_args(args: &[String]) -> Vec<String> {
+    let split = args.iter().position(|a| a == "--").unwrap_or(args.len());
+    args[..split]
+        .iter()
+        .filter(|a| *a != "--detach")
+        .chain(&args[split..])
+        .cloned()
+        .collect()
+}
+
+/// Re-runs this command as a session leader in the background, logging to a file, and
+/// returns as soon as it is spawned. Meant for compositor keybinds.
+pub(crate) fn detach_session() -> Result<(), Box<dyn Error>> {
+    use std::os::unix::process::CommandExt;
+    let exe = std::env::current_exe()?;
+    let args = detached_args(&std::env::args().skip(1).collect::<Vec<_>>());
+    let log_path = std::env::temp_dir().join(DETACHED_LOG_FILE_NAME);
+    let log = std::fs::File::create(&log_path)
+        .map_err(|e| MyError(format!("Failed to open {:?}: {}", log_path, e)))?;
+    // setsid drops the controlling terminal too; without it a new process group still keeps
+    // the session out of the terminal's Ctrl+C and SIGHUP.
+    let mut cmd = if command_in_path("setsid") {
+        let mut cmd = Command::new("setsid");
+        cmd.arg(exe);
+        cmd
+    } else {
+        let mut cmd = Command::new(exe);
+        cmd.process_group(0);
+        cmd
+    };
+    let child = cmd
+        .args(&args)
+        .env(DETACHED_ENV_VAR, "1")
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .spawn()
+        .map_err(|e| MyError(format!("Failed to start the detached session: {}", e)))?;
+    info(&trf(
+        Msg::DetachedStarted,
+        &[&child.id(), &log_path.display()],
+    ));
+    Ok(())
+}
+
+pub(crate) fn run_watchdog(parent: u32) -> Result<(), Box<dyn Error>> {
+    let parent_proc = format!("/proc/{}", parent);
+    loop {
+        thread::sleep(Duration::from_secs(1));
+        let Ok(state_file_path) = get_gamescope_state_file_path() else {
+            return Ok(());
+        };
+        // Per-monitor sessions keep their own state file.
+        let per_monitor_launcher = per_monitor_launcher_pid();
+        if per_monitor_launcher.is_none() && !state_file_path.exists() {
+            // The launcher cleaned up after itself.
+            return Ok(());
+        }
+        if std::path::Path::new(&parent_proc).exists() {
+            continue;
+        }
+        let launcher_pid = match per_monitor_launcher {
+            Some(pid) => Some(pid),
+            None => {
+                parse_gamescope_state(&std::fs::read_to_string(&state_file_path)?)?.launcher_pid
+            }
+        };
+        if launcher_pid != Some(parent) {
+            // A newer session owns the state file now.
+            return Ok(());
+        }
+        debug_log_line(&format!(
+            "watchdog: launcher {} exited without cleanup; tearing down",
+            parent
+        ));
+        return gamescope_down();
+    }
+}
+
+fn restart_request_path() -> std::path::PathBuf {
+    std::env::temp_dir().join(RESTART_REQUEST_FILE_NAME)
+}
+
+/// Consumes a pending `hyprfinity restart` / `set-scale` request.
+fn take_restart_request() -> Option<RestartRequest> {
+    let path = restart_request_path();
+    let text = std::fs::read_to_string(&path).ok()?;
+    let _ = std::fs::remove_file(&path);
+    Some(serde_json::from_str(&text).unwrap_or_default())
+}
+
+fn write_restart_request(request: &RestartRequest) -> Result<(), Box<dyn Error>> {
+    if per_monitor_session_running() {
+        return Err(MyError(
+            "The running session is per-monitor; restart and set-scale only apply to a single \
+             Gamescope session. Stop it with `hyprfinity gamescope-down` and relaunch instead."
+                .to_string(),
+        )
+        .into());
+    }
+    if !matches!(session_status(), SessionStatus::Active(_)) {
+        return Err(MyError("No active Gamescope session to restart.".to_string()).into());
+    }
+    std::fs::write(restart_request_path(), serde_json::to_string(request)?)?;
+    Ok(())
+}
+
+/// Asks the running session to restart gamescope; its loop picks this up within a second.
+pub(crate) fn request_session_restart() -> Result<(), Box<dyn Error>> {
+    write_restart_request(&RestartRequest::default())?;
+    info(tr(Msg::RestartQueued));
+    Ok(())
+}
+
+/// Restarts the running session's gamescope at a new internal size for `scale`.
+/// Scales above 1 need the session's `allow_supersample`; otherwise they stop at the output size.
+pub(crate) fn request_session_scale(scale: f32) -> Result<(), Box<dyn Error>> {
+    if !(scale > 0.0 && scale <= MAX_SUPERSAMPLE_SCALE) {
+        return Err(MyError(format!(
+            "Render scale must be above 0 and at most {}, got {}.",
+            MAX_SUPERSAMPLE_SCALE, scale
+        ))
+        .into());
+    }
+    write_restart_request(&RestartRequest {
+        render_scale: Some(scale),
+    })?;
+    info(&trf(Msg::ScaleQueued, &[&scale]));
+    Ok(())
+}
+
+/// What the OSD shows about the running session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SessionSnapshot {
+    pub(crate) game: String,
+    pub(crate) started_at: u64,
+    pub(crate) output: Option<(i32, i32)>,
+    pub(crate) internal: Option<(i32, i32)>,
+}
+
+/// The active session's game and sizes; after `set-scale` the internal size is the new one.
+pub(crate) fn active_session_snapshot() -> Option<SessionSnapshot> {
+    let path = get_gamescope_state_file_path().ok()?;
+    let state = parse_gamescope_state(&std::fs::read_to_string(path).ok()?).ok()?;
+    if !pid_alive(state.gamescope_pid) {
+        return None;
+    }
+    let size = |w: &str, h: &str| {
+        let value = |flag| arg_value(&state.gamescope_args, flag)?.parse::<i32>().ok();
+        Some((value(w)?, value(h)?))
+    };
+    Some(SessionSnapshot {
+        game: state.game.clone(),
+        started_at: state.started_at,
+        output: size("-W", "-H"),
+        internal: size("-w", "-h"),
+    })
+}
+
+pub(crate) fn session_has_replay() -> bool {
+    load_gamescope_state().is_ok_and(|state| state.recorder.is_some())
+}
+
+pub(crate) fn save_session_replay(verbose: bool) -> Result<(), Box<dyn Error>> {
+    let state = load_gamescope_state()?;
+    let Some(rec) = state.recorder.as_ref() else {
+        return Err(MyError("The active session has no replay buffer running.".to_string()).into());
+    };
+    save_replay(rec, verbose)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn exit_confirm_marker_only_counts_while_fresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join(EXIT_PENDING_FILE_NAME);
+        assert!(!exit_pending(&marker));
+        std::fs::write(&marker, "1").unwrap();
+        assert!(exit_pending(&marker));
+        let stale = std::time::SystemTime::now() - EXIT_CONFIRM_GRACE * 2;
+        std::fs::File::options()
+            .write(true)
+            .open(&marker)
+            .unwrap()
+            .set_modified(stale)
+            .unwrap();
+        assert!(!exit_pending(&marker));
+        assert_eq!(
+            register_exit_hotkey("SUPER", HotkeyBind::Press, true, false)
+                .unwrap_err()
+                .to_string(),
+            "Invalid exit_hotkey `SUPER` (expected \"MODS, KEY\")."
+        );
+    }
+
+    #[test]
+    fn launch_plan_builder_spans_the_given_monitors() {
+        let monitor = |name: &str, x: i32, focused: bool| Monitor {
+            name: Some(name.to_string()),
+            width: 1920,
+            height: 1080,
+            x,
+            focused,
+            ..Monitor::default()
+        };
+        let monitors = vec![monitor("DP-1", 0, false), monitor("DP-2", 1920, true)];
+        let settings = LaunchSettings::from_config(&Default::default());
+
+        let plan = LaunchPlan::builder(settings.clone())
+            .monitors(monitors.clone())
+            .build(false)
+            .unwrap();
+        assert_eq!(plan.span(), (0, 0, 3840, 1080));
+        assert_eq!(plan.monitors.len(), 2);
+
+        let plan = LaunchPlan::builder(settings)
+            .span_target(SpanTarget::Focused)
+            .output_size(2560, 1440)
+            .pin(false)
+            .monitors(monitors)
+            .build(false)
+            .unwrap();
+        assert_eq!(plan.span(), (1920, 0, 1920, 1080));
+        assert_eq!(plan.all_monitors.len(), 2);
+        assert_eq!(plan.settings().output_width, Some(2560));
+        assert!(plan.settings().no_pin);
+    }
+
+    proptest! {
+        #[test]
+        fn derived_sizes_are_even_and_bounded(
+            span_width in any::<i32>(),
+            span_height in any::<i32>(),
+            requested_width in proptest::option::of(any::<i32>()),
+            requested_height in proptest::option::of(any::<i32>()),
+        ) {
+            let (w, h) =
+                derive_output_size(span_width, span_height, requested_width, requested_height);
+            prop_assert!(w >= 2 && w <= span_width.max(2));
+            prop_assert!(h >= 2 && h <= span_height.max(2));
+            if requested_width.is_some() || requested_height.is_some() {
+                prop_assert!(w % 2 == 0 && h % 2 == 0);
+            }
+        }
+
+        #[test]
+        fn derived_internal_sizes_stay_within_the_supersample_limit(
+            span_width in 2..=32768i32,
+            span_height in 2..=32768i32,
+            scale_x in 0.0f32..=4.0,
+            scale_y in 0.0f32..=4.0,
+            virtual_width in proptest::option::of(any::<i32>()),
+            virtual_height in proptest::option::of(any::<i32>()),
+            allow_supersample in any::<bool>(),
+        ) {
+            let (max_width, max_height) =
+                max_internal_size(span_width, span_height, allow_supersample);
+            let (w, h) = derive_internal_size(
+                span_width,
+                span_height,
+                RenderScale { x: scale_x, y: scale_y },
+                virtual_width,
+                virtual_height,
+                allow_supersample,
+            );
+            prop_assert!(w >= 2 && w % 2 == 0 && w <= max_width.max(2));
+            prop_assert!(h >= 2 && h % 2 == 0 && h <= max_height.max(2));
+        }
+    }
+
+    #[test]
+    fn saved_launch_plan_round_trips_and_replaces_the_span() {
+        let monitors = ["DP-1", "DP-2"].map(|name| Monitor {
+            name: Some(name.to_string()),
+            width: 1920,
+            height: 1080,
+            x: if name == "DP-1" { 0 } else { 1920 },
+            ..Monitor::default()
+        });
+        let mut settings = LaunchSettings::from_config(&Default::default());
+        settings.render_scale = RenderScale { x: 0.75, y: 1.0 };
+        let resolved = ResolvedLaunch {
+            version: LAUNCH_PLAN_VERSION,
+            span: (1920, 0, 1920, 1080),
+            monitors: vec!["DP-2".to_string()],
+            output: (1920, 1080),
+            internal: (1440, 810),
+            args: ["-W", "1920", "-H", "1080", "--", "vkcube"]
+                .map(str::to_string)
+                .to_vec(),
+            env: vec![("MANGOHUD_CONFIG".to_string(), "fps".to_string())],
+            window: WindowStrategy {
+                placement: WindowPlacement::Reflow,
+                pin: true,
+                class: None,
+                title: None,
+            },
+            hooks: BTreeMap::new(),
+            all_monitors: monitors.to_vec(),
+            settings,
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plan.json");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, serde_json::to_string(&resolved).unwrap()).unwrap();
+        assert_eq!(read_launch_plan(path).unwrap(), resolved);
+
+        let plan = LaunchPlan::builder(resolved.settings.clone())
+            .monitors(resolved.all_monitors.clone())
+            .resolved(resolved.clone())
+            .build(false)
+            .unwrap();
+        assert_eq!(plan.span(), (1920, 0, 1920, 1080));
+        assert_eq!(plan.monitors.len(), 1);
+
+        let stale = ResolvedLaunch {
+            version: LAUNCH_PLAN_VERSION + 1,
+            ..resolved
+        };
+        std::fs::write(path, serde_json::to_string(&stale).unwrap()).unwrap();
+        assert!(read_launch_plan(path).is_err());
+    }
+
+    #[test]
+    fn span_center_offsets_from_span_origin() {
+        assert_eq!(span_center(-1920, 0, 5760, 1080), (960, 540));
+    }
+
+    #[test]
+    fn snap_to_integer_scale_uses_exact_divisors_of_the_output() {
+        assert_eq!(
+            &integer_scale_sizes(5760, 1080)[..4],
+            [(5760, 1080), (2880, 540), (1920, 360), (1440, 270)]
+        );
+        assert_eq!(
+            snap_to_integer_scale((5760, 1080), (4320, 810)),
+            (2880, 540)
+        );
+        assert_eq!(
+            snap_to_integer_scale((5760, 1080), (5760, 1080)),
+            (5760, 1080)
+        );
+        assert_eq!(snap_to_integer_scale((5760, 1080), (2, 2)), (32, 6));
+        assert_eq!(
+            snap_to_integer_scale((1921, 1081), (1280, 720)),
+            (1280, 720)
+        );
+    }
+
+    #[test]
+    fn explicit_refresh_beats_the_derived_rate() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            apply_refresh_rate(args(&["--", "game"]), Some(144)),
+            args(&["-r", "144", "--", "game"])
+        );
+        assert_eq!(
+            apply_refresh_rate(args(&["-r", "60", "--", "game"]), Some(144)),
+            args(&["-r", "60", "--", "game"])
+        );
+        assert_eq!(
+            apply_refresh_rate(args(&["--nested-refresh", "90"]), Some(144)),
+            args(&["--nested-refresh", "90"])
+        );
+        assert_eq!(apply_refresh_rate(args(&["-f"]), None), args(&["-f"]));
+    }
+
+    #[test]
+    fn refresh_placeholder_never_leaks_the_literal_token() {
+        let args = ["--", "game", "--fps={refresh}"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        let mut capped = args.clone();
+        capped.splice(0..0, ["--nested-refresh".to_string(), "144".to_string()]);
+        assert_eq!(refresh_placeholder(&capped), "144");
+        let refresh = refresh_placeholder(&args);
+        assert_eq!(refresh, "");
+        let out = substitute_command_placeholders(args, &[("refresh", refresh)]);
+        assert_eq!(out, vec!["--", "game", "--fps="]);
+    }
+
+    #[test]
+    fn substitute_command_placeholders_only_touches_game_command() {
+        let args = [
+            "-r",
+            "60",
+            "--",
+            "game",
+            "-screen-width",
+            "{internal_width}",
+            "{unknown}",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+        assert_eq!(arg_value(&args, "-r"), Some("60".to_string()));
+        let out = substitute_command_placeholders(
+            args,
+            &[
+                ("internal_width", "2880".to_string()),
+                ("refresh", "60".to_string()),
+            ],
+        );
+        assert_eq!(
+            out,
+            vec![
+                "-r",
+                "60",
+                "--",
+                "game",
+                "-screen-width",
+                "2880",
+                "{unknown}"
+            ]
+        );
+    }
+
+    #[test]
+    fn chain_pre_command_wraps_only_the_game_command() {
+        let args = ["-r", "60", "--", "game", "--fullscreen"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(chain_pre_command(args.clone(), None), args);
+        let pre = vec![
+            "game-daemon".to_string(),
+            "--log".to_string(),
+            "a b".to_string(),
+        ];
+        let out = chain_pre_command(args, Some(&pre));
+        assert_eq!(&out[..3], ["-r", "60", "--"]);
+        assert_eq!(out[3..6], ["sh", "-c", CHAIN_SCRIPT]);
+        assert_eq!(out[7], "game-daemon --log 'a b'");
+        assert_eq!(&out[8..], ["game", "--fullscreen"]);
+        assert_eq!(game_command_label(&out), "game --fullscreen");
+    }
+
+    #[test]
+    fn resolution_hint_env_matches_internal_size_and_wayland_exposure() {
+        let env = resolution_hint_env((2880, 810), false);
+        let value = |key: &str| env.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+        assert_eq!(value("HYPRFINITY_INTERNAL_WIDTH"), Some("2880"));
+        assert_eq!(value("HYPRFINITY_INTERNAL_HEIGHT"), Some("810"));
+        if std::env::var_os("SDL_VIDEODRIVER").is_none() {
+            assert_eq!(value("SDL_VIDEODRIVER"), Some("x11"));
+            let wayland = resolution_hint_env((2880, 810), true);
+            assert!(wayland.contains(&("SDL_VIDEODRIVER".to_string(), "wayland".to_string())));
+        }
+    }
+
+    #[test]
+    fn with_internal_size_replaces_nested_size_before_the_game_command() {
+        let args = [
+            "-W",
+            "5120",
+            "--nested-width",
+            "2560",
+            "-h",
+            "720",
+            "--",
+            "game",
+            "-w",
+            "1",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+        assert_eq!(
+            with_internal_size(args, (4096, 1152)),
+            vec![
+                "-W", "5120", "-w", "4096", "-h", "1152", "--", "game", "-w", "1"
+            ]
+        );
+    }
+
+    #[test]
+    fn gamescope_crashed_ignores_clean_and_requested_exits() {
+        use std::os::unix::process::ExitStatusExt;
+        assert!(!gamescope_crashed(ExitStatus::from_raw(0)));
+        assert!(!gamescope_crashed(ExitStatus::from_raw(15)));
+        assert!(gamescope_crashed(ExitStatus::from_raw(11)));
+        assert!(gamescope_crashed(ExitStatus::from_raw(1 << 8)));
+    }
+
+    #[test]
+    fn gamescope_failure_hint_matches_common_errors() {
+        assert!(
+            gamescope_failure_hint("gamescope: unrecognized option '--hdr-enabled'")
+                .unwrap()
+                .contains("gamescope_args")
+        );
+        assert!(
+            gamescope_failure_hint("No CAP_SYS_NICE, falling back to regular-priority compute")
+                .unwrap()
+                .contains("setcap")
+        );
+        assert_eq!(gamescope_failure_hint("all good"), None);
+    }
+
+    #[test]
+    fn non_interactive_game_command_honors_picker_fallback() {
+        let cmd = vec!["steam".to_string(), "-bigpicture".to_string()];
+        assert_eq!(
+            non_interactive_game_command(PickerFallback::DefaultCommand, Some(cmd.clone()), None)
+                .unwrap(),
+            cmd
+        );
+        assert!(non_interactive_game_command(PickerFallback::DefaultCommand, None, None).is_err());
+        assert!(
+            non_interactive_game_command(PickerFallback::Error, Some(cmd.clone()), None).is_err()
+        );
+
+        let recent = vec!["heroic".to_string()];
+        assert_eq!(
+            non_interactive_game_command(PickerFallback::Recent, Some(cmd), Some(recent.clone()))
+                .unwrap(),
+            recent
+        );
+        assert!(non_interactive_game_command(PickerFallback::Recent, None, None).is_err());
+    }
+
+    #[test]
+    fn parse_gamescope_state_migrates_legacy_and_tolerates_newer_files() {
+        let legacy = r#"{"gamescope_pid": 4242, "span_x": 0, "span_y": 0,
+            "span_width": 5760, "span_height": 1080}"#;
+        let state = parse_gamescope_state(legacy).unwrap();
+        assert_eq!(state.gamescope_pid, 4242);
+        assert_eq!(state.version, GAMESCOPE_STATE_VERSION);
+        assert!(!state.waybar_was_stopped);
+
+        let newer = r#"{"version": 99, "gamescope_pid": 7, "span_x": -1920, "span_y": 0,
+            "span_width": 5760, "span_height": 1080, "gamescope_args": [],
+            "waybar_was_stopped": true, "some_future_field": {"a": 1}}"#;
+        let state = parse_gamescope_state(newer).unwrap();
+        assert_eq!((state.version, state.span_x), (99, -1920));
+
+        assert!(parse_gamescope_state(r#"{"version": 2, "gamescope_pid": 12"#).is_err());
+        assert!(parse_gamescope_state("").is_err());
+    }
+
+    #[test]
+    fn classify_session_distinguishes_active_stale_and_inactive() {
+        let state = || {
+            Some(parse_gamescope_state(
+                r#"{"gamescope_pid": 4242, "span_x": 0, "span_y": 0,
+                "span_width": 5760, "span_height": 1080}"#,
+            ))
+        };
+        assert_eq!(classify_session(None, |_| true), SessionStatus::Inactive);
+        assert_eq!(
+            classify_session(state(), |pid| pid == 4242),
+            SessionStatus::Active(4242)
+        );
+        assert_eq!(classify_session(state(), |_| false), SessionStatus::Stale);
+        assert_eq!(
+            classify_session(Some(parse_gamescope_state("{")), |_| true),
+            SessionStatus::Stale
+        );
+    }
+
+    #[test]
+    fn detached_args_drop_only_hyprfinitys_detach_flag() {
+        let words = |s: &str| s.split(' ').map(str::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            detached_args(&words(
+                "--profile couch up --detach -- -r 60 -- game --detach"
+            )),
+            words("--profile couch up -- -r 60 -- game --detach")
+        );
+        assert_eq!(detached_args(&words("up --detach")), words("up"));
+    }
+}
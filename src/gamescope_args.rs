@@ -0,0 +1,364 @@
+use crate::gamescope::max_internal_size;
+use crate::util::even_floor;
+
+/// Gamescope flags hyprfinity knows by both spellings, and whether they take a value. Flags not
+/// listed here are kept as given; a bare word after one is treated as its value.
+const KNOWN_FLAGS: &[(Option<&str>, &str, bool)] = &[
+    (Some("-W"), "--output-width", true),
+    (Some("-H"), "--output-height", true),
+    (Some("-w"), "--nested-width", true),
+    (Some("-h"), "--nested-height", true),
+    (Some("-r"), "--nested-refresh", true),
+    (Some("-o"), "--nested-unfocused-refresh", true),
+    (Some("-F"), "--filter", true),
+    (Some("-S"), "--scaler", true),
+    (Some("-O"), "--prefer-output", true),
+    (Some("-R"), "--ready-fd", true),
+    (Some("-T"), "--stats-path", true),
+    (Some("-s"), "--mouse-sensitivity", true),
+    (None, "--sharpness", true),
+    (None, "--fsr-sharpness", true),
+    (None, "--backend", true),
+    (None, "--framerate-limit", true),
+    (None, "--cursor", true),
+    (None, "--xwayland-count", true),
+    (None, "--prefer-vk-device", true),
+    (None, "--default-touch-mode", true),
+    (None, "--generate-drm-mode", true),
+    (None, "--hide-cursor-delay", true),
+    (None, "--fade-out-duration", true),
+    (None, "--max-scale", true),
+    (None, "--sdr-gamut-wideness", true),
+    (None, "--hdr-sdr-content-nits", true),
+    (Some("-e"), "--steam", false),
+    (Some("-f"), "--fullscreen", false),
+    (Some("-b"), "--borderless", false),
+    (Some("-g"), "--grab", false),
+    (None, "--rt", false),
+    (None, "--immediate-flips", false),
+    (None, "--adaptive-sync", false),
+    (None, "--force-grab-cursor", false),
+    (None, "--expose-wayland", false),
+    (None, "--mangoapp", false),
+    (None, "--hdr-enabled", false),
+];
+
+/// The long name `flag` is known by (or `flag` itself), and whether it takes a value.
+fn known_flag(flag: &str) -> Option<(&'static str, bool)> {
+    KNOWN_FLAGS
+        .iter()
+        .find(|(short, long, _)| *short == Some(flag) || *long == flag)
+        .map(|(_, long, takes_value)| (*long, *takes_value))
+}
+
+fn canonical(flag: &str) -> String {
+    known_flag(flag).map_or_else(|| flag.to_string(), |(long, _)| long.to_string())
+}
+
+/// One gamescope flag as the user spelled it, keyed by its long name so `-w` and
+/// `--nested-width` are recognised as the same setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GamescopeFlag {
+    key: String,
+    spelling: String,
+    value: Option<String>,
+    /// How the value was attached: `=` for `--flag=value`, empty for `-W5760`, `None` when it
+    /// was the next argument.
+    joiner: Option<&'static str>,
+}
+
+impl GamescopeFlag {
+    fn emit(&self, out: &mut Vec<String>) {
+        match (&self.value, self.joiner) {
+            (Some(value), Some(joiner)) => {
+                out.push(format!("{}{}{}", self.spelling, joiner, value))
+            }
+            (Some(value), None) => out.extend([self.spelling.clone(), value.clone()]),
+            (None, _) => out.push(self.spelling.clone()),
+        }
+    }
+}
+
+/// Gamescope arguments split into flags (before `--`) and the game command (from `--` on).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct GamescopeArgs {
+    flags: Vec<GamescopeFlag>,
+    command: Vec<String>,
+}
+
+impl GamescopeArgs {
+    pub(crate) fn parse(args: &[String]) -> Self {
+        let split = args.iter().position(|a| a == "--").unwrap_or(args.len());
+        let mut flags: Vec<GamescopeFlag> = Vec::new();
+        let mut iter = args[..split].iter();
+        while let Some(arg) = iter.next() {
+            if let Some((name, value)) = arg.split_once('=')
+                && name.starts_with("--")
+            {
+                flags.push(GamescopeFlag {
+                    key: canonical(name),
+                    spelling: name.to_string(),
+                    value: Some(value.to_string()),
+                    joiner: Some("="),
+                });
+                continue;
+            }
+            match known_flag(arg) {
+                Some((long, true)) => {
+                    flags.push(GamescopeFlag {
+                        key: long.to_string(),
+                        spelling: arg.clone(),
+                        value: iter.next().cloned(),
+                        joiner: None,
+                    });
+                    continue;
+                }
+                Some((long, false)) => {
+                    flags.push(GamescopeFlag {
+                        key: long.to_string(),
+                        spelling: arg.clone(),
+                        value: None,
+                        joiner: None,
+                    });
+                    continue;
+                }
+                None => {}
+            }
+            // getopt also takes `-W5760`; only numbers are read that way, so an unknown flag
+            // such as `-rt` is not mistaken for `-r t`.
+            if !arg.starts_with("--")
+                && let Some(short) = arg.get(..2)
+                && let Some((long, true)) = known_flag(short)
+                && arg[2..].parse::<f64>().is_ok()
+            {
+                flags.push(GamescopeFlag {
+                    key: long.to_string(),
+                    spelling: short.to_string(),
+                    value: Some(arg[2..].to_string()),
+                    joiner: Some(""),
+                });
+                continue;
+            }
+            if !arg.starts_with('-')
+                && let Some(last) = flags.last_mut()
+                && last.value.is_none()
+                && known_flag(&last.spelling).is_none()
+            {
+                last.value = Some(arg.clone());
+                continue;
+            }
+            flags.push(GamescopeFlag {
+                key: canonical(arg),
+                spelling: arg.clone(),
+                value: None,
+                joiner: None,
+            });
+        }
+        GamescopeArgs {
+            flags,
+            command: args[split..].to_vec(),
+        }
+    }
+
+    /// Whether `flag` (by either spelling) is set.
+    pub(crate) fn has(&self, flag: &str) -> bool {
+        let key = canonical(flag);
+        self.flags.iter().any(|f| f.key == key)
+    }
+
+    /// The value `flag` takes effect with: gamescope reads arguments in order, so the last one.
+    pub(crate) fn value(&self, flag: &str) -> Option<&str> {
+        let key = canonical(flag);
+        self.flags
+            .iter()
+            .rev()
+            .find(|f| f.key == key)
+            .and_then(|f| f.value.as_deref())
+    }
+
+    /// Collapses repeated flags to one, keeping the first position and the last occurrence (the
+    /// one gamescope would use). Returns a description of each flag that was given conflicting values.
+    pub(crate) fn dedup(&mut self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        let mut kept: Vec<GamescopeFlag> = Vec::new();
+        for flag in self.flags.drain(..) {
+            match kept.iter_mut().find(|k| k.key == flag.key) {
+                Some(existing) => {
+                    if let (Some(old), Some(new)) = (&existing.value, &flag.value)
+                        && old != new
+                    {
+                        conflicts.push(format!(
+                            "{} given as {} and {}; using {}",
+                            flag.key, old, new, new
+                        ));
+                    }
+                    *existing = flag;
+                }
+                None => kept.push(flag),
+            }
+        }
+        self.flags = kept;
+        conflicts
+    }
+
+    /// Drops every occurrence of `flag`, under either spelling.
+    pub(crate) fn remove(&mut self, flag: &str) {
+        let key = canonical(flag);
+        self.flags.retain(|f| f.key != key);
+    }
+
+    /// Adds `flag value` unless the user already set it. Returns the user's value when it
+    /// differs from the one hyprfinity derived.
+    pub(crate) fn set_default(&mut self, flag: &str, value: &str) -> Option<String> {
+        if let Some(existing) = self.value(flag) {
+            return (existing != value).then(|| existing.to_string());
+        }
+        self.flags.push(GamescopeFlag {
+            key: canonical(flag),
+            spelling: flag.to_string(),
+            value: Some(value.to_string()),
+            joiner: None,
+        });
+        None
+    }
+
+    /// Adds the switch `flag` unless the user already set it.
+    pub(crate) fn set_default_switch(&mut self, flag: &str) {
+        if self.has(flag) {
+            return;
+        }
+        self.flags.push(GamescopeFlag {
+            key: canonical(flag),
+            spelling: flag.to_string(),
+            value: None,
+            joiner: None,
+        });
+    }
+
+    /// Replaces the value of `flag` where it already appears.
+    fn set(&mut self, flag: &str, value: &str) {
+        let key = canonical(flag);
+        for f in self.flags.iter_mut().filter(|f| f.key == key) {
+            f.value = Some(value.to_string());
+        }
+    }
+
+    /// Checks the final output and nested sizes and corrects values gamescope would mishandle:
+    /// missing or non-positive sizes fall back to the derived ones, odd sizes are rounded down to
+    /// even, and a nested size larger than the output (or, with `allow_supersample`, than the
+    /// supersampling limit) is clamped to it. Returns a message for each correction.
+    pub(crate) fn correct_sizes(
+        &mut self,
+        derived_output: (i32, i32),
+        derived_internal: (i32, i32),
+        allow_supersample: bool,
+    ) -> Vec<String> {
+        let mut corrections = Vec::new();
+        let mut size = |args: &mut Self, flag: &str, derived: i32, max: Option<(&str, i32)>| {
+            let given = args.value(flag).unwrap_or_default().to_string();
+            let mut value = match given.parse::<i32>() {
+                Ok(v) if v > 0 => v,
+                _ => {
+                    corrections.push(format!(
+                        "{} {:?} is not a positive size; using {}",
+                        flag, given, derived
+                    ));
+                    derived
+                }
+            };
+            if value % 2 != 0 {
+                let even = even_floor(value);
+                corrections.push(format!("{} {} is odd; using {}", flag, value, even));
+                value = even;
+            }
+            if let Some((max_flag, max)) = max
+                && value > max
+            {
+                corrections.push(format!(
+                    "{} {} is larger than {} allows ({}); using {}",
+                    flag, value, max_flag, max, max
+                ));
+                value = max;
+            }
+            if value.to_string() != given {
+                args.set(flag, &value.to_string());
+            }
+            value
+        };
+        let output_w = size(self, "-W", derived_output.0, None);
+        let output_h = size(self, "-H", derived_output.1, None);
+        let (max_w, max_h) = max_internal_size(output_w, output_h, allow_supersample);
+        size(self, "-w", derived_internal.0, Some(("-W", max_w)));
+        size(self, "-h", derived_internal.1, Some(("-H", max_h)));
+        corrections
+    }
+
+    pub(crate) fn into_args(self) -> Vec<String> {
+        let mut out = Vec::new();
+        for flag in &self.flags {
+            flag.emit(&mut out);
+        }
+        out.extend(self.command);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(s: &str) -> Vec<String> {
+        s.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn parse_aliases_spellings_and_resolves_conflicts_last_wins() {
+        let mut args = GamescopeArgs::parse(&words(
+            "-w 1280 --rt -W5760 --nested-width=1920 --future-flag 3 -rt -- game -w 640",
+        ));
+        assert!(args.has("--nested-width"));
+        assert!(args.has("-rt"));
+        assert!(!args.has("-r"));
+        assert_eq!(args.value("-w"), Some("1920"));
+        assert_eq!(args.value("--output-width"), Some("5760"));
+        assert_eq!(args.value("--future-flag"), Some("3"));
+
+        assert_eq!(
+            args.dedup(),
+            vec!["--nested-width given as 1280 and 1920; using 1920".to_string()]
+        );
+        assert_eq!(args.set_default("-W", "3840"), Some("5760".to_string()));
+        assert_eq!(args.set_default("-h", "720"), None);
+        args.set_default_switch("--rt");
+        assert_eq!(
+            args.into_args(),
+            words("--nested-width=1920 --rt -W5760 --future-flag 3 -rt -h 720 -- game -w 640")
+        );
+    }
+
+    #[test]
+    fn correct_sizes_fixes_non_positive_odd_and_oversized_values() {
+        let mut args = GamescopeArgs::parse(&words("-W 3840 -H 0 -w 5121 --nested-height=1080"));
+        let mut supersampled = args.clone();
+        let corrections = args.correct_sizes((3840, 1080), (2880, 810), false);
+        assert_eq!(corrections.len(), 3);
+        assert!(corrections[0].starts_with("-H \"0\" is not a positive size"));
+        assert_eq!(corrections[1], "-w 5121 is odd; using 5120");
+        assert_eq!(
+            corrections[2],
+            "-w 5120 is larger than -W allows (3840); using 3840"
+        );
+        assert_eq!(
+            args.into_args(),
+            words("-W 3840 -H 1080 -w 3840 --nested-height=1080")
+        );
+
+        assert_eq!(
+            supersampled
+                .correct_sizes((3840, 1080), (2880, 810), true)
+                .len(),
+            2
+        );
+        assert_eq!(supersampled.value("-w"), Some("5120"));
+    }
+}
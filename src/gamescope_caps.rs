@@ -0,0 +1,164 @@
+use crate::backend::gamescope_launcher;
+use crate::debuglog::debug_log_line;
+use crate::output::debug;
+use crate::util::find_in_path;
+use std::collections::BTreeSet;
+use std::process::{Command, Stdio};
+
+// Flags renamed between gamescope releases (3.12 folded the upscaler switches into
+// -F/--filter and -S/--scaler). Each pair is tried in both directions.
+const FLAG_RENAMES: &[(&[&str], &[&str])] = &[
+    (&["-U"], &["-F", "fsr"]),
+    (&["--fsr-upscaling"], &["--filter", "fsr"]),
+    (&["-Y"], &["-F", "nis"]),
+    (&["--nis-upscaling"], &["--filter", "nis"]),
+    (&["-n"], &["-F", "nearest"]),
+    (&["--nearest-neighbor-filter"], &["--filter", "nearest"]),
+    (&["-i"], &["-S", "integer"]),
+    (&["--integer-scale"], &["--scaler", "integer"]),
+    (&["--fsr-sharpness"], &["--sharpness"]),
+];
+
+pub(crate) fn parse_help_flags(help: &str) -> BTreeSet<String> {
+    let mut flags = BTreeSet::new();
+    for token in help.split_whitespace() {
+        let token = token.trim_end_matches([',', ':', ';']);
+        let token = token.split(['=', '[']).next().unwrap_or_default();
+        if token.len() < 2 || token == "--" || !token.starts_with('-') {
+            continue;
+        }
+        let name = token.trim_start_matches('-');
+        if name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            flags.insert(token.to_string());
+        }
+    }
+    flags
+}
+
+pub(crate) fn probe_gamescope_flags(verbose: bool) -> Option<BTreeSet<String>> {
+    let help = gamescope_launcher().help()?;
+    let flags = parse_help_flags(&help);
+    debug_log_line(&format!(
+        "gamescope --help advertised {} flags",
+        flags.len()
+    ));
+    if verbose {
+        debug(&format!(
+            "gamescope --help advertised {} flags.",
+            flags.len()
+        ));
+    }
+    if flags.is_empty() { None } else { Some(flags) }
+}
+
+/// Whether the gamescope binary carries CAP_SYS_NICE (needed for `--rt`), or `None` when
+/// getcap is unavailable.
+pub(crate) fn gamescope_has_sys_nice() -> Option<bool> {
+    let gamescope = find_in_path("gamescope")?;
+    let output = Command::new("getcap")
+        .arg(&gamescope)
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    let caps = String::from_utf8_lossy(&output.stdout).to_lowercase();
+    Some(caps.contains("cap_sys_nice"))
+}
+
+fn flag_name(arg: &str) -> &str {
+    arg.split('=').next().unwrap_or(arg)
+}
+
+fn is_supported(supported: &BTreeSet<String>, arg: &str) -> bool {
+    supported.contains(flag_name(arg))
+}
+
+pub(crate) fn adapt_gamescope_args(
+    args: Vec<String>,
+    supported: &BTreeSet<String>,
+) -> (Vec<String>, Vec<String>) {
+    let split = args.iter().position(|a| a == "--").unwrap_or(args.len());
+    let (pre, post) = args.split_at(split);
+    let mut adapted: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+
+    let mut i = 0;
+    'outer: while i < pre.len() {
+        let arg = &pre[i];
+        let is_flag = arg.starts_with('-')
+            && arg.len() >= 2
+            && !arg[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.');
+        if !is_flag || is_supported(supported, arg) {
+            adapted.push(arg.clone());
+            i += 1;
+            continue;
+        }
+
+        for (old, new) in FLAG_RENAMES {
+            for (from, to) in [(*old, *new), (*new, *old)] {
+                let matches = from
+                    .iter()
+                    .enumerate()
+                    .all(|(offset, tok)| pre.get(i + offset).is_some_and(|a| a == tok));
+                if matches && is_supported(supported, to[0]) {
+                    warnings.push(format!(
+                        "gamescope does not support `{}`; using `{}` instead.",
+                        from.join(" "),
+                        to.join(" ")
+                    ));
+                    adapted.extend(to.iter().map(|s| s.to_string()));
+                    i += from.len();
+                    continue 'outer;
+                }
+            }
+        }
+
+        warnings.push(format!(
+            "`{}` is not supported by the installed gamescope; it will likely fail to start.",
+            flag_name(arg)
+        ));
+        adapted.push(arg.clone());
+        i += 1;
+    }
+
+    adapted.extend(post.iter().cloned());
+    (adapted, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_help_flags_collects_short_and_long_names() {
+        let help = "usage: gamescope [options...] -- [command...]\n\
+                    \x20 -W, --output-width       output width\n\
+                    \x20 -F, --filter             upscaler filter (linear, fsr)\n\
+                    \x20 --mangoapp               launch mangoapp\n";
+        let flags = parse_help_flags(help);
+        for flag in ["-W", "--output-width", "-F", "--filter", "--mangoapp"] {
+            assert!(flags.contains(flag), "missing {flag}");
+        }
+        assert!(!flags.contains("--"));
+    }
+
+    #[test]
+    fn adapt_gamescope_args_maps_renamed_flags_and_warns() {
+        let supported = ["-F", "-W", "--sharpness"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<BTreeSet<_>>();
+        let args = ["-U", "--fsr-sharpness", "5", "--hdr-enabled", "--", "-U"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        let (adapted, warnings) = adapt_gamescope_args(args, &supported);
+        assert_eq!(
+            adapted,
+            vec!["-F", "fsr", "--sharpness", "5", "--hdr-enabled", "--", "-U"]
+        );
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[2].contains("--hdr-enabled"));
+    }
+}
@@ -0,0 +1,167 @@
+use crate::cli::Cli;
+use crate::i18n::{Msg, trf};
+use crate::output::info;
+use clap::{Arg, Command, CommandFactory};
+use std::error::Error;
+use std::path::Path;
+
+/// The CLI definition with global options propagated and help subcommands added, as `--help`
+/// sees it.
+fn built_cli() -> Command {
+    let mut cmd = Cli::command();
+    cmd.build();
+    cmd
+}
+
+/// Subcommands worth documenting: visible ones other than clap's `help`.
+fn documented_subcommands(cmd: &Command) -> impl Iterator<Item = &Command> {
+    cmd.get_subcommands()
+        .filter(|sub| !sub.is_hide_set() && sub.get_name() != "help")
+}
+
+/// Writes `NAME.1` for `cmd` and one page per subcommand, named `hyprfinity-SUB.1` the way git
+/// and cargo name theirs.
+fn write_man_pages(cmd: &Command, name: &str, dir: &Path) -> Result<usize, Box<dyn Error>> {
+    let mut page = Vec::new();
+    clap_mangen::Man::new(cmd.clone().display_name(name.to_string()))
+        .title(name)
+        .render(&mut page)?;
+    std::fs::write(dir.join(format!("{}.1", name)), page)?;
+    let mut written = 1;
+    for sub in documented_subcommands(cmd) {
+        written += write_man_pages(sub, &format!("{}-{}", name, sub.get_name()), dir)?;
+    }
+    Ok(written)
+}
+
+fn arg_signature(arg: &Arg) -> String {
+    let values = arg
+        .get_value_names()
+        .map(|names| {
+            names
+                .iter()
+                .map(|name| format!("<{}>", name))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_else(|| format!("<{}>", arg.get_id().as_str().to_uppercase()));
+    let takes_value = arg.get_action().takes_values();
+    match (arg.get_long(), arg.get_short()) {
+        (None, None) => values,
+        (long, short) => {
+            let flags = short
+                .map(|s| format!("-{}", s))
+                .into_iter()
+                .chain(long.map(|l| format!("--{}", l)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            if takes_value {
+                format!("{} {}", flags, values)
+            } else {
+                flags
+            }
+        }
+    }
+}
+
+fn arg_line(arg: &Arg) -> String {
+    let mut line = format!("- `{}`", arg_signature(arg));
+    if let Some(help) = arg.get_long_help().or(arg.get_help()) {
+        line.push_str(&format!(": {}", help.to_string().replace('\n', " ")));
+    }
+    let possible = arg
+        .get_possible_values()
+        .iter()
+        .filter(|value| !value.is_hide_set())
+        .map(|value| format!("`{}`", value.get_name()))
+        .collect::<Vec<_>>();
+    if !possible.is_empty() && arg.get_action().takes_values() {
+        line.push_str(&format!(" One of {}.", possible.join(", ")));
+    }
+    let defaults = arg
+        .get_default_values()
+        .iter()
+        .map(|value| value.to_string_lossy())
+        .collect::<Vec<_>>();
+    if !defaults.is_empty() && arg.get_action().takes_values() {
+        line.push_str(&format!(" Default: `{}`.", defaults.join(" ")));
+    }
+    line
+}
+
+fn push_markdown_section(cmd: &Command, path: &str, out: &mut String) {
+    out.push_str(&format!("\n## `{}`\n\n", path));
+    if let Some(about) = cmd.get_long_about().or(cmd.get_about()) {
+        out.push_str(&format!("{}\n\n", about));
+    }
+    let usage = cmd.clone().bin_name(path.to_string()).render_usage();
+    out.push_str(&format!(
+        "```text\n{}\n```\n",
+        usage.to_string().trim_start_matches("Usage: ")
+    ));
+    let args = cmd
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set() && !matches!(arg.get_id().as_str(), "help" | "version"))
+        // Global options are listed once, under the top-level command.
+        .filter(|arg| path == cmd.get_name() || !arg.is_global_set())
+        .map(arg_line)
+        .collect::<Vec<_>>();
+    if !args.is_empty() {
+        out.push_str(&format!("\n{}\n", args.join("\n")));
+    }
+    for sub in documented_subcommands(cmd) {
+        push_markdown_section(sub, &format!("{} {}", path, sub.get_name()), out);
+    }
+}
+
+/// Markdown reference for every command, generated from the same definition as `--help`.
+pub(crate) fn markdown_reference() -> String {
+    let cmd = built_cli();
+    let mut out = format!(
+        "# {} command-line reference\n\nGenerated by `{} gen-docs` from version {}; do not edit by hand.\n",
+        cmd.get_name(),
+        cmd.get_name(),
+        env!("CARGO_PKG_VERSION")
+    );
+    push_markdown_section(&cmd, cmd.get_name(), &mut out);
+    out
+}
+
+/// Writes man pages to `OUT_DIR/man` and the markdown reference to `OUT_DIR/cli-reference.md`.
+pub(crate) fn gen_docs(out_dir: &str) -> Result<(), Box<dyn Error>> {
+    let out_dir = Path::new(out_dir);
+    let man_dir = out_dir.join("man");
+    std::fs::create_dir_all(&man_dir)?;
+    let cmd = built_cli();
+    let pages = write_man_pages(&cmd, cmd.get_name(), &man_dir)?;
+    let reference = out_dir.join("cli-reference.md");
+    std::fs::write(&reference, markdown_reference())?;
+    info(&trf(
+        Msg::WroteManPages,
+        &[&pages, &man_dir.display(), &reference.display()],
+    ));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn docs_cover_every_visible_subcommand() {
+        let reference = markdown_reference();
+        assert!(reference.contains("\n## `hyprfinity gamescope-up`\n"));
+        assert!(reference.contains("\n## `hyprfinity config schema`\n"));
+        assert!(reference.contains("- `--config <CONFIG>`"));
+        // Globals are documented once, not under every subcommand.
+        assert_eq!(reference.matches("- `--config <CONFIG>`").count(), 1);
+        assert!(!reference.contains("## `hyprfinity help`"));
+
+        let dir = tempfile::tempdir().unwrap();
+        gen_docs(dir.path().to_str().unwrap()).unwrap();
+        let root = std::fs::read_to_string(dir.path().join("man/hyprfinity.1")).unwrap();
+        assert!(root.contains(".TH"));
+        assert!(dir.path().join("man/hyprfinity-gamescope-up.1").exists());
+        assert!(dir.path().join("man/hyprfinity-config-schema.1").exists());
+    }
+}
@@ -0,0 +1,176 @@
+use crate::config::resolve_config_path;
+use crate::debuglog::debug_log_line;
+use crate::i18n::{Msg, trf};
+use crate::output::{debug, warn};
+use std::collections::BTreeMap;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const HOOKS_DIR_NAME: &str = "hooks.d";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HookStage {
+    PreUp,
+    PostUp,
+    PreDown,
+    PostDown,
+}
+
+impl HookStage {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            HookStage::PreUp => "pre-up",
+            HookStage::PostUp => "post-up",
+            HookStage::PreDown => "pre-down",
+            HookStage::PostDown => "post-down",
+        }
+    }
+}
+
+/// Session details handed to hooks as HYPRFINITY_* environment variables.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HookContext {
+    pub(crate) span: (i32, i32, i32, i32),
+    pub(crate) pid: Option<u32>,
+    pub(crate) game: Option<String>,
+}
+
+impl HookContext {
+    fn env(&self, stage: HookStage) -> Vec<(String, String)> {
+        let (x, y, width, height) = self.span;
+        let mut env = vec![
+            ("HYPRFINITY_HOOK".to_string(), stage.as_str().to_string()),
+            ("HYPRFINITY_SPAN_X".to_string(), x.to_string()),
+            ("HYPRFINITY_SPAN_Y".to_string(), y.to_string()),
+            ("HYPRFINITY_SPAN_W".to_string(), width.to_string()),
+            ("HYPRFINITY_SPAN_H".to_string(), height.to_string()),
+        ];
+        if let Some(pid) = self.pid {
+            env.push(("HYPRFINITY_PID".to_string(), pid.to_string()));
+        }
+        if let Some(game) = self.game.as_ref() {
+            env.push(("HYPRFINITY_GAME".to_string(), game.clone()));
+        }
+        env
+    }
+}
+
+pub(crate) fn hooks_dir() -> Option<PathBuf> {
+    resolve_config_path(&None)
+        .ok()
+        .map(|path| path.with_file_name(HOOKS_DIR_NAME))
+}
+
+/// Executables in `dir` named `<stage>-*`, in file-name order.
+fn find_hooks(dir: &Path, stage: HookStage) -> Vec<PathBuf> {
+    let prefix = format!("{}-", stage.as_str());
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut hooks: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .filter(|entry| {
+            entry
+                .metadata()
+                .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        })
+        .map(|entry| entry.path())
+        .collect();
+    hooks.sort();
+    hooks
+}
+
+/// The hooks each stage would run now, by stage name.
+pub(crate) fn planned_hooks() -> BTreeMap<String, Vec<String>> {
+    let Some(dir) = hooks_dir() else {
+        return BTreeMap::new();
+    };
+    [
+        HookStage::PreUp,
+        HookStage::PostUp,
+        HookStage::PreDown,
+        HookStage::PostDown,
+    ]
+    .into_iter()
+    .map(|stage| {
+        let hooks = find_hooks(&dir, stage)
+            .iter()
+            .map(|hook| hook.to_string_lossy().to_string())
+            .collect();
+        (stage.as_str().to_string(), hooks)
+    })
+    .collect()
+}
+
+/// Runs every hook for `stage` and waits for each; a failing hook is reported, never fatal.
+pub(crate) fn run_hooks(stage: HookStage, context: &HookContext, verbose: bool) {
+    let Some(dir) = hooks_dir() else {
+        return;
+    };
+    let env = context.env(stage);
+    for hook in find_hooks(&dir, stage) {
+        if verbose {
+            debug(&format!("Running {} hook {:?}", stage.as_str(), hook));
+        }
+        debug_log_line(&format!("hook {} {:?}", stage.as_str(), hook));
+        match Command::new(&hook)
+            .envs(env.iter().cloned())
+            .stdin(Stdio::null())
+            .status()
+        {
+            Ok(status) if status.success() => {}
+            Ok(status) => warn(&trf(Msg::HookExited, &[&hook.display(), &status])),
+            Err(e) => warn(&trf(Msg::HookFailed, &[&hook.display(), &e])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_hook(dir: &Path, name: &str, mode: u32) {
+        let path = dir.join(name);
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn find_hooks_matches_stage_prefix_and_executable_bit() {
+        let dir = tempfile::tempdir().unwrap();
+        write_hook(dir.path(), "pre-up-20-obs", 0o755);
+        write_hook(dir.path(), "pre-up-10-lights", 0o755);
+        write_hook(dir.path(), "pre-up-notes.txt", 0o644);
+        write_hook(dir.path(), "post-up-rgb", 0o755);
+        write_hook(dir.path(), "pre-down-rgb", 0o755);
+
+        let names = |stage| {
+            find_hooks(dir.path(), stage)
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(HookStage::PreUp),
+            vec!["pre-up-10-lights", "pre-up-20-obs"]
+        );
+        assert_eq!(names(HookStage::PostUp), vec!["post-up-rgb"]);
+        assert!(names(HookStage::PostDown).is_empty());
+    }
+
+    #[test]
+    fn hook_context_env_includes_span_and_optional_fields() {
+        let context = HookContext {
+            span: (-1920, 0, 5760, 1080),
+            pid: Some(42),
+            game: None,
+        };
+        let env = context.env(HookStage::PostUp);
+        assert!(env.contains(&("HYPRFINITY_HOOK".to_string(), "post-up".to_string())));
+        assert!(env.contains(&("HYPRFINITY_SPAN_W".to_string(), "5760".to_string())));
+        assert!(env.contains(&("HYPRFINITY_PID".to_string(), "42".to_string())));
+        assert!(!env.iter().any(|(key, _)| key == "HYPRFINITY_GAME"));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::compositor::compositor;
+use crate::debuglog::debug_log_line;
+use crate::output::debug;
+use std::io::{BufRead, BufReader};
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+/// Hyprland's event socket: under `$XDG_RUNTIME_DIR/hypr` since 0.40, `/tmp/hypr` before.
+fn event_socket_path() -> Option<PathBuf> {
+    let signature = std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").filter(|s| !s.is_empty())?;
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(|dir| PathBuf::from(dir).join("hypr"))
+        .into_iter()
+        .chain([PathBuf::from("/tmp/hypr")])
+        .map(|dir| dir.join(&signature).join(".socket2.sock"))
+        .find(|path| path.exists())
+}
+
+/// Events arrive as `NAME>>DATA`, one per line.
+fn is_config_reload(line: &str) -> bool {
+    line.split_once(">>")
+        .is_some_and(|(event, _)| event == "configreloaded")
+}
+
+/// Follows Hyprland's `configreloaded` event for the length of a session.
+pub(crate) struct ReloadWatcher {
+    stream: UnixStream,
+    reloaded: Arc<AtomicBool>,
+}
+
+impl ReloadWatcher {
+    /// Whether the config was reloaded since the last call.
+    pub(crate) fn take_reloaded(&self) -> bool {
+        self.reloaded.swap(false, Ordering::SeqCst)
+    }
+}
+
+impl Drop for ReloadWatcher {
+    fn drop(&mut self) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+/// Connects to the event socket; `None` outside Hyprland or when the socket is missing.
+pub(crate) fn start_reload_watcher(verbose: bool) -> Option<ReloadWatcher> {
+    if compositor().name() != "hyprland" {
+        return None;
+    }
+    let path = event_socket_path()?;
+    let stream = match UnixStream::connect(&path) {
+        Ok(stream) => stream,
+        Err(e) => {
+            if verbose {
+                debug(&format!(
+                    "Cannot connect to Hyprland events at {}: {}",
+                    path.display(),
+                    e
+                ));
+            }
+            return None;
+        }
+    };
+    let reader = stream.try_clone().ok()?;
+    let reloaded = Arc::new(AtomicBool::new(false));
+    {
+        let reloaded = Arc::clone(&reloaded);
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines().map_while(Result::ok) {
+                if is_config_reload(&line) {
+                    debug_log_line("hyprland configreloaded event");
+                    reloaded.store(true, Ordering::SeqCst);
+                }
+            }
+        });
+    }
+    debug_log_line(&format!("watching Hyprland events at {}", path.display()));
+    Some(ReloadWatcher { stream, reloaded })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_reload_events_are_recognised() {
+        assert!(is_config_reload("configreloaded>>"));
+        assert!(!is_config_reload("activewindow>>gamescope,Steam"));
+        assert!(!is_config_reload("windowtitle>>configreloaded>>"));
+        assert!(!is_config_reload("configreloaded"));
+    }
+}
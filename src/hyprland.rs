@@ -1,76 +1,156 @@
 use crate::MyError;
+use crate::backend::hyprland;
+use crate::compositor::{Compositor, WindowRect, compositor};
 use crate::debuglog::debug_log_line;
-use crate::types::{Client, Monitor};
-use std::process::Command;
+use crate::i18n::{Msg, tr, trf};
+use crate::metrics::{count_fit_failure, count_hyprctl_error};
+use crate::output::{debug, warn};
+use crate::trace::record_hyprctl;
+use crate::types::{
+    Client, FitSettings, HotkeyBind, Monitor, SpanStrategy, SpanTarget, WindowProbe,
+};
+use regex::Regex;
+use std::cmp::Reverse;
+use std::sync::OnceLock;
 use std::thread;
 use std::time::Duration;
 
-pub(crate) fn execute_hyprctl(
+pub(crate) const DEFAULT_HYPRCTL_ATTEMPTS: u32 = 3;
+pub(crate) const DEFAULT_HYPRCTL_BACKOFF_MS: u64 = 100;
+
+#[derive(Debug, Clone, Copy)]
+struct HyprctlRetryPolicy {
+    attempts: u32,
+    backoff_ms: u64,
+}
+
+static HYPRCTL_RETRY_POLICY: OnceLock<HyprctlRetryPolicy> = OnceLock::new();
+
+pub(crate) fn init_hyprctl_retry(attempts: u32, backoff_ms: u64) {
+    let _ = HYPRCTL_RETRY_POLICY.set(HyprctlRetryPolicy {
+        attempts: attempts.max(1),
+        backoff_ms,
+    });
+}
+
+fn hyprctl_retry_policy() -> HyprctlRetryPolicy {
+    HYPRCTL_RETRY_POLICY
+        .get()
+        .copied()
+        .unwrap_or(HyprctlRetryPolicy {
+            attempts: DEFAULT_HYPRCTL_ATTEMPTS,
+            backoff_ms: DEFAULT_HYPRCTL_BACKOFF_MS,
+        })
+}
+
+pub(crate) fn retry_delay(backoff_ms: u64, attempt: u32) -> Duration {
+    let factor = 1_u64 << attempt.saturating_sub(1).min(6);
+    Duration::from_millis(backoff_ms.saturating_mul(factor))
+}
+
+fn run_hyprctl(
     args: &[&str],
     verbose: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
-    debug_log_line(&format!("hyprctl {:?} (void)", args));
-    if verbose {
-        println!(
-            "Hyprfinity (DEBUG): Executing hyprctl with args: {:?}",
-            args
-        );
+    mode: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let policy = hyprctl_retry_policy();
+    let mut attempt = 1;
+    loop {
+        match run_hyprctl_once(args, verbose, mode) {
+            Ok(stdout) => return Ok(stdout),
+            // A missing hyprctl binary will not fix itself; only retry runtime failures.
+            Err(e)
+                if attempt < policy.attempts
+                    && e.downcast_ref::<std::io::Error>()
+                        .is_none_or(|io| io.kind() != std::io::ErrorKind::NotFound) =>
+            {
+                let delay = retry_delay(policy.backoff_ms, attempt);
+                debug_log_line(&format!(
+                    "hyprctl {:?} attempt {}/{} failed ({}); retrying in {}ms",
+                    args,
+                    attempt,
+                    policy.attempts,
+                    e,
+                    delay.as_millis()
+                ));
+                if verbose {
+                    debug(&format!(
+                        "hyprctl attempt {}/{} failed: {}; retrying in {}ms",
+                        attempt,
+                        policy.attempts,
+                        e,
+                        delay.as_millis()
+                    ));
+                }
+                thread::sleep(delay);
+                attempt += 1;
+            }
+            Err(e) => {
+                count_hyprctl_error();
+                return Err(e);
+            }
+        }
     }
-    let output = Command::new("hyprctl").args(args).output()?;
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+fn run_hyprctl_once(
+    args: &[&str],
+    verbose: bool,
+    mode: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    debug_log_line(&format!("hyprctl {:?} ({})", args, mode));
+    if verbose {
+        debug(&format!("Executing hyprctl with args: {:?}", args));
+    }
+    let output = hyprland().hyprctl(args)?;
+    record_hyprctl(args, &output);
+    let (stdout, stderr) = (output.stdout, output.stderr);
 
     if verbose {
-        println!("Hyprfinity (DEBUG): hyprctl stdout: {}", stdout.trim());
-        println!("Hyprfinity (DEBUG): hyprctl stderr: {}", stderr.trim());
-        println!("Hyprfinity (DEBUG): hyprctl exit status: {}", output.status);
+        debug(&format!("hyprctl stdout: {}", stdout.trim()));
+        debug(&format!("hyprctl stderr: {}", stderr.trim()));
+        debug(&format!("hyprctl succeeded: {}", output.success));
     }
     debug_log_line(&format!(
-        "hyprctl status={} stdout='{}' stderr='{}'",
-        output.status,
+        "hyprctl success={} stdout='{}' stderr='{}'",
+        output.success,
         stdout.trim(),
         stderr.trim()
     ));
 
-    if !output.status.success() {
+    if !output.success {
         return Err(MyError(format!("hyprctl failed for args {:?}: {}", args, stderr)).into());
     }
-    Ok(())
+    Ok(stdout)
+}
+
+pub(crate) fn execute_hyprctl(
+    args: &[&str],
+    verbose: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_hyprctl(args, verbose, "void").map(|_| ())
 }
 
 pub(crate) fn execute_hyprctl_output(
     args: &[&str],
     verbose: bool,
 ) -> Result<String, Box<dyn std::error::Error>> {
-    debug_log_line(&format!("hyprctl {:?} (capture)", args));
-    if verbose {
-        println!(
-            "Hyprfinity (DEBUG): Executing hyprctl with args: {:?}",
-            args
-        );
-    }
-    let output = Command::new("hyprctl").args(args).output()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    run_hyprctl(args, verbose, "capture")
+}
 
-    if verbose {
-        println!("Hyprfinity (DEBUG): hyprctl stdout: {}", stdout.trim());
-        println!("Hyprfinity (DEBUG): hyprctl stderr: {}", stderr.trim());
-        println!("Hyprfinity (DEBUG): hyprctl exit status: {}", output.status);
-    }
-    debug_log_line(&format!(
-        "hyprctl status={} stdout='{}' stderr='{}'",
-        output.status,
-        stdout.trim(),
-        stderr.trim()
-    ));
+pub(crate) fn hyprctl_dispatch(dispatcher: &str, arg: &str) -> String {
+    format!("dispatch {} {}", dispatcher, arg)
+}
 
-    if !output.status.success() {
-        return Err(MyError(format!("hyprctl failed for args {:?}: {}", args, stderr)).into());
+pub(crate) fn execute_hyprctl_batch(
+    commands: &[String],
+    verbose: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if commands.is_empty() {
+        return Ok(());
     }
-    Ok(stdout)
+    let batch = commands.join(" ; ");
+    execute_hyprctl(&["--batch", &batch], verbose)
 }
 
 fn normalize_bind_token(value: &str) -> String {
@@ -96,32 +176,199 @@ pub(crate) fn bind_exists(
     Ok(false)
 }
 
-pub(crate) fn get_monitors(verbose: bool) -> Result<Vec<Monitor>, Box<dyn std::error::Error>> {
-    let stdout = execute_hyprctl_output(&["monitors", "-j"], verbose)?;
-    debug_log_line(&format!("raw monitors json: {}", stdout.trim()));
-    let monitors: Vec<Monitor> = serde_json::from_str(&stdout)
-        .map_err(|e| MyError(format!("Failed to parse hyprctl output: {}", e)))?;
+/// Reads an integer Hyprland option (`hyprctl getoption NAME -j`).
+pub(crate) fn get_option_int(name: &str, verbose: bool) -> Result<i64, Box<dyn std::error::Error>> {
+    let stdout = execute_hyprctl_output(&["getoption", name, "-j"], verbose)?;
+    let value: serde_json::Value = serde_json::from_str(&stdout)
+        .map_err(|e| MyError(format!("Failed to parse hyprctl getoption output: {}", e)))?;
+    value
+        .get("int")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| MyError(format!("Hyprland option {} is not an integer.", name)).into())
+}
+
+/// Hyprland through hyprctl (or the mock and replay stand-ins behind it).
+pub(crate) struct HyprlandCompositor;
+
+impl Compositor for HyprlandCompositor {
+    fn name(&self) -> &'static str {
+        "hyprland"
+    }
+
+    fn monitors(&self, verbose: bool) -> Result<Vec<Monitor>, Box<dyn std::error::Error>> {
+        let stdout = execute_hyprctl_output(&["monitors", "-j"], verbose)?;
+        debug_log_line(&format!("raw monitors json: {}", stdout.trim()));
+        Ok(serde_json::from_str(&stdout)
+            .map_err(|e| MyError(format!("Failed to parse hyprctl output: {}", e)))?)
+    }
+
+    fn clients(&self, verbose: bool) -> Result<Vec<Client>, Box<dyn std::error::Error>> {
+        let stdout = execute_hyprctl_output(&["clients", "-j"], verbose)?;
+        Ok(serde_json::from_str(&stdout)
+            .map_err(|e| MyError(format!("Failed to parse hyprctl clients output: {}", e)))?)
+    }
 
+    fn window_selector(&self, pid: u32, client: Option<&Client>) -> String {
+        client
+            .and_then(|c| c.address.as_ref().map(|a| format!("address:{}", a)))
+            .unwrap_or_else(|| format!("pid:{}", pid))
+    }
+
+    fn place_window(
+        &self,
+        window: &str,
+        (x, y, width, height): WindowRect,
+        float: bool,
+        pin: bool,
+        verbose: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Float/pin ride along with the move/resize so the window never shows an
+        // intermediate tiled or unpinned state.
+        let mut batch = Vec::new();
+        if float {
+            batch.push(hyprctl_dispatch("setfloating", window));
+            if pin {
+                batch.push(hyprctl_dispatch("pin", window));
+            }
+        }
+        batch.push(hyprctl_dispatch(
+            "movewindowpixel",
+            &format!("exact {} {},{}", x, y, window),
+        ));
+        batch.push(hyprctl_dispatch(
+            "resizewindowpixel",
+            &format!("exact {} {},{}", width, height, window),
+        ));
+        execute_hyprctl_batch(&batch, verbose)
+    }
+
+    fn strip_decorations(
+        &self,
+        window: &str,
+        verbose: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Locked window properties override rules and the theme, and die with the window.
+        let batch = [("bordersize", "0"), ("rounding", "0"), ("noshadow", "1")]
+            .iter()
+            .map(|(prop, value)| format!("setprop {} {} {} lock", window, prop, value))
+            .collect::<Vec<_>>();
+        execute_hyprctl_batch(&batch, verbose)
+    }
+
+    fn bind_key(
+        &self,
+        mods: &str,
+        key: &str,
+        bind: HotkeyBind,
+        command: &str,
+        verbose: bool,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        if bind_exists(mods, key, verbose)? {
+            return Ok(false);
+        }
+        let keyword = match bind {
+            HotkeyBind::Press => "bind",
+            HotkeyBind::Hold => "bindo",
+        };
+        let binding = format!("{mods}, {key}, exec, {command}");
+        execute_hyprctl(&["keyword", keyword, &binding], verbose)?;
+        Ok(true)
+    }
+
+    fn unbind_key(
+        &self,
+        mods: &str,
+        key: &str,
+        verbose: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        execute_hyprctl(
+            &["keyword", "unbind", &format!("{}, {}", mods, key)],
+            verbose,
+        )
+    }
+}
+
+pub(crate) fn get_monitors(verbose: bool) -> Result<Vec<Monitor>, Box<dyn std::error::Error>> {
+    let compositor = compositor();
+    let monitors = compositor.monitors(verbose)?;
     if monitors.is_empty() {
-        return Err(MyError("No monitors detected. Is Hyprland running?".to_string()).into());
+        return Err(MyError(format!(
+            "No monitors detected. Is {} running?",
+            compositor.name()
+        ))
+        .into());
     }
     Ok(monitors)
 }
 
+pub(crate) fn select_span_monitors(monitors: &[Monitor], target: SpanTarget) -> Vec<Monitor> {
+    match target {
+        SpanTarget::All => monitors.to_vec(),
+        SpanTarget::Focused => monitors
+            .iter()
+            .filter(|m| m.contributes_to_span())
+            .find(|m| m.focused)
+            .or_else(|| monitors.iter().find(|m| m.contributes_to_span()))
+            .cloned()
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Names of active monitors left out of the span.
+pub(crate) fn excluded_monitor_names(monitors: &[Monitor], span: &[Monitor]) -> Vec<String> {
+    monitors
+        .iter()
+        .filter(|m| m.contributes_to_span())
+        .filter_map(|m| m.name.clone())
+        .filter(|name| {
+            !span
+                .iter()
+                .any(|s| s.name.as_deref() == Some(name.as_str()))
+        })
+        .collect()
+}
+
+pub(crate) fn span_refresh_rate(monitors: &[Monitor]) -> Option<i32> {
+    monitors
+        .iter()
+        .filter(|m| m.contributes_to_span())
+        .filter_map(|m| m.refresh_rate)
+        .filter(|r| *r > 0.0)
+        .map(|r| r.round() as i32)
+        .min()
+}
+
 pub(crate) fn compute_monitor_span(
     monitors: &[Monitor],
 ) -> Result<(i32, i32, i32, i32), Box<dyn std::error::Error>> {
+    let monitors = monitors
+        .iter()
+        .filter(|m| m.contributes_to_span())
+        .collect::<Vec<_>>();
     if monitors.is_empty() {
         return Err(MyError("No monitors detected.".to_string()).into());
     }
 
+    // Edges are summed in i64 and clamped to `i32::MAX`, so a bogus report from hyprctl saturates
+    // instead of overflowing, and `x + width` of the span always fits.
     let min_x = monitors.iter().map(|m| m.x).min().unwrap_or(0);
     let min_y = monitors.iter().map(|m| m.y).min().unwrap_or(0);
-    let max_x = monitors.iter().map(|m| m.x + m.width).max().unwrap_or(0);
-    let max_y = monitors.iter().map(|m| m.y + m.height).max().unwrap_or(0);
+    let max_x = monitors
+        .iter()
+        .map(|m| i64::from(m.x) + i64::from(m.width))
+        .max()
+        .unwrap_or(0)
+        .min(i64::from(i32::MAX));
+    let max_y = monitors
+        .iter()
+        .map(|m| i64::from(m.y) + i64::from(m.height))
+        .max()
+        .unwrap_or(0)
+        .min(i64::from(i32::MAX));
 
-    let span_width = max_x - min_x;
-    let span_height = max_y - min_y;
+    let span_width = (max_x - i64::from(min_x)).min(i64::from(i32::MAX)) as i32;
+    let span_height = (max_y - i64::from(min_y)).min(i64::from(i32::MAX)) as i32;
     let monitor_dump = monitors
         .iter()
         .map(|m| {
@@ -136,71 +383,357 @@ pub(crate) fn compute_monitor_span(
         })
         .collect::<Vec<_>>()
         .join(", ");
+    let coverage = span_coverage(&monitors);
     debug_log_line(&format!(
-        "compute_span monitors=[{}] => origin=({}, {}), size={}x{}",
-        monitor_dump, min_x, min_y, span_width, span_height
+        "compute_span monitors=[{}] => origin=({}, {}), size={}x{}, dead_area={}, overlap_area={}",
+        monitor_dump,
+        min_x,
+        min_y,
+        span_width,
+        span_height,
+        coverage.dead_area,
+        coverage.overlap_area
     ));
 
     Ok((min_x, min_y, span_width, span_height))
 }
 
-pub(crate) fn wait_for_client_pid(
-    pid: u32,
+/// The biggest rectangle no part of which is off every monitor, for layouts whose bounding box
+/// has dead zones (L shapes, a grid missing a corner). The first one found wins ties, scanning
+/// from the top left.
+pub(crate) fn largest_covered_rect(
+    monitors: &[Monitor],
+) -> Result<(i32, i32, i32, i32), Box<dyn std::error::Error>> {
+    let rects = monitors
+        .iter()
+        .filter(|m| m.contributes_to_span())
+        .map(monitor_rect)
+        .collect::<Vec<_>>();
+    if rects.is_empty() {
+        return Err(MyError("No monitors detected.".to_string()).into());
+    }
+    let mut xs = rects.iter().flat_map(|r| [r.0, r.2]).collect::<Vec<_>>();
+    let mut ys = rects.iter().flat_map(|r| [r.1, r.3]).collect::<Vec<_>>();
+    xs.sort_unstable();
+    xs.dedup();
+    ys.sort_unstable();
+    ys.dedup();
+    // uncovered[i][j]: grid cells off every monitor within the first i columns and j rows.
+    let mut uncovered = vec![vec![0usize; ys.len()]; xs.len()];
+    for i in 1..xs.len() {
+        for j in 1..ys.len() {
+            let covered = rects
+                .iter()
+                .any(|r| r.0 <= xs[i - 1] && xs[i] <= r.2 && r.1 <= ys[j - 1] && ys[j] <= r.3);
+            uncovered[i][j] = usize::from(!covered) + uncovered[i - 1][j] + uncovered[i][j - 1]
+                - uncovered[i - 1][j - 1];
+        }
+    }
+    let mut best = (0i64, (xs[0], ys[0], xs[0], ys[0]));
+    for left in 0..xs.len() {
+        for right in left + 1..xs.len() {
+            for top in 0..ys.len() {
+                for bottom in top + 1..ys.len() {
+                    let holes = uncovered[right][bottom] + uncovered[left][top]
+                        - uncovered[left][bottom]
+                        - uncovered[right][top];
+                    if holes > 0 {
+                        // Taller rectangles from this top edge contain the same hole.
+                        break;
+                    }
+                    let area = (xs[right] - xs[left]).saturating_mul(ys[bottom] - ys[top]);
+                    if area > best.0 {
+                        best = (area, (xs[left], ys[top], xs[right], ys[bottom]));
+                    }
+                }
+            }
+        }
+    }
+    let (_, (left, top, right, bottom)) = best;
+    let rect = (
+        i32::try_from(left).unwrap_or(i32::MAX),
+        i32::try_from(top).unwrap_or(i32::MAX),
+        (right - left).min(i64::from(i32::MAX)) as i32,
+        (bottom - top).min(i64::from(i32::MAX)) as i32,
+    );
+    debug_log_line(&format!(
+        "largest_covered_rect => origin=({}, {}), size={}x{}",
+        rect.0, rect.1, rect.2, rect.3
+    ));
+    Ok(rect)
+}
+
+/// The span rectangle for `monitors` under `strategy`.
+pub(crate) fn span_rect(
+    monitors: &[Monitor],
+    strategy: SpanStrategy,
+) -> Result<(i32, i32, i32, i32), Box<dyn std::error::Error>> {
+    match strategy {
+        SpanStrategy::BoundingBox => compute_monitor_span(monitors),
+        SpanStrategy::LargestRect => largest_covered_rect(monitors),
+    }
+}
+
+/// How much of a span's bounding box no monitor shows, and how much more than one monitor shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct SpanCoverage {
+    pub(crate) span_area: i64,
+    pub(crate) dead_area: i64,
+    pub(crate) overlap_area: i64,
+}
+
+fn monitor_rect(m: &Monitor) -> (i64, i64, i64, i64) {
+    let (x, y) = (i64::from(m.x), i64::from(m.y));
+    (x, y, x + i64::from(m.width), y + i64::from(m.height))
+}
+
+/// Area covered by the union of `(left, top, right, bottom)` rectangles, over a grid of their
+/// edges.
+fn union_area(rects: &[(i64, i64, i64, i64)]) -> i64 {
+    let mut xs = rects.iter().flat_map(|r| [r.0, r.2]).collect::<Vec<_>>();
+    let mut ys = rects.iter().flat_map(|r| [r.1, r.3]).collect::<Vec<_>>();
+    xs.sort_unstable();
+    xs.dedup();
+    ys.sort_unstable();
+    ys.dedup();
+    let mut area = 0;
+    for x in xs.windows(2) {
+        for y in ys.windows(2) {
+            if rects
+                .iter()
+                .any(|r| r.0 <= x[0] && x[1] <= r.2 && r.1 <= y[0] && y[1] <= r.3)
+            {
+                area = ((x[1] - x[0]).saturating_mul(y[1] - y[0])).saturating_add(area);
+            }
+        }
+    }
+    area
+}
+
+pub(crate) fn span_coverage(monitors: &[&Monitor]) -> SpanCoverage {
+    let rects = monitors
+        .iter()
+        .filter(|m| m.contributes_to_span())
+        .map(|m| monitor_rect(m))
+        .collect::<Vec<_>>();
+    if rects.is_empty() {
+        return SpanCoverage::default();
+    }
+    let left = rects.iter().map(|r| r.0).min().unwrap_or(0);
+    let top = rects.iter().map(|r| r.1).min().unwrap_or(0);
+    let right = rects.iter().map(|r| r.2).max().unwrap_or(0);
+    let bottom = rects.iter().map(|r| r.3).max().unwrap_or(0);
+    // Products saturate: layouts with sides near `i32::MAX` would overflow i64.
+    let span_area = (right - left).saturating_mul(bottom - top);
+    let covered = union_area(&rects);
+    let total = rects.iter().fold(0i64, |sum, r| {
+        sum.saturating_add((r.2 - r.0).saturating_mul(r.3 - r.1))
+    });
+    SpanCoverage {
+        span_area,
+        dead_area: span_area.saturating_sub(covered),
+        overlap_area: total.saturating_sub(covered),
+    }
+}
+
+// Every subset is tried, so the suggestion is skipped for unusually large layouts.
+const MAX_SUBSET_SEARCH_MONITORS: usize = 10;
+
+/// Indices of the largest proper subset of `monitors` that tiles its own bounding box exactly.
+/// Ties go to the subset with more monitors, then to the one listed first.
+fn best_tiling_subset(monitors: &[&Monitor]) -> Option<Vec<usize>> {
+    if monitors.len() > MAX_SUBSET_SEARCH_MONITORS {
+        return None;
+    }
+    (1u32..(1 << monitors.len()) - 1)
+        .map(|mask| {
+            (0..monitors.len())
+                .filter(|idx| mask & (1 << idx) != 0)
+                .collect::<Vec<_>>()
+        })
+        .filter_map(|subset| {
+            let coverage =
+                span_coverage(&subset.iter().map(|idx| monitors[*idx]).collect::<Vec<_>>());
+            (coverage.dead_area == 0 && coverage.overlap_area == 0)
+                .then_some((coverage.span_area, subset))
+        })
+        .min_by_key(|(area, subset)| (Reverse(*area), Reverse(subset.len())))
+        .map(|(_, subset)| subset)
+}
+
+fn percent_of(part: i64, whole: i64) -> f64 {
+    if whole > 0 {
+        part as f64 * 100.0 / whole as f64
+    } else {
+        0.0
+    }
+}
+
+/// Warnings for a span whose bounding box has areas no monitor shows, or that monitors overlap,
+/// with a monitor subset that avoids both when there is one.
+pub(crate) fn span_layout_warnings(monitors: &[Monitor]) -> Vec<String> {
+    let (names, monitors): (Vec<String>, Vec<&Monitor>) = monitors
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.contributes_to_span())
+        .map(|(idx, m)| (m.display_name(idx), m))
+        .unzip();
+    let coverage = span_coverage(&monitors);
+    let mut warnings = Vec::new();
+    if coverage.dead_area > 0 {
+        let percent = format!("{:.1}", percent_of(coverage.dead_area, coverage.span_area));
+        warnings.push(trf(Msg::SpanDeadArea, &[&percent]));
+    }
+    if coverage.overlap_area > 0 {
+        warnings.push(trf(Msg::SpanOverlap, &[&coverage.overlap_area]));
+    }
+    if !warnings.is_empty()
+        && let Some(subset) = best_tiling_subset(&monitors)
+    {
+        let msg = if subset.len() == 1 {
+            Msg::SpanGaplessMonitor
+        } else {
+            Msg::SpanGaplessRect
+        };
+        let subset = subset
+            .iter()
+            .map(|idx| names[*idx].as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        warnings.push(trf(msg, &[&subset]));
+    }
+    warnings
+}
+
+fn probe_regex(
+    key: &str,
+    pattern: Option<&str>,
+) -> Result<Option<Regex>, Box<dyn std::error::Error>> {
+    pattern
+        .map(|p| Regex::new(p).map_err(|e| MyError(format!("Invalid {} `{}`: {}", key, p, e))))
+        .transpose()
+        .map_err(Into::into)
+}
+
+fn window_area(client: &Client) -> i64 {
+    let (w, h) = client.size.map(|s| (s[0], s[1])).unwrap_or((0, 0));
+    i64::from(w.max(0)) * i64::from(h.max(0))
+}
+
+/// Identifies the session window: one owned by gamescope's PID, or failing that one whose
+/// class/title matches the configured regexes (`steam -gamepadui`, flatpak, XWayland children).
+#[derive(Debug, Clone)]
+pub(crate) struct WindowMatcher {
+    pub(crate) pid: u32,
+    class: Option<Regex>,
+    title: Option<Regex>,
+}
+
+impl WindowMatcher {
+    pub(crate) fn new(pid: u32, probe: &WindowProbe) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(WindowMatcher {
+            pid,
+            class: probe_regex("window_class", probe.class.as_deref())?,
+            title: probe_regex("window_title", probe.title.as_deref())?,
+        })
+    }
+
+    /// The same class/title rules for a new gamescope process, e.g. after a restart.
+    pub(crate) fn for_pid(&self, pid: u32) -> Self {
+        WindowMatcher {
+            pid,
+            ..self.clone()
+        }
+    }
+
+    /// Checks the current and initial class/title, so a game renaming its window still matches.
+    fn matches_class_title(&self, client: &Client) -> bool {
+        let matches = |re: Option<&Regex>, current: &Option<String>, initial: &Option<String>| {
+            re.is_none_or(|re| {
+                [current, initial]
+                    .into_iter()
+                    .flatten()
+                    .any(|v| re.is_match(v))
+            })
+        };
+        (self.class.is_some() || self.title.is_some())
+            && matches(self.class.as_ref(), &client.class, &client.initial_class)
+            && matches(self.title.as_ref(), &client.title, &client.initial_title)
+    }
+
+    /// The largest window of gamescope's PID, otherwise the largest class/title match.
+    pub(crate) fn primary_client<'a>(&self, clients: &'a [Client]) -> Option<&'a Client> {
+        primary_client_for_pid(clients, self.pid).or_else(|| {
+            clients
+                .iter()
+                .filter(|c| self.matches_class_title(c))
+                .max_by_key(|c| window_area(c))
+        })
+    }
+}
+
+/// Waits for the gamescope window and returns it. It may belong to another PID than gamescope's
+/// when the class/title rules found it.
+pub(crate) fn wait_for_client(
+    matcher: &WindowMatcher,
     timeout_secs: u64,
+    poll_ms: u64,
     verbose: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+    mut process_exited: impl FnMut() -> bool,
+) -> Result<Client, Box<dyn std::error::Error>> {
     let deadline = std::time::Instant::now() + Duration::from_secs(timeout_secs);
     while std::time::Instant::now() < deadline {
-        let stdout = execute_hyprctl_output(&["clients", "-j"], verbose)?;
-        let clients: Vec<Client> = serde_json::from_str(&stdout)
-            .map_err(|e| MyError(format!("Failed to parse hyprctl clients output: {}", e)))?;
-
-        if clients.iter().any(|c| c.pid == pid as i32) {
-            return Ok(());
+        if process_exited() {
+            return Err(MyError(format!(
+                "Gamescope (PID {}) exited before its window appeared.",
+                matcher.pid
+            ))
+            .into());
+        }
+        let clients = compositor().clients(verbose)?;
+        if let Some(client) = matcher.primary_client(&clients) {
+            if client.pid != matcher.pid as i32 {
+                debug_log_line(&format!(
+                    "startup probe: window {:?} (pid {}) matched by class/title",
+                    client.address, client.pid
+                ));
+            }
+            return Ok(client.clone());
         }
-        thread::sleep(Duration::from_millis(250));
+        thread::sleep(Duration::from_millis(poll_ms));
     }
 
     Err(MyError(format!(
         "Timed out waiting for Gamescope window (PID {}).",
-        pid
+        matcher.pid
     ))
     .into())
 }
 
-fn primary_client_for_pid(clients: &[Client], pid: u32) -> Option<&Client> {
+pub(crate) fn primary_client_for_pid(clients: &[Client], pid: u32) -> Option<&Client> {
     clients
         .iter()
         .filter(|c| c.pid == pid as i32)
-        .max_by_key(|c| {
-            let (w, h) = c.size.map(|s| (s[0], s[1])).unwrap_or((0, 0));
-            i64::from(w.max(0)) * i64::from(h.max(0))
-        })
+        .max_by_key(|c| window_area(c))
 }
 
 pub(crate) fn get_primary_window_selector(
-    pid: u32,
+    matcher: &WindowMatcher,
     verbose: bool,
 ) -> Result<String, Box<dyn std::error::Error>> {
-    let stdout = execute_hyprctl_output(&["clients", "-j"], verbose)?;
-    let clients: Vec<Client> = serde_json::from_str(&stdout)
-        .map_err(|e| MyError(format!("Failed to parse hyprctl clients output: {}", e)))?;
-    let selector = primary_client_for_pid(&clients, pid)
-        .and_then(|c| c.address.as_ref().map(|a| format!("address:{}", a)))
-        .unwrap_or_else(|| format!("pid:{}", pid));
-    Ok(selector)
+    let compositor = compositor();
+    let clients = compositor.clients(verbose)?;
+    let client = matcher.primary_client(&clients);
+    let pid = client.map_or(matcher.pid, |c| c.pid as u32);
+    Ok(compositor.window_selector(pid, client))
 }
 
-type ClientGeometry = (i32, i32, i32, i32);
-
 fn get_client_geometry(
-    pid: u32,
+    matcher: &WindowMatcher,
     verbose: bool,
-) -> Result<Option<ClientGeometry>, Box<dyn std::error::Error>> {
-    let stdout = execute_hyprctl_output(&["clients", "-j"], verbose)?;
-    let clients: Vec<Client> = serde_json::from_str(&stdout)
-        .map_err(|e| MyError(format!("Failed to parse hyprctl clients output: {}", e)))?;
-    let client = primary_client_for_pid(&clients, pid);
+) -> Result<Option<WindowRect>, Box<dyn std::error::Error>> {
+    let clients = compositor().clients(verbose)?;
+    let client = matcher.primary_client(&clients);
     if let Some(c) = client
         && let (Some(at), Some(size)) = (c.at, c.size)
     {
@@ -209,52 +742,62 @@ fn get_client_geometry(
     Ok(None)
 }
 
+/// Pins the window again if Hyprland reports it unpinned. `pin` is a toggle, so this checks
+/// first rather than dispatching blindly; compositors that do not report pinning are skipped.
+pub(crate) fn reassert_pin(
+    matcher: &WindowMatcher,
+    window: &str,
+    verbose: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let clients = compositor().clients(verbose)?;
+    if matcher
+        .primary_client(&clients)
+        .is_some_and(|c| c.pinned == Some(false))
+    {
+        debug_log_line(&format!("re-pinning {}", window));
+        execute_hyprctl(&["dispatch", "pin", window], verbose)?;
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn fit_window_to_span(
-    pid: u32,
+    matcher: &WindowMatcher,
     window: &str,
     target_x: i32,
     target_y: i32,
     target_w: i32,
     target_h: i32,
+    pin: bool,
+    fit: &FitSettings,
     verbose: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut req_w = target_w;
     let mut req_h = target_h;
+    let tol = fit.tolerance_px.max(0);
 
-    for attempt in 1..=4 {
-        let move_params = format!("exact {} {}", target_x, target_y);
-        execute_hyprctl(
-            &[
-                "dispatch",
-                "movewindowpixel",
-                &format!("{},{}", move_params, window),
-            ],
-            verbose,
-        )?;
-
-        let resize_params = format!("exact {} {}", req_w, req_h);
-        execute_hyprctl(
-            &[
-                "dispatch",
-                "resizewindowpixel",
-                &format!("{},{}", resize_params, window),
-            ],
+    for attempt in 1..=fit.attempts.max(1) {
+        compositor().place_window(
+            window,
+            (target_x, target_y, req_w, req_h),
+            attempt == 1,
+            pin,
             verbose,
         )?;
 
-        thread::sleep(Duration::from_millis(80));
+        thread::sleep(Duration::from_millis(fit.settle_ms));
 
-        let Some((x, y, w, h)) = get_client_geometry(pid, verbose)? else {
+        let Some((x, y, w, h)) = get_client_geometry(matcher, verbose)? else {
             continue;
         };
-        let pos_ok = (x - target_x).abs() <= 1 && (y - target_y).abs() <= 1;
-        let size_ok = (w - target_w).abs() <= 1 && (h - target_h).abs() <= 1;
+        let pos_ok = (x - target_x).abs() <= tol && (y - target_y).abs() <= tol;
+        let size_ok = (w - target_w).abs() <= tol && (h - target_h).abs() <= tol;
         if pos_ok && size_ok {
             if verbose {
-                println!(
-                    "Hyprfinity (DEBUG): Window fit success on attempt {}: at=({}, {}), size={}x{}",
+                debug(&format!(
+                    "Window fit success on attempt {}: at=({}, {}), size={}x{}",
                     attempt, x, y, w, h
-                );
+                ));
             }
             return Ok(());
         }
@@ -262,20 +805,21 @@ pub(crate) fn fit_window_to_span(
         req_w = (req_w + (target_w - w)).max(2);
         req_h = (req_h + (target_h - h)).max(2);
         if verbose {
-            println!(
-                "Hyprfinity (DEBUG): Window fit attempt {} mismatch: at=({}, {}), size={}x{}, target=({}, {}) {}x{}, next request={}x{}",
+            debug(&format!(
+                "Window fit attempt {} mismatch: at=({}, {}), size={}x{}, target=({}, {}) {}x{}, next request={}x{}",
                 attempt, x, y, w, h, target_x, target_y, target_w, target_h, req_w, req_h
-            );
+            ));
         }
     }
 
-    if let Some((x, y, w, h)) = get_client_geometry(pid, verbose)? {
-        eprintln!(
-            "Hyprfinity: Warning: Gamescope window may not fully cover span (actual at=({}, {}), size={}x{}; target at=({}, {}), size={}x{}).",
-            x, y, w, h, target_x, target_y, target_w, target_h
-        );
+    count_fit_failure();
+    if let Some((x, y, w, h)) = get_client_geometry(matcher, verbose)? {
+        warn(&trf(
+            Msg::WindowMayNotCover,
+            &[&x, &y, &w, &h, &target_x, &target_y, &target_w, &target_h],
+        ));
     } else {
-        eprintln!("Hyprfinity: Warning: Unable to verify final Gamescope window geometry.");
+        warn(tr(Msg::WindowGeometryUnknown));
     }
     Ok(())
 }
@@ -284,6 +828,23 @@ pub(crate) fn fit_window_to_span(
 mod tests {
     use super::*;
     use crate::types::{Client, Monitor};
+    use proptest::prelude::*;
+
+    #[test]
+    fn hyprctl_dispatch_formats_batch_entries() {
+        assert_eq!(
+            hyprctl_dispatch("resizewindowpixel", "exact 3840 1080,address:0x1"),
+            "dispatch resizewindowpixel exact 3840 1080,address:0x1"
+        );
+    }
+
+    #[test]
+    fn retry_delay_backs_off_exponentially() {
+        assert_eq!(retry_delay(100, 1), Duration::from_millis(100));
+        assert_eq!(retry_delay(100, 2), Duration::from_millis(200));
+        assert_eq!(retry_delay(100, 3), Duration::from_millis(400));
+        assert_eq!(retry_delay(0, 5), Duration::ZERO);
+    }
 
     #[test]
     fn compute_monitor_span_combines_offsets() {
@@ -294,6 +855,7 @@ mod tests {
                 height: 1080,
                 x: -1920,
                 y: 0,
+                ..Default::default()
             },
             Monitor {
                 name: Some("right".to_string()),
@@ -301,6 +863,7 @@ mod tests {
                 height: 1440,
                 x: 0,
                 y: 0,
+                ..Default::default()
             },
         ];
         let (min_x, min_y, w, h) = compute_monitor_span(&monitors).unwrap();
@@ -310,6 +873,171 @@ mod tests {
         assert_eq!(h, 1440);
     }
 
+    #[test]
+    fn span_layout_warnings_flag_dead_area_and_overlap() {
+        let monitor = |name: &str, x: i32, y: i32| Monitor {
+            name: Some(name.to_string()),
+            width: 1920,
+            height: 1080,
+            x,
+            y,
+            ..Default::default()
+        };
+        // A 2x2 grid missing its bottom-right corner.
+        let grid = vec![
+            monitor("top-left", 0, 0),
+            monitor("top-right", 1920, 0),
+            monitor("bottom-left", 0, 1080),
+        ];
+        let refs = grid.iter().collect::<Vec<_>>();
+        let coverage = span_coverage(&refs);
+        assert_eq!(coverage.dead_area, 1920 * 1080);
+        assert_eq!(coverage.overlap_area, 0);
+        let warnings = span_layout_warnings(&grid);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("25.0%"), "{:?}", warnings);
+        assert!(
+            warnings[1].contains("top-left, top-right"),
+            "{:?}",
+            warnings
+        );
+
+        let overlapping = vec![monitor("left", 0, 0), monitor("right", 960, 0)];
+        let warnings = span_layout_warnings(&overlapping);
+        assert!(
+            warnings[0].contains("overlap by 1036800px"),
+            "{:?}",
+            warnings
+        );
+        assert!(span_layout_warnings(&overlapping[..1]).is_empty());
+        assert!(span_layout_warnings(&[monitor("a", 0, 0), monitor("b", 1920, 0)]).is_empty());
+    }
+
+    #[test]
+    fn largest_rect_strategy_skips_dead_zones() {
+        let monitor = |width: i32, height: i32, x: i32, y: i32| Monitor {
+            width,
+            height,
+            x,
+            y,
+            ..Default::default()
+        };
+        // A 1440p monitor next to a 1080p one, tops aligned.
+        let l_shape = vec![monitor(2560, 1440, 0, 0), monitor(1920, 1080, 2560, 0)];
+        assert_eq!(
+            span_rect(&l_shape, SpanStrategy::BoundingBox).unwrap(),
+            (0, 0, 4480, 1440)
+        );
+        assert_eq!(
+            span_rect(&l_shape, SpanStrategy::LargestRect).unwrap(),
+            (0, 0, 4480, 1080)
+        );
+        // The tall portrait monitor alone beats the full-width strip.
+        let portrait = vec![monitor(1440, 2560, 0, 0), monitor(1920, 1080, 1440, 0)];
+        assert_eq!(largest_covered_rect(&portrait).unwrap(), (0, 0, 1440, 2560));
+        let grid = vec![
+            monitor(1920, 1080, 0, 0),
+            monitor(1920, 1080, 1920, 0),
+            monitor(1920, 1080, 0, 1080),
+            monitor(1920, 1080, 1920, 1080),
+        ];
+        assert_eq!(
+            largest_covered_rect(&grid).unwrap(),
+            compute_monitor_span(&grid).unwrap()
+        );
+        assert!(largest_covered_rect(&[]).is_err());
+    }
+
+    fn arbitrary_monitor(coord: i32, size: i32) -> impl Strategy<Value = Monitor> {
+        (-coord..=coord, -coord..=coord, 0..=size, 0..=size).prop_map(|(x, y, width, height)| {
+            Monitor {
+                width,
+                height,
+                x,
+                y,
+                ..Default::default()
+            }
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn compute_monitor_span_saturates_instead_of_overflowing(
+            monitors in proptest::collection::vec(arbitrary_monitor(i32::MAX, i32::MAX), 1..6)
+        ) {
+            if let Ok((x, y, w, h)) = compute_monitor_span(&monitors) {
+                prop_assert!(w >= 0 && h >= 0);
+                prop_assert!(x.checked_add(w).is_some() && y.checked_add(h).is_some());
+            }
+        }
+
+        #[test]
+        fn compute_monitor_span_covers_every_monitor(
+            monitors in proptest::collection::vec(arbitrary_monitor(16384, 8192), 1..6)
+        ) {
+            let visible = monitors
+                .iter()
+                .filter(|m| m.width > 0 && m.height > 0)
+                .collect::<Vec<_>>();
+            match compute_monitor_span(&monitors) {
+                Ok((x, y, w, h)) => {
+                    prop_assert!(w > 0 && h > 0);
+                    for m in visible {
+                        prop_assert!(m.x >= x && m.y >= y);
+                        prop_assert!(m.x + m.width <= x + w && m.y + m.height <= y + h);
+                    }
+                }
+                Err(_) => prop_assert!(visible.is_empty()),
+            }
+        }
+
+        #[test]
+        fn largest_covered_rect_is_inside_the_bounding_box(
+            monitors in proptest::collection::vec(arbitrary_monitor(8192, 4096), 1..5)
+        ) {
+            if let Ok((bx, by, bw, bh)) = compute_monitor_span(&monitors) {
+                let (x, y, w, h) = largest_covered_rect(&monitors).unwrap();
+                prop_assert!(w > 0 && h > 0);
+                prop_assert!(x >= bx && y >= by && x + w <= bx + bw && y + h <= by + bh);
+                let refs = monitors.iter().collect::<Vec<_>>();
+                if span_coverage(&refs).dead_area == 0 {
+                    prop_assert_eq!((x, y, w, h), (bx, by, bw, bh));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn select_span_monitors_and_refresh_follow_target() {
+        let monitors = vec![
+            Monitor {
+                name: Some("left".to_string()),
+                width: 1920,
+                height: 1080,
+                refresh_rate: Some(59.94),
+                ..Default::default()
+            },
+            Monitor {
+                name: Some("right".to_string()),
+                width: 2560,
+                height: 1440,
+                x: 1920,
+                refresh_rate: Some(143.99),
+                focused: true,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(span_refresh_rate(&monitors), Some(60));
+
+        let focused = select_span_monitors(&monitors, SpanTarget::Focused);
+        assert_eq!(focused.len(), 1);
+        assert_eq!(focused[0].name.as_deref(), Some("right"));
+        assert_eq!(span_refresh_rate(&focused), Some(144));
+        assert_eq!(select_span_monitors(&monitors, SpanTarget::All).len(), 2);
+        assert_eq!(excluded_monitor_names(&monitors, &focused), vec!["left"]);
+        assert!(excluded_monitor_names(&monitors, &monitors).is_empty());
+    }
+
     #[test]
     fn primary_client_for_pid_prefers_largest_area() {
         let clients = vec![
@@ -318,21 +1046,80 @@ mod tests {
                 address: Some("0x1".to_string()),
                 at: Some([0, 0]),
                 size: Some([800, 600]),
+                ..Default::default()
             },
             Client {
                 pid: 100,
                 address: Some("0x2".to_string()),
                 at: Some([0, 0]),
                 size: Some([1920, 1080]),
+                ..Default::default()
             },
             Client {
                 pid: 200,
                 address: Some("0x3".to_string()),
                 at: Some([0, 0]),
                 size: Some([3840, 2160]),
+                ..Default::default()
             },
         ];
         let selected = primary_client_for_pid(&clients, 100).unwrap();
         assert_eq!(selected.address.as_deref(), Some("0x2"));
     }
+
+    #[test]
+    fn window_matcher_falls_back_to_class_and_title() {
+        let clients = vec![
+            Client {
+                pid: 300,
+                address: Some("0x4".to_string()),
+                class: Some("firefox".to_string()),
+                ..Default::default()
+            },
+            Client {
+                pid: 301,
+                address: Some("0x5".to_string()),
+                class: Some(String::new()),
+                initial_class: Some("gamescope".to_string()),
+                title: Some("Steam Big Picture Mode".to_string()),
+                ..Default::default()
+            },
+        ];
+        let matcher = |class: Option<&str>, title: Option<&str>| {
+            WindowMatcher::new(
+                200,
+                &WindowProbe {
+                    class: class.map(str::to_string),
+                    title: title.map(str::to_string),
+                    poll_ms: 250,
+                },
+            )
+            .unwrap()
+        };
+        let found = |matcher: &WindowMatcher| {
+            matcher
+                .primary_client(&clients)
+                .and_then(|c| c.address.clone())
+        };
+        let gamescope = matcher(Some("^gamescope$"), None);
+        assert_eq!(found(&gamescope.for_pid(300)).as_deref(), Some("0x4"));
+        assert_eq!(found(&gamescope).as_deref(), Some("0x5"));
+        assert_eq!(
+            found(&matcher(Some("^gamescope$"), Some("^Steam"))).as_deref(),
+            Some("0x5")
+        );
+        assert_eq!(found(&matcher(None, Some("^Lutris"))), None);
+        assert_eq!(found(&matcher(None, None)), None);
+        assert!(
+            WindowMatcher::new(
+                200,
+                &WindowProbe {
+                    class: Some("(".to_string()),
+                    title: None,
+                    poll_ms: 250,
+                }
+            )
+            .is_err()
+        );
+    }
 }
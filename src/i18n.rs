@@ -0,0 +1,1234 @@
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Locale {
+    En,
+    Es,
+}
+
+// Message ids for translated strings. `{}` placeholders are filled in order by `trf`.
+// Everything printed through `output::info`/`warn`/`error` and the TUI labels go through here.
+// Still English: errors returned as `Err` (printed once by `main`), `--verbose`/debug-log
+// lines, gamescope argument conflict and adjustment details, and community game notes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Msg {
+    LaunchingSession,
+    LaunchingSteamSession,
+    TearingDownSession,
+    RelaunchingSession,
+    ToggleStopping,
+    TogglePerMonitorStopping,
+    ToggleStarting,
+    ToggleStale,
+    ConfigExistsOverwrite,
+    KeepingConfig,
+    ConfigInitCancelled,
+    ConfigUpdateCancelled,
+    WroteConfig,
+    InteractiveConfigAt,
+    ConfigDone,
+    ConfigPath,
+    NoSessionsYet,
+    RelaunchHint,
+    YesNoRetry,
+    TableKey,
+    TableValue,
+    TableSource,
+    ConfigDiffSummary,
+    TuiConfigInit,
+    TuiConfigEditor,
+    TuiContext,
+    TuiAutoRecommendation,
+    TuiSpan,
+    TuiUnknown,
+    TuiField,
+    TuiSave,
+    TuiCancel,
+    TuiHelp,
+    TuiKeys,
+    // Session launch and teardown
+    SavedState,
+    LoadedState,
+    RecordPlaytimeFailed,
+    RefreshCapped,
+    RefreshUnknown,
+    ConflictingArgs,
+    IntegerScaleArgConflict,
+    AdjustedSize,
+    RtPriorityNoCap,
+    NoSystemdInhibit,
+    GamescopeStartupExit,
+    GamescopeNoStderr,
+    GamescopeStderrTail,
+    Hint,
+    HotkeyExit,
+    HotkeyQuickMenu,
+    HotkeyOsd,
+    HotkeyReplaySave,
+    HotkeyAlreadyBound,
+    HotkeyBound,
+    HotkeyBoundHeld,
+    HotkeyRestoreFailed,
+    HotkeyRegisterFailed,
+    InvalidSaveHotkey,
+    ExitCancelled,
+    ExitCancelledNotice,
+    ExitCountdownNotice,
+    ReadOptionFailed,
+    RestoreOptionFailed,
+    ConfineCursorFailed,
+    BlankMonitorFailed,
+    DimMonitorsFailed,
+    WakeMonitorFailed,
+    ComputedSpan,
+    NoTerminalSizePicker,
+    SizePickerCancelled,
+    IntegerScaling,
+    InternalRenderSize,
+    FlatpakMatchByClass,
+    LaunchingSavedPlan,
+    LaunchingGamescopeArgs,
+    GamescopeStarted,
+    PluginLockFailed,
+    RemoveDecorationsFailed,
+    CompanionStartFailed,
+    CompanionNoRoom,
+    WarpCursorFailed,
+    ReplayStartFailed,
+    ObsRegionFailed,
+    CtrlCTearingDown,
+    TeardownFailed,
+    GamescopeRunning,
+    ChordEnding,
+    ChordRestarting,
+    ScaleRestarting,
+    RestartRequested,
+    GameExited,
+    CrashRestarting,
+    CrashGivingUp,
+    GamescopeExitStatus,
+    ResumeRefitting,
+    SpanMovedAcrossSuspend,
+    ResumeRefitFailed,
+    ReloadRestoring,
+    ReloadRefitFailed,
+    StateUnreadableRemoving,
+    StoppingGamescope,
+    GamescopeKilled,
+    KillFailedStatus,
+    KillError,
+    StateRemoving,
+    CleanedState,
+    WatchdogFailed,
+    DetachedStarted,
+    RestartQueued,
+    ScaleQueued,
+    // Window placement and span layout
+    WindowMayNotCover,
+    WindowGeometryUnknown,
+    SpanDeadArea,
+    SpanOverlap,
+    SpanGaplessMonitor,
+    SpanGaplessRect,
+    // Per-monitor sessions
+    PerMonitorLaunching,
+    PerMonitorStateFailed,
+    PerMonitorNoPreCommand,
+    PerMonitorCtrlC,
+    PerMonitorStopFailed,
+    PerMonitorRunning,
+    PerMonitorExited,
+    PerMonitorWaitFailed,
+    PerMonitorStopping,
+    PerMonitorGameExited,
+    PerMonitorCrashRestarting,
+    PerMonitorCrashGivingUp,
+    PerMonitorRestartFailed,
+    WaybarRestartFailed,
+    // Updates
+    UpdateAvailable,
+    UpToDate,
+    SkippingAttestation,
+    Updating,
+    Updated,
+    // OSD
+    OsdOff,
+    OsdOn,
+    // Pickers
+    SizeSupersampled,
+    SizeNative,
+    SizeInteger,
+    SizeScaled,
+    SizeHorizontal,
+    SizeCommonHeight,
+    SizePixels,
+    SizeRecommended,
+    SizeHeavier,
+    SizeLighter,
+    DetectedMonitors,
+    RecommendedSize,
+    SelectSizePrompt,
+    SelectAppPrompt,
+    // Steam wrapper
+    WrappingSteamApp,
+    WrapNoSteamAppId,
+    // Config and profiles
+    UsingProfile,
+    MatchSteamAppId,
+    MatchExecutable,
+    MatchedProfileFailed,
+    RenderScaleClamped,
+    RenderScaleClampedSupersample,
+    ConfigVersionNewer,
+    ConfigNote,
+    ConfigMigrated,
+    ConfigMigrateWriteFailed,
+    MigrationDropped,
+    MigrationRenamed,
+    MigrationRemoved,
+    MigrationOldDefault,
+    KeyDeprecated,
+    KeyUnused,
+    // Files and tools
+    WroteFile,
+    WroteBugreport,
+    WroteManPages,
+    CompanionStarted,
+    DebugLogFallback,
+    DebugLogEnabled,
+    HookExited,
+    HookFailed,
+    MetricsFailed,
+    MetricsServing,
+    // Embedded (VT) sessions
+    BackOnVt,
+    ChvtFailed,
+    StartingEmbedded,
+    // Game detection
+    NoEngineDetected,
+    PassingEngineSize,
+    // Gamepad
+    UnknownGamepadButton,
+    NoGamepads,
+    GamepadsUnreadable,
+    GamepadChordExit,
+    GamepadChordRestart,
+    // Night light
+    NightlightUnreadable,
+    NightlightPauseFailed,
+    NightlightResumeFailed,
+    GammastepPauseFailed,
+    GammastepResumeFailed,
+    // Presets and profile sharing
+    UsingCachedCatalog,
+    PresetPickerCancelled,
+    AddedProfile,
+    AddedProfileAutoMatch,
+    ImportedProfile,
+    IgnoredUnknownKeys,
+    CheckHardwareKeys,
+    ReviewProfileCommands,
+    ImportTrustPrompt,
+    // Startup progress
+    StageChecking,
+    StageStarting,
+    StageRestarting,
+    StageWaitingWindow,
+    StageFitting,
+    StageInProgress,
+    StageDone,
+    SessionReady,
+    NotifyStarting,
+    NotifyReady,
+    NotifyFailed,
+    NotifyStageFailed,
+    // Recording and screenshots
+    ReplayStarted,
+    NoGpuScreenRecorder,
+    ObsReplayStarted,
+    ReplaySaved,
+    ReplayStopFailed,
+    SavedScreenshot,
+    // Traces
+    TraceRecorded,
+    TraceWriteFailed,
+    TraceVersionNewer,
+    // Headless outputs and streaming
+    StreamerMissing,
+    Streaming,
+    StartFailed,
+    VirtualStateUnreadable,
+    RemovedHeadless,
+    RemoveHeadlessFailed,
+    CreatedHeadless,
+    // Sway
+    SwayNoHoldBinds,
+    // First-run wizard
+    WizardStart,
+    WizardStepMonitors,
+    WizardMonitor,
+    WizardMonitorUnnamed,
+    WizardMonitorFocused,
+    WizardMonitorsFailed,
+    WizardSpanPrompt,
+    WizardSpanRetry,
+    WizardStepRenderScale,
+    WizardAutoTune,
+    WizardRenderScalePrompt,
+    WizardRenderScaleRetry,
+    WizardStepDefaultApp,
+    WizardDefaultAppPrompt,
+    WizardDefaultCommand,
+    WizardNoDefaultApp,
+    WizardStepExitHotkey,
+    WizardExitHotkeyPrompt,
+    WizardExitHotkeyRetry,
+    WizardStepSave,
+    WizardConfigTitle,
+    WizardWritePrompt,
+    WizardCancelled,
+    WizardLaunchPrompt,
+    // Profiles editor
+    TuiProfiles,
+    TuiProfile,
+    TuiOverrides,
+    TuiProfilesKeys,
+    TuiProfileNewName,
+    TuiProfileConfirmDelete,
+    TuiProfileDeleted,
+    TuiProfileDuplicated,
+    TuiProfileNameInvalid,
+    TuiProfileNameTaken,
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+fn locale_from_env(lc_all: Option<&str>, lc_messages: Option<&str>, lang: Option<&str>) -> Locale {
+    // POSIX precedence: the first non-empty of LC_ALL, LC_MESSAGES, LANG wins.
+    let value = [lc_all, lc_messages, lang]
+        .into_iter()
+        .flatten()
+        .find(|v| !v.is_empty())
+        .unwrap_or("C");
+    let language = value
+        .split(['_', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match language.as_str() {
+        "es" => Locale::Es,
+        _ => Locale::En,
+    }
+}
+
+pub(crate) fn locale() -> Locale {
+    // Unit tests assert English output whatever the developer's locale is.
+    if cfg!(test) {
+        return Locale::En;
+    }
+    *LOCALE.get_or_init(|| {
+        let var = |name: &str| std::env::var(name).ok();
+        locale_from_env(
+            var("LC_ALL").as_deref(),
+            var("LC_MESSAGES").as_deref(),
+            var("LANG").as_deref(),
+        )
+    })
+}
+
+fn catalog(msg: Msg) -> (&'static str, &'static str) {
+    match msg {
+        Msg::LaunchingSession => (
+            "Launching Gamescope span session...",
+            "Iniciando sesión de Gamescope extendida...",
+        ),
+        Msg::LaunchingSteamSession => (
+            "Launching Steam Big Picture span session...",
+            "Iniciando sesión extendida de Steam Big Picture...",
+        ),
+        Msg::TearingDownSession => (
+            "Tearing down Gamescope session...",
+            "Cerrando la sesión de Gamescope...",
+        ),
+        Msg::RelaunchingSession => ("Relaunching session #{}...", "Relanzando la sesión #{}..."),
+        Msg::ToggleStopping => (
+            "Toggle: session running (gamescope PID {}), tearing it down.",
+            "Alternar: hay una sesión activa (gamescope PID {}), cerrándola.",
+        ),
+        Msg::TogglePerMonitorStopping => (
+            "Toggle: per-monitor session running, tearing it down.",
+            "Alternar: hay una sesión por monitor activa, cerrándola.",
+        ),
+        Msg::ToggleStarting => (
+            "Toggle: no session running, launching one.",
+            "Alternar: no hay sesión activa, iniciando una.",
+        ),
+        Msg::ToggleStale => (
+            "Toggle: previous session ended without cleanup; restoring state first.",
+            "Alternar: la sesión anterior terminó sin limpiar; restaurando el estado primero.",
+        ),
+        Msg::ConfigExistsOverwrite => (
+            "Config already exists at {}. Overwrite it?",
+            "Ya existe una configuración en {}. ¿Sobrescribirla?",
+        ),
+        Msg::KeepingConfig => (
+            "Keeping existing config unchanged.",
+            "Se conserva la configuración existente.",
+        ),
+        Msg::ConfigInitCancelled => (
+            "Config init cancelled.",
+            "Creación de la configuración cancelada.",
+        ),
+        Msg::ConfigUpdateCancelled => (
+            "Config update cancelled.",
+            "Actualización de la configuración cancelada.",
+        ),
+        Msg::WroteConfig => ("Wrote config to {}", "Configuración guardada en {}"),
+        Msg::InteractiveConfigAt => (
+            "Interactive config at {}",
+            "Configuración interactiva en {}",
+        ),
+        Msg::ConfigDone => (
+            "Done. Use `hyprfinity config-show` to inspect effective values.",
+            "Listo. Usa `hyprfinity config-show` para ver los valores efectivos.",
+        ),
+        Msg::ConfigPath => ("Config path: {}", "Ruta de configuración: {}"),
+        Msg::NoSessionsYet => (
+            "No recorded sessions yet.",
+            "Todavía no hay sesiones registradas.",
+        ),
+        Msg::RelaunchHint => (
+            "Use `hyprfinity sessions --relaunch <id>` to repeat one.",
+            "Usa `hyprfinity sessions --relaunch <id>` para repetir una.",
+        ),
+        Msg::YesNoRetry => ("Please answer y/yes or n/no.", "Responde s/sí o n/no."),
+        Msg::TableKey => ("Key", "Clave"),
+        Msg::TableValue => ("Value", "Valor"),
+        Msg::TableSource => ("Source", "Origen"),
+        Msg::ConfigDiffSummary => (
+            "{} effective values differ from the built-in defaults.",
+            "{} valores efectivos difieren de los valores predeterminados.",
+        ),
+        Msg::TuiConfigInit => ("Config Init", "Crear configuración"),
+        Msg::TuiConfigEditor => ("Config Editor", "Editor de configuración"),
+        Msg::TuiContext => ("Context", "Contexto"),
+        Msg::TuiAutoRecommendation => ("Auto recommendation", "Recomendación automática"),
+        Msg::TuiSpan => ("Span", "Área"),
+        Msg::TuiUnknown => ("unknown", "desconocido"),
+        Msg::TuiField => ("Field", "Campo"),
+        Msg::TuiSave => ("Write config and exit", "Guardar y salir"),
+        Msg::TuiCancel => ("Discard changes", "Descartar cambios"),
+        Msg::TuiHelp => ("Help", "Ayuda"),
+        Msg::TuiKeys => (
+            "Keys: ↑/↓ select  ←/→ change  Enter activate/toggle  s save  q/Esc cancel",
+            "Teclas: ↑/↓ elegir  ←/→ cambiar  Enter activar  s guardar  q/Esc cancelar",
+        ),
+        Msg::SavedState => (
+            "Saved Gamescope state to {}",
+            "Estado de Gamescope guardado en {}",
+        ),
+        Msg::LoadedState => (
+            "Loaded Gamescope state from {}",
+            "Estado de Gamescope cargado desde {}",
+        ),
+        Msg::RecordPlaytimeFailed => (
+            "Failed to record session playtime: {}",
+            "No se pudo registrar el tiempo de juego: {}",
+        ),
+        Msg::RefreshCapped => (
+            "gamescope_args sets -r {}, below the {} Hz the span supports; remove it to use the full rate.",
+            "gamescope_args fija -r {}, por debajo de los {} Hz que admite el área; quítalo para usar la frecuencia completa.",
+        ),
+        Msg::RefreshUnknown => (
+            "The game command uses {refresh}, but no refresh rate is known; substituting an empty value. Set `-r` in gamescope_args to fix it.",
+            "El comando del juego usa {refresh}, pero no se conoce la frecuencia de refresco; se sustituye por un valor vacío. Fija `-r` en gamescope_args para corregirlo.",
+        ),
+        Msg::ConflictingArgs => (
+            "Conflicting gamescope args: {}.",
+            "Argumentos de gamescope en conflicto: {}.",
+        ),
+        Msg::IntegerScaleArgConflict => (
+            "integer_scale wants gamescope {} {}, but the args set {}; keeping {}.",
+            "integer_scale requiere gamescope {} {}, pero los argumentos fijan {}; se mantiene {}.",
+        ),
+        Msg::AdjustedSize => (
+            "Adjusted gamescope size: {}.",
+            "Tamaño de gamescope ajustado: {}.",
+        ),
+        Msg::RtPriorityNoCap => (
+            "rt_priority is set but gamescope lacks CAP_SYS_NICE; run `sudo setcap 'CAP_SYS_NICE=eip' $(command -v gamescope)`.",
+            "rt_priority está activado pero gamescope no tiene CAP_SYS_NICE; ejecuta `sudo setcap 'CAP_SYS_NICE=eip' $(command -v gamescope)`.",
+        ),
+        Msg::NoSystemdInhibit => (
+            "idle inhibit requested, but systemd-inhibit was not found in PATH.",
+            "Se pidió inhibir la inactividad, pero systemd-inhibit no está en el PATH.",
+        ),
+        Msg::GamescopeStartupExit => (
+            "gamescope exited during startup with status {}.",
+            "gamescope terminó durante el arranque con estado {}.",
+        ),
+        Msg::GamescopeNoStderr => (
+            "gamescope produced no stderr output.",
+            "gamescope no escribió nada en stderr.",
+        ),
+        Msg::GamescopeStderrTail => (
+            "Last gamescope stderr lines:",
+            "Últimas líneas de stderr de gamescope:",
+        ),
+        Msg::Hint => ("Hint: {}", "Sugerencia: {}"),
+        Msg::HotkeyExit => ("Exit hotkey", "Atajo de salida"),
+        Msg::HotkeyQuickMenu => ("Quick menu hotkey", "Atajo del menú rápido"),
+        Msg::HotkeyOsd => ("OSD hotkey", "Atajo del OSD"),
+        Msg::HotkeyReplaySave => ("Replay save hotkey", "Atajo para guardar la repetición"),
+        Msg::HotkeyAlreadyBound => (
+            "{} {}+{} is already bound; skipping.",
+            "{} {}+{} ya está asignado; se omite.",
+        ),
+        Msg::HotkeyBound => (
+            "{} bound: {}+{} (runs `hyprfinity {}`).",
+            "{} asignado: {}+{} (ejecuta `hyprfinity {}`).",
+        ),
+        Msg::HotkeyBoundHeld => (
+            "{} bound: {}+{}, held (runs `hyprfinity {}`).",
+            "{} asignado: {}+{}, manteniendo pulsado (ejecuta `hyprfinity {}`).",
+        ),
+        Msg::HotkeyRestoreFailed => (
+            "Failed to restore the hotkey ({}): {}",
+            "No se pudo restaurar el atajo ({}): {}",
+        ),
+        Msg::HotkeyRegisterFailed => (
+            "Failed to register the hotkey ({}): {}",
+            "No se pudo registrar el atajo ({}): {}",
+        ),
+        Msg::InvalidSaveHotkey => (
+            "Invalid recording save_hotkey `{}` (expected \"MODS, KEY\").",
+            "save_hotkey de grabación no válido `{}` (se esperaba \"MODS, KEY\").",
+        ),
+        Msg::ExitCancelled => ("Exit cancelled.", "Salida cancelada."),
+        Msg::ExitCancelledNotice => ("Exit cancelled", "Salida cancelada"),
+        Msg::ExitCountdownNotice => (
+            "Exiting in {}s, press the exit hotkey again to cancel",
+            "Saliendo en {}s, pulsa de nuevo el atajo de salida para cancelar",
+        ),
+        Msg::ReadOptionFailed => ("Failed to read {}: {}", "No se pudo leer {}: {}"),
+        Msg::RestoreOptionFailed => ("Failed to restore {}: {}", "No se pudo restaurar {}: {}"),
+        Msg::ConfineCursorFailed => (
+            "Failed to confine cursor focus: {}",
+            "No se pudo confinar el foco del cursor: {}",
+        ),
+        Msg::BlankMonitorFailed => (
+            "Failed to blank monitor {}: {}",
+            "No se pudo apagar el monitor {}: {}",
+        ),
+        Msg::DimMonitorsFailed => (
+            "Failed to dim excluded monitors: {}",
+            "No se pudieron atenuar los monitores excluidos: {}",
+        ),
+        Msg::WakeMonitorFailed => (
+            "Failed to wake monitor {}: {}",
+            "No se pudo encender el monitor {}: {}",
+        ),
+        Msg::ComputedSpan => (
+            "Computed monitor span: origin=({}, {}), size={}x{}",
+            "Área de monitores calculada: origen=({}, {}), tamaño={}x{}",
+        ),
+        Msg::NoTerminalSizePicker => (
+            "No terminal available for the size picker, using configured/default size.",
+            "No hay terminal para el selector de tamaño; se usa el tamaño configurado o predeterminado.",
+        ),
+        Msg::SizePickerCancelled => (
+            "Internal size picker cancelled, using configured/default size.",
+            "Selector de tamaño interno cancelado; se usa el tamaño configurado o predeterminado.",
+        ),
+        Msg::IntegerScaling => (
+            "Integer scaling: using {}x{}, since {}x{} does not divide the output evenly.",
+            "Escalado entero: se usa {}x{}, porque {}x{} no divide la salida de forma exacta.",
+        ),
+        Msg::InternalRenderSize => (
+            "Internal render size: {}x{} (output span {}x{})",
+            "Tamaño de renderizado interno: {}x{} (área de salida {}x{})",
+        ),
+        Msg::FlatpakMatchByClass => (
+            "Flatpak launch: matching the window by class `{}`, since its PID is sandboxed.",
+            "Lanzamiento Flatpak: se busca la ventana por la clase `{}`, porque su PID está aislado.",
+        ),
+        Msg::LaunchingSavedPlan => (
+            "Launching the saved plan as-is.",
+            "Lanzando el plan guardado tal cual.",
+        ),
+        Msg::LaunchingGamescopeArgs => (
+            "Launching gamescope with args: {}",
+            "Lanzando gamescope con los argumentos: {}",
+        ),
+        Msg::GamescopeStarted => (
+            "gamescope started with PID {}.",
+            "gamescope iniciado con PID {}.",
+        ),
+        Msg::PluginLockFailed => (
+            "Hyprland plugin lock failed, falling back to reflow: {}",
+            "Falló el bloqueo del plugin de Hyprland; se recoloca la ventana: {}",
+        ),
+        Msg::RemoveDecorationsFailed => (
+            "Failed to remove window decorations: {}",
+            "No se pudieron quitar las decoraciones de la ventana: {}",
+        ),
+        Msg::CompanionStartFailed => (
+            "Failed to start the companion app: {}",
+            "No se pudo iniciar la aplicación acompañante: {}",
+        ),
+        Msg::CompanionNoRoom => (
+            "The span covers every monitor; not starting companion_command.",
+            "El área cubre todos los monitores; no se inicia companion_command.",
+        ),
+        Msg::WarpCursorFailed => (
+            "Failed to warp cursor into the span: {}",
+            "No se pudo mover el cursor al área: {}",
+        ),
+        Msg::ReplayStartFailed => (
+            "Failed to start replay buffer: {}",
+            "No se pudo iniciar el búfer de repetición: {}",
+        ),
+        Msg::ObsRegionFailed => (
+            "Failed to write OBS capture region: {}",
+            "No se pudo escribir la región de captura de OBS: {}",
+        ),
+        Msg::CtrlCTearingDown => (
+            "Ctrl+C received, tearing down Gamescope session...",
+            "Ctrl+C recibido, cerrando la sesión de Gamescope...",
+        ),
+        Msg::TeardownFailed => (
+            "Failed to tear down Gamescope session: {}",
+            "No se pudo cerrar la sesión de Gamescope: {}",
+        ),
+        Msg::GamescopeRunning => (
+            "Gamescope is running. Press Ctrl+C to stop.",
+            "Gamescope está en marcha. Pulsa Ctrl+C para detenerlo.",
+        ),
+        Msg::ChordEnding => (
+            "Gamepad chord held, ending session...",
+            "Combinación del mando mantenida, terminando la sesión...",
+        ),
+        Msg::ChordRestarting => (
+            "Gamepad chord held, restarting gamescope...",
+            "Combinación del mando mantenida, reiniciando gamescope...",
+        ),
+        Msg::ScaleRestarting => (
+            "Render scale {} requested, restarting gamescope at {}x{}...",
+            "Escala de renderizado {} solicitada, reiniciando gamescope a {}x{}...",
+        ),
+        Msg::RestartRequested => (
+            "Restart requested, restarting gamescope...",
+            "Reinicio solicitado, reiniciando gamescope...",
+        ),
+        Msg::GameExited => (
+            "Game exited, ending session...",
+            "El juego terminó, cerrando la sesión...",
+        ),
+        Msg::CrashRestarting => (
+            "Gamescope exited unexpectedly ({}); restarting in {}ms (attempt {}/{})...",
+            "Gamescope terminó inesperadamente ({}); reiniciando en {}ms (intento {}/{})...",
+        ),
+        Msg::CrashGivingUp => (
+            "Gamescope crashed again; giving up after {} restarts.",
+            "Gamescope volvió a fallar; se abandona tras {} reinicios.",
+        ),
+        Msg::GamescopeExitStatus => (
+            "Gamescope exited with status {}.",
+            "Gamescope terminó con estado {}.",
+        ),
+        Msg::ResumeRefitting => (
+            "Resumed from suspend, re-fitting the gamescope window...",
+            "Reanudado tras la suspensión, reajustando la ventana de gamescope...",
+        ),
+        Msg::SpanMovedAcrossSuspend => (
+            "The span moved to {}x{} at ({}, {}) across suspend; keeping the session's. Restart the session to span the new layout.",
+            "El área pasó a {}x{} en ({}, {}) durante la suspensión; se mantiene la de la sesión. Reinicia la sesión para usar la nueva disposición.",
+        ),
+        Msg::ResumeRefitFailed => (
+            "Re-fitting after resume failed: {}",
+            "Falló el reajuste tras la reanudación: {}",
+        ),
+        Msg::ReloadRestoring => (
+            "Hyprland config reloaded, restoring session hotkeys and window placement...",
+            "Configuración de Hyprland recargada, restaurando los atajos y la posición de la ventana...",
+        ),
+        Msg::ReloadRefitFailed => (
+            "Re-fitting after the config reload failed: {}",
+            "Falló el reajuste tras recargar la configuración: {}",
+        ),
+        Msg::StateUnreadableRemoving => (
+            "{} Removing {}; restore Waybar or hotkeys manually if needed.",
+            "{} Se elimina {}; restaura Waybar o los atajos a mano si hace falta.",
+        ),
+        Msg::StoppingGamescope => (
+            "Stopping gamescope PID {}...",
+            "Deteniendo gamescope PID {}...",
+        ),
+        Msg::GamescopeKilled => (
+            "Gamescope process killed.",
+            "Proceso de Gamescope terminado.",
+        ),
+        Msg::KillFailedStatus => (
+            "Failed to kill gamescope process. Status: {}",
+            "No se pudo terminar el proceso de gamescope. Estado: {}",
+        ),
+        Msg::KillError => (
+            "Error killing gamescope process: {}",
+            "Error al terminar el proceso de gamescope: {}",
+        ),
+        Msg::StateRemoving => ("{} Removing {}.", "{} Se elimina {}."),
+        Msg::CleanedState => (
+            "Cleaned up Gamescope state file {}",
+            "Archivo de estado de Gamescope eliminado: {}",
+        ),
+        Msg::WatchdogFailed => (
+            "Failed to start teardown watchdog: {}",
+            "No se pudo iniciar el vigilante de cierre: {}",
+        ),
+        Msg::DetachedStarted => (
+            "Session started in the background (PID {}); log: {}",
+            "Sesión iniciada en segundo plano (PID {}); registro: {}",
+        ),
+        Msg::RestartQueued => (
+            "Restart requested; gamescope will relaunch the game shortly.",
+            "Reinicio solicitado; gamescope relanzará el juego en breve.",
+        ),
+        Msg::ScaleQueued => (
+            "Render scale {} requested; gamescope will restart and relaunch the game shortly.",
+            "Escala de renderizado {} solicitada; gamescope se reiniciará y relanzará el juego en breve.",
+        ),
+        Msg::WindowMayNotCover => (
+            "Gamescope window may not fully cover span (actual at=({}, {}), size={}x{}; target at=({}, {}), size={}x{}).",
+            "Puede que la ventana de Gamescope no cubra toda el área (actual en=({}, {}), tamaño={}x{}; objetivo en=({}, {}), tamaño={}x{}).",
+        ),
+        Msg::WindowGeometryUnknown => (
+            "Unable to verify final Gamescope window geometry.",
+            "No se pudo comprobar la geometría final de la ventana de Gamescope.",
+        ),
+        Msg::SpanDeadArea => (
+            "The span includes dead area: {}% of it is on no monitor, so parts of the game render where nothing shows them. span_strategy = \"largest-rect\" keeps it to the biggest fully covered rectangle.",
+            "El área incluye zonas muertas: el {}% no está en ningún monitor, así que partes del juego se dibujan donde nada las muestra. span_strategy = \"largest-rect\" la limita al mayor rectángulo totalmente cubierto.",
+        ),
+        Msg::SpanOverlap => (
+            "Monitors in the span overlap by {}px², so parts of the game show on more than one of them.",
+            "Los monitores del área se solapan {}px², así que partes del juego se ven en más de uno.",
+        ),
+        Msg::SpanGaplessMonitor => (
+            "Spanning only {} would cover a gapless monitor.",
+            "Usar solo {} cubriría un monitor sin huecos.",
+        ),
+        Msg::SpanGaplessRect => (
+            "Spanning only {} would cover a gapless rectangle.",
+            "Usar solo {} cubriría un rectángulo sin huecos.",
+        ),
+        Msg::PerMonitorLaunching => (
+            "{}: launching gamescope at {}x{} (internal {}x{}) with args: {}",
+            "{}: lanzando gamescope a {}x{} (interno {}x{}) con los argumentos: {}",
+        ),
+        Msg::PerMonitorStateFailed => (
+            "Failed to update the per-monitor state: {}",
+            "No se pudo actualizar el estado por monitor: {}",
+        ),
+        Msg::PerMonitorNoPreCommand => (
+            "pre_command is not run in per-monitor mode.",
+            "pre_command no se ejecuta en el modo por monitor.",
+        ),
+        Msg::PerMonitorCtrlC => (
+            "Ctrl+C received, stopping the per-monitor session...",
+            "Ctrl+C recibido, deteniendo la sesión por monitor...",
+        ),
+        Msg::PerMonitorStopFailed => (
+            "Failed to stop the per-monitor session: {}",
+            "No se pudo detener la sesión por monitor: {}",
+        ),
+        Msg::PerMonitorRunning => (
+            "Per-monitor session running on {} monitor(s); stop it with `hyprfinity gamescope-down`.",
+            "Sesión por monitor en marcha en {} monitor(es); detenla con `hyprfinity gamescope-down`.",
+        ),
+        Msg::PerMonitorExited => ("{}: gamescope exited ({}).", "{}: gamescope terminó ({})."),
+        Msg::PerMonitorWaitFailed => (
+            "{}: failed to wait for gamescope: {}",
+            "{}: no se pudo esperar a gamescope: {}",
+        ),
+        Msg::PerMonitorStopping => (
+            "Stopping gamescope PID {} on {}...",
+            "Deteniendo gamescope PID {} en {}...",
+        ),
+        Msg::PerMonitorGameExited => (
+            "{}: the game exited, closing its gamescope...",
+            "{}: el juego terminó, cerrando su gamescope...",
+        ),
+        Msg::PerMonitorCrashRestarting => (
+            "{}: gamescope exited unexpectedly; restarting in {}ms (attempt {}/{})...",
+            "{}: gamescope terminó inesperadamente; reiniciando en {}ms (intento {}/{})...",
+        ),
+        Msg::PerMonitorCrashGivingUp => (
+            "{}: gamescope crashed again; giving up after {} restarts.",
+            "{}: gamescope volvió a fallar; se abandona tras {} reinicios.",
+        ),
+        Msg::PerMonitorRestartFailed => (
+            "{}: failed to restart gamescope: {}",
+            "{}: no se pudo reiniciar gamescope: {}",
+        ),
+        Msg::WaybarRestartFailed => (
+            "Failed to restart Waybar: {}",
+            "No se pudo reiniciar Waybar: {}",
+        ),
+        Msg::UpdateAvailable => (
+            "hyprfinity {} is available (installed: {}): {}",
+            "hyprfinity {} está disponible (instalado: {}): {}",
+        ),
+        Msg::UpToDate => (
+            "hyprfinity {} is up to date.",
+            "hyprfinity {} está actualizado.",
+        ),
+        Msg::SkippingAttestation => (
+            "Skipping build attestation: the download only matches its published checksum.",
+            "Se omite la atestación de compilación: la descarga solo coincide con su suma de comprobación publicada.",
+        ),
+        Msg::Updating => (
+            "Updating {} from {} to {}...",
+            "Actualizando {} de {} a {}...",
+        ),
+        Msg::Updated => ("Updated hyprfinity to {}.", "hyprfinity actualizado a {}."),
+        Msg::OsdOff => ("OSD off.", "OSD desactivado."),
+        Msg::OsdOn => ("OSD on.", "OSD activado."),
+        Msg::SizeSupersampled => ("Supersampled: {}x{} ({}%)", "Supermuestreo: {}x{} ({}%)"),
+        Msg::SizeNative => ("Native span: {}x{} (100%)", "Área nativa: {}x{} (100%)"),
+        Msg::SizeInteger => ("Integer scale: {}x{} (1/{})", "Escala entera: {}x{} (1/{})"),
+        Msg::SizeScaled => ("Scaled: {}x{} ({}%)", "Escalado: {}x{} ({}%)"),
+        Msg::SizeHorizontal => (
+            "Horizontal only: {}x{} ({}% x 100%)",
+            "Solo horizontal: {}x{} ({}% x 100%)",
+        ),
+        Msg::SizeCommonHeight => (
+            "Common height: {}x{} (~{}p tall)",
+            "Altura común: {}x{} (~{}p de alto)",
+        ),
+        Msg::SizePixels => ("{} - ~{}% pixels{}", "{} - ~{}% de píxeles{}"),
+        Msg::SizeRecommended => (", recommended for your GPU", ", recomendado para tu GPU"),
+        Msg::SizeHeavier => (
+            ", heavier than recommended",
+            ", más pesado que lo recomendado",
+        ),
+        Msg::SizeLighter => (
+            ", lighter than recommended",
+            ", más ligero que lo recomendado",
+        ),
+        Msg::DetectedMonitors => ("Detected monitors: {}", "Monitores detectados: {}"),
+        Msg::RecommendedSize => (
+            "Recommended internal size: {}x{}",
+            "Tamaño interno recomendado: {}x{}",
+        ),
+        Msg::SelectSizePrompt => ("Select internal size> ", "Elige el tamaño interno> "),
+        Msg::SelectAppPrompt => ("Select app> ", "Elige una aplicación> "),
+        Msg::WrappingSteamApp => (
+            "Wrapping Steam app {}.",
+            "Envolviendo la aplicación de Steam {}.",
+        ),
+        Msg::WrapNoSteamAppId => (
+            "SteamAppId is not set; `wrap` is meant for Steam launch options. Launching anyway.",
+            "SteamAppId no está definido; `wrap` está pensado para las opciones de lanzamiento de Steam. Se lanza de todos modos.",
+        ),
+        Msg::UsingProfile => (
+            "Using profile `{}` ({} matched).",
+            "Usando el perfil `{}` (coincide {}).",
+        ),
+        Msg::MatchSteamAppId => ("Steam app id {}", "el id de Steam {}"),
+        Msg::MatchExecutable => ("executable {}", "el ejecutable {}"),
+        Msg::MatchedProfileFailed => (
+            "Failed to apply matched profile `{}`: {}",
+            "No se pudo aplicar el perfil `{}`: {}",
+        ),
+        Msg::RenderScaleClamped => (
+            "render_scale {} is out of range; clamping to [0.1, {}].",
+            "render_scale {} está fuera de rango; se limita a [0.1, {}].",
+        ),
+        Msg::RenderScaleClampedSupersample => (
+            "render_scale {} is out of range; clamping to [0.1, {}] (set allow_supersample = true to render above the output size).",
+            "render_scale {} está fuera de rango; se limita a [0.1, {}] (activa allow_supersample = true para renderizar por encima del tamaño de salida).",
+        ),
+        Msg::ConfigVersionNewer => (
+            "{} has config_version {}, newer than this Hyprfinity understands ({}).",
+            "{} tiene config_version {}, más reciente de lo que entiende esta versión de Hyprfinity ({}).",
+        ),
+        Msg::ConfigNote => ("{}: {}.", "{}: {}."),
+        Msg::ConfigMigrated => (
+            "Migrated {} to config_version {} ({}); previous file saved as {}.",
+            "{} migrado a config_version {} ({}); el archivo anterior se guardó como {}.",
+        ),
+        Msg::ConfigMigrateWriteFailed => (
+            "Could not rewrite {} ({}); using the migrated values for this run: {}.",
+            "No se pudo reescribir {} ({}); se usan los valores migrados en esta ejecución: {}.",
+        ),
+        Msg::MigrationDropped => (
+            "{}dropped `{}` (`{}` is already set)",
+            "{}se eliminó `{}` (`{}` ya está definido)",
+        ),
+        Msg::MigrationRenamed => ("{}renamed `{}` to `{}`", "{}`{}` renombrado a `{}`"),
+        Msg::MigrationRemoved => ("{}removed `{}`", "{}se eliminó `{}`"),
+        Msg::MigrationOldDefault => (
+            "{}removed the old default `{} = {}`",
+            "{}se eliminó el antiguo valor predeterminado `{} = {}`",
+        ),
+        Msg::KeyDeprecated => (
+            "{}`{}` is deprecated; use `{}`",
+            "{}`{}` está obsoleto; usa `{}`",
+        ),
+        Msg::KeyUnused => ("{}`{}` is no longer used", "{}`{}` ya no se usa"),
+        Msg::WroteFile => ("Wrote {}", "Escrito {}"),
+        Msg::WroteBugreport => (
+            "Wrote {}; review it, then attach it to a GitHub issue.",
+            "Escrito {}; revísalo y adjúntalo a una incidencia de GitHub.",
+        ),
+        Msg::WroteManPages => (
+            "Wrote {} man pages to {} and {}",
+            "Escritas {} páginas de manual en {} y {}",
+        ),
+        Msg::CompanionStarted => (
+            "Started companion `{}` (PID {}).",
+            "Acompañante `{}` iniciado (PID {}).",
+        ),
+        Msg::DebugLogFallback => (
+            "Failed to open debug log at {} ({}), falling back to {}",
+            "No se pudo abrir el registro de depuración en {} ({}); se usa {}",
+        ),
+        Msg::DebugLogEnabled => (
+            "Debug log enabled at {}",
+            "Registro de depuración activado en {}",
+        ),
+        Msg::HookExited => ("Hook {} exited with {}.", "El hook {} terminó con {}."),
+        Msg::HookFailed => (
+            "Failed to run hook {}: {}",
+            "No se pudo ejecutar el hook {}: {}",
+        ),
+        Msg::MetricsFailed => (
+            "Failed to start metrics endpoint on {}: {}",
+            "No se pudo iniciar el servicio de métricas en {}: {}",
+        ),
+        Msg::MetricsServing => (
+            "Serving metrics on http://{}/metrics",
+            "Sirviendo métricas en http://{}/metrics",
+        ),
+        Msg::BackOnVt => ("Back on VT{}.", "De vuelta en VT{}."),
+        Msg::ChvtFailed => (
+            "chvt {} failed ({}); switch back with Ctrl+Alt+F{}.",
+            "chvt {} falló ({}); vuelve con Ctrl+Alt+F{}.",
+        ),
+        Msg::StartingEmbedded => (
+            "Starting embedded gamescope on VT{} (Hyprland stays on VT{}) with args: {}",
+            "Iniciando gamescope integrado en VT{} (Hyprland sigue en VT{}) con los argumentos: {}",
+        ),
+        Msg::NoEngineDetected => (
+            "engine_hints: no supported engine detected for this game command.",
+            "engine_hints: no se detectó ningún motor compatible para este comando de juego.",
+        ),
+        Msg::PassingEngineSize => (
+            "Passing the internal size to the {} engine: {} {} {} {}",
+            "Pasando el tamaño interno al motor {}: {} {} {} {}",
+        ),
+        Msg::UnknownGamepadButton => (
+            "Unknown gamepad button `{}` in gamepad_chord; gamepad control disabled.",
+            "Botón de mando desconocido `{}` en gamepad_chord; control con mando desactivado.",
+        ),
+        Msg::NoGamepads => (
+            "No gamepads found; gamepad chord is inactive.",
+            "No se encontraron mandos; la combinación del mando está inactiva.",
+        ),
+        Msg::GamepadsUnreadable => (
+            "Gamepads found but none are readable (check input group/uaccess); gamepad chord is inactive.",
+            "Hay mandos pero ninguno es legible (revisa el grupo input/uaccess); la combinación del mando está inactiva.",
+        ),
+        Msg::GamepadChordExit => (
+            "Gamepad chord: hold {} for {}s to exit the session.",
+            "Combinación del mando: mantén {} durante {}s para salir de la sesión.",
+        ),
+        Msg::GamepadChordRestart => (
+            "Gamepad chord: hold {} for {}s to restart the session.",
+            "Combinación del mando: mantén {} durante {}s para reiniciar la sesión.",
+        ),
+        Msg::NightlightUnreadable => (
+            "Could not read the hyprsunset temperature; leaving night light on.",
+            "No se pudo leer la temperatura de hyprsunset; la luz nocturna sigue activa.",
+        ),
+        Msg::NightlightPauseFailed => ("Failed to pause {}: {}", "No se pudo pausar {}: {}"),
+        Msg::NightlightResumeFailed => ("Failed to resume {}: {}", "No se pudo reanudar {}: {}"),
+        Msg::GammastepPauseFailed => ("Failed to pause gammastep.", "No se pudo pausar gammastep."),
+        Msg::GammastepResumeFailed => (
+            "Failed to resume gammastep.",
+            "No se pudo reanudar gammastep.",
+        ),
+        Msg::UsingCachedCatalog => (
+            "{}; using the cached preset catalog.",
+            "{}; se usa el catálogo de presets en caché.",
+        ),
+        Msg::PresetPickerCancelled => {
+            ("Preset picker cancelled.", "Selector de presets cancelado.")
+        }
+        Msg::AddedProfile => (
+            "Added profile `{}`; use it with `hyprfinity --profile {}`.",
+            "Perfil `{}` añadido; úsalo con `hyprfinity --profile {}`.",
+        ),
+        Msg::AddedProfileAutoMatch => (
+            "Added profile `{}`; use it with `hyprfinity --profile {}`, or launch the game and it is picked automatically.",
+            "Perfil `{}` añadido; úsalo con `hyprfinity --profile {}`, o lanza el juego y se elegirá automáticamente.",
+        ),
+        Msg::ImportedProfile => ("Imported profile `{}`.", "Perfil `{}` importado."),
+        Msg::IgnoredUnknownKeys => (
+            "Ignored keys this version does not know: {}.",
+            "Se ignoraron claves que esta versión no conoce: {}.",
+        ),
+        Msg::CheckHardwareKeys => (
+            "Check the hardware-dependent values for your setup: {}.",
+            "Revisa los valores que dependen del hardware para tu equipo: {}.",
+        ),
+        Msg::ReviewProfileCommands => (
+            "Profile `{}` sets keys that run commands, use programs or paths, or apply it to other games: {}.",
+            "El perfil `{}` define claves que ejecutan comandos, usan programas o rutas, o lo aplican a otros juegos: {}.",
+        ),
+        Msg::ImportTrustPrompt => ("Import it anyway?", "¿Importarlo de todos modos?"),
+        Msg::StageChecking => ("Checking gamescope", "Comprobando gamescope"),
+        Msg::StageStarting => ("Starting gamescope", "Iniciando gamescope"),
+        Msg::StageRestarting => ("Restarting gamescope", "Reiniciando gamescope"),
+        Msg::StageWaitingWindow => (
+            "Waiting for the gamescope window",
+            "Esperando la ventana de gamescope",
+        ),
+        Msg::StageFitting => (
+            "Fitting the window to the span",
+            "Ajustando la ventana al área",
+        ),
+        Msg::StageInProgress => ("{}...", "{}..."),
+        Msg::StageDone => ("{} ({}s)", "{} ({}s)"),
+        Msg::SessionReady => ("Session ready after {}s.", "Sesión lista tras {}s."),
+        Msg::NotifyStarting => (
+            "Starting gamescope session",
+            "Iniciando la sesión de gamescope",
+        ),
+        Msg::NotifyReady => ("Gamescope session ready", "Sesión de gamescope lista"),
+        Msg::NotifyFailed => (
+            "Gamescope session failed to start",
+            "La sesión de gamescope no pudo iniciarse",
+        ),
+        Msg::NotifyStageFailed => (
+            "{} failed; see the detached log.",
+            "{} falló; consulta el registro en segundo plano.",
+        ),
+        Msg::ReplayStarted => (
+            "Replay buffer started ({}s, gpu-screen-recorder PID {}).",
+            "Búfer de repetición iniciado ({}s, gpu-screen-recorder PID {}).",
+        ),
+        Msg::NoGpuScreenRecorder => (
+            "Recording enabled, but gpu-screen-recorder was not found in PATH.",
+            "La grabación está activada, pero gpu-screen-recorder no está en el PATH.",
+        ),
+        Msg::ObsReplayStarted => (
+            "OBS replay buffer started.",
+            "Búfer de repetición de OBS iniciado.",
+        ),
+        Msg::ReplaySaved => ("Replay saved.", "Repetición guardada."),
+        Msg::ReplayStopFailed => (
+            "Failed to stop replay buffer: {}",
+            "No se pudo detener el búfer de repetición: {}",
+        ),
+        Msg::SavedScreenshot => ("Saved screenshot to {}", "Captura guardada en {}"),
+        Msg::TraceRecorded => ("Recorded {} events to {}.", "{} eventos registrados en {}."),
+        Msg::TraceWriteFailed => (
+            "Failed to write trace {}: {}",
+            "No se pudo escribir la traza {}: {}",
+        ),
+        Msg::TraceVersionNewer => (
+            "Trace version {} is newer than this Hyprfinity understands ({}).",
+            "La versión de traza {} es más reciente de lo que entiende esta versión de Hyprfinity ({}).",
+        ),
+        Msg::StreamerMissing => (
+            "{} was requested, but it was not found in PATH; not streaming.",
+            "Se pidió {}, pero no está en el PATH; no se transmite.",
+        ),
+        Msg::Streaming => (
+            "Streaming {} with {} (pid {}).",
+            "Transmitiendo {} con {} (pid {}).",
+        ),
+        Msg::StartFailed => ("Failed to start {}: {}", "No se pudo iniciar {}: {}"),
+        Msg::VirtualStateUnreadable => (
+            "Ignoring unreadable virtual output state: {}",
+            "Se ignora el estado ilegible de la salida virtual: {}",
+        ),
+        Msg::RemovedHeadless => (
+            "Removed headless output {}.",
+            "Salida headless {} eliminada.",
+        ),
+        Msg::RemoveHeadlessFailed => (
+            "Failed to remove headless output {}: {}",
+            "No se pudo eliminar la salida headless {}: {}",
+        ),
+        Msg::CreatedHeadless => (
+            "Created headless output {} ({}x{}@{}).",
+            "Salida headless {} creada ({}x{}@{}).",
+        ),
+        Msg::SwayNoHoldBinds => (
+            "sway has no long-press binds; binding a plain press instead.",
+            "sway no admite atajos de pulsación larga; se asigna una pulsación normal.",
+        ),
+        Msg::WizardStart => (
+            "No config found; starting first-run setup (Ctrl+C to abort).",
+            "No se encontró configuración; iniciando la configuración inicial (Ctrl+C para cancelar).",
+        ),
+        Msg::WizardStepMonitors => ("Step 1/5: Monitors", "Paso 1/5: Monitores"),
+        Msg::WizardMonitor => ("  {} {}x{} at ({}, {}){}", "  {} {}x{} en ({}, {}){}"),
+        Msg::WizardMonitorUnnamed => ("(unnamed)", "(sin nombre)"),
+        Msg::WizardMonitorFocused => (" [focused]", " [enfocado]"),
+        Msg::WizardMonitorsFailed => (
+            "Could not list monitors: {}",
+            "No se pudieron listar los monitores: {}",
+        ),
+        Msg::WizardSpanPrompt => (
+            "Span all monitors or only the focused one? (all/focused)",
+            "¿Abarcar todos los monitores o solo el enfocado? (all/focused)",
+        ),
+        Msg::WizardSpanRetry => ("Please answer all or focused.", "Responde all o focused."),
+        Msg::WizardStepRenderScale => ("Step 2/5: Render scale", "Paso 2/5: Escala de renderizado"),
+        Msg::WizardAutoTune => ("  Auto-tune: {}", "  Ajuste automático: {}"),
+        Msg::WizardRenderScalePrompt => (
+            "Internal render scale (0.1-1.0)",
+            "Escala de renderizado interna (0.1-1.0)",
+        ),
+        Msg::WizardRenderScaleRetry => (
+            "Please enter a number between 0.1 and 1.0.",
+            "Introduce un número entre 0.1 y 1.0.",
+        ),
+        Msg::WizardStepDefaultApp => (
+            "Step 3/5: Default app",
+            "Paso 3/5: Aplicación predeterminada",
+        ),
+        Msg::WizardDefaultAppPrompt => (
+            "Pick an app to launch when none is given?",
+            "¿Elegir una aplicación para lanzar cuando no se indique ninguna?",
+        ),
+        Msg::WizardDefaultCommand => ("  Default command: {}", "  Comando predeterminado: {}"),
+        Msg::WizardNoDefaultApp => (
+            "No default app set: {}",
+            "No se configuró una aplicación predeterminada: {}",
+        ),
+        Msg::WizardStepExitHotkey => ("Step 4/5: Exit hotkey", "Paso 4/5: Atajo de salida"),
+        Msg::WizardExitHotkeyPrompt => (
+            "Hotkey that ends the session (MODS, KEY)",
+            "Atajo que termina la sesión (MODS, TECLA)",
+        ),
+        Msg::WizardExitHotkeyRetry => (
+            "Please use the form \"SUPER SHIFT, F12\".",
+            "Usa el formato \"SUPER SHIFT, F12\".",
+        ),
+        Msg::WizardStepSave => ("Step 5/5: Save", "Paso 5/5: Guardar"),
+        Msg::WizardConfigTitle => ("First-run Config", "Configuración inicial"),
+        Msg::WizardWritePrompt => (
+            "Write this config to {}?",
+            "¿Escribir esta configuración en {}?",
+        ),
+        Msg::WizardCancelled => (
+            "Setup cancelled; nothing was written. Run `hyprfinity config-init` any time.",
+            "Configuración cancelada; no se escribió nada. Ejecuta `hyprfinity config-init` cuando quieras.",
+        ),
+        Msg::WizardLaunchPrompt => ("Launch a session now?", "¿Lanzar una sesión ahora?"),
+        Msg::TuiProfiles => ("Profiles", "Perfiles"),
+        Msg::TuiProfile => ("Profile", "Perfil"),
+        Msg::TuiOverrides => ("Overrides", "Cambios"),
+        Msg::TuiProfilesKeys => (
+            "Keys: ↑/↓ select  Enter launch  c duplicate  r rename  d delete  s save  q/Esc cancel",
+            "Teclas: ↑/↓ elegir  Enter lanzar  c duplicar  r renombrar  d borrar  s guardar  q/Esc cancelar",
+        ),
+        Msg::TuiProfileNewName => (
+            "New name: {}_  (Enter confirm, Esc cancel)",
+            "Nuevo nombre: {}_  (Enter confirmar, Esc cancelar)",
+        ),
+        Msg::TuiProfileConfirmDelete => ("Delete this profile? y/n", "¿Borrar este perfil? y/n"),
+        Msg::TuiProfileDeleted => ("Deleted `{}`.", "Se borró `{}`."),
+        Msg::TuiProfileDuplicated => ("Duplicated `{}` as `{}`.", "Se duplicó `{}` como `{}`."),
+        Msg::TuiProfileNameInvalid => (
+            "`{}` is not a valid profile name.",
+            "`{}` no es un nombre de perfil válido.",
+        ),
+        Msg::TuiProfileNameTaken => (
+            "A profile named `{}` already exists.",
+            "Ya existe un perfil llamado `{}`.",
+        ),
+    }
+}
+
+fn translate(locale: Locale, msg: Msg) -> &'static str {
+    let (en, es) = catalog(msg);
+    match locale {
+        Locale::En => en,
+        Locale::Es => es,
+    }
+}
+
+pub(crate) fn tr(msg: Msg) -> &'static str {
+    translate(locale(), msg)
+}
+
+fn fill(template: &str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    if let Some(first) = parts.next() {
+        out.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+pub(crate) fn trf(msg: Msg, args: &[&dyn std::fmt::Display]) -> String {
+    fill(tr(msg), args)
+}
+
+// Accept the localized short/long forms alongside English so muscle memory still works.
+pub(crate) fn is_yes(answer: &str) -> bool {
+    matches!(answer, "y" | "yes") || (locale() == Locale::Es && matches!(answer, "s" | "si" | "sí"))
+}
+
+pub(crate) fn yes_no_hint(default: bool) -> &'static str {
+    match (locale(), default) {
+        (Locale::Es, true) => "S/n",
+        (Locale::Es, false) => "s/N",
+        (Locale::En, true) => "Y/n",
+        (Locale::En, false) => "y/N",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_from_env_follows_posix_precedence() {
+        assert_eq!(locale_from_env(None, None, Some("es_ES.UTF-8")), Locale::Es);
+        assert_eq!(
+            locale_from_env(Some("en_US.UTF-8"), None, Some("es_ES.UTF-8")),
+            Locale::En
+        );
+        assert_eq!(
+            locale_from_env(Some(""), Some("es_MX"), Some("de_DE")),
+            Locale::Es
+        );
+        assert_eq!(locale_from_env(None, None, Some("C")), Locale::En);
+        assert_eq!(locale_from_env(None, None, None), Locale::En);
+    }
+
+    #[test]
+    fn trf_fills_placeholders_in_order() {
+        assert_eq!(
+            fill(translate(Locale::Es, Msg::WroteConfig), &[&"/tmp/c.toml"]),
+            "Configuración guardada en /tmp/c.toml"
+        );
+        assert_eq!(
+            fill(translate(Locale::En, Msg::RelaunchingSession), &[&12]),
+            "Relaunching session #12..."
+        );
+    }
+}
@@ -0,0 +1,623 @@
+use clap::Parser;
+use std::{error::Error, fmt};
+
+mod autotune;
+mod backend;
+mod bugreport;
+mod cli;
+mod companion;
+mod compat;
+mod compositor;
+mod config;
+mod config_migrate;
+mod config_schema;
+mod debuglog;
+mod desktop_cache;
+mod desktop_entry;
+mod embedded;
+mod fetch;
+mod flatpak;
+mod game_db;
+mod gamepad;
+mod gamescope;
+mod gamescope_args;
+mod gamescope_caps;
+mod gen_docs;
+mod hooks;
+mod hypr_events;
+mod hyprland;
+mod i18n;
+mod metrics;
+mod nightlight;
+mod niri;
+mod osd;
+mod output;
+mod per_monitor;
+mod picker;
+mod plugin;
+mod preset_catalog;
+mod presets;
+mod proctree;
+mod profile_share;
+mod progress;
+mod quick_menu;
+mod recording;
+mod screenshot;
+mod span;
+mod stats;
+mod streaming;
+mod suspend;
+mod sway;
+mod trace;
+mod tui_config;
+mod types;
+mod upgrade;
+mod util;
+mod virtual_output;
+mod wizard;
+mod wlroots;
+mod x11;
+
+use crate::bugreport::write_bugreport;
+use crate::cli::{Cli, Commands, ConfigAction, PresetsAction, ProfileAction, flag_override};
+use crate::compat::apply_umu_game;
+use crate::compositor::init_compositor;
+use crate::config::{
+    CliOverrides, apply_config, apply_game_profile, apply_overrides, apply_profile,
+    configured_game_command, interactive_config, manage_profiles, show_config,
+    write_default_config,
+};
+use crate::config_schema::print_config_schema;
+use crate::debuglog::init_debug_logging;
+use crate::desktop_entry::export_desktop;
+use crate::fetch::init_offline;
+use crate::flatpak::init_gamescope_flatpak;
+use crate::gamescope::{
+    SessionStatus, clear_stale_session, confirm_gamescope_down, detach_session, gamescope_down,
+    launch_plan_file, launch_session, pick_game_for_profiles, print_launch_plan, read_launch_plan,
+    request_session_restart, request_session_scale, run_watchdog, save_session_replay,
+    session_status, use_picked_game,
+};
+use crate::gen_docs::gen_docs;
+use crate::hyprland::{DEFAULT_HYPRCTL_ATTEMPTS, DEFAULT_HYPRCTL_BACKOFF_MS, init_hyprctl_retry};
+use crate::i18n::{Msg, tr, trf};
+use crate::osd::{run_osd, toggle_osd};
+use crate::output::{info, init_color, init_output, warn};
+use crate::per_monitor::{per_monitor_session_running, run_per_monitor_session};
+use crate::picker::init_app_picker;
+use crate::plugin::show_plugin_status;
+use crate::preset_catalog::browse_presets;
+use crate::presets::{steam_bigpicture_args, steam_wrap_args};
+use crate::profile_share::{export_profile, import_profiles};
+use crate::quick_menu::quick_menu;
+use crate::screenshot::take_screenshot;
+use crate::span::{show_monitors, show_span};
+use crate::stats::{session_args, show_sessions, show_stats};
+use crate::trace::{replay_trace, start_trace};
+use crate::upgrade::{self_update, upgrade_check};
+use crate::virtual_output::virtual_up;
+use crate::wizard::{run_first_run_wizard, should_run_first_run_wizard};
+
+// Library surface for other frontends: resolve settings from a config, plan, then launch.
+pub use crate::config::{Config, LaunchSettings, load_config};
+pub use crate::gamescope::{LaunchPlan, LaunchPlanBuilder};
+pub use crate::types::{Monitor, SpanStrategy, SpanTarget};
+
+#[derive(Debug)]
+struct MyError(String);
+
+impl fmt::Display for MyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for MyError {}
+
+/// Runs the `hyprfinity` command line with the process arguments.
+pub fn run() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    init_output(cli.quiet, cli.verbose);
+    init_debug_logging(cli.debug, &cli.debug_log)?;
+    init_compositor(cli.backend);
+    if let Some(Commands::GenDocs { out_dir }) = &cli.command {
+        return gen_docs(out_dir);
+    }
+    // The watchdog must run even when the config is broken, and must not migrate it.
+    if let Some(Commands::Watchdog { parent }) = &cli.command {
+        return run_watchdog(*parent);
+    }
+    // A saved plan carries its own settings, so the config is not even read.
+    if let Some(Commands::GamescopeUp {
+        plan: Some(path),
+        detach,
+        ..
+    }) = &cli.command
+    {
+        if *detach {
+            return detach_session();
+        }
+        let _trace = cli.record.as_deref().map(start_trace).transpose()?;
+        return launch_plan_file(path, cli.verbose);
+    }
+    let mut config = load_config(&cli.config)?;
+    init_color(config.color.unwrap_or_default());
+    if cli.command.is_none() && should_run_first_run_wizard(&cli.config) {
+        if !run_first_run_wizard(&cli.config, cli.verbose)? {
+            return Ok(());
+        }
+        config = load_config(&cli.config)?;
+    }
+    if let Some(name) = cli.profile.as_deref() {
+        config = apply_profile(&config, name)?;
+    }
+    // A profile picked by `match` rules goes on top of this, under `--set` and the CLI flags.
+    let before_set = config.clone();
+    config = apply_overrides(&config, &cli.set)?;
+    init_hyprctl_retry(
+        config.hyprctl_attempts.unwrap_or(DEFAULT_HYPRCTL_ATTEMPTS),
+        config
+            .hyprctl_backoff_ms
+            .unwrap_or(DEFAULT_HYPRCTL_BACKOFF_MS),
+    );
+    init_gamescope_flatpak(config.gamescope_flatpak.clone());
+    init_offline(config.offline.unwrap_or(false));
+    init_app_picker(
+        matches!(
+            &cli.command,
+            Some(Commands::GamescopeUp { show_all: true, .. })
+        ),
+        config.picker_terminal.clone(),
+    );
+    // The detached child records its own trace.
+    if let Some(Commands::GamescopeUp { detach: true, .. }) = &cli.command {
+        return detach_session();
+    }
+    let _trace = cli.record.as_deref().map(start_trace).transpose()?;
+    // Launches that may run the picker select their profile once the game is chosen.
+    if let Some(args) = fixed_launch_args(&cli.command)?
+        && let Some((_, profiled)) = apply_game_profile(
+            &before_set,
+            &cli.set,
+            &configured_game_command(&args, &config),
+        )?
+    {
+        config = profiled;
+    }
+
+    match &cli.command {
+        Some(Commands::GamescopeUp {
+            startup_timeout_secs,
+            no_pin,
+            pin,
+            pick,
+            no_pick,
+            // Read by init_app_picker above.
+            show_all: _,
+            idle_inhibit,
+            no_idle_inhibit,
+            hide_waybar,
+            no_hide_waybar,
+            pick_size,
+            no_pick_size,
+            overlay,
+            no_overlay,
+            mangohud_config,
+            render_scale,
+            virtual_width,
+            virtual_height,
+            output_width,
+            output_height,
+            output_size,
+            umu,
+            embedded,
+            immediate_flips,
+            rt_priority,
+            force_grab_cursor,
+            expose_wayland,
+            cursor_confine,
+            cursor_warp,
+            disable_nightlight,
+            integer_scale,
+            borderless,
+            resolution_hints,
+            stream,
+            exit_with_game,
+            no_exit_with_game,
+            window_class,
+            window_title,
+            per_monitor,
+            apps,
+            plan_json,
+            plan_from,
+            // Handled before the config is loaded.
+            plan: _,
+            // Handled before the trace starts.
+            detach: _,
+            gamescope_args,
+        }) => {
+            if !*plan_json {
+                info(tr(Msg::LaunchingSession));
+            }
+            let with_umu = |config: &Config| match umu {
+                Some(gameid) => apply_umu_game(config, gameid),
+                None => Ok(config.clone()),
+            };
+            let mut config = with_umu(&config)?;
+            let mut gamescope_args = gamescope_args.clone();
+            let mut pick = flag_override(*pick, *no_pick);
+            let picked = pick_game_for_profiles(&gamescope_args, pick, &config)?;
+            let game = picked
+                .clone()
+                .unwrap_or_else(|| configured_game_command(&gamescope_args, &config));
+            if let Some((_, profiled)) = apply_game_profile(&before_set, &cli.set, &game)? {
+                config = with_umu(&profiled)?;
+            }
+            config.embedded = embedded.or(config.embedded);
+            config.immediate_flips = immediate_flips.or(config.immediate_flips);
+            config.rt_priority = rt_priority.or(config.rt_priority);
+            config.force_grab_cursor = force_grab_cursor.or(config.force_grab_cursor);
+            config.expose_wayland = expose_wayland.or(config.expose_wayland);
+            config.cursor_confine = cursor_confine.or(config.cursor_confine);
+            config.cursor_warp = cursor_warp.or(config.cursor_warp);
+            config.disable_nightlight = disable_nightlight.or(config.disable_nightlight);
+            config.integer_scale = integer_scale.or(config.integer_scale);
+            config.borderless = borderless.or(config.borderless);
+            config.resolution_hints = resolution_hints.or(config.resolution_hints);
+            config.window_class = window_class.clone().or(config.window_class);
+            config.window_title = window_title.clone().or(config.window_title);
+            if let Some(stream) = stream {
+                config.streaming.get_or_insert_default().enabled = Some(*stream);
+            }
+            let (output_width, output_height) = output_size
+                .map(|(w, h)| (Some(w), Some(h)))
+                .unwrap_or((*output_width, *output_height));
+            if let Some(game) = picked {
+                use_picked_game(game, &mut gamescope_args, &mut pick, &mut config);
+            }
+            let idle_inhibit = flag_override(*idle_inhibit, *no_idle_inhibit);
+            let hide_waybar = flag_override(*hide_waybar, *no_hide_waybar);
+            let pick_size = flag_override(*pick_size, *no_pick_size);
+            let overlay = flag_override(*overlay, *no_overlay);
+            let exit_with_game = flag_override(*exit_with_game, *no_exit_with_game);
+            let launch = apply_config(
+                &CliOverrides {
+                    args: gamescope_args,
+                    no_pin: flag_override(*no_pin, *pin),
+                    pick,
+                    idle_inhibit,
+                    hide_waybar,
+                    pick_size,
+                    overlay,
+                    mangohud_config: mangohud_config.clone(),
+                    render_scale: *render_scale,
+                    virtual_width: *virtual_width,
+                    virtual_height: *virtual_height,
+                    output_width,
+                    output_height,
+                    exit_with_game,
+                    startup_timeout_secs: *startup_timeout_secs,
+                },
+                &config,
+            );
+            if *plan_json {
+                print_launch_plan(&launch, cli.verbose)
+            } else if let Some(path) = plan_from.as_deref() {
+                LaunchPlan::builder(launch)
+                    .resolved(read_launch_plan(path)?)
+                    .build(cli.verbose)?
+                    .launch(cli.verbose)
+            } else if *per_monitor {
+                let apps = apps
+                    .iter()
+                    .map(|app| {
+                        shell_words::split(app)
+                            .ok()
+                            .filter(|words| !words.is_empty())
+                            .ok_or_else(|| MyError(format!("Invalid --app command `{}`.", app)))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                run_per_monitor_session(&launch, &apps, cli.verbose)
+            } else {
+                launch_session(&launch, cli.verbose)
+            }
+        }
+        None => {
+            info(tr(Msg::LaunchingSession));
+            let config = pick_game_profile(&config, &before_set, &cli.set)?;
+            launch_session(&LaunchSettings::from_config(&config), cli.verbose)
+        }
+        Some(Commands::Wrap { command }) => {
+            // Steam passes `-- %command%`; clap keeps the separator in trailing args.
+            let command = command.strip_prefix(&["--".to_string()]).unwrap_or(command);
+            if command.is_empty() {
+                return Err(MyError(
+                    "wrap needs the game command: `hyprfinity wrap -- %command%`.".into(),
+                )
+                .into());
+            }
+            match std::env::var("SteamAppId").or_else(|_| std::env::var("SteamGameId")) {
+                Ok(appid) => info(&trf(Msg::WrappingSteamApp, &[&appid])),
+                Err(_) => warn(tr(Msg::WrapNoSteamAppId)),
+            }
+            let ld_preload = std::env::var("LD_PRELOAD").ok().filter(|v| !v.is_empty());
+            let mut launch = apply_config(
+                &CliOverrides {
+                    args: steam_wrap_args(
+                        config.gamescope_args.clone().unwrap_or_default(),
+                        command,
+                        ld_preload.as_deref(),
+                    ),
+                    pick: Some(false),
+                    ..CliOverrides::default()
+                },
+                &config,
+            );
+            // Steam's overlay library in gamescope itself breaks its frame pacing.
+            if ld_preload.is_some() {
+                launch
+                    .extra_env
+                    .push(("LD_PRELOAD".to_string(), String::new()));
+            }
+            launch_session(&launch, cli.verbose)
+        }
+        Some(Commands::Steam { gamescope_args }) => {
+            info(tr(Msg::LaunchingSteamSession));
+            let base = if gamescope_args.is_empty() {
+                config.gamescope_args.clone().unwrap_or_default()
+            } else {
+                gamescope_args.clone()
+            };
+            let launch = apply_config(&CliOverrides::args(steam_bigpicture_args(base)), &config);
+            launch_session(&launch, cli.verbose)
+        }
+        Some(Commands::Config { action: None }) => interactive_config(&cli.config, cli.verbose),
+        Some(Commands::Config {
+            action: Some(ConfigAction::Schema { output }),
+        }) => print_config_schema(output.as_deref()),
+        Some(Commands::Profiles) => match manage_profiles(&cli.config)? {
+            Some(profiled) => {
+                info(tr(Msg::LaunchingSession));
+                let launch = LaunchSettings::from_config(&profiled);
+                launch_session(&launch, cli.verbose)
+            }
+            None => Ok(()),
+        },
+        Some(Commands::VirtualUp {
+            size,
+            refresh,
+            stream,
+            gamescope_args,
+        }) => {
+            let base = if gamescope_args.is_empty() {
+                config.gamescope_args.clone().unwrap_or_default()
+            } else {
+                gamescope_args.clone()
+            };
+            let launch = apply_config(&CliOverrides::args(base), &config);
+            virtual_up(&launch, *size, *refresh, *stream, cli.verbose)
+        }
+        Some(Commands::Plugin) => show_plugin_status(cli.verbose),
+        Some(Commands::GamescopeDown { confirm: true }) => confirm_gamescope_down(cli.verbose),
+        Some(Commands::GamescopeDown { confirm: false }) => {
+            info(tr(Msg::TearingDownSession));
+            gamescope_down()
+        }
+        Some(Commands::Toggle) => {
+            let status = session_status();
+            if let SessionStatus::Active(pid) = status {
+                info(&trf(Msg::ToggleStopping, &[&pid]));
+                gamescope_down()
+            } else if per_monitor_session_running() {
+                info(tr(Msg::TogglePerMonitorStopping));
+                gamescope_down()
+            } else {
+                if status == SessionStatus::Stale {
+                    info(tr(Msg::ToggleStale));
+                    clear_stale_session()?;
+                }
+                info(tr(Msg::ToggleStarting));
+                let config = pick_game_profile(&config, &before_set, &cli.set)?;
+                let launch = LaunchSettings::from_config(&config);
+                launch_session(&launch, cli.verbose)
+            }
+        }
+        Some(Commands::Monitors { json }) => {
+            show_monitors(config.span_target.unwrap_or_default(), *json, cli.verbose)
+        }
+        Some(Commands::Span { json }) => {
+            let launch = LaunchSettings::from_config(&config);
+            show_span(&launch, *json, cli.verbose)
+        }
+        Some(Commands::Replay { trace }) => {
+            let launch = LaunchSettings::from_config(&config);
+            replay_trace(trace, &launch, cli.verbose)
+        }
+        Some(Commands::UpgradeCheck { json }) => upgrade_check(*json),
+        Some(Commands::SelfUpdate {
+            force,
+            skip_attestation,
+        }) => self_update(*force, *skip_attestation),
+        // Handled before the config is loaded.
+        Some(Commands::GenDocs { .. }) => Ok(()),
+        Some(Commands::Bugreport { output }) => {
+            write_bugreport(&cli.config, &cli.debug_log, output.as_deref(), cli.verbose)
+        }
+        Some(Commands::Screenshot {
+            region,
+            monitor,
+            output,
+        }) => take_screenshot(
+            *region,
+            monitor.as_deref(),
+            output.clone(),
+            config.span_target.unwrap_or_default(),
+            config.span_strategy.unwrap_or_default(),
+            cli.verbose,
+        ),
+        Some(Commands::Watchdog { .. }) => Ok(()),
+        Some(Commands::ReplaySave) => save_session_replay(cli.verbose),
+        Some(Commands::ExportDesktop) => export_desktop(&cli.config, cli.profile.as_deref()),
+        Some(Commands::Profile {
+            action: ProfileAction::Export { name, output },
+        }) => export_profile(&cli.config, name, output.as_deref()),
+        Some(Commands::Profile {
+            action:
+                ProfileAction::Import {
+                    source,
+                    name,
+                    force,
+                    trust,
+                },
+        }) => import_profiles(&cli.config, source, name.as_deref(), *force, *trust),
+        Some(Commands::Presets {
+            action:
+                PresetsAction::Browse {
+                    id,
+                    name,
+                    list,
+                    refresh,
+                    force,
+                },
+        }) => browse_presets(
+            &cli.config,
+            config.preset_catalog_url.as_deref(),
+            config.picker_command.as_deref(),
+            id.as_deref(),
+            name.as_deref(),
+            *list,
+            *refresh,
+            *force,
+        ),
+        Some(Commands::Restart) => request_session_restart(),
+        Some(Commands::SetScale { scale }) => request_session_scale(*scale),
+        Some(Commands::QuickMenu) => quick_menu(
+            config.picker_command.as_deref(),
+            config.span_target.unwrap_or_default(),
+            config.span_strategy.unwrap_or_default(),
+            cli.verbose,
+        ),
+        Some(Commands::Osd { run: true }) => {
+            run_osd(config.mangohud_config.as_deref(), cli.verbose)
+        }
+        Some(Commands::Osd { run: false }) => toggle_osd(),
+        Some(Commands::Stats { days, json }) => show_stats(*days, *json),
+        Some(Commands::Sessions {
+            relaunch: Some(id), ..
+        }) => {
+            let args = session_args(*id)?;
+            info(&trf(Msg::RelaunchingSession, &[id]));
+            // Recorded args are the final gamescope args; explicit flags win over derived ones.
+            let launch = apply_config(&CliOverrides::args(args.clone()), &config);
+            launch_session(&launch, cli.verbose)
+        }
+        Some(Commands::Sessions { last, json, .. }) => show_sessions(*last, *json),
+        Some(Commands::ConfigInit { force }) => {
+            write_default_config(&cli.config, *force)?;
+            Ok(())
+        }
+        Some(Commands::ConfigShow {
+            no_pin,
+            pin,
+            pick,
+            no_pick,
+            idle_inhibit,
+            no_idle_inhibit,
+            hide_waybar,
+            no_hide_waybar,
+            pick_size,
+            no_pick_size,
+            overlay,
+            no_overlay,
+            mangohud_config,
+            render_scale,
+            virtual_width,
+            virtual_height,
+            output_width,
+            output_height,
+            output_size,
+            exit_with_game,
+            no_exit_with_game,
+            startup_timeout_secs,
+            diff,
+            json,
+            gamescope_args,
+        }) => {
+            let (output_width, output_height) = output_size
+                .map(|(w, h)| (Some(w), Some(h)))
+                .unwrap_or((*output_width, *output_height));
+            let pick = flag_override(*pick, *no_pick);
+            let idle_inhibit = flag_override(*idle_inhibit, *no_idle_inhibit);
+            let hide_waybar = flag_override(*hide_waybar, *no_hide_waybar);
+            let pick_size = flag_override(*pick_size, *no_pick_size);
+            let overlay = flag_override(*overlay, *no_overlay);
+            let exit_with_game = flag_override(*exit_with_game, *no_exit_with_game);
+            show_config(
+                &cli.config,
+                cli.profile.as_deref(),
+                &cli.set,
+                &CliOverrides {
+                    args: gamescope_args.clone(),
+                    no_pin: flag_override(*no_pin, *pin),
+                    pick,
+                    idle_inhibit,
+                    hide_waybar,
+                    pick_size,
+                    overlay,
+                    mangohud_config: mangohud_config.clone(),
+                    render_scale: *render_scale,
+                    virtual_width: *virtual_width,
+                    virtual_height: *virtual_height,
+                    output_width,
+                    output_height,
+                    exit_with_game,
+                    startup_timeout_secs: *startup_timeout_secs,
+                },
+                *diff,
+                *json,
+            )?;
+            Ok(())
+        }
+    }
+}
+
+/// Gamescope args of commands that launch without the picker, so their game command can
+/// select a profile by its `match` rules. `None` for everything else.
+fn fixed_launch_args(command: &Option<Commands>) -> Result<Option<Vec<String>>, Box<dyn Error>> {
+    Ok(match command {
+        Some(Commands::Wrap { command }) => {
+            let command = command.strip_prefix(&["--".to_string()]).unwrap_or(command);
+            Some(steam_wrap_args(Vec::new(), command, None))
+        }
+        Some(Commands::Steam { .. }) => Some(steam_bigpicture_args(Vec::new())),
+        Some(Commands::VirtualUp { gamescope_args, .. }) => Some(gamescope_args.clone()),
+        Some(Commands::Span { .. } | Commands::Replay { .. }) => Some(Vec::new()),
+        Some(Commands::Sessions {
+            relaunch: Some(id), ..
+        }) => Some(session_args(*id)?),
+        _ => None,
+    })
+}
+
+/// Config for a launch without gamescope args: the picker runs when profiles have `match`
+/// rules, and the game it returns selects the profile layered under `--set`.
+fn pick_game_profile(
+    config: &Config,
+    before_set: &Config,
+    set: &[String],
+) -> Result<Config, Box<dyn Error>> {
+    let mut args = Vec::new();
+    let mut pick = None;
+    let picked = pick_game_for_profiles(&args, pick, config)?;
+    let game = picked
+        .clone()
+        .unwrap_or_else(|| configured_game_command(&args, config));
+    let mut config = match apply_game_profile(before_set, set, &game)? {
+        Some((_, profiled)) => profiled,
+        None => config.clone(),
+    };
+    if let Some(game) = picked {
+        use_picked_game(game, &mut args, &mut pick, &mut config);
+    }
+    Ok(config)
+}
@@ -0,0 +1,163 @@
+use crate::debuglog::debug_log_line;
+use crate::i18n::{Msg, trf};
+use crate::output::{error, info};
+use crate::stats::unix_now;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+static SESSION_STARTED_AT: AtomicU64 = AtomicU64::new(0);
+static REFLOW_COUNT: AtomicU64 = AtomicU64::new(0);
+static FIT_FAILURES: AtomicU64 = AtomicU64::new(0);
+static HYPRCTL_ERRORS: AtomicU64 = AtomicU64::new(0);
+static GAMESCOPE_RESTARTS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct MetricsSnapshot {
+    session_up: bool,
+    reflow_count: u64,
+    fit_failures: u64,
+    hyprctl_errors: u64,
+    gamescope_restarts: u64,
+    uptime_secs: u64,
+}
+
+pub(crate) fn mark_session_up(up: bool) {
+    SESSION_STARTED_AT.store(if up { unix_now().max(1) } else { 0 }, Ordering::Relaxed);
+}
+
+pub(crate) fn count_reflow() {
+    REFLOW_COUNT.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn count_fit_failure() {
+    FIT_FAILURES.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn count_hyprctl_error() {
+    HYPRCTL_ERRORS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn count_gamescope_restart() {
+    GAMESCOPE_RESTARTS.fetch_add(1, Ordering::Relaxed);
+}
+
+fn snapshot() -> MetricsSnapshot {
+    let started_at = SESSION_STARTED_AT.load(Ordering::Relaxed);
+    MetricsSnapshot {
+        session_up: started_at > 0,
+        reflow_count: REFLOW_COUNT.load(Ordering::Relaxed),
+        fit_failures: FIT_FAILURES.load(Ordering::Relaxed),
+        hyprctl_errors: HYPRCTL_ERRORS.load(Ordering::Relaxed),
+        gamescope_restarts: GAMESCOPE_RESTARTS.load(Ordering::Relaxed),
+        uptime_secs: if started_at > 0 {
+            unix_now().saturating_sub(started_at)
+        } else {
+            0
+        },
+    }
+}
+
+fn render_prometheus(snap: &MetricsSnapshot) -> String {
+    let metrics: [(&str, &str, &str, u64); 6] = [
+        (
+            "session_up",
+            "gauge",
+            "Whether a Gamescope span session is running.",
+            u64::from(snap.session_up),
+        ),
+        (
+            "reflow_count",
+            "counter",
+            "Window re-fits performed by the reflow loop.",
+            snap.reflow_count,
+        ),
+        (
+            "fit_failures",
+            "counter",
+            "Window fits that did not converge or errored.",
+            snap.fit_failures,
+        ),
+        (
+            "hyprctl_errors",
+            "counter",
+            "Failed hyprctl invocations (after retries).",
+            snap.hyprctl_errors,
+        ),
+        (
+            "gamescope_restarts",
+            "counter",
+            "Gamescope relaunches (crash recovery or gamepad restart).",
+            snap.gamescope_restarts,
+        ),
+        (
+            "uptime_seconds",
+            "gauge",
+            "Seconds since the current session started.",
+            snap.uptime_secs,
+        ),
+    ];
+    let mut out = String::new();
+    for (name, kind, help, value) in metrics {
+        out.push_str(&format!(
+            "# HELP hyprfinity_{name} {help}\n# TYPE hyprfinity_{name} {kind}\nhyprfinity_{name} {value}\n"
+        ));
+    }
+    out
+}
+
+pub(crate) fn write_metrics_textfile(path: &str) {
+    // Write then rename so node_exporter never scrapes a half-written file.
+    let tmp = format!("{}.tmp", path);
+    let result = std::fs::write(&tmp, render_prometheus(&snapshot()))
+        .and_then(|_| std::fs::rename(&tmp, path));
+    if let Err(e) = result {
+        debug_log_line(&format!("failed to write metrics textfile {}: {}", path, e));
+    }
+}
+
+pub(crate) fn start_metrics_server(listen: &str) {
+    let listener = match TcpListener::bind(listen) {
+        Ok(listener) => listener,
+        Err(e) => {
+            error(&trf(Msg::MetricsFailed, &[&listen, &e]));
+            return;
+        }
+    };
+    info(&trf(Msg::MetricsServing, &[&listen]));
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut stream = stream;
+            // Every path gets the metrics; only drain the request line and headers.
+            let mut buf = [0_u8; 1024];
+            let _ = stream.read(&mut buf);
+            let body = render_prometheus(&snapshot());
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_prometheus_emits_help_type_and_value() {
+        let text = render_prometheus(&MetricsSnapshot {
+            session_up: true,
+            reflow_count: 12,
+            gamescope_restarts: 1,
+            ..Default::default()
+        });
+        assert!(text.contains("# TYPE hyprfinity_session_up gauge\nhyprfinity_session_up 1\n"));
+        assert!(text.contains("hyprfinity_reflow_count 12\n"));
+        assert!(text.contains("hyprfinity_gamescope_restarts 1\n"));
+        assert!(text.contains("hyprfinity_uptime_seconds 0\n"));
+    }
+}
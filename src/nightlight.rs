@@ -0,0 +1,124 @@
+use crate::hyprland::{execute_hyprctl, execute_hyprctl_output};
+use crate::i18n::{Msg, tr, trf};
+use crate::output::{debug, error, warn};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// What was paused for the session, kept in the state file so a crashed session can still be
+/// restored by `gamescope-down`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "tool", rename_all = "snake_case")]
+pub(crate) enum NightlightRestore {
+    Hyprsunset { temperature: u32 },
+    Gammastep { pid: u32 },
+}
+
+fn running_pids(name: &str) -> Vec<u32> {
+    Command::new("pgrep")
+        .args(["-x", name])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.trim().parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_hyprsunset_temperature(stdout: &str) -> Option<u32> {
+    stdout
+        .split_whitespace()
+        .find_map(|token| token.trim_end_matches('K').parse().ok())
+}
+
+// gammastep has no IPC; SIGUSR1 toggles it between enabled and disabled.
+fn toggle_gammastep(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-USR1", &pid.to_string()])
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Switches hyprsunset to identity gamma or toggles gammastep off. Returns what to restore.
+pub(crate) fn pause_nightlight(verbose: bool) -> Option<NightlightRestore> {
+    if !running_pids("hyprsunset").is_empty() {
+        let temperature = match execute_hyprctl_output(&["hyprsunset", "temperature"], verbose)
+            .ok()
+            .as_deref()
+            .and_then(parse_hyprsunset_temperature)
+        {
+            Some(value) => value,
+            None => {
+                warn(tr(Msg::NightlightUnreadable));
+                return None;
+            }
+        };
+        if let Err(e) = execute_hyprctl(&["hyprsunset", "identity"], verbose) {
+            error(&trf(Msg::NightlightPauseFailed, &[&"hyprsunset", &e]));
+            return None;
+        }
+        if verbose {
+            debug(&format!("Paused hyprsunset ({}K).", temperature));
+        }
+        return Some(NightlightRestore::Hyprsunset { temperature });
+    }
+    if let Some(pid) = running_pids("gammastep").first().copied() {
+        if !toggle_gammastep(pid) {
+            error(tr(Msg::GammastepPauseFailed));
+            return None;
+        }
+        if verbose {
+            debug(&format!("Paused gammastep (pid {}).", pid));
+        }
+        return Some(NightlightRestore::Gammastep { pid });
+    }
+    None
+}
+
+pub(crate) fn resume_nightlight(restore: &NightlightRestore, verbose: bool) {
+    match restore {
+        NightlightRestore::Hyprsunset { temperature } => {
+            if let Err(e) = execute_hyprctl(
+                &["hyprsunset", "temperature", &temperature.to_string()],
+                verbose,
+            ) {
+                error(&trf(Msg::NightlightResumeFailed, &[&"hyprsunset", &e]));
+            }
+        }
+        NightlightRestore::Gammastep { pid } => {
+            // A restarted gammastep comes back enabled; only toggle the instance we paused.
+            if running_pids("gammastep").contains(pid) && !toggle_gammastep(*pid) {
+                error(tr(Msg::GammastepResumeFailed));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hyprsunset_temperature_reads_first_number() {
+        assert_eq!(parse_hyprsunset_temperature("4500\n"), Some(4500));
+        assert_eq!(
+            parse_hyprsunset_temperature("temperature: 6000K"),
+            Some(6000)
+        );
+        assert_eq!(parse_hyprsunset_temperature("error"), None);
+    }
+
+    #[test]
+    fn nightlight_restore_round_trips_through_state_json() {
+        let restore = NightlightRestore::Gammastep { pid: 42 };
+        let json = serde_json::to_string(&restore).unwrap();
+        assert_eq!(json, r#"{"tool":"gammastep","pid":42}"#);
+        assert_eq!(
+            serde_json::from_str::<NightlightRestore>(&json).unwrap(),
+            restore
+        );
+    }
+}
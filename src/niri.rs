@@ -0,0 +1,293 @@
+use crate::MyError;
+use crate::compositor::{Compositor, WindowRect, run_ipc, transform_index};
+use crate::types::{Client, HotkeyBind, Monitor};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// niri through `niri msg --json`. Windows belong to a single output, so a session covers one
+/// monitor (`span_target = "focused"`); there is no pinning and no runtime keybinding IPC.
+pub(crate) struct NiriCompositor;
+
+#[derive(Debug, Deserialize)]
+struct NiriMode {
+    width: i32,
+    height: i32,
+    /// mHz
+    refresh_rate: i32,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+struct NiriLogical {
+    x: i32,
+    y: i32,
+    scale: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct NiriOutput {
+    name: String,
+    #[serde(default)]
+    make: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    modes: Vec<NiriMode>,
+    #[serde(default)]
+    current_mode: Option<usize>,
+    #[serde(default)]
+    vrr_enabled: bool,
+    #[serde(default)]
+    logical: Option<NiriLogical>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NiriWorkspace {
+    id: u64,
+    #[serde(default)]
+    output: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NiriLayout {
+    #[serde(default)]
+    window_size: Option<[i32; 2]>,
+    #[serde(default)]
+    tile_pos_in_workspace_view: Option<[f64; 2]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NiriWindow {
+    id: u64,
+    #[serde(default)]
+    pid: Option<i32>,
+    #[serde(default)]
+    workspace_id: Option<u64>,
+    #[serde(default)]
+    layout: Option<NiriLayout>,
+    #[serde(default)]
+    app_id: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+}
+
+fn niri_outputs_to_monitors(outputs: HashMap<String, serde_json::Value>) -> Vec<Monitor> {
+    let mut monitors = outputs
+        .into_values()
+        .filter_map(|value| {
+            // The transform is a string enum; read it here so NiriLogical stays Copy.
+            let transform = value
+                .pointer("/logical/transform")
+                .and_then(|t| t.as_str())
+                .map(transform_index);
+            let output: NiriOutput = serde_json::from_value(value).ok()?;
+            let mode = output.current_mode.and_then(|i| output.modes.get(i));
+            Some(Monitor {
+                description: Some(
+                    [output.make.as_deref(), output.model.as_deref()]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                )
+                .filter(|d| !d.is_empty()),
+                width: mode.map_or(0, |m| m.width),
+                height: mode.map_or(0, |m| m.height),
+                x: output.logical.map_or(0, |l| l.x),
+                y: output.logical.map_or(0, |l| l.y),
+                refresh_rate: mode.map(|m| m.refresh_rate as f32 / 1000.0),
+                scale: output.logical.map(|l| l.scale),
+                vrr: Some(output.vrr_enabled),
+                disabled: output.logical.is_none() || mode.is_none(),
+                transform,
+                model: output.model,
+                name: Some(output.name),
+                ..Monitor::default()
+            })
+        })
+        .collect::<Vec<_>>();
+    // niri reports outputs as a map; keep a stable left-to-right order, disabled ones last.
+    monitors.sort_by_key(|m| (m.disabled, m.x, m.y, m.name.clone()));
+    monitors
+}
+
+/// Window positions are relative to the workspace view; offset them by their output's origin.
+fn niri_windows_to_clients(
+    windows: Vec<NiriWindow>,
+    workspaces: &[NiriWorkspace],
+    monitors: &[Monitor],
+) -> Vec<Client> {
+    let origin = |workspace_id: Option<u64>| {
+        let output = workspaces
+            .iter()
+            .find(|w| Some(w.id) == workspace_id)
+            .and_then(|w| w.output.as_deref())?;
+        monitors
+            .iter()
+            .find(|m| m.name.as_deref() == Some(output))
+            .map(|m| (m.x, m.y))
+    };
+    windows
+        .into_iter()
+        .filter_map(|window| {
+            let pid = window.pid?;
+            let layout = window.layout.as_ref();
+            let at = layout
+                .and_then(|l| l.tile_pos_in_workspace_view)
+                .zip(origin(window.workspace_id))
+                .map(|([x, y], (ox, oy))| [ox + x.round() as i32, oy + y.round() as i32]);
+            Some(Client {
+                pid,
+                address: Some(window.id.to_string()),
+                at,
+                size: layout.and_then(|l| l.window_size),
+                class: window.app_id,
+                title: window.title,
+                ..Client::default()
+            })
+        })
+        .collect()
+}
+
+impl NiriCompositor {
+    fn action(&self, args: &[&str], verbose: bool) -> Result<(), Box<dyn Error>> {
+        let mut full = vec!["msg", "action"];
+        full.extend_from_slice(args);
+        run_ipc("niri", &full, verbose).map(|_| ())
+    }
+}
+
+impl Compositor for NiriCompositor {
+    fn name(&self) -> &'static str {
+        "niri"
+    }
+
+    fn monitors(&self, verbose: bool) -> Result<Vec<Monitor>, Box<dyn Error>> {
+        let stdout = run_ipc("niri", &["msg", "--json", "outputs"], verbose)?;
+        let outputs: HashMap<String, serde_json::Value> = serde_json::from_str(&stdout)
+            .map_err(|e| MyError(format!("Failed to parse niri outputs: {}", e)))?;
+        Ok(niri_outputs_to_monitors(outputs))
+    }
+
+    fn clients(&self, verbose: bool) -> Result<Vec<Client>, Box<dyn Error>> {
+        let windows: Vec<NiriWindow> =
+            serde_json::from_str(&run_ipc("niri", &["msg", "--json", "windows"], verbose)?)
+                .map_err(|e| MyError(format!("Failed to parse niri windows: {}", e)))?;
+        let workspaces: Vec<NiriWorkspace> =
+            serde_json::from_str(&run_ipc("niri", &["msg", "--json", "workspaces"], verbose)?)
+                .map_err(|e| MyError(format!("Failed to parse niri workspaces: {}", e)))?;
+        let monitors = self.monitors(verbose)?;
+        Ok(niri_windows_to_clients(windows, &workspaces, &monitors))
+    }
+
+    fn window_selector(&self, pid: u32, client: Option<&Client>) -> String {
+        client
+            .and_then(|c| c.address.clone())
+            .unwrap_or_else(|| format!("pid:{}", pid))
+    }
+
+    fn place_window(
+        &self,
+        window: &str,
+        (x, y, width, height): WindowRect,
+        float: bool,
+        _pin: bool,
+        verbose: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        if window.starts_with("pid:") {
+            return Err(MyError(format!("niri has no window id for {} yet.", window)).into());
+        }
+        if float {
+            self.action(&["move-window-to-floating", "--id", window], verbose)?;
+        }
+        // Floating positions are relative to the output the window is on.
+        let (origin_x, origin_y) = self
+            .monitors(verbose)?
+            .iter()
+            .find(|m| x >= m.x && x < m.x + m.width && y >= m.y && y < m.y + m.height)
+            .map_or((0, 0), |m| (m.x, m.y));
+        self.action(
+            &["set-window-width", "--id", window, &width.to_string()],
+            verbose,
+        )?;
+        self.action(
+            &["set-window-height", "--id", window, &height.to_string()],
+            verbose,
+        )?;
+        self.action(
+            &[
+                "move-floating-window",
+                "--id",
+                window,
+                "-x",
+                &(x - origin_x).to_string(),
+                "-y",
+                &(y - origin_y).to_string(),
+            ],
+            verbose,
+        )
+    }
+
+    fn bind_key(
+        &self,
+        mods: &str,
+        key: &str,
+        _bind: HotkeyBind,
+        command: &str,
+        _verbose: bool,
+    ) -> Result<bool, Box<dyn Error>> {
+        Err(MyError(format!(
+            "niri cannot add keybinds at runtime; bind {}+{} to `{}` in config.kdl instead.",
+            mods, key, command
+        ))
+        .into())
+    }
+
+    fn unbind_key(&self, _mods: &str, _key: &str, _verbose: bool) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn niri_outputs_and_windows_map_to_global_geometry() {
+        let outputs: HashMap<String, serde_json::Value> = serde_json::from_str(
+            r#"{
+                "HDMI-A-1": {"name": "HDMI-A-1", "make": "LG", "model": "27GL850",
+                    "modes": [{"width": 2560, "height": 1440, "refresh_rate": 143912, "is_preferred": true}],
+                    "current_mode": 0, "vrr_enabled": false,
+                    "logical": {"x": 1920, "y": 0, "width": 2560, "height": 1440, "scale": 1.0, "transform": "Normal"}},
+                "DP-1": {"name": "DP-1", "modes": [{"width": 1920, "height": 1080, "refresh_rate": 60000}],
+                    "current_mode": 0, "logical": {"x": 0, "y": 0, "width": 1920, "height": 1080, "scale": 1.0, "transform": "_90"}},
+                "eDP-1": {"name": "eDP-1", "modes": [], "current_mode": null, "logical": null}
+            }"#,
+        )
+        .unwrap();
+        let monitors = niri_outputs_to_monitors(outputs);
+        let names = monitors
+            .iter()
+            .map(|m| m.name.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["DP-1", "HDMI-A-1", "eDP-1"]);
+        assert_eq!(monitors[0].transform, Some(1));
+        assert_eq!(monitors[1].refresh_rate, Some(143.912));
+        assert!(monitors[2].disabled);
+
+        let windows: Vec<NiriWindow> = serde_json::from_str(
+            r#"[{"id": 12, "pid": 300, "workspace_id": 2, "is_floating": true,
+                 "layout": {"window_size": [1280, 720], "tile_pos_in_workspace_view": [100.4, 50.0]}},
+                {"id": 13, "pid": null, "workspace_id": 2}]"#,
+        )
+        .unwrap();
+        let workspaces: Vec<NiriWorkspace> =
+            serde_json::from_str(r#"[{"id": 2, "output": "HDMI-A-1"}]"#).unwrap();
+        let clients = niri_windows_to_clients(windows, &workspaces, &monitors);
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].address.as_deref(), Some("12"));
+        assert_eq!(clients[0].at, Some([2020, 50]));
+        assert_eq!(clients[0].size, Some([1280, 720]));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::MyError;
+use crate::compositor::compositor;
+use crate::debuglog::debug_log_line;
+use crate::gamescope::{
+    SessionSnapshot, SessionStatus, active_session_snapshot, pid_alive, session_status,
+};
+use crate::hyprland::execute_hyprctl;
+use crate::i18n::{Msg, tr};
+use crate::output::info;
+use crate::stats::unix_now;
+use std::error::Error;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+const OSD_PID_FILE_NAME: &str = "hyprfinity_osd.pid";
+const OSD_REFRESH: Duration = Duration::from_secs(1);
+/// Each line expires as the next one is shown, so lines do not pile up and other notifications
+/// (such as the exit countdown) are never dismissed along with them.
+const OSD_NOTIFY_MS: &str = "1000";
+/// A MangoHud log untouched for this long belongs to an earlier run, or logging is off.
+const FPS_LOG_MAX_AGE: Duration = Duration::from_secs(10);
+
+fn osd_pid_path() -> PathBuf {
+    std::env::temp_dir().join(OSD_PID_FILE_NAME)
+}
+
+fn running_osd_pid() -> Option<u32> {
+    std::fs::read_to_string(osd_pid_path())
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|pid| pid_alive(*pid))
+}
+
+/// `output_folder` from a MangoHud config string such as `fps,output_folder=/tmp/mh,autostart_log=1`.
+fn mangohud_log_dir(mangohud_config: Option<&str>) -> Option<PathBuf> {
+    mangohud_config?
+        .split(',')
+        .find_map(|entry| entry.trim().strip_prefix("output_folder="))
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+/// The FPS of the last frame-metrics row of a MangoHud CSV log.
+fn parse_mangohud_fps(contents: &str) -> Option<f32> {
+    let mut lines = contents
+        .lines()
+        .skip_while(|line| !line.starts_with("fps,"));
+    lines.next()?;
+    lines
+        .filter(|line| !line.trim().is_empty())
+        .last()?
+        .split(',')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Reads the FPS from the newest MangoHud log in `dir` while it is still being written.
+fn latest_fps(dir: &Path) -> Option<f32> {
+    let newest = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "csv"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)?;
+    if newest.0.elapsed().ok()? > FPS_LOG_MAX_AGE {
+        return None;
+    }
+    parse_mangohud_fps(&std::fs::read_to_string(newest.1).ok()?)
+}
+
+fn format_uptime(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+fn osd_text(snapshot: &SessionSnapshot, fps: Option<f32>, now: u64) -> String {
+    let mut parts = vec![format!("hyprfinity: {}", snapshot.game)];
+    match (snapshot.internal, snapshot.output) {
+        (Some((iw, ih)), Some((ow, oh))) if ow > 0 => parts.push(format!(
+            "{}x{} -> {}x{} ({}%)",
+            iw,
+            ih,
+            ow,
+            oh,
+            (f64::from(iw) * 100.0 / f64::from(ow)).round()
+        )),
+        (Some((iw, ih)), _) => parts.push(format!("{}x{}", iw, ih)),
+        _ => {}
+    }
+    if let Some(fps) = fps {
+        parts.push(format!("{:.0} FPS", fps));
+    }
+    if snapshot.started_at > 0 {
+        parts.push(format_uptime(now.saturating_sub(snapshot.started_at)));
+    }
+    parts.join(" | ")
+}
+
+/// Turns the OSD off when it is showing, otherwise starts it in the background for the active
+/// session (bound to osd_hotkey).
+pub(crate) fn toggle_osd() -> Result<(), Box<dyn Error>> {
+    if compositor().name() != "hyprland" {
+        return Err(MyError("The OSD uses Hyprland's notification overlay.".to_string()).into());
+    }
+    if let Some(pid) = running_osd_pid() {
+        let _ = Command::new("kill").arg(pid.to_string()).status();
+        let _ = std::fs::remove_file(osd_pid_path());
+        info(tr(Msg::OsdOff));
+        return Ok(());
+    }
+    if !matches!(session_status(), SessionStatus::Active(_)) {
+        return Err(MyError("No active Gamescope session.".to_string()).into());
+    }
+    // Own process group, so the OSD outlives the short-lived hotkey invocation.
+    let child = Command::new(std::env::current_exe()?)
+        .args(["osd", "--run"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()?;
+    std::fs::write(osd_pid_path(), child.id().to_string())?;
+    info(tr(Msg::OsdOn));
+    Ok(())
+}
+
+/// Refreshes the status line every second until the session ends or the OSD is toggled off.
+pub(crate) fn run_osd(mangohud_config: Option<&str>, verbose: bool) -> Result<(), Box<dyn Error>> {
+    let log_dir = mangohud_log_dir(mangohud_config);
+    while let Some(snapshot) = active_session_snapshot() {
+        let fps = log_dir.as_deref().and_then(latest_fps);
+        let text = osd_text(&snapshot, fps, unix_now());
+        if let Err(e) = execute_hyprctl(&["notify", "-1", OSD_NOTIFY_MS, "0", &text], verbose) {
+            debug_log_line(&format!("osd: notify failed: {}", e));
+        }
+        thread::sleep(OSD_REFRESH);
+    }
+    if std::fs::read_to_string(osd_pid_path())
+        .is_ok_and(|pid| pid.trim() == std::process::id().to_string())
+    {
+        let _ = std::fs::remove_file(osd_pid_path());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn osd_text_reads_scale_and_fps_from_mangohud_logs() {
+        let log = "os,cpu,gpu\nLinux,Ryzen,RX\n--------------------FRAME METRICS--------------------\n\
+                   fps,frametime,cpu_load\n143.2,6.98,40\n118.6,8.43,42\n";
+        assert_eq!(parse_mangohud_fps(log), Some(118.6));
+        assert_eq!(parse_mangohud_fps("os,cpu\nLinux,Ryzen\n"), None);
+        assert_eq!(
+            mangohud_log_dir(Some("fps,output_folder=/tmp/mh,autostart_log=1")),
+            Some(PathBuf::from("/tmp/mh"))
+        );
+
+        let snapshot = SessionSnapshot {
+            game: "steam -applaunch 620".to_string(),
+            started_at: 1_000,
+            output: Some((5760, 1080)),
+            internal: Some((4320, 810)),
+        };
+        assert_eq!(
+            osd_text(&snapshot, Some(118.6), 1_000 + 3_725),
+            "hyprfinity: steam -applaunch 620 | 4320x810 -> 5760x1080 (75%) | 119 FPS | 1:02:05"
+        );
+    }
+}
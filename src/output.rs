@@ -0,0 +1,164 @@
+use crate::types::ColorMode;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum OutputLevel {
+    Quiet = 0,
+    Normal = 1,
+    Verbose = 2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Tone {
+    Accent,
+    Bold,
+    Dim,
+    Warn,
+    Error,
+}
+
+static OUTPUT_LEVEL: AtomicU8 = AtomicU8::new(OutputLevel::Normal as u8);
+static COLOR_MODE: AtomicU8 = AtomicU8::new(0);
+/// Whether a startup spinner currently owns the last stderr line.
+static SPINNER_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn init_output(quiet: bool, verbose: bool) {
+    let level = if quiet {
+        OutputLevel::Quiet
+    } else if verbose {
+        OutputLevel::Verbose
+    } else {
+        OutputLevel::Normal
+    };
+    OUTPUT_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub(crate) fn init_color(mode: ColorMode) {
+    let value = match mode {
+        ColorMode::Auto => 0,
+        ColorMode::Always => 1,
+        ColorMode::Never => 2,
+    };
+    COLOR_MODE.store(value, Ordering::Relaxed);
+}
+
+fn resolve_color(mode: ColorMode, is_tty: bool, no_color: bool) -> bool {
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => is_tty && !no_color,
+    }
+}
+
+fn color_enabled(is_tty: bool) -> bool {
+    let mode = match COLOR_MODE.load(Ordering::Relaxed) {
+        1 => ColorMode::Always,
+        2 => ColorMode::Never,
+        _ => ColorMode::Auto,
+    };
+    // https://no-color.org: any non-empty NO_COLOR disables color.
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    resolve_color(mode, is_tty, no_color)
+}
+
+pub(crate) fn stdout_color() -> bool {
+    color_enabled(std::io::stdout().is_terminal())
+}
+
+fn stderr_color() -> bool {
+    color_enabled(std::io::stderr().is_terminal())
+}
+
+pub(crate) fn paint(text: &str, tone: Tone, enabled: bool) -> String {
+    if !enabled {
+        return text.to_string();
+    }
+    let code = match tone {
+        Tone::Accent => "36",
+        Tone::Bold => "1",
+        Tone::Dim => "2",
+        Tone::Warn => "33",
+        Tone::Error => "31",
+    };
+    format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
+pub(crate) fn set_spinner_active(active: bool) {
+    SPINNER_ACTIVE.store(active, Ordering::SeqCst);
+}
+
+fn prefixed(prefix: &str, tone: Tone, message: &str) {
+    let mut stderr = std::io::stderr().lock();
+    // Clear the spinner line first; the spinner redraws below the message.
+    let clear = if SPINNER_ACTIVE.load(Ordering::SeqCst) {
+        "\r\x1b[2K"
+    } else {
+        ""
+    };
+    let _ = writeln!(
+        stderr,
+        "{}{} {}",
+        clear,
+        paint(prefix, tone, stderr_color()),
+        message
+    );
+}
+
+pub(crate) fn output_level() -> OutputLevel {
+    match OUTPUT_LEVEL.load(Ordering::Relaxed) {
+        0 => OutputLevel::Quiet,
+        2 => OutputLevel::Verbose,
+        _ => OutputLevel::Normal,
+    }
+}
+
+// Messages go to stderr so stdout only carries data (tables, JSON, templates).
+pub(crate) fn info(message: &str) {
+    if output_level() >= OutputLevel::Normal {
+        prefixed("Hyprfinity:", Tone::Accent, message);
+    }
+}
+
+pub(crate) fn warn(message: &str) {
+    if output_level() >= OutputLevel::Normal {
+        prefixed("Hyprfinity: Warning:", Tone::Warn, message);
+    }
+}
+
+pub(crate) fn error(message: &str) {
+    prefixed("Hyprfinity:", Tone::Error, message);
+}
+
+pub(crate) fn debug(message: &str) {
+    if output_level() >= OutputLevel::Verbose {
+        prefixed("Hyprfinity (DEBUG):", Tone::Dim, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_output_prefers_quiet_over_verbose() {
+        init_output(true, true);
+        assert_eq!(output_level(), OutputLevel::Quiet);
+        init_output(false, true);
+        assert_eq!(output_level(), OutputLevel::Verbose);
+        init_output(false, false);
+        assert_eq!(output_level(), OutputLevel::Normal);
+    }
+
+    #[test]
+    fn color_follows_mode_tty_and_no_color() {
+        assert!(resolve_color(ColorMode::Auto, true, false));
+        assert!(!resolve_color(ColorMode::Auto, true, true));
+        assert!(!resolve_color(ColorMode::Auto, false, false));
+        assert!(resolve_color(ColorMode::Always, false, true));
+        assert!(!resolve_color(ColorMode::Never, true, false));
+        assert_eq!(paint("DP-1", Tone::Accent, false), "DP-1");
+        assert_eq!(paint("DP-1", Tone::Accent, true), "\x1b[36mDP-1\x1b[0m");
+    }
+}
@@ -0,0 +1,660 @@
+use crate::MyError;
+use crate::backend::gamescope_launcher;
+use crate::compositor::{WindowRect, compositor};
+use crate::config::LaunchSettings;
+use crate::debuglog::debug_log_line;
+use crate::gamepad::start_gamepad_watcher;
+use crate::gamescope::{
+    ExitHotkey, GAME_EXIT_GRACE_TICKS, LaunchPlan, ensure_game_command, game_command_label,
+    gamescope_crashed, maybe_start_idle_inhibit, maybe_start_waybar, maybe_stop_idle_inhibit,
+    maybe_stop_waybar, pid_alive, register_exit_hotkey, resolve_launch, spawn_watchdog,
+    unregister_exit_hotkey,
+};
+use crate::hyprland::{
+    WindowMatcher, compute_monitor_span, fit_window_to_span, get_monitors, retry_delay,
+    select_span_monitors, wait_for_client,
+};
+use crate::i18n::{Msg, tr, trf};
+use crate::metrics::count_gamescope_restart;
+use crate::output::{error, info, warn};
+use crate::proctree::game_processes;
+use crate::stats::{SessionRecord, record_session, unix_now};
+use crate::trace::finish_trace;
+use crate::types::{GamepadAction, Monitor, WindowProbe};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+const PER_MONITOR_STATE_FILE_NAME: &str = "hyprfinity_per_monitor_state.json";
+// The same tick as a spanned session, which GAME_EXIT_GRACE_TICKS counts in.
+const EXIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// One gamescope instance of a `--per-monitor` session, fitted to a single monitor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PerMonitorInstance {
+    monitor: String,
+    gamescope_pid: u32,
+    rect: WindowRect,
+    game: String,
+    args: Vec<String>,
+    started_at: u64,
+}
+
+/// Desktop changes made once for the whole session, as a spanned session makes them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PerMonitorDesktop {
+    waybar_was_stopped: bool,
+    idle_inhibit_pid: Option<u32>,
+    exit_hotkey: Option<ExitHotkey>,
+}
+
+impl PerMonitorDesktop {
+    /// Hides Waybar, inhibits idle and binds the exit hotkey. What was done before an error is
+    /// kept, so `restore` still undoes it.
+    fn set_up(&mut self, launch: &LaunchSettings, verbose: bool) -> Result<(), Box<dyn Error>> {
+        if launch.hide_waybar {
+            self.waybar_was_stopped = maybe_stop_waybar(verbose)?;
+        }
+        if launch.idle_inhibit {
+            self.idle_inhibit_pid = maybe_start_idle_inhibit(verbose)?;
+        }
+        match register_exit_hotkey(
+            &launch.exit_hotkey,
+            launch.exit_hotkey_bind,
+            launch.exit_hotkey_confirm,
+            verbose,
+        ) {
+            Ok(hotkey) => self.exit_hotkey = hotkey,
+            Err(e) => error(&trf(Msg::HotkeyRegisterFailed, &[&tr(Msg::HotkeyExit), &e])),
+        }
+        Ok(())
+    }
+
+    fn restore(&self, verbose: bool) {
+        if let Some(pid) = self.idle_inhibit_pid {
+            maybe_stop_idle_inhibit(pid, verbose);
+        }
+        if self.waybar_was_stopped
+            && let Err(e) = maybe_start_waybar(verbose)
+        {
+            error(&trf(Msg::WaybarRestartFailed, &[&e]));
+        }
+        if let Some(hotkey) = self.exit_hotkey.as_ref() {
+            unregister_exit_hotkey(hotkey, verbose);
+        }
+    }
+}
+
+/// The instances still running; entries are dropped as their gamescope exits.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PerMonitorState {
+    instances: Vec<PerMonitorInstance>,
+    /// The launcher undoes `desktop` itself; a teardown does it only once the launcher is gone.
+    #[serde(default)]
+    launcher_pid: Option<u32>,
+    #[serde(default)]
+    desktop: PerMonitorDesktop,
+}
+
+/// A running instance and what the session loop tracks for it.
+struct RunningInstance {
+    child: Child,
+    instance: PerMonitorInstance,
+    /// Index into the session's assignments, to start the same instance again.
+    slot: usize,
+    crash_restarts: u32,
+    game_seen: bool,
+    game_gone_ticks: u32,
+}
+
+fn per_monitor_state_path() -> std::path::PathBuf {
+    std::env::temp_dir().join(PER_MONITOR_STATE_FILE_NAME)
+}
+
+fn load_per_monitor_state() -> Option<PerMonitorState> {
+    let json = std::fs::read_to_string(per_monitor_state_path()).ok()?;
+    match serde_json::from_str(&json) {
+        Ok(state) => Some(state),
+        Err(e) => {
+            debug_log_line(&format!("per-monitor state unreadable: {}", e));
+            Some(PerMonitorState::default())
+        }
+    }
+}
+
+/// Whether a per-monitor session has an instance running.
+pub(crate) fn per_monitor_session_running() -> bool {
+    load_per_monitor_state().is_some_and(|state| {
+        state
+            .instances
+            .iter()
+            .any(|instance| pid_alive(instance.gamescope_pid))
+    })
+}
+
+/// The launcher of the recorded per-monitor session, if there is one.
+pub(crate) fn per_monitor_launcher_pid() -> Option<u32> {
+    load_per_monitor_state()?.launcher_pid
+}
+
+fn save_per_monitor_state(state: &PerMonitorState) -> Result<(), Box<dyn Error>> {
+    let path = per_monitor_state_path();
+    if state.instances.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        return Ok(());
+    }
+    // Write to a sibling temp file and rename so readers never see a partial file.
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    std::fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+fn record_instance(instance: &PerMonitorInstance, exit: &str) {
+    let record = SessionRecord {
+        game: instance.game.clone(),
+        started_at: instance.started_at,
+        ended_at: unix_now().max(instance.started_at),
+        args: instance.args.clone(),
+        span: Some(instance.rect),
+        exit: Some(exit.to_string()),
+    };
+    if let Err(e) = record_session(&record) {
+        error(&trf(Msg::RecordPlaytimeFailed, &[&e]));
+    }
+}
+
+/// A monitor and the command its gamescope instance runs.
+type Assignment = (Monitor, Vec<String>);
+
+/// Pairs monitors with what they run: every monitor runs `command`, or `apps` assigns one
+/// command per monitor in order and monitors beyond the list stay free.
+fn assign_commands(
+    monitors: Vec<Monitor>,
+    command: Option<Vec<String>>,
+    apps: &[Vec<String>],
+) -> Result<Vec<Assignment>, Box<dyn Error>> {
+    if monitors.is_empty() {
+        return Err(MyError("No monitors detected.".to_string()).into());
+    }
+    match command {
+        Some(_) if !apps.is_empty() => Err(MyError(
+            "Pass either a game command after `--` or --app, not both.".to_string(),
+        )
+        .into()),
+        Some(command) => Ok(monitors
+            .into_iter()
+            .map(|monitor| (monitor, command.clone()))
+            .collect()),
+        None if apps.len() > monitors.len() => Err(MyError(format!(
+            "{} apps were given for {} monitors.",
+            apps.len(),
+            monitors.len()
+        ))
+        .into()),
+        None => Ok(monitors.into_iter().zip(apps.iter().cloned()).collect()),
+    }
+}
+
+fn launch_instance(
+    launch: &LaunchSettings,
+    all_monitors: &[Monitor],
+    gamescope_flags: &[String],
+    (monitor, name): (&Monitor, String),
+    command: Vec<String>,
+    verbose: bool,
+) -> Result<(Child, PerMonitorInstance), Box<dyn Error>> {
+    let mut settings = launch.clone();
+    settings.args = gamescope_flags
+        .iter()
+        .cloned()
+        .chain(["--".to_string()])
+        .chain(command)
+        .collect();
+    // Commands are picked once for the whole session; a size picker per monitor would be a chore.
+    settings.pick = false;
+    settings.pick_size = false;
+    settings.pre_command = None;
+    let plan = LaunchPlan {
+        settings,
+        all_monitors: all_monitors.to_vec(),
+        monitors: vec![monitor.clone()],
+        span: compute_monitor_span(std::slice::from_ref(monitor))?,
+        resolved: None,
+    };
+    let resolved = resolve_launch(&plan, verbose)?;
+    let rect = resolved.span;
+    info(&trf(
+        Msg::PerMonitorLaunching,
+        &[
+            &name,
+            &resolved.output.0,
+            &resolved.output.1,
+            &resolved.internal.0,
+            &resolved.internal.1,
+            &format!("{:?}", resolved.args),
+        ],
+    ));
+
+    let mut cmd = Command::new("gamescope");
+    cmd.args(&resolved.args);
+    cmd.envs(resolved.env.iter().cloned());
+    if !verbose {
+        cmd.stdout(Stdio::null());
+        cmd.stderr(Stdio::null());
+    }
+    let mut child = gamescope_launcher().spawn(&mut cmd)?;
+    let gamescope_pid = child.id();
+    let started_at = unix_now();
+    let placed = (|| -> Result<(), Box<dyn Error>> {
+        let probe = WindowProbe {
+            class: resolved.window.class.clone(),
+            title: resolved.window.title.clone(),
+            ..launch.probe.clone()
+        };
+        let matcher = WindowMatcher::new(gamescope_pid, &probe)?;
+        let client = wait_for_client(
+            &matcher,
+            launch.timeout,
+            launch.probe.poll_ms,
+            verbose,
+            || matches!(child.try_wait(), Ok(Some(_))),
+        )?;
+        let window = compositor().window_selector(client.pid as u32, Some(&client));
+        fit_window_to_span(
+            &matcher,
+            &window,
+            rect.0,
+            rect.1,
+            rect.2,
+            rect.3,
+            resolved.window.pin,
+            &launch.fit,
+            verbose,
+        )?;
+        if launch.borderless
+            && let Err(e) = compositor().strip_decorations(&window, verbose)
+        {
+            warn(&trf(Msg::RemoveDecorationsFailed, &[&e]));
+        }
+        Ok(())
+    })();
+    if let Err(e) = placed {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(MyError(format!("{}: {}", name, e)).into());
+    }
+    Ok((
+        child,
+        PerMonitorInstance {
+            monitor: name,
+            gamescope_pid,
+            rect,
+            game: game_command_label(&resolved.args),
+            args: resolved.args,
+            started_at,
+        },
+    ))
+}
+
+/// Drops a finished instance from the state file, putting `replacement` in its place, and
+/// records its playtime. Returns `false` when a teardown already did both.
+fn finish_instance(
+    instance: &PerMonitorInstance,
+    exit: &str,
+    replacement: Option<&PerMonitorInstance>,
+) -> bool {
+    let Some(mut state) = load_per_monitor_state() else {
+        return false;
+    };
+    let before = state.instances.len();
+    state
+        .instances
+        .retain(|entry| entry.gamescope_pid != instance.gamescope_pid);
+    if state.instances.len() == before {
+        return false;
+    }
+    record_instance(instance, exit);
+    state.instances.extend(replacement.cloned());
+    if let Err(e) = save_per_monitor_state(&state) {
+        error(&trf(Msg::PerMonitorStateFailed, &[&e]));
+    }
+    true
+}
+
+/// Whether the game under an instance's gamescope has exited. Launchers often hand off to a new
+/// process, so it counts only after a short gap.
+fn game_exited(entry: &mut RunningInstance) -> bool {
+    if !game_processes(entry.instance.gamescope_pid).is_empty() {
+        entry.game_seen = true;
+        entry.game_gone_ticks = 0;
+        return false;
+    }
+    if entry.game_seen {
+        entry.game_gone_ticks += 1;
+    }
+    entry.game_gone_ticks >= GAME_EXIT_GRACE_TICKS
+}
+
+/// Runs one gamescope per selected monitor, each fitted to its own output, until they have all
+/// exited. `apps` assigns a command per monitor; without it every monitor runs the game command.
+pub(crate) fn run_per_monitor_session(
+    launch: &LaunchSettings,
+    apps: &[Vec<String>],
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    if launch.embedded {
+        return Err(MyError(
+            "--per-monitor runs nested gamescope windows and cannot be combined with --embedded."
+                .to_string(),
+        )
+        .into());
+    }
+    if !compositor().window_control() || !compositor().chooses_output() {
+        return Err(MyError(
+            "--per-monitor places one window per monitor, which needs Hyprland, Sway, niri or X11."
+                .to_string(),
+        )
+        .into());
+    }
+    if per_monitor_session_running() {
+        return Err(MyError(
+            "A per-monitor session is already running; stop it with `hyprfinity gamescope-down`."
+                .to_string(),
+        )
+        .into());
+    }
+
+    let all_monitors = get_monitors(verbose)?;
+    let monitors = select_span_monitors(&all_monitors, launch.span_target)
+        .into_iter()
+        .filter(|monitor| monitor.contributes_to_span())
+        .collect::<Vec<_>>();
+    let split = launch.args.iter().position(|a| a == "--");
+    let gamescope_flags = launch.args[..split.unwrap_or(launch.args.len())].to_vec();
+    let has_command = split.is_some_and(|idx| idx + 1 < launch.args.len());
+    let command = if apps.is_empty() || has_command {
+        let args = ensure_game_command(
+            launch.args.clone(),
+            launch.pick,
+            launch.picker_fallback,
+            launch.picker_command.as_deref(),
+            launch.default_command.clone(),
+        )?;
+        let idx = args.iter().position(|a| a == "--").unwrap_or(args.len());
+        Some(args[(idx + 1).min(args.len())..].to_vec())
+    } else {
+        None
+    };
+    if launch.pre_command.is_some() {
+        warn(tr(Msg::PerMonitorNoPreCommand));
+    }
+    let assignments = assign_commands(monitors, command, apps)?;
+
+    let mut session = PerMonitorState {
+        launcher_pid: Some(std::process::id()),
+        ..PerMonitorState::default()
+    };
+    let result = (|| -> Result<(), Box<dyn Error>> {
+        session.desktop.set_up(launch, verbose)?;
+        let desktop = session.desktop.clone();
+        ctrlc::set_handler(move || {
+            info(tr(Msg::PerMonitorCtrlC));
+            if let Err(e) = stop_per_monitor_session() {
+                error(&trf(Msg::PerMonitorStopFailed, &[&e]));
+            }
+            desktop.restore(false);
+            finish_trace();
+            std::process::exit(130);
+        })?;
+        run_instances(
+            launch,
+            &all_monitors,
+            &gamescope_flags,
+            &assignments,
+            &session,
+            verbose,
+        )
+    })();
+    session.desktop.restore(verbose);
+    result
+}
+
+/// Starts an instance per assignment and watches them until all have exited, restarting crashed
+/// ones and ending them with their game or the gamepad chord as the settings ask.
+fn run_instances(
+    launch: &LaunchSettings,
+    all_monitors: &[Monitor],
+    gamescope_flags: &[String],
+    assignments: &[Assignment],
+    session: &PerMonitorState,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    let start = |slot: usize, crash_restarts: u32| -> Result<RunningInstance, Box<dyn Error>> {
+        let (monitor, command) = &assignments[slot];
+        let (child, instance) = launch_instance(
+            launch,
+            all_monitors,
+            gamescope_flags,
+            (monitor, monitor.display_name(slot)),
+            command.clone(),
+            verbose,
+        )?;
+        Ok(RunningInstance {
+            child,
+            instance,
+            slot,
+            crash_restarts,
+            game_seen: false,
+            game_gone_ticks: 0,
+        })
+    };
+    // Starts an exited instance again in its place; `false` when it is gone for good.
+    let restart = |entry: &mut RunningInstance, exit: &str, crash_restarts: u32| -> bool {
+        let mut next = match start(entry.slot, crash_restarts) {
+            Ok(next) => next,
+            Err(e) => {
+                error(&trf(
+                    Msg::PerMonitorRestartFailed,
+                    &[&entry.instance.monitor, &e],
+                ));
+                finish_instance(&entry.instance, exit, None);
+                return false;
+            }
+        };
+        if !finish_instance(&entry.instance, exit, Some(&next.instance)) {
+            // The session was torn down while the instance was starting again.
+            let _ = next.child.kill();
+            let _ = next.child.wait();
+            return false;
+        }
+        count_gamescope_restart();
+        *entry = next;
+        true
+    };
+
+    let mut state = session.clone();
+    let mut running = Vec::new();
+    for slot in 0..assignments.len() {
+        match start(slot, 0) {
+            Ok(entry) => {
+                state.instances.push(entry.instance.clone());
+                save_per_monitor_state(&state)?;
+                running.push(entry);
+            }
+            Err(e) => {
+                // Started together, torn down together.
+                let _ = stop_per_monitor_session();
+                return Err(e);
+            }
+        }
+    }
+    spawn_watchdog(verbose);
+    info(&trf(Msg::PerMonitorRunning, &[&running.len()]));
+
+    let mut gamepad_watcher = start_gamepad_watcher(&launch.gamepad, verbose);
+    while !running.is_empty() {
+        thread::sleep(EXIT_POLL_INTERVAL);
+        if let Some(watcher) = gamepad_watcher.as_mut()
+            && watcher.triggered()
+        {
+            debug_log_line(&format!(
+                "gamepad chord fired: {}",
+                launch.gamepad.action.as_str()
+            ));
+            for entry in &mut running {
+                let _ = entry.child.kill();
+                let _ = entry.child.wait();
+            }
+            match launch.gamepad.action {
+                GamepadAction::Exit => {
+                    info(tr(Msg::ChordEnding));
+                    for entry in running.drain(..) {
+                        finish_instance(&entry.instance, "exited", None);
+                    }
+                }
+                GamepadAction::Restart => {
+                    info(tr(Msg::ChordRestarting));
+                    running.retain_mut(|entry| {
+                        let crash_restarts = entry.crash_restarts;
+                        restart(entry, "restarted", crash_restarts)
+                    });
+                }
+            }
+            continue;
+        }
+        running.retain_mut(|entry| {
+            if launch.exit_with_game && game_exited(entry) {
+                info(&trf(Msg::PerMonitorGameExited, &[&entry.instance.monitor]));
+                let _ = entry.child.kill();
+                let _ = entry.child.wait();
+                finish_instance(&entry.instance, "exited", None);
+                return false;
+            }
+            let status = match entry.child.try_wait() {
+                Ok(None) => return true,
+                Ok(Some(status)) => status,
+                Err(e) => {
+                    error(&trf(
+                        Msg::PerMonitorWaitFailed,
+                        &[&entry.instance.monitor, &e],
+                    ));
+                    finish_instance(&entry.instance, "unknown", None);
+                    return false;
+                }
+            };
+            info(&trf(
+                Msg::PerMonitorExited,
+                &[&entry.instance.monitor, &status],
+            ));
+            let exit = if status.success() {
+                "exited".to_string()
+            } else {
+                status.to_string()
+            };
+            let policy = launch.restart;
+            if !policy.enabled || !gamescope_crashed(status) {
+                finish_instance(&entry.instance, &exit, None);
+                return false;
+            }
+            if entry.crash_restarts >= policy.max_retries {
+                error(&trf(
+                    Msg::PerMonitorCrashGivingUp,
+                    &[&entry.instance.monitor, &policy.max_retries],
+                ));
+                finish_instance(&entry.instance, &exit, None);
+                return false;
+            }
+            let attempt = entry.crash_restarts + 1;
+            let delay = retry_delay(policy.backoff_ms, attempt);
+            error(&trf(
+                Msg::PerMonitorCrashRestarting,
+                &[
+                    &entry.instance.monitor,
+                    &delay.as_millis(),
+                    &attempt,
+                    &policy.max_retries,
+                ],
+            ));
+            thread::sleep(delay);
+            restart(entry, &exit, attempt)
+        });
+    }
+    Ok(())
+}
+
+/// Stops every instance of a per-monitor session; `false` when none was running.
+pub(crate) fn stop_per_monitor_session() -> Result<bool, Box<dyn Error>> {
+    let Some(state) = load_per_monitor_state() else {
+        return Ok(false);
+    };
+    for instance in &state.instances {
+        if !pid_alive(instance.gamescope_pid) {
+            continue;
+        }
+        info(&trf(
+            Msg::PerMonitorStopping,
+            &[&instance.gamescope_pid, &instance.monitor],
+        ));
+        match Command::new("kill")
+            .arg(instance.gamescope_pid.to_string())
+            .status()
+        {
+            Ok(status) if status.success() => record_instance(instance, "stopped"),
+            Ok(status) => error(&trf(Msg::KillFailedStatus, &[&status])),
+            Err(e) => error(&trf(Msg::KillError, &[&e])),
+        }
+    }
+    // A running launcher undoes the desktop changes itself once its instances exit.
+    if !state.launcher_pid.is_some_and(pid_alive) {
+        state.desktop.restore(false);
+    }
+    save_per_monitor_state(&PerMonitorState::default())?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32) -> Monitor {
+        Monitor {
+            name: Some(name.to_string()),
+            width: 1920,
+            height: 1080,
+            x,
+            ..Monitor::default()
+        }
+    }
+
+    #[test]
+    fn assign_commands_shares_one_command_or_pairs_apps_in_order() {
+        let monitors = vec![monitor("DP-1", 0), monitor("DP-2", 1920)];
+        let game = vec!["retroarch".to_string()];
+        let shared = assign_commands(monitors.clone(), Some(game.clone()), &[]).unwrap();
+        assert_eq!(shared.len(), 2);
+        assert!(shared.iter().all(|(_, command)| *command == game));
+
+        let apps = vec![vec!["mame".to_string()]];
+        let paired = assign_commands(monitors.clone(), None, &apps).unwrap();
+        assert_eq!(paired.len(), 1);
+        assert_eq!(paired[0].0.name.as_deref(), Some("DP-1"));
+        assert_eq!(paired[0].1, apps[0]);
+
+        assert!(assign_commands(monitors.clone(), Some(game), &apps).is_err());
+        assert!(assign_commands(monitors, None, &vec![apps[0].clone(); 3]).is_err());
+        assert!(assign_commands(Vec::new(), None, &apps).is_err());
+    }
+
+    #[test]
+    fn state_files_without_launcher_or_desktop_still_load() {
+        let state: PerMonitorState = serde_json::from_str(r#"{"instances":[]}"#).unwrap();
+        assert_eq!(state.launcher_pid, None);
+        assert!(!state.desktop.waybar_was_stopped);
+        assert!(state.desktop.exit_hotkey.is_none());
+    }
+}
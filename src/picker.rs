@@ -1,15 +1,46 @@
 use crate::MyError;
+use crate::compat::umu_picker_entries;
+use crate::desktop_cache::{CachedDesktopFile, DesktopAppCache, mtime_ns};
+use crate::gamescope::{integer_scale_sizes, max_internal_size};
+use crate::i18n::{Msg, tr, trf};
+use crate::output::info;
 use crate::types::{DesktopApp, Monitor, SizePreset};
-use crate::util::{clamp_i32, even_floor, scaled_dimensions};
+use crate::util::{clamp_i32, even_floor, scaled_dimensions, scaled_dimensions_within};
 use skim::prelude::*;
 use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
 
-pub(crate) fn build_size_presets(span_width: i32, span_height: i32) -> Vec<SizePreset> {
+/// Integer-scale presets stop here; smaller sizes are left to explicit `virtual_height`.
+const MIN_INTEGER_PRESET_HEIGHT: i32 = 180;
+
+fn preset_pixels(preset: &SizePreset) -> i64 {
+    i64::from(preset.width) * i64::from(preset.height)
+}
+
+pub(crate) fn build_size_presets(
+    span_width: i32,
+    span_height: i32,
+    recommended_scale: Option<f32>,
+    allow_supersample: bool,
+    integer_scale: bool,
+) -> Vec<SizePreset> {
     let mut options: Vec<SizePreset> = Vec::new();
     let mut seen: BTreeSet<(i32, i32)> = BTreeSet::new();
+    let (max_width, max_height) = max_internal_size(span_width, span_height, allow_supersample);
+    let integer_sizes = if integer_scale {
+        integer_scale_sizes(span_width, span_height)
+    } else {
+        Vec::new()
+    };
 
     let mut add = |label: String, width: i32, height: i32| {
-        if width <= 0 || height <= 0 || width > span_width || height > span_height {
+        if width <= 0 || height <= 0 || width > max_width || height > max_height {
+            return;
+        }
+        if !integer_sizes.is_empty() && !integer_sizes.contains(&(width, height)) {
             return;
         }
         if seen.insert((width, height)) {
@@ -17,24 +48,73 @@ pub(crate) fn build_size_presets(span_width: i32, span_height: i32) -> Vec<SizeP
                 label,
                 width,
                 height,
+                recommended: false,
             });
         }
     };
 
+    if allow_supersample {
+        for scale in [2.0_f32, 1.5, 1.25] {
+            let (w, h) = scaled_dimensions_within(
+                span_width,
+                span_height,
+                (scale, scale),
+                (max_width, max_height),
+            );
+            add(
+                trf(
+                    Msg::SizeSupersampled,
+                    &[&w, &h, &((scale * 100.0).round() as i32)],
+                ),
+                w,
+                h,
+            );
+        }
+    }
     add(
-        format!("Native span: {}x{} (100%)", span_width, span_height),
+        trf(Msg::SizeNative, &[&span_width, &span_height]),
         span_width,
         span_height,
     );
+    for &(w, h) in integer_sizes
+        .iter()
+        .take_while(|(_, h)| *h >= MIN_INTEGER_PRESET_HEIGHT)
+    {
+        add(trf(Msg::SizeInteger, &[&w, &h, &(span_width / w)]), w, h);
+    }
     for scale in [0.9_f32, 0.85, 0.8, 0.75, 0.67, 0.6, 0.5] {
         let (w, h) = scaled_dimensions(span_width, span_height, scale);
         add(
-            format!("Scaled: {}x{} ({}%)", w, h, (scale * 100.0).round() as i32),
+            trf(
+                Msg::SizeScaled,
+                &[&w, &h, &((scale * 100.0).round() as i32)],
+            ),
             w,
             h,
         );
     }
 
+    // On ultrawide spans the stretched edges hide lost horizontal detail, so also offer narrower
+    // renders that keep the full height.
+    if span_width >= span_height * 2 {
+        for scale_x in [0.75_f32, 0.67, 0.5] {
+            let (w, h) = scaled_dimensions_within(
+                span_width,
+                span_height,
+                (scale_x, 1.0),
+                (span_width, span_height),
+            );
+            add(
+                trf(
+                    Msg::SizeHorizontal,
+                    &[&w, &h, &((scale_x * 100.0).round() as i32)],
+                ),
+                w,
+                h,
+            );
+        }
+    }
+
     for target_h in [1440_i32, 1200, 1080, 900, 720] {
         if target_h >= span_height {
             continue;
@@ -42,19 +122,127 @@ pub(crate) fn build_size_presets(span_width: i32, span_height: i32) -> Vec<SizeP
         let w = ((target_h as f32 * span_width as f32) / span_height as f32).round() as i32;
         let w = even_floor(clamp_i32(w, 2, span_width));
         add(
-            format!("Common height: {}x{} (~{}p tall)", w, target_h, target_h),
+            trf(Msg::SizeCommonHeight, &[&w, &target_h, &target_h]),
             w,
             target_h,
         );
     }
 
+    let native_pixels = i64::from(span_width) * i64::from(span_height);
+    let recommended_idx = recommended_scale.and_then(|scale| {
+        let (w, h) = scaled_dimensions(span_width, span_height, scale);
+        let target = i64::from(w) * i64::from(h);
+        options
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, o)| (preset_pixels(o) - target).abs())
+            .map(|(idx, _)| idx)
+    });
+    let recommended_pixels = recommended_idx.map(|idx| preset_pixels(&options[idx]));
+
+    for (idx, opt) in options.iter_mut().enumerate() {
+        let pixels = preset_pixels(opt);
+        let pct = if native_pixels > 0 {
+            (pixels as f64 * 100.0 / native_pixels as f64).round() as i64
+        } else {
+            0
+        };
+        let hint = match recommended_pixels {
+            Some(_) if Some(idx) == recommended_idx => tr(Msg::SizeRecommended),
+            Some(rec) if pixels > rec => tr(Msg::SizeHeavier),
+            Some(_) => tr(Msg::SizeLighter),
+            None => "",
+        };
+        opt.label = trf(Msg::SizePixels, &[&opt.label, &pct, &hint]);
+        opt.recommended = Some(idx) == recommended_idx;
+    }
+
+    // Put the recommended option first so it is the preselected row in any picker.
+    if let Some(idx) = recommended_idx {
+        let preset = options.remove(idx);
+        options.insert(0, preset);
+    }
+
     options
 }
 
+fn run_skim_picker(
+    prompt: &str,
+    choices: &[String],
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let options = SkimOptionsBuilder::default()
+        .height(Some("70%"))
+        .prompt(Some(prompt))
+        .reverse(true)
+        .multi(false)
+        .build()
+        .map_err(|e| MyError(format!("Failed to build skim options: {}", e)))?;
+
+    let reader = SkimItemReader::default();
+    let items = reader.of_bufread(std::io::Cursor::new(choices.join("\n")));
+    let selected = Skim::run_with(&options, Some(items))
+        .map(|out| out.selected_items)
+        .unwrap_or_default();
+
+    Ok(selected.first().map(|item| item.output().to_string()))
+}
+
+pub(crate) fn run_external_picker(
+    picker_command: &[String],
+    choices: &[String],
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let Some((program, args)) = picker_command.split_first() else {
+        return Err(MyError("picker_command is empty.".to_string()).into());
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| MyError(format!("Failed to start picker {}: {}", program, e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let mut input = choices.join("\n");
+        input.push('\n');
+        // A picker may exit before reading everything (e.g. immediate cancel); ignore EPIPE.
+        let _ = stdin.write_all(input.as_bytes());
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let selected = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    if selected.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(selected))
+}
+
+pub(crate) fn select_one(
+    prompt: &str,
+    choices: &[String],
+    picker_command: Option<&[String]>,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    match picker_command {
+        Some(cmd) => run_external_picker(cmd, choices),
+        None => run_skim_picker(prompt, choices),
+    }
+}
+
 pub(crate) fn pick_internal_size(
     monitors: &[Monitor],
     span_width: i32,
     span_height: i32,
+    recommended_scale: Option<f32>,
+    allow_supersample: bool,
+    integer_scale: bool,
+    picker_command: Option<&[String]>,
 ) -> Result<Option<(i32, i32)>, Box<dyn std::error::Error>> {
     let monitor_summary = monitors
         .iter()
@@ -73,37 +261,31 @@ pub(crate) fn pick_internal_size(
         })
         .collect::<Vec<_>>()
         .join(", ");
-    println!("Hyprfinity: Detected monitors: {}", monitor_summary);
+    info(&trf(Msg::DetectedMonitors, &[&monitor_summary]));
 
-    let options_data = build_size_presets(span_width, span_height);
+    let options_data = build_size_presets(
+        span_width,
+        span_height,
+        recommended_scale,
+        allow_supersample,
+        integer_scale,
+    );
     if options_data.is_empty() {
         return Ok(None);
     }
+    if let Some(rec) = options_data.iter().find(|o| o.recommended) {
+        info(&trf(Msg::RecommendedSize, &[&rec.width, &rec.height]));
+    }
 
-    let options = SkimOptionsBuilder::default()
-        .height(Some("70%"))
-        .prompt(Some("Select internal size> "))
-        .reverse(true)
-        .multi(false)
-        .build()
-        .map_err(|e| MyError(format!("Failed to build skim options: {}", e)))?;
-
-    let input = options_data
+    let labels = options_data
         .iter()
         .map(|opt| opt.label.clone())
-        .collect::<Vec<String>>()
-        .join("\n");
-    let reader = SkimItemReader::default();
-    let items = reader.of_bufread(std::io::Cursor::new(input));
-    let selected = Skim::run_with(&options, Some(items))
-        .map(|out| out.selected_items)
-        .unwrap_or_default();
-
-    if selected.is_empty() {
+        .collect::<Vec<String>>();
+    let Some(selected_label) = select_one(tr(Msg::SelectSizePrompt), &labels, picker_command)?
+    else {
         return Ok(None);
-    }
+    };
 
-    let selected_label = selected[0].output().to_string();
     let selected_opt = options_data
         .iter()
         .find(|o| o.label == selected_label)
@@ -111,75 +293,204 @@ pub(crate) fn pick_internal_size(
     Ok(Some((selected_opt.width, selected_opt.height)))
 }
 
-pub(crate) fn list_desktop_apps() -> Result<Vec<DesktopApp>, Box<dyn std::error::Error>> {
-    let mut apps: Vec<DesktopApp> = Vec::new();
+fn desktop_list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
 
-    let mut dirs: Vec<std::path::PathBuf> = vec![
-        std::path::PathBuf::from("/usr/share/applications"),
-        std::path::PathBuf::from("/usr/local/share/applications"),
-    ];
-    if let Ok(home) = std::env::var("HOME") {
-        dirs.push(std::path::PathBuf::from(home).join(".local/share/applications"));
-    }
+/// Parses the `[Desktop Entry]` group; `None` when the entry is deleted (`Hidden=true`) or lacks
+/// Name or Exec.
+fn parse_desktop_entry(content: &str) -> Option<DesktopApp> {
+    let mut in_desktop_entry = false;
+    let mut name: Option<String> = None;
+    let mut exec: Option<String> = None;
+    let mut app = DesktopApp::default();
+    let mut hidden = false;
 
-    for dir in dirs {
-        if !dir.exists() {
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') && line.ends_with(']') {
+            in_desktop_entry = line == "[Desktop Entry]";
             continue;
         }
-        for entry in std::fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) != Some("desktop") {
-                continue;
+        if !in_desktop_entry || line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("Name=") {
+            if !rest.is_empty() {
+                name = Some(rest.to_string());
             }
-            let content = match std::fs::read_to_string(&path) {
-                Ok(c) => c,
-                Err(_) => continue,
-            };
-            let mut in_desktop_entry = false;
-            let mut name: Option<String> = None;
-            let mut exec: Option<String> = None;
-            let mut hidden = false;
-
-            for line in content.lines() {
-                let line = line.trim();
-                if line.starts_with('[') && line.ends_with(']') {
-                    in_desktop_entry = line == "[Desktop Entry]";
-                    continue;
-                }
-                if !in_desktop_entry || line.is_empty() || line.starts_with('#') {
-                    continue;
-                }
-                if let Some(rest) = line.strip_prefix("Name=") {
-                    if !rest.is_empty() {
-                        name = Some(rest.to_string());
-                    }
-                } else if let Some(rest) = line.strip_prefix("Exec=") {
-                    if !rest.is_empty() {
-                        exec = Some(rest.to_string());
-                    }
-                } else if let Some(rest) = line.strip_prefix("NoDisplay=") {
-                    if rest.eq_ignore_ascii_case("true") {
-                        hidden = true;
-                    }
-                } else if let Some(rest) = line.strip_prefix("Hidden=")
-                    && rest.eq_ignore_ascii_case("true")
-                {
-                    hidden = true;
-                }
+        } else if let Some(rest) = line.strip_prefix("Exec=") {
+            if !rest.is_empty() {
+                exec = Some(rest.to_string());
             }
-
-            if hidden {
-                continue;
+        } else if let Some(rest) = line.strip_prefix("Icon=") {
+            if !rest.is_empty() {
+                app.icon = Some(rest.to_string());
             }
+        } else if let Some(rest) = line.strip_prefix("NoDisplay=") {
+            app.no_display = rest.eq_ignore_ascii_case("true");
+        } else if let Some(rest) = line.strip_prefix("Terminal=") {
+            app.terminal = rest.eq_ignore_ascii_case("true");
+        } else if let Some(rest) = line.strip_prefix("OnlyShowIn=") {
+            app.only_show_in = desktop_list(rest);
+        } else if let Some(rest) = line.strip_prefix("NotShowIn=") {
+            app.not_show_in = desktop_list(rest);
+        } else if let Some(rest) = line.strip_prefix("Hidden=")
+            && rest.eq_ignore_ascii_case("true")
+        {
+            hidden = true;
+        }
+    }
+
+    if hidden {
+        return None;
+    }
+    Some(DesktopApp {
+        name: name?,
+        exec: exec?,
+        ..app
+    })
+}
+
+/// One scanned .desktop file; files without an mtime are parsed but never cached.
+struct ScannedDesktopFile {
+    path: String,
+    mtime_ns: Option<u64>,
+    app: Option<DesktopApp>,
+}
+
+/// Stats one .desktop file and parses it unless the cache holds it at the same mtime.
+fn scan_desktop_file(path: &Path, cache: &DesktopAppCache) -> Option<ScannedDesktopFile> {
+    let path = path.to_string_lossy().to_string();
+    let mtime_ns = std::fs::metadata(&path)
+        .ok()
+        .and_then(|meta| mtime_ns(&meta));
+    if let Some(mtime) = mtime_ns
+        && let Some(cached) = cache.get(&path, mtime)
+    {
+        return Some(ScannedDesktopFile {
+            app: cached.app.clone(),
+            path,
+            mtime_ns,
+        });
+    }
+    let content = std::fs::read_to_string(&path).ok()?;
+    Some(ScannedDesktopFile {
+        app: parse_desktop_entry(&content),
+        path,
+        mtime_ns,
+    })
+}
 
-            if let (Some(name), Some(exec)) = (name, exec) {
-                apps.push(DesktopApp { name, exec });
+/// Scans the files on one thread per available core; a few hundred entries otherwise make the
+/// picker noticeably slow to open on a cold cache.
+fn scan_desktop_files(files: &[PathBuf], cache: &DesktopAppCache) -> Vec<ScannedDesktopFile> {
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = files.len().div_ceil(workers).max(1);
+    std::thread::scope(|scope| {
+        let handles = files
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .filter_map(|path| scan_desktop_file(path, cache))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    })
+}
+
+/// `applications` directories in XDG precedence order: `$XDG_DATA_HOME` (the user's own entries)
+/// first, then each of `$XDG_DATA_DIRS`, which also covers Flatpak and Nix exports.
+fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Ok(data) = std::env::var("XDG_DATA_HOME")
+        && !data.is_empty()
+    {
+        dirs.push(PathBuf::from(data).join("applications"));
+    } else if let Ok(home) = std::env::var("HOME") {
+        dirs.push(PathBuf::from(home).join(".local/share/applications"));
+    }
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    for dir in data_dirs.split(':').filter(|dir| !dir.is_empty()) {
+        let dir = PathBuf::from(dir).join("applications");
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Adds the .desktop files under `dir` with their desktop IDs: the path below the applications
+/// directory with `/` replaced by `-`, so `kde4/foo.desktop` is `kde4-foo.desktop`.
+fn collect_desktop_files(dir: &Path, id_prefix: &str, files: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            collect_desktop_files(&path, &format!("{}{}-", id_prefix, name), files);
+        } else if path.extension().and_then(|s| s.to_str()) == Some("desktop") {
+            files.push((format!("{}{}", id_prefix, name), path));
+        }
+    }
+}
+
+/// One file per desktop ID, taken from the first directory that has it. A user entry therefore
+/// replaces the system one of the same ID, and a user entry with `Hidden=true` removes it.
+fn desktop_files_by_id(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut seen: BTreeSet<String> = BTreeSet::new();
+    let mut files: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        let mut dir_files = Vec::new();
+        collect_desktop_files(dir, "", &mut dir_files);
+        for (id, path) in dir_files {
+            if seen.insert(id) {
+                files.push(path);
             }
         }
     }
+    files
+}
+
+pub(crate) fn list_desktop_apps() -> Result<Vec<DesktopApp>, Box<dyn std::error::Error>> {
+    let files = desktop_files_by_id(&application_dirs());
+    let mut cache = DesktopAppCache::load();
+    let scanned = scan_desktop_files(&files, &cache);
+    let mut apps: Vec<DesktopApp> = scanned.iter().filter_map(|file| file.app.clone()).collect();
+    cache.update(
+        scanned
+            .into_iter()
+            .filter_map(|file| {
+                let mtime_ns = file.mtime_ns?;
+                Some((
+                    file.path,
+                    CachedDesktopFile {
+                        mtime_ns,
+                        app: file.app,
+                    },
+                ))
+            })
+            .collect(),
+    );
 
-    apps.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    apps.extend(umu_picker_entries());
+    apps.sort_by_key(|a| a.name.to_lowercase());
     Ok(apps)
 }
 
@@ -194,48 +505,221 @@ pub(crate) fn sanitize_exec(exec: &str) -> String {
     cleaned.trim().to_string()
 }
 
-pub(crate) fn pick_desktop_app_command() -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let apps = list_desktop_apps()?;
-    if apps.is_empty() {
-        return Err(MyError("No desktop applications found.".to_string()).into());
-    }
+/// App picker settings: `--show-all` and the `picker_terminal` config key.
+#[derive(Debug, Default)]
+struct AppPickerOptions {
+    show_all: bool,
+    terminal: Option<Vec<String>>,
+}
 
-    let options = SkimOptionsBuilder::default()
-        .height(Some("70%"))
-        .prompt(Some("Select app> "))
-        .reverse(true)
-        .multi(false)
-        .build()
-        .map_err(|e| MyError(format!("Failed to build skim options: {}", e)))?;
+static APP_PICKER_OPTIONS: OnceLock<AppPickerOptions> = OnceLock::new();
 
-    let input = apps
-        .iter()
-        .map(|app| app.name.clone())
-        .collect::<Vec<String>>()
-        .join("\n");
+pub(crate) fn init_app_picker(show_all: bool, terminal: Option<Vec<String>>) {
+    let _ = APP_PICKER_OPTIONS.set(AppPickerOptions {
+        show_all,
+        terminal: terminal.filter(|cmd| !cmd.is_empty()),
+    });
+}
 
-    let reader = SkimItemReader::default();
-    let items = reader.of_bufread(std::io::Cursor::new(input));
+fn app_picker_options() -> &'static AppPickerOptions {
+    APP_PICKER_OPTIONS.get_or_init(AppPickerOptions::default)
+}
 
-    let selected = Skim::run_with(&options, Some(items))
-        .map(|out| out.selected_items)
-        .unwrap_or_default();
+/// Desktops named in `$XDG_CURRENT_DESKTOP`, matched against `OnlyShowIn`/`NotShowIn`.
+fn current_desktops() -> Vec<String> {
+    std::env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .split(':')
+        .filter(|desktop| !desktop.is_empty())
+        .map(str::to_string)
+        .collect()
+}
 
-    if selected.is_empty() {
-        return Err(MyError("User cancelled selection.".to_string()).into());
+/// Whether the app picker offers `app`. Menu-hidden entries, entries for other desktops and,
+/// without a `picker_terminal` to run them in, console programs are left out unless
+/// `--show-all` is given.
+fn app_is_listed(app: &DesktopApp, desktops: &[String], options: &AppPickerOptions) -> bool {
+    if options.show_all {
+        return true;
     }
+    let on_current = |list: &[String]| {
+        list.iter()
+            .any(|item| desktops.iter().any(|d| d.eq_ignore_ascii_case(item)))
+    };
+    !app.no_display
+        && (!app.terminal || options.terminal.is_some())
+        && (app.only_show_in.is_empty() || on_current(&app.only_show_in))
+        && !on_current(&app.not_show_in)
+}
 
-    let selected_name = selected[0].output().to_string();
-    let app = apps
-        .iter()
-        .find(|a| a.name == selected_name)
-        .ok_or_else(|| MyError("Selected app not found.".to_string()))?;
-
+/// The picked app's command line, run inside `picker_terminal` when it is a console program.
+fn app_command(
+    app: &DesktopApp,
+    options: &AppPickerOptions,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let exec = sanitize_exec(&app.exec);
     let args = shell_words::split(&exec)
         .map_err(|e| MyError(format!("Failed to parse Exec for {}: {}", app.name, e)))?;
     if args.is_empty() {
         return Err(MyError(format!("No executable found for {}.", app.name)).into());
     }
-    Ok(args)
+    match options.terminal.as_ref().filter(|_| app.terminal) {
+        Some(terminal) => Ok(terminal.iter().cloned().chain(args).collect()),
+        None => Ok(args),
+    }
+}
+
+pub(crate) fn pick_desktop_app_command(
+    picker_command: Option<&[String]>,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let options = app_picker_options();
+    let desktops = current_desktops();
+    let apps = list_desktop_apps()?
+        .into_iter()
+        .filter(|app| app_is_listed(app, &desktops, options))
+        .collect::<Vec<_>>();
+    if apps.is_empty() {
+        return Err(MyError("No desktop applications found.".to_string()).into());
+    }
+
+    let names = apps
+        .iter()
+        .map(|app| app.name.clone())
+        .collect::<Vec<String>>();
+    let Some(selected_name) = select_one(tr(Msg::SelectAppPrompt), &names, picker_command)? else {
+        return Err(MyError("User cancelled selection.".to_string()).into());
+    };
+    let app = apps
+        .iter()
+        .find(|a| a.name == selected_name)
+        .ok_or_else(|| MyError("Selected app not found.".to_string()))?;
+    app_command(app, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_size_presets_marks_recommended_first() {
+        let presets = build_size_presets(3840, 1080, Some(0.75), false, false);
+        assert!(presets[0].recommended);
+        assert_eq!((presets[0].width, presets[0].height), (2880, 810));
+        assert!(presets[0].label.contains("~56% pixels, recommended"));
+        assert_eq!(presets.iter().filter(|p| p.recommended).count(), 1);
+        assert!(
+            presets[1]
+                .label
+                .contains("~100% pixels, heavier than recommended")
+        );
+
+        let plain = build_size_presets(3840, 1080, None, false, false);
+        assert!(plain.iter().all(|p| !p.recommended));
+        assert!(plain[0].label.ends_with("~100% pixels"));
+        assert!(plain.iter().any(|p| {
+            p.label
+                .starts_with("Horizontal only: 2880x1080 (75% x 100%)")
+        }));
+        assert!(
+            build_size_presets(2560, 1440, None, false, false)
+                .iter()
+                .all(|p| !p.label.starts_with("Horizontal only"))
+        );
+
+        let integer = build_size_presets(3840, 1080, None, true, true);
+        assert_eq!(
+            integer
+                .iter()
+                .map(|p| (p.width, p.height))
+                .collect::<Vec<_>>(),
+            [
+                (3840, 1080),
+                (1920, 540),
+                (1280, 360),
+                (960, 270),
+                (768, 216),
+                (640, 180)
+            ]
+        );
+        assert_eq!(
+            integer[1].label,
+            "Integer scale: 1920x540 (1/2) - ~25% pixels"
+        );
+
+        let supersampled = build_size_presets(3840, 1080, None, true, false);
+        assert_eq!(
+            supersampled[0].label,
+            "Supersampled: 7680x2160 (200%) - ~400% pixels"
+        );
+        assert!(supersampled[3].label.starts_with("Native span"));
+    }
+
+    #[test]
+    fn run_external_picker_reads_selected_line() {
+        let cmd = vec!["sed".to_string(), "-n".to_string(), "2p".to_string()];
+        let choices = vec!["first".to_string(), "second".to_string()];
+        assert_eq!(
+            run_external_picker(&cmd, &choices).unwrap(),
+            Some("second".to_string())
+        );
+
+        let cancel = vec!["false".to_string()];
+        assert_eq!(run_external_picker(&cancel, &choices).unwrap(), None);
+    }
+
+    #[test]
+    fn desktop_files_by_id_prefers_earlier_dirs() {
+        let user = tempfile::tempdir().unwrap();
+        let system = tempfile::tempdir().unwrap();
+        std::fs::write(user.path().join("steam.desktop"), "").unwrap();
+        std::fs::write(system.path().join("steam.desktop"), "").unwrap();
+        std::fs::create_dir(system.path().join("kde4")).unwrap();
+        std::fs::write(system.path().join("kde4/steam.desktop"), "").unwrap();
+        std::fs::write(system.path().join("notes.txt"), "").unwrap();
+
+        let mut files =
+            desktop_files_by_id(&[user.path().to_path_buf(), system.path().to_path_buf()]);
+        files.sort();
+        let mut expected = vec![
+            user.path().join("steam.desktop"),
+            system.path().join("kde4/steam.desktop"),
+        ];
+        expected.sort();
+        assert_eq!(files, expected);
+    }
+
+    #[test]
+    fn app_picker_filters_by_desktop_and_wraps_terminal_apps() {
+        let htop = parse_desktop_entry(
+            "[Desktop Entry]\nName=htop\nExec=htop\nTerminal=true\nNotShowIn=Hyprland;\n",
+        )
+        .unwrap();
+        assert!(htop.terminal);
+        assert_eq!(htop.not_show_in, vec!["Hyprland".to_string()]);
+        let gnome_tool = parse_desktop_entry(
+            "[Desktop Entry]\nName=Tweaks\nExec=gnome-tweaks\nOnlyShowIn=GNOME;\n",
+        )
+        .unwrap();
+
+        let desktops = vec!["Hyprland".to_string()];
+        let terminal = AppPickerOptions {
+            show_all: false,
+            terminal: Some(vec!["foot".to_string(), "-e".to_string()]),
+        };
+        assert!(!app_is_listed(&gnome_tool, &desktops, &terminal));
+        assert!(!app_is_listed(&htop, &desktops, &terminal));
+        assert!(app_is_listed(&htop, &[], &terminal));
+        assert!(!app_is_listed(&htop, &[], &AppPickerOptions::default()));
+        let show_all = AppPickerOptions {
+            show_all: true,
+            terminal: None,
+        };
+        assert!(app_is_listed(&gnome_tool, &desktops, &show_all));
+
+        assert_eq!(
+            app_command(&htop, &terminal).unwrap(),
+            ["foot", "-e", "htop"]
+        );
+        assert_eq!(app_command(&htop, &show_all).unwrap(), ["htop"]);
+    }
 }
@@ -0,0 +1,121 @@
+use crate::compositor::{WindowRect, compositor};
+use crate::config::print_kv_table;
+use crate::hyprland::{execute_hyprctl, execute_hyprctl_output};
+use crate::output::info;
+use serde::Deserialize;
+use std::error::Error;
+
+/// Name the companion plugin registers with Hyprland.
+const PLUGIN_NAME: &str = "hyprfinity";
+/// Major version of the dispatcher protocol in docs/hyprland-plugin.md. The plugin reports it
+/// as the leading component of its version string.
+const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Deserialize)]
+struct LoadedPlugin {
+    name: String,
+    #[serde(default)]
+    version: String,
+}
+
+/// Version string of the loaded companion plugin, from `hyprctl plugin list -j`.
+fn parse_plugin_version(json: &str) -> Option<String> {
+    serde_json::from_str::<Vec<LoadedPlugin>>(json)
+        .ok()?
+        .into_iter()
+        .find(|p| p.name == PLUGIN_NAME)
+        .map(|p| p.version)
+}
+
+fn protocol_compatible(version: &str) -> bool {
+    version
+        .trim_start_matches('v')
+        .split('.')
+        .next()
+        .and_then(|major| major.parse::<u32>().ok())
+        == Some(PROTOCOL_VERSION)
+}
+
+fn loaded_plugin_version(verbose: bool) -> Option<String> {
+    if compositor().name() != "hyprland" {
+        return None;
+    }
+    execute_hyprctl_output(&["plugin", "list", "-j"], verbose)
+        .ok()
+        .as_deref()
+        .and_then(parse_plugin_version)
+}
+
+/// Whether a plugin speaking this build's protocol is loaded.
+pub(crate) fn plugin_available(verbose: bool) -> bool {
+    loaded_plugin_version(verbose).is_some_and(|v| protocol_compatible(&v))
+}
+
+/// Asks the plugin to hold every window of `pid` at `rect`, from the moment it maps until it
+/// closes. Replaces the startup fit race and the reflow loop.
+pub(crate) fn lock_window_geometry(
+    pid: u32,
+    (x, y, width, height): WindowRect,
+    pin: bool,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    let arg = format!(
+        "pid:{} {} {} {} {} {}",
+        pid,
+        x,
+        y,
+        width,
+        height,
+        if pin { "pin" } else { "nopin" }
+    );
+    execute_hyprctl(&["dispatch", "hyprfinity:lock", &arg], verbose)
+}
+
+pub(crate) fn show_plugin_status(verbose: bool) -> Result<(), Box<dyn Error>> {
+    let version = loaded_plugin_version(verbose);
+    let compatible = version.as_deref().is_some_and(protocol_compatible);
+    print_kv_table(
+        "Hyprland Plugin",
+        vec![
+            ("compositor", compositor().name().to_string()),
+            ("loaded", version.is_some().to_string()),
+            (
+                "version",
+                version.clone().unwrap_or_else(|| "-".to_string()),
+            ),
+            ("protocol", PROTOCOL_VERSION.to_string()),
+            ("in use", compatible.to_string()),
+        ],
+    );
+    match (version, compatible) {
+        (None, _) => info(
+            "Without the plugin, sessions keep the window in place from userspace. \
+             docs/hyprland-plugin.md describes the protocol a plugin build must implement.",
+        ),
+        (Some(version), false) => info(&format!(
+            "Plugin {} does not speak protocol {}; update it or hyprfinity.",
+            version, PROTOCOL_VERSION
+        )),
+        (Some(_), true) => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_version_is_read_and_checked_against_the_protocol() {
+        let json = r#"[
+            {"name": "hyprexpo", "author": "Vaxry", "handle": "0x1", "version": "0.1", "description": ""},
+            {"name": "hyprfinity", "author": "hyprfinity", "handle": "0x2", "version": "1.2.0", "description": ""}
+        ]"#;
+        assert_eq!(parse_plugin_version(json).as_deref(), Some("1.2.0"));
+        assert_eq!(parse_plugin_version("[]"), None);
+        assert_eq!(parse_plugin_version("ok"), None);
+        assert!(protocol_compatible("1.2.0"));
+        assert!(protocol_compatible("v1"));
+        assert!(!protocol_compatible("2.0"));
+    }
+}
@@ -0,0 +1,268 @@
+use crate::MyError;
+use crate::config::{Config, ProfileMatch, print_kv_table};
+use crate::debuglog::debug_log_line;
+use crate::fetch::read_source;
+use crate::i18n::{Msg, tr, trf};
+use crate::output::{info, warn};
+use crate::picker::select_one;
+use crate::profile_share::add_profiles;
+use crate::util::{stdio_is_interactive, xdg_cache_path};
+use serde::Deserialize;
+use std::error::Error;
+use std::path::Path;
+use std::time::Duration;
+
+pub(crate) const DEFAULT_PRESET_CATALOG_URL: &str =
+    "https://raw.githubusercontent.com/dustinleblanc/hyprfinity/main/presets/index.json";
+const CATALOG_CACHE_REL_PATH: &str = "hyprfinity/preset-catalog.json";
+/// A cached catalog younger than this is used without fetching.
+const CATALOG_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+/// Newest catalog format this build understands.
+const CATALOG_VERSION: u32 = 1;
+
+#[derive(Debug, Deserialize)]
+struct PresetCatalog {
+    version: u32,
+    presets: Vec<CatalogPreset>,
+}
+
+/// One game's recommended settings from the community catalog.
+#[derive(Debug, Deserialize)]
+struct CatalogPreset {
+    id: String,
+    name: String,
+    #[serde(default)]
+    steam_appid: Vec<u32>,
+    #[serde(default)]
+    exe: Vec<String>,
+    render_scale: Option<f32>,
+    #[serde(default)]
+    gamescope_args: Vec<String>,
+    notes: Option<String>,
+}
+
+impl CatalogPreset {
+    /// Only gamescope flags are taken from `gamescope_args`: a catalog never supplies the
+    /// command that gets run.
+    fn flags(&self) -> Vec<String> {
+        self.gamescope_args
+            .iter()
+            .take_while(|arg| *arg != "--")
+            .cloned()
+            .collect()
+    }
+
+    fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(scale) = self.render_scale {
+            parts.push(format!("render_scale {}", scale));
+        }
+        let flags = self.flags();
+        if !flags.is_empty() {
+            parts.push(flags.join(" "));
+        }
+        if parts.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} ({})", self.name, parts.join(", "))
+        }
+    }
+
+    /// The preset as a profile that also selects itself for the game.
+    fn to_profile(&self) -> Config {
+        let flags = self.flags();
+        let profile_match =
+            (!self.exe.is_empty() || !self.steam_appid.is_empty()).then(|| ProfileMatch {
+                exe: (!self.exe.is_empty()).then(|| self.exe.clone()),
+                steam_appid: (!self.steam_appid.is_empty()).then(|| self.steam_appid.clone()),
+            });
+        Config {
+            render_scale: self.render_scale,
+            gamescope_args: (!flags.is_empty()).then_some(flags),
+            profile_match,
+            ..Config::default()
+        }
+    }
+}
+
+fn parse_catalog(contents: &str) -> Result<Vec<CatalogPreset>, Box<dyn Error>> {
+    let catalog: PresetCatalog = serde_json::from_str(contents)
+        .map_err(|e| MyError(format!("Failed to parse the preset catalog: {}", e)))?;
+    if catalog.version > CATALOG_VERSION {
+        return Err(MyError(format!(
+            "The preset catalog is format {}, this hyprfinity reads up to {}; update hyprfinity.",
+            catalog.version, CATALOG_VERSION
+        ))
+        .into());
+    }
+    Ok(catalog.presets)
+}
+
+fn cache_is_fresh(cache: &Path) -> bool {
+    std::fs::metadata(cache)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < CATALOG_MAX_AGE)
+}
+
+/// Loads the catalog from `source`, using `cache` while it is fresh (unless `refresh`) and
+/// whenever `source` cannot be reached.
+fn load_catalog_from(
+    source: &str,
+    cache: Option<&Path>,
+    refresh: bool,
+) -> Result<Vec<CatalogPreset>, Box<dyn Error>> {
+    let cached = || cache.and_then(|path| std::fs::read_to_string(path).ok());
+    if !refresh
+        && cache.is_some_and(cache_is_fresh)
+        && let Some(presets) = cached().and_then(|contents| parse_catalog(&contents).ok())
+    {
+        return Ok(presets);
+    }
+    match read_source(source) {
+        Ok(contents) => {
+            let presets = parse_catalog(&contents)?;
+            if let Some(path) = cache {
+                let written = path
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| std::fs::write(path, &contents));
+                if let Err(e) = written {
+                    debug_log_line(&format!(
+                        "preset catalog: failed to cache {}: {}",
+                        path.display(),
+                        e
+                    ));
+                }
+            }
+            Ok(presets)
+        }
+        Err(e) => match cached().and_then(|contents| parse_catalog(&contents).ok()) {
+            Some(presets) => {
+                warn(&trf(Msg::UsingCachedCatalog, &[&e]));
+                Ok(presets)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn browse_presets(
+    config_path: &Option<String>,
+    catalog_url: Option<&str>,
+    picker_command: Option<&[String]>,
+    id: Option<&str>,
+    name: Option<&str>,
+    list: bool,
+    refresh: bool,
+    force: bool,
+) -> Result<(), Box<dyn Error>> {
+    let presets = load_catalog_from(
+        catalog_url.unwrap_or(DEFAULT_PRESET_CATALOG_URL),
+        xdg_cache_path(CATALOG_CACHE_REL_PATH).as_deref(),
+        refresh,
+    )?;
+    if list {
+        print_kv_table(
+            "Preset catalog",
+            presets
+                .iter()
+                .map(|preset| (preset.id.as_str(), preset.summary()))
+                .collect(),
+        );
+        return Ok(());
+    }
+    let preset = match id {
+        Some(id) => presets
+            .iter()
+            .find(|preset| preset.id == id)
+            .ok_or_else(|| MyError(format!("No preset `{}` in the catalog.", id)))?,
+        None => {
+            if picker_command.is_none() && !stdio_is_interactive() {
+                return Err(MyError(
+                    "No terminal for the preset picker; pass --id (see --list).".to_string(),
+                )
+                .into());
+            }
+            let labels = presets
+                .iter()
+                .map(|preset| format!("{}: {}", preset.id, preset.summary()))
+                .collect::<Vec<_>>();
+            let Some(selected) = select_one("Select preset> ", &labels, picker_command)? else {
+                info(tr(Msg::PresetPickerCancelled));
+                return Ok(());
+            };
+            let idx = labels
+                .iter()
+                .position(|label| *label == selected)
+                .ok_or_else(|| MyError("Selected preset not found.".to_string()))?;
+            &presets[idx]
+        }
+    };
+    if let Some(notes) = &preset.notes {
+        info(&format!("{}: {}", preset.name, notes));
+    }
+    let name = name.unwrap_or(preset.id.as_str());
+    add_profiles(
+        config_path,
+        vec![(name.to_string(), preset.to_profile())],
+        force,
+    )?;
+    let msg = if preset.exe.is_empty() && preset.steam_appid.is_empty() {
+        Msg::AddedProfile
+    } else {
+        Msg::AddedProfileAutoMatch
+    };
+    info(&trf(msg, &[&name, &name]));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG: &str = r#"{
+        "version": 1,
+        "presets": [{
+            "id": "witcher3",
+            "name": "The Witcher 3",
+            "steam_appid": [292030],
+            "exe": ["witcher3.exe"],
+            "render_scale": 0.75,
+            "gamescope_args": ["-F", "fsr", "--", "sh", "-c", "echo hi"]
+        }]
+    }"#;
+
+    #[test]
+    fn catalog_falls_back_to_cache_and_keeps_only_gamescope_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("index.json");
+        let cache = dir.path().join("cache/preset-catalog.json");
+        std::fs::write(&source, CATALOG).unwrap();
+        let presets = load_catalog_from(source.to_str().unwrap(), Some(&cache), true).unwrap();
+        assert_eq!(
+            presets[0].summary(),
+            "The Witcher 3 (render_scale 0.75, -F fsr)"
+        );
+        assert!(cache.exists());
+
+        let profile = presets[0].to_profile();
+        assert_eq!(profile.render_scale, Some(0.75));
+        assert_eq!(
+            profile.gamescope_args,
+            Some(vec!["-F".to_string(), "fsr".to_string()])
+        );
+        assert_eq!(
+            profile.profile_match.and_then(|m| m.steam_appid),
+            Some(vec![292030])
+        );
+
+        let missing = dir.path().join("offline.json");
+        let offline = load_catalog_from(missing.to_str().unwrap(), Some(&cache), true).unwrap();
+        assert_eq!(offline[0].id, "witcher3");
+        assert!(load_catalog_from(missing.to_str().unwrap(), None, true).is_err());
+        assert!(parse_catalog(r#"{"version": 2, "presets": []}"#).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use crate::gamescope::ensure_gamescope_flag;
+
+const STEAM_BIGPICTURE_COMMAND: &[&str] = &["steam", "-gamepadui"];
+
+pub(crate) fn steam_bigpicture_args(base: Vec<String>) -> Vec<String> {
+    let mut args = base;
+    if let Some(idx) = args.iter().position(|a| a == "--") {
+        args.truncate(idx);
+    }
+    // -e is the short form of --steam; either enables gamescope's Steam integration.
+    args = ensure_gamescope_flag(args, "-e");
+    args.push("--".to_string());
+    args.extend(STEAM_BIGPICTURE_COMMAND.iter().map(|s| s.to_string()));
+    args
+}
+
+/// Gamescope args for `wrap`: the configured args with Steam's `%command%` as the game command.
+/// Steam's overlay is preloaded into the game again instead of into gamescope.
+pub(crate) fn steam_wrap_args(
+    base: Vec<String>,
+    command: &[String],
+    ld_preload: Option<&str>,
+) -> Vec<String> {
+    let mut args = base;
+    if let Some(idx) = args.iter().position(|a| a == "--") {
+        args.truncate(idx);
+    }
+    args.push("--".to_string());
+    if let Some(preload) = ld_preload {
+        args.extend(["env".to_string(), format!("LD_PRELOAD={}", preload)]);
+    }
+    args.extend(command.iter().cloned());
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steam_bigpicture_args_adds_steam_flag_and_replaces_command() {
+        let args = steam_bigpicture_args(vec![
+            "-r".to_string(),
+            "144".to_string(),
+            "--".to_string(),
+            "vkcube".to_string(),
+        ]);
+        assert_eq!(args, vec!["-r", "144", "-e", "--", "steam", "-gamepadui"]);
+
+        let args = steam_bigpicture_args(vec!["--steam".to_string()]);
+        assert_eq!(args, vec!["--steam", "--", "steam", "-gamepadui"]);
+    }
+
+    #[test]
+    fn steam_wrap_args_moves_the_overlay_preload_into_the_game_command() {
+        let command = ["reaper", "AppId=620", "--", "portal2"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            steam_wrap_args(
+                vec![
+                    "-r".to_string(),
+                    "60".to_string(),
+                    "--".to_string(),
+                    "x".to_string()
+                ],
+                &command,
+                Some("overlay.so"),
+            ),
+            vec![
+                "-r",
+                "60",
+                "--",
+                "env",
+                "LD_PRELOAD=overlay.so",
+                "reaper",
+                "AppId=620",
+                "--",
+                "portal2"
+            ]
+        );
+        assert_eq!(
+            steam_wrap_args(Vec::new(), &command, None)[..2],
+            ["--", "reaper"]
+        );
+    }
+}
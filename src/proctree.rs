@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+// Processes gamescope spawns for itself; anything else under it belongs to the game.
+// Names are /proc/<pid>/comm values, which the kernel truncates to 15 bytes.
+const GAMESCOPE_HELPERS: &[&str] = &[
+    "gamescope",
+    "gamescopereaper",
+    "gamescope-wl",
+    "Xwayland",
+    "mangoapp",
+    "mangohud",
+];
+
+fn parse_stat(stat: &str) -> Option<(u32, u32, String)> {
+    // Format: `pid (comm) state ppid ...`; comm may itself contain spaces or parens.
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let pid = stat[..open].trim().parse().ok()?;
+    let comm = stat.get(open + 1..close)?.to_string();
+    let mut rest = stat.get(close + 1..)?.split_whitespace();
+    let _state = rest.next()?;
+    let ppid = rest.next()?.parse().ok()?;
+    Some((pid, ppid, comm))
+}
+
+fn read_process_table() -> Vec<(u32, u32, String)> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+        })
+        .filter_map(|e| std::fs::read_to_string(e.path().join("stat")).ok())
+        .filter_map(|stat| parse_stat(&stat))
+        .collect()
+}
+
+fn descendants_in(table: &[(u32, u32, String)], root: u32) -> Vec<(u32, String)> {
+    let mut children: BTreeMap<u32, Vec<(u32, &str)>> = BTreeMap::new();
+    for (pid, ppid, comm) in table {
+        children.entry(*ppid).or_default().push((*pid, comm));
+    }
+    let mut found = Vec::new();
+    let mut stack = vec![root];
+    while let Some(pid) = stack.pop() {
+        for (child, comm) in children.get(&pid).map(Vec::as_slice).unwrap_or_default() {
+            found.push((*child, comm.to_string()));
+            stack.push(*child);
+        }
+    }
+    found
+}
+
+fn game_processes_in(table: &[(u32, u32, String)], gamescope_pid: u32) -> Vec<(u32, String)> {
+    descendants_in(table, gamescope_pid)
+        .into_iter()
+        .filter(|(_, comm)| !GAMESCOPE_HELPERS.contains(&comm.as_str()))
+        .collect()
+}
+
+pub(crate) fn game_processes(gamescope_pid: u32) -> Vec<(u32, String)> {
+    game_processes_in(&read_process_table(), gamescope_pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_stat_handles_parens_in_comm() {
+        assert_eq!(
+            parse_stat("4242 (Game (x64)) S 4200 4242 4242 0 -1"),
+            Some((4242, 4200, "Game (x64)".to_string()))
+        );
+        assert_eq!(parse_stat("garbage"), None);
+    }
+
+    #[test]
+    fn game_processes_skip_gamescope_helpers() {
+        let table = vec![
+            (100, 1, "gamescope".to_string()),
+            (101, 100, "Xwayland".to_string()),
+            (102, 100, "gamescopereaper".to_string()),
+            (103, 102, "steam".to_string()),
+            (104, 103, "game.exe".to_string()),
+            (200, 1, "firefox".to_string()),
+        ];
+        let mut pids = game_processes_in(&table, 100)
+            .into_iter()
+            .map(|(pid, _)| pid)
+            .collect::<Vec<_>>();
+        pids.sort();
+        assert_eq!(pids, vec![103, 104]);
+    }
+}
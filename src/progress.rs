@@ -0,0 +1,220 @@
+use crate::debuglog::debug_log_line;
+use crate::i18n::{Msg, tr, trf};
+use crate::output::{OutputLevel, info, output_level, set_spinner_active};
+use crate::util::command_in_path;
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Set by `--detach` on the background copy, which reports through notifications instead.
+pub(crate) const DETACHED_ENV_VAR: &str = "HYPRFINITY_DETACHED";
+
+const SPINNER_FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProgressMode {
+    /// Animated line on a terminal; finished stages are printed with their times.
+    Spinner,
+    /// One message per stage, for logs and pipes.
+    Plain,
+    /// Desktop notifications via notify-send, for sessions started with `--detach`.
+    Notify,
+}
+
+fn progress_mode() -> ProgressMode {
+    if std::env::var_os(DETACHED_ENV_VAR).is_some() && command_in_path("notify-send") {
+        ProgressMode::Notify
+    } else if output_level() == OutputLevel::Normal && std::io::stderr().is_terminal() {
+        ProgressMode::Spinner
+    } else {
+        ProgressMode::Plain
+    }
+}
+
+fn spinner_line(frame: usize, label: &str, elapsed: Duration) -> String {
+    format!(
+        "{} {}... {:.1}s",
+        SPINNER_FRAMES[frame % SPINNER_FRAMES.len()],
+        label,
+        elapsed.as_secs_f32()
+    )
+}
+
+/// Replaces the previous hyprfinity notification instead of stacking a new one per stage.
+fn notify(summary: &str, body: &str) {
+    let _ = Command::new("notify-send")
+        .args([
+            "--app-name=hyprfinity",
+            "--hint=string:x-canonical-private-synchronous:hyprfinity",
+            summary,
+            body,
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+struct Spinner {
+    label: Arc<Mutex<(String, Instant)>>,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl Spinner {
+    fn start(label: &str) -> Self {
+        let label = Arc::new(Mutex::new((label.to_string(), Instant::now())));
+        let stop = Arc::new(AtomicBool::new(false));
+        set_spinner_active(true);
+        let handle = {
+            let label = Arc::clone(&label);
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                let mut frame = 0;
+                while !stop.load(Ordering::SeqCst) {
+                    if let Ok(current) = label.lock() {
+                        let mut stderr = std::io::stderr().lock();
+                        let _ = write!(
+                            stderr,
+                            "\r\x1b[2K{}",
+                            spinner_line(frame, &current.0, current.1.elapsed())
+                        );
+                        let _ = stderr.flush();
+                    }
+                    frame += 1;
+                    thread::sleep(SPINNER_INTERVAL);
+                }
+            })
+        };
+        Spinner {
+            label,
+            stop,
+            handle,
+        }
+    }
+
+    fn relabel(&self, label: &str) {
+        if let Ok(mut current) = self.label.lock() {
+            *current = (label.to_string(), Instant::now());
+        }
+    }
+
+    fn stop(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        let _ = self.handle.join();
+        set_spinner_active(false);
+        let _ = write!(std::io::stderr().lock(), "\r\x1b[2K");
+    }
+}
+
+/// Reports the startup stages (gamescope checks, launch, window wait, fit) with elapsed times, so
+/// a slow start does not look like a hang. Dropping it without `done` means startup failed.
+pub(crate) struct Progress {
+    mode: ProgressMode,
+    started: Instant,
+    stage: String,
+    stage_started: Instant,
+    spinner: Option<Spinner>,
+}
+
+impl Progress {
+    pub(crate) fn begin(label: &str) -> Self {
+        let mode = progress_mode();
+        let progress = Progress {
+            mode,
+            started: Instant::now(),
+            stage: label.to_string(),
+            stage_started: Instant::now(),
+            spinner: (mode == ProgressMode::Spinner).then(|| Spinner::start(label)),
+        };
+        progress.announce();
+        progress
+    }
+
+    fn announce(&self) {
+        debug_log_line(&format!("startup stage: {}", self.stage));
+        match self.mode {
+            ProgressMode::Spinner => {}
+            ProgressMode::Plain => info(&trf(Msg::StageInProgress, &[&self.stage])),
+            ProgressMode::Notify => notify(tr(Msg::NotifyStarting), &self.stage),
+        }
+    }
+
+    fn finish_stage(&self) {
+        let elapsed = self.stage_started.elapsed().as_secs_f32();
+        debug_log_line(&format!(
+            "startup stage done: {} ({:.1}s)",
+            self.stage, elapsed
+        ));
+        if self.mode == ProgressMode::Spinner {
+            info(&trf(
+                Msg::StageDone,
+                &[&self.stage, &format!("{:.1}", elapsed)],
+            ));
+        }
+    }
+
+    pub(crate) fn stage(&mut self, label: &str) {
+        self.finish_stage();
+        self.stage = label.to_string();
+        self.stage_started = Instant::now();
+        if let Some(spinner) = self.spinner.as_ref() {
+            spinner.relabel(label);
+        }
+        self.announce();
+    }
+
+    pub(crate) fn done(mut self) {
+        self.finish_stage();
+        if let Some(spinner) = self.spinner.take() {
+            spinner.stop();
+        }
+        let elapsed = format!("{:.1}", self.started.elapsed().as_secs_f32());
+        let message = trf(Msg::SessionReady, &[&elapsed]);
+        if self.mode == ProgressMode::Notify {
+            notify(tr(Msg::NotifyReady), &message);
+        }
+        info(&message);
+        // Nothing left for Drop to report.
+        self.mode = ProgressMode::Plain;
+        self.stage.clear();
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if let Some(spinner) = self.spinner.take() {
+            spinner.stop();
+        }
+        if self.mode == ProgressMode::Notify && !self.stage.is_empty() {
+            notify(
+                tr(Msg::NotifyFailed),
+                &trf(Msg::NotifyStageFailed, &[&self.stage]),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spinner_line_cycles_frames_and_shows_stage_time() {
+        assert_eq!(
+            spinner_line(
+                0,
+                "Waiting for the gamescope window",
+                Duration::from_millis(2340)
+            ),
+            "⠋ Waiting for the gamescope window... 2.3s"
+        );
+        assert_eq!(
+            spinner_line(SPINNER_FRAMES.len() + 1, "Fitting", Duration::ZERO),
+            "⠙ Fitting... 0.0s"
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::MyError;
+use crate::cli::ScreenshotRegion;
+use crate::gamescope::{
+    SessionStatus, gamescope_down, request_session_restart, request_session_scale,
+    save_session_replay, session_has_replay, session_status,
+};
+use crate::picker::run_external_picker;
+use crate::screenshot::take_screenshot;
+use crate::types::{SpanStrategy, SpanTarget};
+use crate::util::command_in_path;
+use std::error::Error;
+
+/// Menu shown when no picker_command is configured; wofi works fine with a gamepad-mapped keyboard.
+const DEFAULT_MENU_COMMAND: &[&str] = &["wofi", "--dmenu", "--prompt", "hyprfinity"];
+
+/// Offered by "Change scale"; any other value works through `hyprfinity set-scale`.
+const MENU_SCALES: &[f32] = &[0.5, 0.6, 0.67, 0.75, 0.85, 1.0];
+
+/// Quick menu entries. Each one does what the matching subcommand does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuAction {
+    Screenshot,
+    SaveReplay,
+    ChangeScale,
+    Restart,
+    Quit,
+}
+
+impl MenuAction {
+    fn label(self) -> &'static str {
+        match self {
+            MenuAction::Screenshot => "Screenshot",
+            MenuAction::SaveReplay => "Save replay",
+            MenuAction::ChangeScale => "Change scale",
+            MenuAction::Restart => "Restart gamescope",
+            MenuAction::Quit => "Quit session",
+        }
+    }
+}
+
+fn menu_actions(replay: bool) -> Vec<MenuAction> {
+    [
+        MenuAction::Screenshot,
+        MenuAction::SaveReplay,
+        MenuAction::ChangeScale,
+        MenuAction::Restart,
+        MenuAction::Quit,
+    ]
+    .into_iter()
+    .filter(|action| replay || *action != MenuAction::SaveReplay)
+    .collect()
+}
+
+fn action_for(actions: &[MenuAction], selected: &str) -> Option<MenuAction> {
+    actions.iter().copied().find(|a| a.label() == selected)
+}
+
+/// Shows the in-session menu (bound to quick_menu_hotkey) and runs the chosen action.
+pub(crate) fn quick_menu(
+    picker_command: Option<&[String]>,
+    span_target: SpanTarget,
+    span_strategy: SpanStrategy,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    if !matches!(session_status(), SessionStatus::Active(_)) {
+        return Err(MyError("No active Gamescope session.".to_string()).into());
+    }
+    let command = match picker_command {
+        Some(cmd) => cmd.to_vec(),
+        None if command_in_path(DEFAULT_MENU_COMMAND[0]) => {
+            DEFAULT_MENU_COMMAND.iter().map(|s| s.to_string()).collect()
+        }
+        None => {
+            return Err(MyError(
+                "The quick menu needs wofi in PATH, or picker_command set in config.".to_string(),
+            )
+            .into());
+        }
+    };
+    let actions = menu_actions(session_has_replay());
+    let labels = actions
+        .iter()
+        .map(|a| a.label().to_string())
+        .collect::<Vec<_>>();
+    let Some(selected) = run_external_picker(&command, &labels)? else {
+        return Ok(());
+    };
+    match action_for(&actions, &selected) {
+        Some(MenuAction::Screenshot) => take_screenshot(
+            ScreenshotRegion::Span,
+            None,
+            None,
+            span_target,
+            span_strategy,
+            verbose,
+        ),
+        Some(MenuAction::SaveReplay) => save_session_replay(verbose),
+        Some(MenuAction::ChangeScale) => {
+            let choices = MENU_SCALES
+                .iter()
+                .map(|scale| scale.to_string())
+                .collect::<Vec<_>>();
+            match run_external_picker(&command, &choices)? {
+                Some(choice) => request_session_scale(
+                    choice
+                        .parse()
+                        .map_err(|_| MyError(format!("Not a render scale: `{}`.", choice)))?,
+                ),
+                None => Ok(()),
+            }
+        }
+        Some(MenuAction::Restart) => request_session_restart(),
+        Some(MenuAction::Quit) => gamescope_down(),
+        None => Err(MyError(format!("Unknown quick menu entry `{}`.", selected)).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_entry_only_offered_with_a_replay_buffer() {
+        assert!(!menu_actions(false).contains(&MenuAction::SaveReplay));
+        let actions = menu_actions(true);
+        assert_eq!(actions.len(), 5);
+        assert_eq!(
+            action_for(&actions, "Restart gamescope"),
+            Some(MenuAction::Restart)
+        );
+        assert_eq!(action_for(&actions, "restart"), None);
+    }
+}
@@ -0,0 +1,294 @@
+use crate::MyError;
+use crate::debuglog::debug_log_line;
+use crate::i18n::{Msg, tr, trf};
+use crate::output::{debug, error, info};
+use crate::types::{Monitor, RecordingBackend, RecordingSettings};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct RecorderState {
+    pub(crate) backend: RecordingBackend,
+    #[serde(default)]
+    pub(crate) pid: Option<u32>,
+}
+
+fn gpu_screen_recorder_args(
+    settings: &RecordingSettings,
+    span: (i32, i32, i32, i32),
+) -> Vec<String> {
+    let (x, y, width, height) = span;
+    vec![
+        "-w".to_string(),
+        "region".to_string(),
+        "-region".to_string(),
+        format!("{}x{}+{}+{}", width, height, x, y),
+        "-f".to_string(),
+        settings.fps.to_string(),
+        "-r".to_string(),
+        settings.replay_secs.to_string(),
+        "-c".to_string(),
+        "mp4".to_string(),
+        "-o".to_string(),
+        settings.output_dir.clone(),
+    ]
+}
+
+/// Per-monitor crop that frames the span, for an OBS screen capture source per monitor.
+/// `x`/`y` place the cropped source on a canvas the size of the span.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct ObsRegionSource {
+    monitor: String,
+    x: i32,
+    y: i32,
+    crop_left: i32,
+    crop_top: i32,
+    crop_right: i32,
+    crop_bottom: i32,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct ObsRegion {
+    width: i32,
+    height: i32,
+    sources: Vec<ObsRegionSource>,
+}
+
+fn obs_region(monitors: &[Monitor], span: (i32, i32, i32, i32)) -> ObsRegion {
+    let (span_x, span_y, width, height) = span;
+    let sources = monitors
+        .iter()
+        .filter(|m| m.contributes_to_span())
+        .filter_map(|m| {
+            let left = m.x.max(span_x);
+            let top = m.y.max(span_y);
+            let right = (m.x + m.width).min(span_x + width);
+            let bottom = (m.y + m.height).min(span_y + height);
+            if right <= left || bottom <= top {
+                return None;
+            }
+            Some(ObsRegionSource {
+                monitor: m.name.clone()?,
+                x: left - span_x,
+                y: top - span_y,
+                crop_left: left - m.x,
+                crop_top: top - m.y,
+                crop_right: m.x + m.width - right,
+                crop_bottom: m.y + m.height - bottom,
+            })
+        })
+        .collect();
+    ObsRegion {
+        width,
+        height,
+        sources,
+    }
+}
+
+/// Writes the span's OBS crop file. The rename keeps the OBS script from reading a partial file.
+pub(crate) fn write_obs_region(
+    path: &str,
+    monitors: &[Monitor],
+    span: (i32, i32, i32, i32),
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    let path = std::path::Path::new(path);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(
+        &tmp,
+        serde_json::to_string_pretty(&obs_region(monitors, span))?,
+    )?;
+    std::fs::rename(&tmp, path)?;
+    if verbose {
+        debug(&format!("Wrote OBS capture region to {}.", path.display()));
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_hotkey(value: &str) -> Option<(String, String)> {
+    let (mods, key) = value.split_once(',')?;
+    let key = key.trim();
+    if key.is_empty() {
+        return None;
+    }
+    Some((mods.trim().to_string(), key.to_string()))
+}
+
+fn run_obs_cli(args: &[&str], verbose: bool) -> Result<(), Box<dyn Error>> {
+    let mut cmd = Command::new("obs-cli");
+    cmd.args(args);
+    if !verbose {
+        cmd.stdout(Stdio::null()).stderr(Stdio::null());
+    }
+    let status = cmd
+        .status()
+        .map_err(|e| MyError(format!("Failed to run obs-cli: {}", e)))?;
+    if !status.success() {
+        return Err(MyError(format!("obs-cli {:?} failed with status {}.", args, status)).into());
+    }
+    Ok(())
+}
+
+fn signal_pid(signal: &str, pid: u32) -> Result<(), Box<dyn Error>> {
+    let status = Command::new("kill")
+        .arg(signal)
+        .arg(pid.to_string())
+        .status()?;
+    if !status.success() {
+        return Err(MyError(format!(
+            "kill {} {} failed with status {}.",
+            signal, pid, status
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+pub(crate) fn start_recording(
+    settings: &RecordingSettings,
+    span: (i32, i32, i32, i32),
+    verbose: bool,
+) -> Result<Option<RecorderState>, Box<dyn Error>> {
+    if !settings.enabled {
+        return Ok(None);
+    }
+    match settings.backend {
+        RecordingBackend::GpuScreenRecorder => {
+            std::fs::create_dir_all(&settings.output_dir)?;
+            let args = gpu_screen_recorder_args(settings, span);
+            debug_log_line(&format!("gpu-screen-recorder args: {:?}", args));
+            let mut cmd = Command::new("gpu-screen-recorder");
+            cmd.args(&args).stdin(Stdio::null());
+            if !verbose {
+                cmd.stdout(Stdio::null()).stderr(Stdio::null());
+            }
+            match cmd.spawn() {
+                Ok(child) => {
+                    info(&trf(
+                        Msg::ReplayStarted,
+                        &[&settings.replay_secs, &child.id()],
+                    ));
+                    Ok(Some(RecorderState {
+                        backend: settings.backend,
+                        pid: Some(child.id()),
+                    }))
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    error(tr(Msg::NoGpuScreenRecorder));
+                    Ok(None)
+                }
+                Err(e) => {
+                    Err(MyError(format!("Failed to start gpu-screen-recorder: {}", e)).into())
+                }
+            }
+        }
+        RecordingBackend::Obs => {
+            // OBS records its own scene; point a screen capture source at the span for framing.
+            run_obs_cli(&["replaybuffer", "start"], verbose)?;
+            info(tr(Msg::ObsReplayStarted));
+            Ok(Some(RecorderState {
+                backend: settings.backend,
+                pid: None,
+            }))
+        }
+    }
+}
+
+pub(crate) fn save_replay(state: &RecorderState, verbose: bool) -> Result<(), Box<dyn Error>> {
+    match (state.backend, state.pid) {
+        // gpu-screen-recorder writes the replay buffer to disk on SIGUSR1.
+        (RecordingBackend::GpuScreenRecorder, Some(pid)) => signal_pid("-USR1", pid)?,
+        (RecordingBackend::GpuScreenRecorder, None) => {
+            return Err(MyError("No gpu-screen-recorder process recorded.".to_string()).into());
+        }
+        (RecordingBackend::Obs, _) => run_obs_cli(&["replaybuffer", "save"], verbose)?,
+    }
+    info(tr(Msg::ReplaySaved));
+    Ok(())
+}
+
+pub(crate) fn stop_recording(state: &RecorderState, verbose: bool) {
+    let result = match (state.backend, state.pid) {
+        (RecordingBackend::GpuScreenRecorder, Some(pid)) => signal_pid("-INT", pid),
+        (RecordingBackend::GpuScreenRecorder, None) => Ok(()),
+        (RecordingBackend::Obs, _) => run_obs_cli(&["replaybuffer", "stop"], verbose),
+    };
+    match result {
+        Ok(()) => {
+            if verbose {
+                debug("Stopped replay buffer.");
+            }
+        }
+        Err(e) => error(&trf(Msg::ReplayStopFailed, &[&e])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gpu_screen_recorder_args_capture_span_region() {
+        let settings = RecordingSettings {
+            enabled: true,
+            backend: RecordingBackend::GpuScreenRecorder,
+            replay_secs: 45,
+            fps: 60,
+            output_dir: "/tmp/clips".to_string(),
+            save_hotkey: "SUPER SHIFT, F10".to_string(),
+            obs_region_file: None,
+        };
+        let args = gpu_screen_recorder_args(&settings, (-1920, 0, 5760, 1080));
+        assert_eq!(args[3], "5760x1080+-1920+0");
+        assert!(args.windows(2).any(|w| w == ["-r", "45"]));
+        assert_eq!(args.last().map(String::as_str), Some("/tmp/clips"));
+    }
+
+    #[test]
+    fn obs_region_crops_each_monitor_to_the_span() {
+        let monitor = |name: &str, x: i32, width: i32, height: i32| Monitor {
+            name: Some(name.to_string()),
+            x,
+            width,
+            height,
+            ..Monitor::default()
+        };
+        let monitors = vec![
+            monitor("DP-1", 0, 1920, 1080),
+            monitor("DP-2", 1920, 2560, 1440),
+            monitor("HDMI-A-1", 4480, 1920, 1080),
+        ];
+        let region = obs_region(&monitors, (0, 0, 4480, 1080));
+        assert_eq!((region.width, region.height), (4480, 1080));
+        assert_eq!(region.sources.len(), 2);
+        assert_eq!(
+            region.sources[1],
+            ObsRegionSource {
+                monitor: "DP-2".to_string(),
+                x: 1920,
+                y: 0,
+                crop_left: 0,
+                crop_top: 0,
+                crop_right: 0,
+                crop_bottom: 360,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_hotkey_splits_mods_and_key() {
+        assert_eq!(
+            parse_hotkey("SUPER SHIFT, F10"),
+            Some(("SUPER SHIFT".to_string(), "F10".to_string()))
+        );
+        assert_eq!(
+            parse_hotkey(", F9"),
+            Some((String::new(), "F9".to_string()))
+        );
+        assert_eq!(parse_hotkey("F10"), None);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::MyError;
+use crate::cli::ScreenshotRegion;
+use crate::debuglog::debug_log_line;
+use crate::gamescope::active_session_span;
+use crate::hyprland::{get_monitors, select_span_monitors, span_rect};
+use crate::i18n::{Msg, trf};
+use crate::output::{debug, info};
+use crate::stats::unix_now;
+use crate::types::{Monitor, SpanStrategy, SpanTarget};
+use crate::util::command_in_path;
+use std::error::Error;
+use std::process::Command;
+
+fn grim_geometry(x: i32, y: i32, width: i32, height: i32) -> String {
+    format!("{},{} {}x{}", x, y, width, height)
+}
+
+fn find_monitor<'a>(monitors: &'a [Monitor], name: Option<&str>) -> Option<&'a Monitor> {
+    match name {
+        Some(name) => monitors.iter().find(|m| m.name.as_deref() == Some(name)),
+        None => monitors
+            .iter()
+            .find(|m| m.focused)
+            .or_else(|| monitors.first()),
+    }
+}
+
+fn default_screenshot_path() -> std::path::PathBuf {
+    let dir = std::env::var("XDG_PICTURES_DIR")
+        .map(std::path::PathBuf::from)
+        .or_else(|_| std::env::var("HOME").map(|h| std::path::PathBuf::from(h).join("Pictures")))
+        .unwrap_or_else(|_| std::env::temp_dir());
+    dir.join(format!("hyprfinity-{}.png", unix_now()))
+}
+
+pub(crate) fn take_screenshot(
+    region: ScreenshotRegion,
+    monitor: Option<&str>,
+    output: Option<String>,
+    span_target: SpanTarget,
+    span_strategy: SpanStrategy,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    if !command_in_path("grim") {
+        return Err(MyError(
+            "grim not found in PATH; install grim to take screenshots.".to_string(),
+        )
+        .into());
+    }
+
+    let mut args: Vec<String> = Vec::new();
+    match region {
+        ScreenshotRegion::Span => {
+            // Prefer the geometry the running session was fitted to.
+            let (x, y, w, h) = match active_session_span() {
+                Some(span) => span,
+                None => span_rect(
+                    &select_span_monitors(&get_monitors(verbose)?, span_target),
+                    span_strategy,
+                )?,
+            };
+            args.push("-g".to_string());
+            args.push(grim_geometry(x, y, w, h));
+        }
+        ScreenshotRegion::Monitor => {
+            let monitors = get_monitors(verbose)?;
+            let selected = find_monitor(&monitors, monitor).ok_or_else(|| {
+                MyError(format!(
+                    "Monitor {} not found.",
+                    monitor.unwrap_or("(focused)")
+                ))
+            })?;
+            let name = selected
+                .name
+                .clone()
+                .ok_or_else(|| MyError("Selected monitor has no name.".to_string()))?;
+            args.push("-o".to_string());
+            args.push(name);
+        }
+    }
+
+    let path = output
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(default_screenshot_path);
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    args.push(path.display().to_string());
+
+    debug_log_line(&format!("grim args: {:?}", args));
+    if verbose {
+        debug(&format!("Running grim {:?}", args));
+    }
+    let status = Command::new("grim").args(&args).status()?;
+    if !status.success() {
+        return Err(MyError(format!("grim failed with status {}.", status)).into());
+    }
+    info(&trf(Msg::SavedScreenshot, &[&path.display()]));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_monitor_prefers_name_then_focus() {
+        let monitors = vec![
+            Monitor {
+                name: Some("DP-1".to_string()),
+                ..Default::default()
+            },
+            Monitor {
+                name: Some("DP-2".to_string()),
+                focused: true,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            find_monitor(&monitors, Some("DP-1")).and_then(|m| m.name.as_deref()),
+            Some("DP-1")
+        );
+        assert_eq!(
+            find_monitor(&monitors, None).and_then(|m| m.name.as_deref()),
+            Some("DP-2")
+        );
+        assert!(find_monitor(&monitors, Some("HDMI-A-1")).is_none());
+        assert_eq!(grim_geometry(-1920, 0, 5760, 1080), "-1920,0 5760x1080");
+    }
+}
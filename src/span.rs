@@ -0,0 +1,315 @@
+use crate::config::{LaunchSettings, print_kv_table};
+use crate::gamescope::{derive_internal_size, derive_output_size, snap_to_integer_scale};
+use crate::hyprland::{get_monitors, select_span_monitors, span_layout_warnings, span_rect};
+use crate::output::warn;
+use crate::types::{Monitor, RenderScale, SpanStrategy, SpanTarget};
+use serde::Serialize;
+use std::error::Error;
+
+#[derive(Debug, Serialize)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+#[derive(Debug, Serialize)]
+struct MonitorContribution {
+    name: String,
+    rect: Rect,
+    offset_x: i32,
+    offset_y: i32,
+    width_share_pct: f32,
+    area_share_pct: f32,
+}
+
+#[derive(Debug, Serialize)]
+struct SpanReport {
+    monitors: Vec<MonitorContribution>,
+    span: Rect,
+    output_width: i32,
+    output_height: i32,
+    internal_width: i32,
+    internal_height: i32,
+    render_scale: RenderScale,
+    warnings: Vec<String>,
+}
+
+fn round_pct(value: f64) -> f32 {
+    ((value * 1000.0).round() / 10.0) as f32
+}
+
+fn build_span_report(
+    monitors: &[Monitor],
+    launch: &LaunchSettings,
+) -> Result<SpanReport, Box<dyn Error>> {
+    let (span_x, span_y, span_width, span_height) = span_rect(monitors, launch.span_strategy)?;
+    let span_area = i64::from(span_width) * i64::from(span_height);
+
+    let contributions = monitors
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.contributes_to_span())
+        .map(|(idx, m)| MonitorContribution {
+            name: m.display_name(idx),
+            rect: Rect {
+                x: m.x,
+                y: m.y,
+                width: m.width,
+                height: m.height,
+            },
+            offset_x: m.x - span_x,
+            offset_y: m.y - span_y,
+            width_share_pct: if span_width > 0 {
+                round_pct(f64::from(m.width) / f64::from(span_width))
+            } else {
+                0.0
+            },
+            area_share_pct: if span_area > 0 {
+                round_pct((i64::from(m.width) * i64::from(m.height)) as f64 / span_area as f64)
+            } else {
+                0.0
+            },
+        })
+        .collect::<Vec<_>>();
+
+    let output = derive_output_size(
+        span_width,
+        span_height,
+        launch.output_width,
+        launch.output_height,
+    );
+    let mut internal = derive_internal_size(
+        output.0,
+        output.1,
+        launch.render_scale,
+        launch.virtual_width,
+        launch.virtual_height,
+        launch.allow_supersample,
+    );
+    if launch.integer_scale {
+        internal = snap_to_integer_scale(output, internal);
+    }
+
+    Ok(SpanReport {
+        monitors: contributions,
+        span: Rect {
+            x: span_x,
+            y: span_y,
+            width: span_width,
+            height: span_height,
+        },
+        output_width: output.0,
+        output_height: output.1,
+        internal_width: internal.0,
+        internal_height: internal.1,
+        render_scale: launch.render_scale,
+        warnings: match launch.span_strategy {
+            SpanStrategy::BoundingBox => span_layout_warnings(monitors),
+            SpanStrategy::LargestRect => Vec::new(),
+        },
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct MonitorInfo {
+    name: String,
+    model: Option<String>,
+    width: i32,
+    height: i32,
+    x: i32,
+    y: i32,
+    scale: Option<f32>,
+    refresh_rate: Option<f32>,
+    vrr: Option<bool>,
+    ten_bit: bool,
+    transform: Option<i32>,
+    focused: bool,
+    in_span: bool,
+}
+
+fn build_monitor_infos(monitors: &[Monitor], span_target: SpanTarget) -> Vec<MonitorInfo> {
+    let span_members = select_span_monitors(monitors, span_target);
+    monitors
+        .iter()
+        .enumerate()
+        .map(|(idx, m)| MonitorInfo {
+            name: m.display_name(idx),
+            model: m.model.clone(),
+            width: m.width,
+            height: m.height,
+            x: m.x,
+            y: m.y,
+            scale: m.scale,
+            refresh_rate: m.refresh_rate,
+            vrr: m.vrr,
+            ten_bit: m.is_ten_bit(),
+            transform: m.transform,
+            focused: m.focused,
+            in_span: m.contributes_to_span() && span_members.iter().any(|s| s.name == m.name),
+        })
+        .collect()
+}
+
+fn format_optional<T: ToString>(value: Option<T>) -> String {
+    value
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+pub(crate) fn show_monitors(
+    span_target: SpanTarget,
+    json: bool,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    let monitors = get_monitors(verbose)?;
+    let infos = build_monitor_infos(&monitors, span_target);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&infos)?);
+        return Ok(());
+    }
+
+    for info in &infos {
+        print_kv_table(
+            &format!("Monitor {}", info.name),
+            vec![
+                ("model", format_optional(info.model.clone())),
+                ("resolution", format!("{}x{}", info.width, info.height)),
+                ("position", format!("{},{}", info.x, info.y)),
+                ("scale", format_optional(info.scale)),
+                (
+                    "refresh_rate",
+                    info.refresh_rate
+                        .map(|r| format!("{:.2} Hz", r))
+                        .unwrap_or_else(|| "unknown".to_string()),
+                ),
+                ("vrr", format_optional(info.vrr)),
+                // The current scanout format, not panel HDR capability.
+                ("ten_bit", info.ten_bit.to_string()),
+                ("transform", format_optional(info.transform)),
+                ("focused", info.focused.to_string()),
+                ("in_span", info.in_span.to_string()),
+            ],
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn show_span(
+    launch: &LaunchSettings,
+    json: bool,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    let monitors = select_span_monitors(&get_monitors(verbose)?, launch.span_target);
+    let report = build_span_report(&monitors, launch)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    for m in &report.monitors {
+        print_kv_table(
+            &format!("Monitor {}", m.name),
+            vec![
+                (
+                    "geometry",
+                    format!(
+                        "{}x{}@{},{}",
+                        m.rect.width, m.rect.height, m.rect.x, m.rect.y
+                    ),
+                ),
+                ("span_offset", format!("{},{}", m.offset_x, m.offset_y)),
+                ("width_share", format!("{:.1}%", m.width_share_pct)),
+                ("area_share", format!("{:.1}%", m.area_share_pct)),
+            ],
+        );
+    }
+    print_kv_table(
+        "Span",
+        vec![
+            ("origin", format!("({}, {})", report.span.x, report.span.y)),
+            (
+                "size",
+                format!("{}x{}", report.span.width, report.span.height),
+            ),
+            (
+                "output_size",
+                format!("{}x{}", report.output_width, report.output_height),
+            ),
+            (
+                "internal_size",
+                format!("{}x{}", report.internal_width, report.internal_height),
+            ),
+            ("render_scale", report.render_scale.to_string()),
+        ],
+    );
+    for warning in &report.warnings {
+        warn(warning);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn build_span_report_computes_contributions_and_sizes() {
+        let monitors = vec![
+            Monitor {
+                name: Some("left".to_string()),
+                width: 1920,
+                height: 1080,
+                x: 0,
+                y: 0,
+                ..Default::default()
+            },
+            Monitor {
+                name: None,
+                width: 1920,
+                height: 1080,
+                x: 1920,
+                y: 0,
+                ..Default::default()
+            },
+        ];
+        let config = Config {
+            render_scale: Some(0.5),
+            ..Config::default()
+        };
+        let launch = LaunchSettings::from_config(&config);
+        let report = build_span_report(&monitors, &launch).unwrap();
+        assert_eq!((report.span.width, report.span.height), (3840, 1080));
+        assert_eq!((report.output_width, report.output_height), (3840, 1080));
+        assert_eq!((report.internal_width, report.internal_height), (1920, 540));
+        assert_eq!(report.monitors[1].name, "monitor2");
+        assert_eq!(report.monitors[1].offset_x, 1920);
+        assert_eq!(report.monitors[1].width_share_pct, 50.0);
+        assert_eq!(report.monitors[0].area_share_pct, 50.0);
+    }
+
+    #[test]
+    fn build_monitor_infos_flags_mirrors_and_ten_bit_outputs() {
+        let monitors: Vec<Monitor> = serde_json::from_str(
+            r#"[
+                {"name": "DP-1", "model": "XG27AQ", "width": 2560, "height": 1440, "x": 0, "y": 0,
+                 "refreshRate": 143.99, "scale": 1.0, "vrr": true, "currentFormat": "XRGB2101010",
+                 "mirrorOf": "none"},
+                {"name": "HDMI-A-1", "width": 1920, "height": 1080, "x": 0, "y": 0,
+                 "mirrorOf": "DP-1", "currentFormat": "XRGB8888"}
+            ]"#,
+        )
+        .unwrap();
+        let infos = build_monitor_infos(&monitors, SpanTarget::All);
+        assert_eq!(infos[0].model.as_deref(), Some("XG27AQ"));
+        assert_eq!(infos[0].refresh_rate, Some(143.99));
+        assert!(infos[0].ten_bit);
+        assert!(infos[0].in_span);
+        assert!(!infos[1].ten_bit);
+        assert!(!infos[1].in_span);
+    }
+}
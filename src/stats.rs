@@ -0,0 +1,341 @@
+use crate::MyError;
+use crate::config::print_kv_table;
+use crate::i18n::{Msg, tr};
+use crate::output::info;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SESSIONS_REL_PATH: &str = "hyprfinity/sessions.jsonl";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) struct SessionRecord {
+    pub(crate) game: String,
+    pub(crate) started_at: u64,
+    pub(crate) ended_at: u64,
+    // Older history lines only carry playtime; the rest default when missing.
+    #[serde(default)]
+    pub(crate) args: Vec<String>,
+    #[serde(default)]
+    pub(crate) span: Option<(i32, i32, i32, i32)>,
+    #[serde(default)]
+    pub(crate) exit: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+struct HistoryEntry {
+    id: usize,
+    #[serde(flatten)]
+    record: SessionRecord,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+struct GameStats {
+    game: String,
+    sessions: u32,
+    total_secs: u64,
+    last_played: u64,
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn sessions_path() -> Result<std::path::PathBuf, Box<dyn Error>> {
+    if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
+        return Ok(std::path::PathBuf::from(xdg).join(SESSIONS_REL_PATH));
+    }
+    if let Ok(home) = std::env::var("HOME") {
+        return Ok(std::path::PathBuf::from(home)
+            .join(".local/share")
+            .join(SESSIONS_REL_PATH));
+    }
+    Err(
+        MyError("Unable to resolve data path (HOME and XDG_DATA_HOME are unset).".to_string())
+            .into(),
+    )
+}
+
+pub(crate) fn record_session(record: &SessionRecord) -> Result<(), Box<dyn Error>> {
+    let path = sessions_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+fn parse_history(contents: &str) -> Vec<HistoryEntry> {
+    // Ids are 1-based line numbers so they stay stable when a line is unreadable.
+    // Skip lines that fail to parse (e.g. a write cut short by a crash).
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            serde_json::from_str(line).ok().map(|record| HistoryEntry {
+                id: idx + 1,
+                record,
+            })
+        })
+        .collect()
+}
+
+fn load_history() -> Result<Vec<HistoryEntry>, Box<dyn Error>> {
+    let path = sessions_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(parse_history(&std::fs::read_to_string(&path)?))
+}
+
+fn load_sessions() -> Result<Vec<SessionRecord>, Box<dyn Error>> {
+    Ok(load_history()?.into_iter().map(|e| e.record).collect())
+}
+
+pub(crate) fn session_args(id: usize) -> Result<Vec<String>, Box<dyn Error>> {
+    let entry = load_history()?
+        .into_iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| MyError(format!("No session with id {} in history.", id)))?;
+    if entry.record.args.is_empty() {
+        return Err(MyError(format!(
+            "Session {} predates argument history and cannot be relaunched.",
+            id
+        ))
+        .into());
+    }
+    Ok(entry.record.args)
+}
+
+fn newest_game_command(history: &[HistoryEntry]) -> Option<Vec<String>> {
+    history.iter().rev().find_map(|entry| {
+        let args = &entry.record.args;
+        let idx = args.iter().position(|a| a == "--")?;
+        let command = &args[idx + 1..];
+        (!command.is_empty()).then(|| command.to_vec())
+    })
+}
+
+/// Game command (everything after `--`) of the newest session that recorded one.
+pub(crate) fn latest_game_command() -> Result<Option<Vec<String>>, Box<dyn Error>> {
+    Ok(newest_game_command(&load_history()?))
+}
+
+fn summarize(records: &[SessionRecord], since: Option<u64>) -> Vec<GameStats> {
+    let mut by_game: BTreeMap<&str, GameStats> = BTreeMap::new();
+    for record in records {
+        if since.is_some_and(|since| record.ended_at < since) {
+            continue;
+        }
+        let entry = by_game
+            .entry(record.game.as_str())
+            .or_insert_with(|| GameStats {
+                game: record.game.clone(),
+                sessions: 0,
+                total_secs: 0,
+                last_played: 0,
+            });
+        entry.sessions += 1;
+        entry.total_secs += record.ended_at.saturating_sub(record.started_at);
+        entry.last_played = entry.last_played.max(record.ended_at);
+    }
+    let mut stats = by_game.into_values().collect::<Vec<_>>();
+    stats.sort_by(|a, b| b.total_secs.cmp(&a.total_secs).then(a.game.cmp(&b.game)));
+    stats
+}
+
+fn format_duration(secs: u64) -> String {
+    let hours = secs / 3600;
+    let minutes = (secs % 3600) / 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+fn format_date(unix_secs: u64) -> String {
+    // Civil-from-days (Howard Hinnant); UTC is close enough for a "last played" column.
+    let days = (unix_secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn describe_session(record: &SessionRecord) -> String {
+    let span = record
+        .span
+        .map(|(x, y, w, h)| format!(" {}x{}@{},{}", w, h, x, y))
+        .unwrap_or_default();
+    format!(
+        "{} {} [{}]{} {}",
+        format_date(record.started_at),
+        format_duration(record.ended_at.saturating_sub(record.started_at)),
+        record.exit.as_deref().unwrap_or("unknown"),
+        span,
+        record.game
+    )
+}
+
+pub(crate) fn show_sessions(last: usize, json: bool) -> Result<(), Box<dyn Error>> {
+    let history = load_history()?;
+    let recent = &history[history.len().saturating_sub(last)..];
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(recent)?);
+        return Ok(());
+    }
+    if recent.is_empty() {
+        info(tr(Msg::NoSessionsYet));
+        return Ok(());
+    }
+
+    let ids = recent
+        .iter()
+        .map(|e| format!("#{}", e.id))
+        .collect::<Vec<_>>();
+    print_kv_table(
+        &format!("Sessions (last {})", recent.len()),
+        recent
+            .iter()
+            .zip(&ids)
+            .rev()
+            .map(|(e, id)| (id.as_str(), describe_session(&e.record)))
+            .collect(),
+    );
+    info(tr(Msg::RelaunchHint));
+    Ok(())
+}
+
+pub(crate) fn show_stats(days: Option<u64>, json: bool) -> Result<(), Box<dyn Error>> {
+    let since = days.map(|d| unix_now().saturating_sub(d * 86_400));
+    let stats = summarize(&load_sessions()?, since);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+    if stats.is_empty() {
+        info(tr(Msg::NoSessionsYet));
+        return Ok(());
+    }
+
+    let total = stats.iter().map(|s| s.total_secs).sum::<u64>();
+    let title = match days {
+        Some(d) => format!(
+            "Playtime (last {} days, total {})",
+            d,
+            format_duration(total)
+        ),
+        None => format!("Playtime (total {})", format_duration(total)),
+    };
+    print_kv_table(
+        &title,
+        stats
+            .iter()
+            .map(|s| {
+                (
+                    s.game.as_str(),
+                    format!(
+                        "{} over {} session{}, last {}",
+                        format_duration(s.total_secs),
+                        s.sessions,
+                        if s.sessions == 1 { "" } else { "s" },
+                        format_date(s.last_played)
+                    ),
+                )
+            })
+            .collect(),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(game: &str, started_at: u64, ended_at: u64) -> SessionRecord {
+        SessionRecord {
+            game: game.to_string(),
+            started_at,
+            ended_at,
+            args: Vec::new(),
+            span: None,
+            exit: None,
+        }
+    }
+
+    #[test]
+    fn summarize_totals_per_game_and_filters_by_window() {
+        let records = vec![
+            record("steam -applaunch 620", 1_000, 4_600),
+            record("retroarch", 5_000, 5_600),
+            record("steam -applaunch 620", 10_000, 10_600),
+        ];
+        let stats = summarize(&records, None);
+        assert_eq!(stats[0].game, "steam -applaunch 620");
+        assert_eq!((stats[0].sessions, stats[0].total_secs), (2, 4_200));
+        assert_eq!(stats[0].last_played, 10_600);
+        assert_eq!(stats[1].total_secs, 600);
+
+        let recent = summarize(&records, Some(6_000));
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].sessions, 1);
+    }
+
+    #[test]
+    fn parse_history_keeps_line_ids_and_reads_old_records() {
+        let contents = concat!(
+            r#"{"game":"retroarch","started_at":10,"ended_at":70}"#,
+            "\n",
+            r#"{"game":"steam","started_at":100"#,
+            "\n",
+            r#"{"game":"steam -applaunch 620","started_at":200,"ended_at":500,"#,
+            r#""args":["-f","--","steam","-applaunch","620"],"span":[-1920,0,5760,1080],"exit":"exited"}"#,
+            "\n",
+        );
+        let history = parse_history(contents);
+        assert_eq!(history.len(), 2);
+        assert_eq!((history[0].id, history[0].record.args.len()), (1, 0));
+        assert_eq!(history[1].id, 3);
+        assert_eq!(history[1].record.span, Some((-1920, 0, 5760, 1080)));
+        assert_eq!(
+            describe_session(&history[1].record),
+            "1970-01-01 5m [exited] 5760x1080@-1920,0 steam -applaunch 620"
+        );
+        assert_eq!(
+            newest_game_command(&history),
+            Some(vec![
+                "steam".to_string(),
+                "-applaunch".to_string(),
+                "620".to_string()
+            ])
+        );
+        assert_eq!(newest_game_command(&history[..1]), None);
+    }
+
+    #[test]
+    fn formats_durations_and_dates() {
+        assert_eq!(format_duration(45), "45s");
+        assert_eq!(format_duration(3_720), "1h 2m");
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(1_700_000_000), "2023-11-14");
+    }
+}
@@ -0,0 +1,68 @@
+/// Resolution and frame rate a Moonlight client asked for. Sunshine exports these to the app
+/// command and its prep commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ClientMode {
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+    pub(crate) fps: Option<u32>,
+}
+
+fn client_mode_from(var: impl Fn(&str) -> Option<String>) -> Option<ClientMode> {
+    let positive = |name: &str| {
+        var(name)
+            .and_then(|v| v.trim().parse::<i32>().ok())
+            .filter(|v| *v > 0)
+    };
+    Some(ClientMode {
+        width: positive("SUNSHINE_CLIENT_WIDTH")?,
+        height: positive("SUNSHINE_CLIENT_HEIGHT")?,
+        fps: positive("SUNSHINE_CLIENT_FPS").map(|fps| fps as u32),
+    })
+}
+
+/// The requesting client's mode when Sunshine launched us, `None` otherwise.
+pub(crate) fn sunshine_client_mode() -> Option<ClientMode> {
+    client_mode_from(|name| std::env::var(name).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_mode_needs_width_and_height() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert_eq!(
+            client_mode_from(env(&[
+                ("SUNSHINE_CLIENT_WIDTH", "2560"),
+                ("SUNSHINE_CLIENT_HEIGHT", "1440"),
+                ("SUNSHINE_CLIENT_FPS", "120"),
+            ])),
+            Some(ClientMode {
+                width: 2560,
+                height: 1440,
+                fps: Some(120)
+            })
+        );
+        assert_eq!(
+            client_mode_from(env(&[
+                ("SUNSHINE_CLIENT_WIDTH", "1920"),
+                ("SUNSHINE_CLIENT_HEIGHT", "1080"),
+                ("SUNSHINE_CLIENT_FPS", "0"),
+            ]))
+            .and_then(|m| m.fps),
+            None
+        );
+        assert_eq!(
+            client_mode_from(env(&[("SUNSHINE_CLIENT_WIDTH", "1920")])),
+            None
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use crate::debuglog::debug_log_line;
+use crate::output::debug;
+use crate::util::command_in_path;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+/// `true` going to sleep, `false` on resume, from a `gdbus monitor` line such as
+/// `/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)`.
+fn parse_prepare_for_sleep(line: &str) -> Option<bool> {
+    let (_, args) = line.split_once(".PrepareForSleep")?;
+    match args
+        .trim()
+        .trim_start_matches('(')
+        .split(',')
+        .next()?
+        .trim()
+    {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Follows logind's PrepareForSleep signal on the system bus for the length of a session.
+pub(crate) struct SleepWatcher {
+    monitor: Child,
+    sleeping: Arc<AtomicBool>,
+    resumed: Arc<AtomicBool>,
+}
+
+impl SleepWatcher {
+    pub(crate) fn sleeping(&self) -> bool {
+        self.sleeping.load(Ordering::SeqCst)
+    }
+
+    /// Whether the system woke up since the last call.
+    pub(crate) fn take_resumed(&self) -> bool {
+        self.resumed.swap(false, Ordering::SeqCst)
+    }
+}
+
+impl Drop for SleepWatcher {
+    fn drop(&mut self) {
+        let _ = self.monitor.kill();
+        let _ = self.monitor.wait();
+    }
+}
+
+/// Starts `gdbus monitor` on logind; `None` when gdbus is missing or the bus is unreachable, in
+/// which case the session simply does not react to suspend.
+pub(crate) fn start_sleep_watcher(verbose: bool) -> Option<SleepWatcher> {
+    if !command_in_path("gdbus") {
+        debug_log_line("gdbus not found; not watching for suspend/resume");
+        return None;
+    }
+    let mut monitor = match Command::new("gdbus")
+        .args([
+            "monitor",
+            "--system",
+            "--dest",
+            "org.freedesktop.login1",
+            "--object-path",
+            "/org/freedesktop/login1",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            if verbose {
+                debug(&format!("Cannot watch logind for suspend: {}", e));
+            }
+            return None;
+        }
+    };
+    let stdout = monitor.stdout.take()?;
+    let sleeping = Arc::new(AtomicBool::new(false));
+    let resumed = Arc::new(AtomicBool::new(false));
+    {
+        let sleeping = Arc::clone(&sleeping);
+        let resumed = Arc::clone(&resumed);
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                let Some(going_down) = parse_prepare_for_sleep(&line) else {
+                    continue;
+                };
+                debug_log_line(&format!("logind PrepareForSleep({})", going_down));
+                sleeping.store(going_down, Ordering::SeqCst);
+                if !going_down {
+                    resumed.store(true, Ordering::SeqCst);
+                }
+            }
+        });
+    }
+    debug_log_line("watching logind for suspend/resume");
+    Some(SleepWatcher {
+        monitor,
+        sleeping,
+        resumed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prepare_for_sleep_lines_are_parsed() {
+        assert_eq!(
+            parse_prepare_for_sleep(
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)"
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            parse_prepare_for_sleep(
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (false,)"
+            ),
+            Some(false)
+        );
+        assert_eq!(
+            parse_prepare_for_sleep(
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForShutdown (true,)"
+            ),
+            None
+        );
+        assert_eq!(
+            parse_prepare_for_sleep(
+                "/org/freedesktop/login1: org.freedesktop.DBus.Properties.PropertiesChanged ()"
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use crate::MyError;
+use crate::compositor::{Compositor, WindowRect, run_ipc, transform_index};
+use crate::i18n::{Msg, tr};
+use crate::output::warn;
+use crate::types::{Client, HotkeyBind, Monitor};
+use serde::Deserialize;
+use std::error::Error;
+
+/// Sway through `swaymsg`. Floating windows may straddle outputs, so a span works as on Hyprland.
+pub(crate) struct SwayCompositor;
+
+#[derive(Debug, Deserialize)]
+struct SwayRect {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct SwayMode {
+    width: i32,
+    height: i32,
+    /// mHz
+    #[serde(default)]
+    refresh: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SwayOutput {
+    name: String,
+    #[serde(default)]
+    make: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    active: bool,
+    rect: SwayRect,
+    #[serde(default)]
+    current_mode: Option<SwayMode>,
+    #[serde(default)]
+    scale: Option<f32>,
+    #[serde(default)]
+    transform: Option<String>,
+    #[serde(default)]
+    focused: bool,
+    #[serde(default)]
+    adaptive_sync_status: Option<String>,
+}
+
+/// X11 properties of XWayland windows.
+#[derive(Debug, Deserialize)]
+struct SwayWindowProperties {
+    #[serde(default)]
+    class: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SwayNode {
+    id: i64,
+    #[serde(default)]
+    pid: Option<i32>,
+    rect: SwayRect,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    app_id: Option<String>,
+    #[serde(default)]
+    window_properties: Option<SwayWindowProperties>,
+    #[serde(default)]
+    nodes: Vec<SwayNode>,
+    #[serde(default)]
+    floating_nodes: Vec<SwayNode>,
+}
+
+fn sway_outputs_to_monitors(outputs: Vec<SwayOutput>) -> Vec<Monitor> {
+    outputs
+        .into_iter()
+        .map(|output| {
+            let (width, height) = output
+                .current_mode
+                .as_ref()
+                .map_or((output.rect.width, output.rect.height), |m| {
+                    (m.width, m.height)
+                });
+            Monitor {
+                description: Some(
+                    [output.make.as_deref(), output.model.as_deref()]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                )
+                .filter(|d| !d.is_empty()),
+                name: Some(output.name),
+                width,
+                height,
+                x: output.rect.x,
+                y: output.rect.y,
+                model: output.model,
+                refresh_rate: output
+                    .current_mode
+                    .and_then(|m| m.refresh)
+                    .map(|mhz| mhz as f32 / 1000.0),
+                scale: output.scale,
+                vrr: output
+                    .adaptive_sync_status
+                    .map(|status| status == "enabled"),
+                disabled: !output.active,
+                transform: output.transform.as_deref().map(transform_index),
+                focused: output.focused,
+                ..Monitor::default()
+            }
+        })
+        .collect()
+}
+
+fn collect_clients(node: &SwayNode, clients: &mut Vec<Client>) {
+    if let Some(pid) = node.pid {
+        clients.push(Client {
+            pid,
+            address: Some(node.id.to_string()),
+            at: Some([node.rect.x, node.rect.y]),
+            size: Some([node.rect.width, node.rect.height]),
+            class: node.app_id.clone().or_else(|| {
+                node.window_properties
+                    .as_ref()
+                    .and_then(|p| p.class.clone())
+            }),
+            title: node.name.clone(),
+            ..Client::default()
+        });
+    }
+    for child in node.nodes.iter().chain(&node.floating_nodes) {
+        collect_clients(child, clients);
+    }
+}
+
+/// Hyprland-style "SUPER SHIFT" + "Escape" as a sway combo, e.g. `Mod4+Shift+Escape`.
+fn sway_combo(mods: &str, key: &str) -> String {
+    let mut parts = mods
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '+')
+        .filter(|m| !m.is_empty())
+        .map(|m| match m.to_ascii_uppercase().as_str() {
+            "SUPER" | "WIN" | "LOGO" | "MOD4" => "Mod4".to_string(),
+            "ALT" | "MOD1" => "Mod1".to_string(),
+            "CTRL" | "CONTROL" => "Ctrl".to_string(),
+            "SHIFT" => "Shift".to_string(),
+            _ => m.to_string(),
+        })
+        .collect::<Vec<_>>();
+    parts.push(key.to_string());
+    parts.join("+")
+}
+
+impl Compositor for SwayCompositor {
+    fn name(&self) -> &'static str {
+        "sway"
+    }
+
+    fn monitors(&self, verbose: bool) -> Result<Vec<Monitor>, Box<dyn Error>> {
+        let stdout = run_ipc("swaymsg", &["-r", "-t", "get_outputs"], verbose)?;
+        let outputs: Vec<SwayOutput> = serde_json::from_str(&stdout)
+            .map_err(|e| MyError(format!("Failed to parse swaymsg outputs: {}", e)))?;
+        Ok(sway_outputs_to_monitors(outputs))
+    }
+
+    fn clients(&self, verbose: bool) -> Result<Vec<Client>, Box<dyn Error>> {
+        let stdout = run_ipc("swaymsg", &["-r", "-t", "get_tree"], verbose)?;
+        let tree: SwayNode = serde_json::from_str(&stdout)
+            .map_err(|e| MyError(format!("Failed to parse swaymsg tree: {}", e)))?;
+        let mut clients = Vec::new();
+        collect_clients(&tree, &mut clients);
+        Ok(clients)
+    }
+
+    fn window_selector(&self, pid: u32, client: Option<&Client>) -> String {
+        match client.and_then(|c| c.address.as_ref()) {
+            Some(id) => format!("[con_id={}]", id),
+            None => format!("[pid={}]", pid),
+        }
+    }
+
+    fn place_window(
+        &self,
+        window: &str,
+        (x, y, width, height): WindowRect,
+        float: bool,
+        pin: bool,
+        verbose: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        // Commands separated by commas share the criteria in `window`.
+        let mut commands = Vec::new();
+        if float {
+            commands.push("floating enable".to_string());
+            commands.push("border none".to_string());
+            if pin {
+                commands.push("sticky enable".to_string());
+            }
+        }
+        commands.push(format!("move absolute position {} {}", x, y));
+        commands.push(format!(
+            "resize set width {} px height {} px",
+            width, height
+        ));
+        let command = format!("{} {}", window, commands.join(", "));
+        run_ipc("swaymsg", &[&command], verbose).map(|_| ())
+    }
+
+    fn bind_key(
+        &self,
+        mods: &str,
+        key: &str,
+        bind: HotkeyBind,
+        command: &str,
+        verbose: bool,
+    ) -> Result<bool, Box<dyn Error>> {
+        if bind == HotkeyBind::Hold {
+            warn(tr(Msg::SwayNoHoldBinds));
+        }
+        let combo = sway_combo(mods, key);
+        let config = run_ipc("swaymsg", &["-r", "-t", "get_config"], verbose)?;
+        let needle = format!("bindsym {}", combo).to_lowercase();
+        if config
+            .to_lowercase()
+            .lines()
+            .any(|line| line.trim_start().starts_with(&needle))
+        {
+            return Ok(false);
+        }
+        run_ipc(
+            "swaymsg",
+            &[&format!("bindsym {} exec {}", combo, command)],
+            verbose,
+        )?;
+        Ok(true)
+    }
+
+    fn unbind_key(&self, mods: &str, key: &str, verbose: bool) -> Result<(), Box<dyn Error>> {
+        run_ipc(
+            "swaymsg",
+            &[&format!("unbindsym {}", sway_combo(mods, key))],
+            verbose,
+        )
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sway_tree_and_outputs_map_to_clients_and_monitors() {
+        let tree: SwayNode = serde_json::from_str(
+            r#"{"id": 1, "rect": {"x": 0, "y": 0, "width": 3840, "height": 1080},
+                "nodes": [{"id": 4, "rect": {"x": 0, "y": 0, "width": 1920, "height": 1080},
+                    "nodes": [{"id": 7, "pid": 100, "rect": {"x": 0, "y": 0, "width": 960, "height": 1080}}],
+                    "floating_nodes": [{"id": 9, "pid": 200, "rect": {"x": 10, "y": 20, "width": 1280, "height": 800}}]}]}"#,
+        )
+        .unwrap();
+        let mut clients = Vec::new();
+        collect_clients(&tree, &mut clients);
+        assert_eq!(clients.len(), 2);
+        assert_eq!(clients[1].pid, 200);
+        assert_eq!(clients[1].address.as_deref(), Some("9"));
+        assert_eq!(clients[1].at, Some([10, 20]));
+
+        let outputs: Vec<SwayOutput> = serde_json::from_str(
+            r#"[{"name": "DP-2", "make": "Dell", "model": "U2720Q", "active": true,
+                 "rect": {"x": 1280, "y": 0, "width": 2560, "height": 1440},
+                 "current_mode": {"width": 3840, "height": 2160, "refresh": 59997},
+                 "scale": 1.5, "transform": "normal", "focused": true}]"#,
+        )
+        .unwrap();
+        let monitors = sway_outputs_to_monitors(outputs);
+        assert_eq!(monitors[0].width, 3840);
+        assert_eq!(monitors[0].x, 1280);
+        assert_eq!(monitors[0].refresh_rate, Some(59.997));
+        assert_eq!(monitors[0].description.as_deref(), Some("Dell U2720Q"));
+        assert!(monitors[0].focused);
+    }
+
+    #[test]
+    fn sway_combo_translates_hyprland_modifiers() {
+        assert_eq!(sway_combo("SUPER SHIFT", "Escape"), "Mod4+Shift+Escape");
+        assert_eq!(sway_combo("CTRL_ALT", "F12"), "Ctrl+Mod1+F12");
+    }
+}
@@ -0,0 +1,461 @@
+use crate::MyError;
+use crate::backend::{HyprctlOutput, HyprlandBackend};
+use crate::config::{LaunchSettings, print_kv_table};
+use crate::gamescope::derive_output_size;
+use crate::hyprland::{get_monitors, primary_client_for_pid, select_span_monitors, span_rect};
+use crate::i18n::{Msg, trf};
+use crate::output::{info, warn};
+use crate::span::show_span;
+use crate::types::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+const TRACE_VERSION: u32 = 1;
+const REDACTED: &str = "<redacted>";
+// Window titles can leak browser tabs, document names and chat previews.
+const REDACTED_JSON_KEYS: &[&str] = &["title", "initialTitle"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum TraceEvent {
+    Hyprctl {
+        args: Vec<String>,
+        success: bool,
+        stdout: String,
+        stderr: String,
+    },
+    Gamescope {
+        args: Vec<String>,
+        env: Vec<(String, String)>,
+        pid: u32,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct TraceEntry {
+    at_ms: u64,
+    #[serde(flatten)]
+    event: TraceEvent,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct Trace {
+    version: u32,
+    hyprfinity: String,
+    events: Vec<TraceEntry>,
+}
+
+struct Recorder {
+    path: PathBuf,
+    started: Instant,
+    redactor: Redactor,
+    trace: Trace,
+}
+
+static RECORDER: OnceLock<Mutex<Recorder>> = OnceLock::new();
+
+/// Replaces the home directory and user name in recorded text.
+pub(crate) struct Redactor {
+    home: Option<String>,
+    user: Option<String>,
+}
+
+impl Redactor {
+    pub(crate) fn from_env() -> Self {
+        let non_empty = |value: String| Some(value).filter(|v| !v.is_empty());
+        Redactor {
+            home: std::env::var("HOME")
+                .ok()
+                .and_then(non_empty)
+                .filter(|home| home != "/"),
+            // Very short names would mangle unrelated text.
+            user: std::env::var("USER")
+                .ok()
+                .and_then(non_empty)
+                .filter(|user| user.len() >= 3),
+        }
+    }
+
+    pub(crate) fn text(&self, value: &str) -> String {
+        let mut value = value.to_string();
+        if let Some(home) = self.home.as_deref() {
+            value = value.replace(home, "~");
+        }
+        if let Some(user) = self.user.as_deref() {
+            value = value.replace(user, "<user>");
+        }
+        value
+    }
+
+    fn json(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, item) in map.iter_mut() {
+                    if REDACTED_JSON_KEYS.contains(&key.as_str()) {
+                        *item = serde_json::Value::String(REDACTED.to_string());
+                    } else {
+                        self.json(item);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(|item| self.json(item)),
+            serde_json::Value::String(text) => *text = self.text(text),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn output(&self, stdout: &str) -> String {
+        match serde_json::from_str::<serde_json::Value>(stdout) {
+            Ok(mut value) if value.is_object() || value.is_array() => {
+                self.json(&mut value);
+                value.to_string()
+            }
+            _ => self.text(stdout),
+        }
+    }
+}
+
+/// Writes the trace when dropped, so early returns and `?` errors still leave one behind.
+pub(crate) struct TraceGuard(());
+
+impl Drop for TraceGuard {
+    fn drop(&mut self) {
+        finish_trace();
+    }
+}
+
+/// Starts capturing hyprctl traffic and gamescope launches; written when the guard drops.
+pub(crate) fn start_trace(path: &str) -> Result<TraceGuard, Box<dyn Error>> {
+    let path = PathBuf::from(path);
+    // Fail before the session starts rather than losing the trace at the end.
+    std::fs::write(&path, "")?;
+    let _ = RECORDER.set(Mutex::new(Recorder {
+        path,
+        started: Instant::now(),
+        redactor: Redactor::from_env(),
+        trace: Trace {
+            version: TRACE_VERSION,
+            hyprfinity: env!("CARGO_PKG_VERSION").to_string(),
+            events: Vec::new(),
+        },
+    }));
+    Ok(TraceGuard(()))
+}
+
+fn record(event: impl FnOnce(&Redactor) -> TraceEvent) {
+    let Some(recorder) = RECORDER.get() else {
+        return;
+    };
+    let mut recorder = recorder.lock().unwrap_or_else(|e| e.into_inner());
+    let event = event(&recorder.redactor);
+    let at_ms = recorder.started.elapsed().as_millis() as u64;
+    recorder.trace.events.push(TraceEntry { at_ms, event });
+}
+
+pub(crate) fn record_hyprctl(args: &[&str], output: &HyprctlOutput) {
+    record(|redactor| TraceEvent::Hyprctl {
+        args: args.iter().map(|arg| redactor.text(arg)).collect(),
+        success: output.success,
+        stdout: redactor.output(&output.stdout),
+        stderr: redactor.text(&output.stderr),
+    });
+}
+
+pub(crate) fn record_gamescope(args: &[String], env: &[(String, String)], pid: u32) {
+    record(|redactor| TraceEvent::Gamescope {
+        args: args.iter().map(|arg| redactor.text(arg)).collect(),
+        env: env
+            .iter()
+            .map(|(key, value)| (key.clone(), redactor.text(value)))
+            .collect(),
+        pid,
+    });
+}
+
+/// Writes the recorded trace, if `--record` was given. Safe to call more than once.
+pub(crate) fn finish_trace() {
+    let Some(recorder) = RECORDER.get() else {
+        return;
+    };
+    let recorder = recorder.lock().unwrap_or_else(|e| e.into_inner());
+    let written = serde_json::to_string_pretty(&recorder.trace)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&recorder.path, json).map_err(|e| e.to_string()));
+    match written {
+        Ok(()) => info(&trf(
+            Msg::TraceRecorded,
+            &[&recorder.trace.events.len(), &recorder.path.display()],
+        )),
+        Err(e) => warn(&trf(Msg::TraceWriteFailed, &[&recorder.path.display(), &e])),
+    }
+}
+
+fn load_trace(path: &Path) -> Result<Trace, Box<dyn Error>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| MyError(format!("Failed to read trace {}: {}", path.display(), e)))?;
+    let trace: Trace = serde_json::from_str(&contents)
+        .map_err(|e| MyError(format!("Invalid trace {}: {}", path.display(), e)))?;
+    if trace.version > TRACE_VERSION {
+        warn(&trf(
+            Msg::TraceVersionNewer,
+            &[&trace.version, &TRACE_VERSION],
+        ));
+    }
+    Ok(trace)
+}
+
+#[derive(Default)]
+struct RecordedResponses {
+    outputs: Vec<(bool, String, String)>,
+    next: usize,
+}
+
+/// Serves recorded hyprctl responses in order per command; the last one repeats once exhausted.
+struct ReplayHyprland {
+    responses: Mutex<HashMap<Vec<String>, RecordedResponses>>,
+}
+
+impl ReplayHyprland {
+    fn new(trace: &Trace) -> Self {
+        let mut responses: HashMap<Vec<String>, RecordedResponses> = HashMap::new();
+        for entry in &trace.events {
+            if let TraceEvent::Hyprctl {
+                args,
+                success,
+                stdout,
+                stderr,
+            } = &entry.event
+            {
+                responses.entry(args.clone()).or_default().outputs.push((
+                    *success,
+                    stdout.clone(),
+                    stderr.clone(),
+                ));
+            }
+        }
+        ReplayHyprland {
+            responses: Mutex::new(responses),
+        }
+    }
+}
+
+impl HyprlandBackend for ReplayHyprland {
+    fn hyprctl(&self, args: &[&str]) -> std::io::Result<HyprctlOutput> {
+        let key = args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let mut responses = self.responses.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(recorded) = responses.get_mut(&key) else {
+            return Ok(HyprctlOutput {
+                success: false,
+                stdout: String::new(),
+                stderr: format!("not in trace: hyprctl {}", key.join(" ")),
+            });
+        };
+        let (success, stdout, stderr) =
+            recorded.outputs[recorded.next.min(recorded.outputs.len() - 1)].clone();
+        recorded.next += 1;
+        Ok(HyprctlOutput {
+            success,
+            stdout,
+            stderr,
+        })
+    }
+}
+
+static REPLAY: OnceLock<ReplayHyprland> = OnceLock::new();
+
+/// The trace-backed hyprctl while `hyprfinity replay` runs.
+pub(crate) fn replay_backend() -> Option<&'static dyn HyprlandBackend> {
+    REPLAY
+        .get()
+        .map(|replay| replay as &'static dyn HyprlandBackend)
+}
+
+fn flag_value(args: &[String], flag: &str) -> Option<i32> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .and_then(|value| value.parse().ok())
+}
+
+/// What the recorded session did: the gamescope launch and where its window ended up.
+#[derive(Debug, Default, PartialEq)]
+struct RecordedSession {
+    gamescope_args: Option<Vec<String>>,
+    output_size: Option<(i32, i32)>,
+    final_window: Option<(i32, i32, i32, i32)>,
+    hyprctl_calls: usize,
+    hyprctl_failures: usize,
+}
+
+fn recorded_session(trace: &Trace) -> RecordedSession {
+    let mut session = RecordedSession::default();
+    let mut pid = None;
+    for entry in &trace.events {
+        match &entry.event {
+            TraceEvent::Gamescope { args, pid: id, .. } => {
+                pid = Some(*id);
+                session.output_size = flag_value(args, "-W").zip(flag_value(args, "-H"));
+                session.gamescope_args = Some(args.clone());
+            }
+            TraceEvent::Hyprctl {
+                args,
+                success,
+                stdout,
+                ..
+            } => {
+                session.hyprctl_calls += 1;
+                if !success {
+                    session.hyprctl_failures += 1;
+                }
+                if let Some(pid) = pid
+                    && *success
+                    && args == &["clients", "-j"]
+                    && let Ok(clients) = serde_json::from_str::<Vec<Client>>(stdout)
+                    && let Some(client) = primary_client_for_pid(&clients, pid)
+                    && let (Some([x, y]), Some([w, h])) = (client.at, client.size)
+                {
+                    session.final_window = Some((x, y, w, h));
+                }
+            }
+        }
+    }
+    session
+}
+
+fn format_rect((x, y, w, h): (i32, i32, i32, i32)) -> String {
+    format!("{}x{}@{},{}", w, h, x, y)
+}
+
+/// Re-runs span and output-size decisions against a recorded trace instead of live Hyprland.
+pub(crate) fn replay_trace(
+    path: &str,
+    launch: &LaunchSettings,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    let trace = load_trace(Path::new(path))?;
+    let _ = REPLAY.set(ReplayHyprland::new(&trace));
+    let session = recorded_session(&trace);
+
+    show_span(launch, false, verbose)?;
+    let monitors = select_span_monitors(&get_monitors(verbose)?, launch.span_target);
+    let span = span_rect(&monitors, launch.span_strategy)?;
+    let output = derive_output_size(span.2, span.3, launch.output_width, launch.output_height);
+
+    let mut rows = vec![
+        ("trace", path.to_string()),
+        ("recorded_by", format!("hyprfinity {}", trace.hyprfinity)),
+        (
+            "hyprctl_calls",
+            format!(
+                "{} ({} failed)",
+                session.hyprctl_calls, session.hyprctl_failures
+            ),
+        ),
+        (
+            "gamescope_args",
+            session
+                .gamescope_args
+                .as_ref()
+                .map(shell_words::join)
+                .unwrap_or_else(|| "not launched".to_string()),
+        ),
+        ("span", format_rect(span)),
+    ];
+    if let Some((w, h)) = session.output_size {
+        let verdict = if (w, h) == output {
+            "matches"
+        } else {
+            "differs from the current config"
+        };
+        rows.push((
+            "recorded_output",
+            format!("{}x{} ({} {}x{})", w, h, verdict, output.0, output.1),
+        ));
+    }
+    match session.final_window {
+        Some(window) => {
+            rows.push(("final_window", format_rect(window)));
+            rows.push((
+                "covers_span",
+                if window == span { "yes" } else { "no" }.to_string(),
+            ));
+        }
+        None => rows.push(("final_window", "not found in trace".to_string())),
+    }
+    print_kv_table("Replay", rows);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redactor_scrubs_home_user_and_window_titles() {
+        let redactor = Redactor {
+            home: Some("/home/alice".to_string()),
+            user: Some("alice".to_string()),
+        };
+        let stdout = r#"[{"pid":7,"title":"Inbox - alice@example.com","class":"game","cwd":"/home/alice/games"}]"#;
+        let redacted = redactor.output(stdout);
+        assert!(!redacted.contains("alice"), "{}", redacted);
+        assert!(redacted.contains(r#""title":"<redacted>""#), "{}", redacted);
+        assert!(redacted.contains(r#""cwd":"~/games""#), "{}", redacted);
+        assert_eq!(redactor.output("ok\n"), "ok\n");
+    }
+
+    #[test]
+    fn recorded_session_tracks_final_window_of_gamescope_pid() {
+        let clients = |x: i32, w: i32| TraceEvent::Hyprctl {
+            args: vec!["clients".to_string(), "-j".to_string()],
+            success: true,
+            stdout: format!(
+                r#"[{{"pid":42,"address":"0x1","at":[{},0],"size":[{},1080]}}]"#,
+                x, w
+            ),
+            stderr: String::new(),
+        };
+        let trace = Trace {
+            version: TRACE_VERSION,
+            hyprfinity: "0.0.0".to_string(),
+            events: [
+                TraceEvent::Gamescope {
+                    args: ["-W", "3840", "-H", "1080", "--", "game"]
+                        .map(String::from)
+                        .to_vec(),
+                    env: Vec::new(),
+                    pid: 42,
+                },
+                clients(0, 1280),
+                clients(-1920, 3840),
+            ]
+            .into_iter()
+            .enumerate()
+            .map(|(i, event)| TraceEntry {
+                at_ms: i as u64,
+                event,
+            })
+            .collect(),
+        };
+
+        let json = serde_json::to_string(&trace).unwrap();
+        assert!(json.contains(r#""kind":"gamescope""#), "{}", json);
+        let trace: Trace = serde_json::from_str(&json).unwrap();
+        let session = recorded_session(&trace);
+        assert_eq!(session.output_size, Some((3840, 1080)));
+        assert_eq!(session.final_window, Some((-1920, 0, 3840, 1080)));
+        assert_eq!(session.hyprctl_calls, 2);
+
+        let replay = ReplayHyprland::new(&trace);
+        let first = replay.hyprctl(&["clients", "-j"]).unwrap().stdout;
+        let second = replay.hyprctl(&["clients", "-j"]).unwrap().stdout;
+        let third = replay.hyprctl(&["clients", "-j"]).unwrap().stdout;
+        assert!(first.contains("1280"));
+        assert_eq!(second, third);
+        assert!(!replay.hyprctl(&["monitors", "-j"]).unwrap().success);
+    }
+}
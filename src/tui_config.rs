@@ -1,4 +1,6 @@
-use crate::config::{Config, DEFAULT_MANGOHUD_CONFIG};
+use crate::config::{Config, DEFAULT_MANGOHUD_CONFIG, profile_summary};
+use crate::i18n::{Msg, tr, trf};
+use crate::types::{DEFAULT_STARTUP_TIMEOUT_SECS, MAX_SUPERSAMPLE_SCALE, PickerFallback};
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
     execute,
@@ -11,6 +13,7 @@ use ratatui::{
     style::{Color, Modifier, Style},
     widgets::{Block, Borders, Cell as TuiCell, Paragraph, Row as TuiRow, Table as TuiTable},
 };
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::time::Duration;
 
@@ -23,9 +26,29 @@ fn format_optional_size(width: Option<i32>, height: Option<i32>) -> String {
     }
 }
 
+/// Highest render scale the editor steps to: above 1.0 only with `allow_supersample`.
+fn render_scale_limit(config: &Config) -> f32 {
+    if config.allow_supersample.unwrap_or(false) {
+        MAX_SUPERSAMPLE_SCALE
+    } else {
+        1.0
+    }
+}
+
+/// Steps a render scale by `delta` from its value (or `fallback` when unset), within the limit.
+fn step_scale(value: Option<f32>, fallback: f32, delta: f32, config: &Config) -> Option<f32> {
+    let s = (value.unwrap_or(fallback) + delta).clamp(0.1, render_scale_limit(config));
+    Some((s * 100.0).round() / 100.0)
+}
+
+/// A per-axis scale, or `-` when the axis follows `render_scale`.
+fn format_axis_scale(value: Option<f32>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{:.2}", v))
+}
+
 pub(crate) fn apply_editor_defaults(mut config: Config, auto_scale: f32) -> Config {
     if config.gamescope_args.is_none() {
-        config.gamescope_args = Some(vec!["-r".to_string(), "60".to_string()]);
+        config.gamescope_args = Some(Vec::new());
     }
     if config.no_pin.is_none() {
         config.no_pin = Some(false);
@@ -33,6 +56,9 @@ pub(crate) fn apply_editor_defaults(mut config: Config, auto_scale: f32) -> Conf
     if config.pick.is_none() {
         config.pick = Some(false);
     }
+    if config.picker_fallback.is_none() {
+        config.picker_fallback = Some(PickerFallback::Error);
+    }
     if config.idle_inhibit.is_none() {
         config.idle_inhibit = Some(true);
     }
@@ -52,7 +78,7 @@ pub(crate) fn apply_editor_defaults(mut config: Config, auto_scale: f32) -> Conf
         config.render_scale = Some(auto_scale);
     }
     if config.startup_timeout_secs.is_none() {
-        config.startup_timeout_secs = Some(10);
+        config.startup_timeout_secs = Some(DEFAULT_STARTUP_TIMEOUT_SECS);
     }
     config
 }
@@ -144,13 +170,19 @@ pub(crate) fn edit_config_tui(
                     .split(f.area());
 
                 let header = Paragraph::new(format!(
-                    "{}\nAuto recommendation: {}\nSpan: {}",
+                    "{}\n{}: {}\n{}: {}",
                     title,
+                    tr(Msg::TuiAutoRecommendation),
                     auto_reason,
+                    tr(Msg::TuiSpan),
                     span.map(|(w, h)| format!("{}x{}", w, h))
-                        .unwrap_or_else(|| "unknown".to_string())
+                        .unwrap_or_else(|| tr(Msg::TuiUnknown).to_string())
                 ))
-                .block(Block::default().borders(Borders::ALL).title("Context"));
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(tr(Msg::TuiContext)),
+                );
                 f.render_widget(header, chunks[0]);
 
                 let rows = vec![
@@ -158,6 +190,8 @@ pub(crate) fn edit_config_tui(
                         "render_scale",
                         format!("{:.2}", config.render_scale.unwrap_or(1.0)),
                     ),
+                    ("render_scale_x", format_axis_scale(config.render_scale_x)),
+                    ("render_scale_y", format_axis_scale(config.render_scale_y)),
                     (
                         "idle_inhibit",
                         config.idle_inhibit.unwrap_or(true).to_string(),
@@ -179,8 +213,8 @@ pub(crate) fn edit_config_tui(
                         "virtual_size",
                         format_optional_size(config.virtual_width, config.virtual_height),
                     ),
-                    ("save", "Write config and exit".to_string()),
-                    ("cancel", "Discard changes".to_string()),
+                    ("save", tr(Msg::TuiSave).to_string()),
+                    ("cancel", tr(Msg::TuiCancel).to_string()),
                 ];
 
                 let table_rows = rows
@@ -201,20 +235,21 @@ pub(crate) fn edit_config_tui(
                 let table =
                     TuiTable::new(table_rows, [Constraint::Length(18), Constraint::Min(24)])
                         .header(
-                            TuiRow::new(vec!["Field", "Value"])
+                            TuiRow::new(vec![tr(Msg::TuiField), tr(Msg::TableValue)])
                                 .style(Style::default().add_modifier(Modifier::BOLD)),
                         )
                         .block(
                             Block::default()
                                 .borders(Borders::ALL)
-                                .title("Config Editor"),
+                                .title(tr(Msg::TuiConfigEditor)),
                         );
                 f.render_widget(table, chunks[1]);
 
-                let footer = Paragraph::new(
-                    "Keys: ↑/↓ select  ←/→ change  Enter activate/toggle  s save  q/Esc cancel",
-                )
-                .block(Block::default().borders(Borders::ALL).title("Help"));
+                let footer = Paragraph::new(tr(Msg::TuiKeys)).block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(tr(Msg::TuiHelp)),
+                );
                 f.render_widget(footer, chunks[2]);
             })?;
 
@@ -227,29 +262,39 @@ pub(crate) fn edit_config_tui(
                     match key.code {
                         KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
                         KeyCode::Char('s') => return Ok(Some(config.clone())),
-                        KeyCode::Down => selected = (selected + 1) % 9,
+                        KeyCode::Down => selected = (selected + 1) % 11,
                         KeyCode::Up => {
-                            selected = if selected == 0 { 8 } else { selected - 1 };
+                            selected = if selected == 0 { 10 } else { selected - 1 };
                         }
                         KeyCode::Left => match selected {
                             0 => {
-                                let s = (config.render_scale.unwrap_or(1.0) - 0.05).clamp(0.1, 1.0);
-                                config.render_scale = Some((s * 100.0).round() / 100.0);
+                                config.render_scale =
+                                    step_scale(config.render_scale, 1.0, -0.05, &config);
                             }
-                            1 => config.idle_inhibit = Some(!config.idle_inhibit.unwrap_or(true)),
-                            2 => config.hide_waybar = Some(!config.hide_waybar.unwrap_or(true)),
-                            3 => {
+                            1 => {
+                                let base = config.render_scale.unwrap_or(1.0);
+                                config.render_scale_x =
+                                    step_scale(config.render_scale_x, base, -0.05, &config);
+                            }
+                            2 => {
+                                let base = config.render_scale.unwrap_or(1.0);
+                                config.render_scale_y =
+                                    step_scale(config.render_scale_y, base, -0.05, &config);
+                            }
+                            3 => config.idle_inhibit = Some(!config.idle_inhibit.unwrap_or(true)),
+                            4 => config.hide_waybar = Some(!config.hide_waybar.unwrap_or(true)),
+                            5 => {
                                 config.overlay_enabled =
                                     Some(!config.overlay_enabled.unwrap_or(true))
                             }
-                            4 => config.pick_size = Some(!config.pick_size.unwrap_or(false)),
-                            5 => cycle_size_setting(
+                            6 => config.pick_size = Some(!config.pick_size.unwrap_or(false)),
+                            7 => cycle_size_setting(
                                 &mut config.output_width,
                                 &mut config.output_height,
                                 &output_opts,
                                 false,
                             ),
-                            6 => cycle_size_setting(
+                            8 => cycle_size_setting(
                                 &mut config.virtual_width,
                                 &mut config.virtual_height,
                                 &virtual_opts,
@@ -259,23 +304,33 @@ pub(crate) fn edit_config_tui(
                         },
                         KeyCode::Right => match selected {
                             0 => {
-                                let s = (config.render_scale.unwrap_or(1.0) + 0.05).clamp(0.1, 1.0);
-                                config.render_scale = Some((s * 100.0).round() / 100.0);
+                                config.render_scale =
+                                    step_scale(config.render_scale, 1.0, 0.05, &config);
+                            }
+                            1 => {
+                                let base = config.render_scale.unwrap_or(1.0);
+                                config.render_scale_x =
+                                    step_scale(config.render_scale_x, base, 0.05, &config);
                             }
-                            1 => config.idle_inhibit = Some(!config.idle_inhibit.unwrap_or(true)),
-                            2 => config.hide_waybar = Some(!config.hide_waybar.unwrap_or(true)),
-                            3 => {
+                            2 => {
+                                let base = config.render_scale.unwrap_or(1.0);
+                                config.render_scale_y =
+                                    step_scale(config.render_scale_y, base, 0.05, &config);
+                            }
+                            3 => config.idle_inhibit = Some(!config.idle_inhibit.unwrap_or(true)),
+                            4 => config.hide_waybar = Some(!config.hide_waybar.unwrap_or(true)),
+                            5 => {
                                 config.overlay_enabled =
                                     Some(!config.overlay_enabled.unwrap_or(true))
                             }
-                            4 => config.pick_size = Some(!config.pick_size.unwrap_or(false)),
-                            5 => cycle_size_setting(
+                            6 => config.pick_size = Some(!config.pick_size.unwrap_or(false)),
+                            7 => cycle_size_setting(
                                 &mut config.output_width,
                                 &mut config.output_height,
                                 &output_opts,
                                 true,
                             ),
-                            6 => cycle_size_setting(
+                            8 => cycle_size_setting(
                                 &mut config.virtual_width,
                                 &mut config.virtual_height,
                                 &virtual_opts,
@@ -284,27 +339,27 @@ pub(crate) fn edit_config_tui(
                             _ => {}
                         },
                         KeyCode::Enter => match selected {
-                            1 => config.idle_inhibit = Some(!config.idle_inhibit.unwrap_or(true)),
-                            2 => config.hide_waybar = Some(!config.hide_waybar.unwrap_or(true)),
-                            3 => {
+                            3 => config.idle_inhibit = Some(!config.idle_inhibit.unwrap_or(true)),
+                            4 => config.hide_waybar = Some(!config.hide_waybar.unwrap_or(true)),
+                            5 => {
                                 config.overlay_enabled =
                                     Some(!config.overlay_enabled.unwrap_or(true))
                             }
-                            4 => config.pick_size = Some(!config.pick_size.unwrap_or(false)),
-                            5 => cycle_size_setting(
+                            6 => config.pick_size = Some(!config.pick_size.unwrap_or(false)),
+                            7 => cycle_size_setting(
                                 &mut config.output_width,
                                 &mut config.output_height,
                                 &output_opts,
                                 true,
                             ),
-                            6 => cycle_size_setting(
+                            8 => cycle_size_setting(
                                 &mut config.virtual_width,
                                 &mut config.virtual_height,
                                 &virtual_opts,
                                 true,
                             ),
-                            7 => return Ok(Some(config.clone())),
-                            8 => return Ok(None),
+                            9 => return Ok(Some(config.clone())),
+                            10 => return Ok(None),
                             _ => {}
                         },
                         _ => {}
@@ -319,3 +374,227 @@ pub(crate) fn edit_config_tui(
     terminal.show_cursor()?;
     result
 }
+
+pub(crate) struct ProfilesOutcome {
+    pub(crate) profiles: BTreeMap<String, Config>,
+    pub(crate) changed: bool,
+    pub(crate) launch: Option<String>,
+}
+
+enum ProfilesMode {
+    Browse,
+    Rename(String),
+    ConfirmDelete,
+}
+
+fn unique_profile_name(profiles: &BTreeMap<String, Config>, base: &str) -> String {
+    let mut candidate = format!("{}-copy", base);
+    let mut n = 2;
+    while profiles.contains_key(&candidate) {
+        candidate = format!("{}-copy{}", base, n);
+        n += 1;
+    }
+    candidate
+}
+
+fn rename_profile(
+    profiles: &mut BTreeMap<String, Config>,
+    from: &str,
+    to: &str,
+) -> Result<(), String> {
+    let to = to.trim();
+    if to.is_empty() || to.contains(['.', '"', ' ']) {
+        return Err(trf(Msg::TuiProfileNameInvalid, &[&to]));
+    }
+    if to != from && profiles.contains_key(to) {
+        return Err(trf(Msg::TuiProfileNameTaken, &[&to]));
+    }
+    if let Some(profile) = profiles.remove(from) {
+        profiles.insert(to.to_string(), profile);
+    }
+    Ok(())
+}
+
+pub(crate) fn profiles_tui(
+    profiles: BTreeMap<String, Config>,
+) -> Result<ProfilesOutcome, Box<dyn Error>> {
+    let mut profiles = profiles;
+    let mut changed = false;
+    let mut selected: usize = 0;
+    let mut mode = ProfilesMode::Browse;
+    let mut status = String::new();
+
+    enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = (|| -> Result<ProfilesOutcome, Box<dyn Error>> {
+        loop {
+            let names = profiles.keys().cloned().collect::<Vec<_>>();
+            selected = selected.min(names.len().saturating_sub(1));
+
+            terminal.draw(|f| {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(6), Constraint::Length(4)])
+                    .split(f.area());
+
+                let table_rows = names
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, name)| {
+                        let style = if idx == selected {
+                            Style::default()
+                                .fg(Color::Yellow)
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default()
+                        };
+                        TuiRow::new(vec![
+                            TuiCell::from(name.clone()),
+                            TuiCell::from(profile_summary(&profiles[name])),
+                        ])
+                        .style(style)
+                    })
+                    .collect::<Vec<_>>();
+                let table =
+                    TuiTable::new(table_rows, [Constraint::Length(18), Constraint::Min(24)])
+                        .header(
+                            TuiRow::new(vec![tr(Msg::TuiProfile), tr(Msg::TuiOverrides)])
+                                .style(Style::default().add_modifier(Modifier::BOLD)),
+                        )
+                        .block(
+                            Block::default()
+                                .borders(Borders::ALL)
+                                .title(tr(Msg::TuiProfiles)),
+                        );
+                f.render_widget(table, chunks[0]);
+
+                let prompt = match &mode {
+                    ProfilesMode::Browse => tr(Msg::TuiProfilesKeys).to_string(),
+                    ProfilesMode::Rename(buffer) => trf(Msg::TuiProfileNewName, &[buffer]),
+                    ProfilesMode::ConfirmDelete => tr(Msg::TuiProfileConfirmDelete).to_string(),
+                };
+                let footer = Paragraph::new(format!("{}\n{}", prompt, status)).block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(tr(Msg::TuiHelp)),
+                );
+                f.render_widget(footer, chunks[1]);
+            })?;
+
+            if !event::poll(Duration::from_millis(200))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let current = names.get(selected).cloned();
+            match &mut mode {
+                ProfilesMode::Rename(buffer) => match key.code {
+                    KeyCode::Esc => mode = ProfilesMode::Browse,
+                    KeyCode::Backspace => {
+                        buffer.pop();
+                    }
+                    KeyCode::Char(c) => buffer.push(c),
+                    KeyCode::Enter => {
+                        if let Some(from) = current {
+                            match rename_profile(&mut profiles, &from, buffer) {
+                                Ok(()) => {
+                                    changed |= from != buffer.trim();
+                                    status.clear();
+                                }
+                                Err(e) => status = e,
+                            }
+                        }
+                        mode = ProfilesMode::Browse;
+                    }
+                    _ => {}
+                },
+                ProfilesMode::ConfirmDelete => {
+                    if let (KeyCode::Char('y'), Some(name)) = (key.code, current) {
+                        profiles.remove(&name);
+                        changed = true;
+                        status = trf(Msg::TuiProfileDeleted, &[&name]);
+                    }
+                    mode = ProfilesMode::Browse;
+                }
+                ProfilesMode::Browse => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        return Ok(ProfilesOutcome {
+                            profiles,
+                            changed: false,
+                            launch: None,
+                        });
+                    }
+                    KeyCode::Char('s') => {
+                        return Ok(ProfilesOutcome {
+                            profiles,
+                            changed,
+                            launch: None,
+                        });
+                    }
+                    KeyCode::Enter if current.is_some() => {
+                        return Ok(ProfilesOutcome {
+                            profiles,
+                            changed,
+                            launch: current,
+                        });
+                    }
+                    KeyCode::Down if !names.is_empty() => selected = (selected + 1) % names.len(),
+                    KeyCode::Up if !names.is_empty() => {
+                        selected = if selected == 0 {
+                            names.len() - 1
+                        } else {
+                            selected - 1
+                        };
+                    }
+                    KeyCode::Char('c') => {
+                        if let Some(name) = current {
+                            let copy = unique_profile_name(&profiles, &name);
+                            let profile = profiles[&name].clone();
+                            profiles.insert(copy.clone(), profile);
+                            changed = true;
+                            status = trf(Msg::TuiProfileDuplicated, &[&name, &copy]);
+                        }
+                    }
+                    KeyCode::Char('r') => {
+                        if let Some(name) = current {
+                            mode = ProfilesMode::Rename(name);
+                        }
+                    }
+                    KeyCode::Char('d') if current.is_some() => mode = ProfilesMode::ConfirmDelete,
+                    _ => {}
+                },
+            }
+        }
+    })();
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_and_rename_keep_names_unique() {
+        let mut profiles = BTreeMap::from([
+            ("couch".to_string(), Config::default()),
+            ("couch-copy".to_string(), Config::default()),
+        ]);
+        assert_eq!(unique_profile_name(&profiles, "couch"), "couch-copy2");
+        assert!(rename_profile(&mut profiles, "couch", "couch-copy").is_err());
+        assert!(rename_profile(&mut profiles, "couch", "bad name").is_err());
+        rename_profile(&mut profiles, "couch", "tv").unwrap();
+        assert!(profiles.contains_key("tv") && !profiles.contains_key("couch"));
+    }
+}
@@ -1,12 +1,201 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub(crate) struct Monitor {
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Monitor {
     pub(crate) name: Option<String>,
     pub(crate) width: i32,
     pub(crate) height: i32,
     pub(crate) x: i32,
     pub(crate) y: i32,
+    #[serde(default)]
+    pub(crate) model: Option<String>,
+    #[serde(default)]
+    pub(crate) refresh_rate: Option<f32>,
+    #[serde(default)]
+    pub(crate) scale: Option<f32>,
+    #[serde(default)]
+    pub(crate) vrr: Option<bool>,
+    #[serde(default)]
+    pub(crate) current_format: Option<String>,
+    #[serde(default)]
+    pub(crate) mirror_of: Option<String>,
+    #[serde(default)]
+    pub(crate) disabled: bool,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) transform: Option<i32>,
+    #[serde(default)]
+    pub(crate) focused: bool,
+}
+
+impl Monitor {
+    /// An enabled monitor at (x, y) in layout coordinates, for planning against a layout the
+    /// caller knows instead of asking the compositor.
+    pub fn new(name: impl Into<String>, x: i32, y: i32, width: i32, height: i32) -> Self {
+        Monitor {
+            name: Some(name.into()),
+            x,
+            y,
+            width,
+            height,
+            ..Monitor::default()
+        }
+    }
+
+    pub fn with_refresh_rate(mut self, hz: f32) -> Self {
+        self.refresh_rate = Some(hz);
+        self
+    }
+
+    /// The focused monitor is the one `SpanTarget::Focused` picks.
+    pub fn with_focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Position as (x, y) in layout coordinates.
+    pub fn position(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    pub fn refresh_rate(&self) -> Option<f32> {
+        self.refresh_rate
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub(crate) fn display_name(&self, idx: usize) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("monitor{}", idx + 1))
+    }
+
+    // Disabled outputs, mirrors and zero-sized reports do not add any visible area to the span.
+    pub(crate) fn contributes_to_span(&self) -> bool {
+        !self.disabled
+            && self.width > 0
+            && self.height > 0
+            && self
+                .mirror_of
+                .as_deref()
+                .is_none_or(|m| m.is_empty() || m == "none")
+    }
+
+    // 10-bit scanout formats (e.g. XRGB2101010) are what Hyprland uses for HDR output.
+    pub(crate) fn is_ten_bit(&self) -> bool {
+        self.current_format
+            .as_deref()
+            .is_some_and(|f| f.contains("2101010"))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanTarget {
+    #[default]
+    All,
+    Focused,
+}
+
+impl SpanTarget {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            SpanTarget::All => "all",
+            SpanTarget::Focused => "focused",
+        }
+    }
+}
+
+/// What fires a session hotkey.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum HotkeyBind {
+    /// A plain press.
+    #[default]
+    Press,
+    /// Only a long press: Hyprland's `bindo`, which fires once the key is held past
+    /// `input:repeat_delay`.
+    Hold,
+}
+
+impl HotkeyBind {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            HotkeyBind::Press => "press",
+            HotkeyBind::Hold => "hold",
+        }
+    }
+}
+
+/// How the span rectangle is derived from the spanned monitors.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpanStrategy {
+    /// The bounding box of every monitor, including areas no monitor shows.
+    #[default]
+    BoundingBox,
+    /// The biggest rectangle that is fully covered by monitors.
+    LargestRect,
+}
+
+impl SpanStrategy {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            SpanStrategy::BoundingBox => "bounding-box",
+            SpanStrategy::LargestRect => "largest-rect",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ExcludedMonitors {
+    #[default]
+    Keep,
+    Blank,
+    Dim,
+}
+
+impl ExcludedMonitors {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ExcludedMonitors::Keep => "keep",
+            ExcludedMonitors::Blank => "blank",
+            ExcludedMonitors::Dim => "dim",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ColorMode {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ColorMode::Auto => "auto",
+            ColorMode::Always => "always",
+            ColorMode::Never => "never",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -15,7 +204,7 @@ pub(crate) struct AutoTuneProfile {
     pub(crate) reason: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub(crate) struct Client {
     pub(crate) pid: i32,
     #[serde(default)]
@@ -24,6 +213,261 @@ pub(crate) struct Client {
     pub(crate) at: Option<[i32; 2]>,
     #[serde(default)]
     pub(crate) size: Option<[i32; 2]>,
+    #[serde(default)]
+    pub(crate) class: Option<String>,
+    #[serde(default)]
+    pub(crate) title: Option<String>,
+    #[serde(default, rename = "initialClass")]
+    pub(crate) initial_class: Option<String>,
+    #[serde(default, rename = "initialTitle")]
+    pub(crate) initial_title: Option<String>,
+    /// Hyprland only; other compositors leave it unset.
+    #[serde(default)]
+    pub(crate) pinned: Option<bool>,
+}
+
+/// How the startup probe finds the gamescope window: by PID, then by class/title regex for
+/// setups where the window belongs to another process (e.g. gamescope's XWayland child).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) struct WindowProbe {
+    pub(crate) class: Option<String>,
+    pub(crate) title: Option<String>,
+    pub(crate) poll_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FitSettings {
+    pub(crate) attempts: u32,
+    pub(crate) settle_ms: u64,
+    pub(crate) tolerance_px: i32,
+}
+
+pub(crate) const DEFAULT_STARTUP_TIMEOUT_SECS: u64 = 10;
+pub(crate) const DEFAULT_STARTUP_POLL_MS: u64 = 250;
+pub(crate) const DEFAULT_WINDOW_CLASS: &str = "^gamescope$";
+/// Upper bound for `render_scale` with `allow_supersample`: rendering at 2x per axis (4x the
+/// pixels) is already beyond what supersampling a multi-monitor span can afford.
+pub(crate) const MAX_SUPERSAMPLE_SCALE: f32 = 2.0;
+
+/// Internal size relative to the output, per axis. Ultrawide spans can drop horizontal
+/// resolution, where the stretched FOV hides it, and keep vertical sharpness.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RenderScale {
+    pub(crate) x: f32,
+    pub(crate) y: f32,
+}
+
+impl RenderScale {
+    pub(crate) const fn uniform(scale: f32) -> Self {
+        RenderScale { x: scale, y: scale }
+    }
+
+    pub(crate) fn is_within(self, max: f32) -> bool {
+        (0.1..=max).contains(&self.x) && (0.1..=max).contains(&self.y)
+    }
+
+    pub(crate) fn clamp(self, max: f32) -> Self {
+        RenderScale {
+            x: self.x.clamp(0.1, max),
+            y: self.y.clamp(0.1, max),
+        }
+    }
+}
+
+/// `0.75`, or `0.75x1` when the axes differ, as `--render-scale` accepts it.
+impl std::fmt::Display for RenderScale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.x == self.y {
+            write!(f, "{}", self.x)
+        } else {
+            write!(f, "{}x{}", self.x, self.y)
+        }
+    }
+}
+
+/// A number when uniform, so JSON consumers of the single scale keep working.
+impl Serialize for RenderScale {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.x == self.y {
+            serializer.serialize_f32(self.x)
+        } else {
+            serializer.collect_str(self)
+        }
+    }
+}
+
+/// Reads what [`Serialize`] writes: a number, or `XxY` for per-axis scales.
+impl<'de> Deserialize<'de> for RenderScale {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Uniform(f32),
+            PerAxis(String),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Uniform(scale) => Ok(RenderScale::uniform(scale)),
+            Repr::PerAxis(value) => {
+                let parse = |v: &str| v.trim().parse::<f32>().ok();
+                value
+                    .split_once(['x', 'X'])
+                    .and_then(|(x, y)| {
+                        Some(RenderScale {
+                            x: parse(x)?,
+                            y: parse(y)?,
+                        })
+                    })
+                    .ok_or_else(|| {
+                        serde::de::Error::custom(format!(
+                            "expected a scale or XxY (e.g. 0.75x1.0), got `{}`",
+                            value
+                        ))
+                    })
+            }
+        }
+    }
+}
+
+pub(crate) const DEFAULT_FIT_ATTEMPTS: u32 = 4;
+pub(crate) const DEFAULT_FIT_SETTLE_MS: u64 = 80;
+pub(crate) const DEFAULT_FIT_TOLERANCE_PX: i32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum GamepadAction {
+    #[default]
+    Exit,
+    Restart,
+}
+
+impl GamepadAction {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            GamepadAction::Exit => "exit",
+            GamepadAction::Restart => "restart",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) struct GamepadSettings {
+    pub(crate) chord: Vec<String>,
+    pub(crate) hold_ms: u64,
+    pub(crate) action: GamepadAction,
+}
+
+pub(crate) const DEFAULT_GAMEPAD_HOLD_MS: u64 = 2000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RestartPolicy {
+    pub(crate) enabled: bool,
+    pub(crate) max_retries: u32,
+    pub(crate) backoff_ms: u64,
+}
+
+pub(crate) const DEFAULT_RESTART_MAX_RETRIES: u32 = 3;
+pub(crate) const DEFAULT_RESTART_BACKOFF_MS: u64 = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub(crate) struct MetricsSettings {
+    pub(crate) listen: Option<String>,
+    pub(crate) textfile: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RecordingBackend {
+    #[default]
+    GpuScreenRecorder,
+    Obs,
+}
+
+impl RecordingBackend {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            RecordingBackend::GpuScreenRecorder => "gpu_screen_recorder",
+            RecordingBackend::Obs => "obs",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) struct RecordingSettings {
+    pub(crate) enabled: bool,
+    pub(crate) backend: RecordingBackend,
+    pub(crate) replay_secs: u32,
+    pub(crate) fps: u32,
+    pub(crate) output_dir: String,
+    pub(crate) save_hotkey: String,
+    /// Span crop rectangles for OBS capture sources, rewritten on every session start.
+    pub(crate) obs_region_file: Option<String>,
+}
+
+pub(crate) const DEFAULT_REPLAY_SECS: u32 = 30;
+pub(crate) const DEFAULT_RECORDING_FPS: u32 = 60;
+pub(crate) const DEFAULT_REPLAY_SAVE_HOTKEY: &str = "SUPER SHIFT, F10";
+pub(crate) const DEFAULT_EXIT_HOTKEY: &str = "SUPER SHIFT, F12";
+
+/// Latency/refresh-related gamescope switches exposed as config keys.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct TuningFlags {
+    pub(crate) immediate_flips: bool,
+    pub(crate) rt_priority: bool,
+    pub(crate) force_grab_cursor: bool,
+    pub(crate) expose_wayland: bool,
+}
+
+impl TuningFlags {
+    pub(crate) fn gamescope_flags(&self) -> Vec<&'static str> {
+        [
+            (self.immediate_flips, "--immediate-flips"),
+            (self.rt_priority, "--rt"),
+            (self.force_grab_cursor, "--force-grab-cursor"),
+            (self.expose_wayland, "--expose-wayland"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, flag)| enabled.then_some(flag))
+        .collect()
+    }
+}
+
+/// Sunshine/Moonlight streaming: size the session for the client and leave the window alone.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct StreamingSettings {
+    pub(crate) enabled: bool,
+    pub(crate) match_client: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct CursorSettings {
+    pub(crate) confine: bool,
+    pub(crate) warp: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CompatTool {
+    Proton,
+    Umu,
+    Wine,
+}
+
+impl CompatTool {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            CompatTool::Proton => "proton",
+            CompatTool::Umu => "umu",
+            CompatTool::Wine => "wine",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub(crate) struct CompatSettings {
+    pub(crate) tool: Option<CompatTool>,
+    pub(crate) proton_version: Option<String>,
+    pub(crate) wine_prefix: Option<String>,
+    pub(crate) umu_gameid: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,10 +475,40 @@ pub(crate) struct SizePreset {
     pub(crate) label: String,
     pub(crate) width: i32,
     pub(crate) height: i32,
+    pub(crate) recommended: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub(crate) struct DesktopApp {
     pub(crate) name: String,
     pub(crate) exec: String,
+    pub(crate) icon: Option<String>,
+    /// `Terminal=true`: a console program that needs a terminal window.
+    pub(crate) terminal: bool,
+    /// `NoDisplay=true`: installed for MIME handling or helpers, not meant for menus.
+    pub(crate) no_display: bool,
+    /// Desktops from `OnlyShowIn=`; empty means every desktop.
+    pub(crate) only_show_in: Vec<String>,
+    /// Desktops from `NotShowIn=`.
+    pub(crate) not_show_in: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum PickerFallback {
+    #[default]
+    Error,
+    DefaultCommand,
+    Recent,
+}
+
+impl PickerFallback {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            PickerFallback::Error => "error",
+            PickerFallback::DefaultCommand => "default_command",
+            PickerFallback::Recent => "recent",
+        }
+    }
 }
@@ -0,0 +1,318 @@
+use crate::MyError;
+use crate::fetch::{download, read_source};
+use crate::i18n::{Msg, tr, trf};
+use crate::output::{info, warn};
+use crate::util::command_in_path;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const REPOSITORY: &str = "dustinleblanc/hyprfinity";
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/dustinleblanc/hyprfinity/releases/latest";
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+impl Release {
+    fn asset(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct UpgradeStatus {
+    current: String,
+    latest: Option<String>,
+    update_available: bool,
+    url: Option<String>,
+    error: Option<String>,
+}
+
+/// `major.minor.patch` of a release tag or crate version, ignoring a leading `v` and any
+/// pre-release or build suffix.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version
+        .trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let version = (
+        parts.next()??,
+        parts.next().flatten().unwrap_or(0),
+        parts.next().flatten().unwrap_or(0),
+    );
+    Some(version)
+}
+
+fn is_newer(latest: &str, current: &str) -> bool {
+    parse_version(latest)
+        .zip(parse_version(current))
+        .is_some_and(|(latest, current)| latest > current)
+}
+
+fn upgrade_status(current: &str, release: Result<Release, Box<dyn Error>>) -> UpgradeStatus {
+    match release {
+        Ok(release) => {
+            let latest = release.tag_name.trim_start_matches('v').to_string();
+            UpgradeStatus {
+                current: current.to_string(),
+                update_available: is_newer(&latest, current),
+                latest: Some(latest),
+                url: Some(release.html_url),
+                error: None,
+            }
+        }
+        Err(e) => UpgradeStatus {
+            current: current.to_string(),
+            latest: None,
+            update_available: false,
+            url: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+fn latest_release() -> Result<Release, Box<dyn Error>> {
+    let body = read_source(LATEST_RELEASE_URL)?;
+    serde_json::from_str(&body)
+        .map_err(|e| MyError(format!("Unexpected GitHub releases response: {}", e)).into())
+}
+
+/// Asks GitHub for the latest release. Only runs when invoked; `offline = true` turns it into an
+/// error without touching the network. With `json`, failures are reported in the `error` field
+/// so status bars can keep polling.
+pub(crate) fn upgrade_check(json: bool) -> Result<(), Box<dyn Error>> {
+    let status = upgrade_status(env!("CARGO_PKG_VERSION"), latest_release());
+    if json {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+    if let Some(e) = &status.error {
+        return Err(MyError(format!("Could not check for a newer release: {}", e)).into());
+    }
+    match &status.latest {
+        Some(latest) if status.update_available => info(&trf(
+            Msg::UpdateAvailable,
+            &[
+                &latest,
+                &status.current,
+                &status.url.as_deref().unwrap_or(LATEST_RELEASE_URL),
+            ],
+        )),
+        _ => info(&trf(Msg::UpToDate, &[&status.current])),
+    }
+    Ok(())
+}
+
+/// Release binary for the running architecture, as published by the release workflow.
+fn binary_asset_name() -> String {
+    format!("hyprfinity-{}-linux", std::env::consts::ARCH)
+}
+
+/// The hash from a `sha256sum` line for `name`, or from a file holding only one hash.
+fn expected_sha256(checksums: &str, name: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let hash = fields.next()?;
+        match fields.next().map(|file| file.trim_start_matches('*')) {
+            Some(file) if file != name => None,
+            _ => Some(hash.to_ascii_lowercase()),
+        }
+    })
+}
+
+fn sha256_of(path: &Path) -> Result<String, Box<dyn Error>> {
+    let output = Command::new("sha256sum")
+        .arg(path)
+        .output()
+        .map_err(|e| MyError(format!("Failed to run sha256sum: {}", e)))?;
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .filter(|_| output.status.success())
+        .map(str::to_ascii_lowercase)
+        .ok_or_else(|| MyError(format!("sha256sum failed for {}.", path.display())).into())
+}
+
+/// Checks the build provenance the release workflow attests to, with the GitHub CLI. The checksum
+/// comes from the same release as the binary, so only this proves who built it.
+fn verify_attestation(path: &Path) -> Result<(), Box<dyn Error>> {
+    if !command_in_path("gh") {
+        return Err(MyError(
+            "gh not found in PATH, so the build attestation cannot be verified. Install the GitHub \
+             CLI, or pass --skip-attestation to trust the release's checksum alone."
+                .to_string(),
+        )
+        .into());
+    }
+    let output = Command::new("gh")
+        .args(["attestation", "verify"])
+        .arg(path)
+        .args(["--repo", REPOSITORY])
+        .output()
+        .map_err(|e| MyError(format!("Failed to run gh attestation verify: {}", e)))?;
+    if !output.status.success() {
+        return Err(MyError(format!(
+            "The downloaded binary failed attestation: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Where the update is staged: next to the executable, so the final rename stays on one
+/// filesystem and is atomic.
+fn staging_path(exe: &Path) -> PathBuf {
+    exe.with_file_name(format!(".hyprfinity-update-{}", std::process::id()))
+}
+
+fn install_update(
+    release: &Release,
+    exe: &Path,
+    skip_attestation: bool,
+) -> Result<(), Box<dyn Error>> {
+    let name = binary_asset_name();
+    let binary = release.asset(&name).ok_or_else(|| {
+        MyError(format!(
+            "Release {} has no {} binary to update to.",
+            release.tag_name, name
+        ))
+    })?;
+    let checksum = release.asset(&format!("{}.sha256", name)).ok_or_else(|| {
+        MyError(format!(
+            "Release {} publishes no checksum for {}; not installing it.",
+            release.tag_name, name
+        ))
+    })?;
+    let expected = expected_sha256(&read_source(&checksum.browser_download_url)?, &name)
+        .ok_or_else(|| MyError(format!("Could not read the checksum for {}.", name)))?;
+
+    let staged = staging_path(exe);
+    download(&binary.browser_download_url, &staged)?;
+    let verified = sha256_of(&staged).and_then(|actual| {
+        if actual != expected {
+            return Err(MyError(format!(
+                "Checksum mismatch for {}: expected {}, got {}.",
+                name, expected, actual
+            ))
+            .into());
+        }
+        if skip_attestation {
+            warn(tr(Msg::SkippingAttestation));
+        } else {
+            verify_attestation(&staged)?;
+        }
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+        // Catches a binary that cannot run here (wrong libc) before it replaces a working one.
+        let runs = Command::new(&staged)
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if !runs {
+            return Err(MyError(format!("The downloaded {} does not run here.", name)).into());
+        }
+        std::fs::rename(&staged, exe)?;
+        Ok(())
+    });
+    if verified.is_err() {
+        let _ = std::fs::remove_file(&staged);
+    }
+    verified
+}
+
+/// Replaces the running executable with the latest release binary for this architecture after
+/// checking its SHA-256 and its build attestation; `skip_attestation` settles for the checksum.
+/// Installs under `/usr` belong to a package manager and are left alone unless `force`, which
+/// also reinstalls the current version.
+pub(crate) fn self_update(force: bool, skip_attestation: bool) -> Result<(), Box<dyn Error>> {
+    let exe = std::env::current_exe()?.canonicalize()?;
+    if exe.starts_with("/usr") && !force {
+        return Err(MyError(format!(
+            "{} looks package-managed; update it with your package manager, or pass --force.",
+            exe.display()
+        ))
+        .into());
+    }
+    let release = latest_release()?;
+    let current = env!("CARGO_PKG_VERSION");
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+    if !is_newer(&latest, current) && !force {
+        info(&trf(Msg::UpToDate, &[&current]));
+        return Ok(());
+    }
+    info(&trf(Msg::Updating, &[&exe.display(), &current, &latest]));
+    install_update(&release, &exe, skip_attestation).map_err(|e| {
+        MyError(format!(
+            "Update failed, {} was left unchanged: {}",
+            exe.display(),
+            e
+        ))
+    })?;
+    info(&trf(Msg::Updated, &[&latest]));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_versions_and_checksums_are_parsed() {
+        assert_eq!(parse_version("v0.10.2"), Some((0, 10, 2)));
+        assert_eq!(parse_version("1.2.0-rc.1"), Some((1, 2, 0)));
+        assert_eq!(parse_version("2"), Some((2, 0, 0)));
+        assert_eq!(parse_version("nightly"), None);
+
+        let release = |tag: &str| {
+            Ok(Release {
+                tag_name: tag.to_string(),
+                html_url: format!("https://example.invalid/{}", tag),
+                assets: Vec::new(),
+            })
+        };
+        let status = upgrade_status("0.2.1", release("v0.10.0"));
+        assert!(status.update_available);
+        assert_eq!(status.latest.as_deref(), Some("0.10.0"));
+        assert!(!upgrade_status("0.2.1", release("v0.2.1")).update_available);
+        assert!(!upgrade_status("0.2.1", release("nightly")).update_available);
+
+        assert_eq!(
+            expected_sha256(
+                "ABC123  hyprfinity-x86_64-linux\n",
+                "hyprfinity-x86_64-linux"
+            ),
+            Some("abc123".to_string())
+        );
+        assert_eq!(
+            expected_sha256(
+                "abc123 *hyprfinity-aarch64-linux",
+                "hyprfinity-x86_64-linux"
+            ),
+            None
+        );
+        assert_eq!(
+            expected_sha256("abc123\n", "hyprfinity-x86_64-linux"),
+            Some("abc123".to_string())
+        );
+
+        let failed = upgrade_status("0.2.1", Err(MyError("offline".to_string()).into()));
+        assert!(!failed.update_available);
+        assert_eq!(failed.error.as_deref(), Some("offline"));
+    }
+}
@@ -13,17 +13,48 @@ pub(crate) fn even_floor(v: i32) -> i32 {
 }
 
 pub(crate) fn scaled_dimensions(span_width: i32, span_height: i32, scale: f32) -> (i32, i32) {
-    let w = (span_width as f32 * scale).round() as i32;
-    let h = (span_height as f32 * scale).round() as i32;
-    let w = even_floor(clamp_i32(w, 2, span_width));
-    let h = even_floor(clamp_i32(h, 2, span_height));
+    scaled_dimensions_within(
+        span_width,
+        span_height,
+        (scale, scale),
+        (span_width, span_height),
+    )
+}
+
+/// Like `scaled_dimensions`, but with a scale per axis and bounded by `max` instead of the span,
+/// so supersampled sizes can exceed it.
+pub(crate) fn scaled_dimensions_within(
+    span_width: i32,
+    span_height: i32,
+    (scale_x, scale_y): (f32, f32),
+    (max_width, max_height): (i32, i32),
+) -> (i32, i32) {
+    let w = (span_width as f32 * scale_x).round() as i32;
+    let h = (span_height as f32 * scale_y).round() as i32;
+    let w = even_floor(clamp_i32(w, 2, max_width));
+    let h = even_floor(clamp_i32(h, 2, max_height));
     (w, h)
 }
 
-pub(crate) fn command_in_path(cmd: &str) -> bool {
-    let Some(paths) = std::env::var_os("PATH") else {
-        return false;
-    };
+/// `rel` under `$XDG_CACHE_HOME`, or `~/.cache` when it is unset.
+pub(crate) fn xdg_cache_path(rel: &str) -> Option<std::path::PathBuf> {
+    if let Ok(xdg) = std::env::var("XDG_CACHE_HOME")
+        && !xdg.is_empty()
+    {
+        return Some(std::path::PathBuf::from(xdg).join(rel));
+    }
+    std::env::var("HOME")
+        .ok()
+        .map(|home| std::path::PathBuf::from(home).join(".cache").join(rel))
+}
+
+pub(crate) fn stdio_is_interactive() -> bool {
+    use std::io::IsTerminal;
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+pub(crate) fn find_in_path(cmd: &str) -> Option<std::path::PathBuf> {
+    let paths = std::env::var_os("PATH")?;
     for path in std::env::split_paths(&paths) {
         let candidate = path.join(cmd);
         if let Ok(meta) = std::fs::metadata(&candidate)
@@ -33,21 +64,26 @@ pub(crate) fn command_in_path(cmd: &str) -> bool {
             {
                 use std::os::unix::fs::PermissionsExt;
                 if meta.permissions().mode() & 0o111 != 0 {
-                    return true;
+                    return Some(candidate);
                 }
             }
             #[cfg(not(unix))]
             {
-                return true;
+                return Some(candidate);
             }
         }
     }
-    false
+    None
+}
+
+pub(crate) fn command_in_path(cmd: &str) -> bool {
+    find_in_path(cmd).is_some()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn scaled_dimensions_rounds_and_clamps() {
@@ -55,5 +91,26 @@ mod tests {
         assert_eq!(scaled_dimensions(5, 5, 1.0), (4, 4));
         assert_eq!(scaled_dimensions(1920, 1080, 0.0001), (2, 2));
         assert_eq!(scaled_dimensions(1920, 1080, 1.2), (1920, 1080));
+        assert_eq!(
+            scaled_dimensions_within(1920, 1080, (1.5, 1.5), (3840, 2160)),
+            (2880, 1620)
+        );
+        assert_eq!(
+            scaled_dimensions_within(5760, 1080, (0.5, 1.0), (5760, 1080)),
+            (2880, 1080)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn scaled_dimensions_are_even_and_within_the_span(
+            span_width in any::<i32>(),
+            span_height in any::<i32>(),
+            scale in any::<f32>(),
+        ) {
+            let (w, h) = scaled_dimensions(span_width, span_height, scale);
+            prop_assert!(w >= 2 && w % 2 == 0 && w <= span_width.max(2));
+            prop_assert!(h >= 2 && h % 2 == 0 && h <= span_height.max(2));
+        }
     }
 }
@@ -0,0 +1,204 @@
+use crate::MyError;
+use crate::cli::StreamServer;
+use crate::compositor::compositor;
+use crate::config::LaunchSettings;
+use crate::gamescope::launch_session;
+use crate::hyprland::{execute_hyprctl, get_monitors};
+use crate::i18n::{Msg, trf};
+use crate::output::{debug, error, info};
+use crate::types::{ExcludedMonitors, SpanTarget};
+use crate::util::command_in_path;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+const VIRTUAL_OUTPUT_STATE_FILE_NAME: &str = "hyprfinity_virtual_output.json";
+const VIRTUAL_OUTPUT_WAIT_TICKS: u32 = 20;
+
+/// The headless output and stream started by `virtual-up`, kept so `gamescope-down` (or a
+/// Ctrl+C) can remove them after the session ends.
+#[derive(Debug, Serialize, Deserialize)]
+struct VirtualOutputState {
+    output: String,
+    #[serde(default)]
+    stream_pid: Option<u32>,
+}
+
+fn virtual_output_state_path() -> std::path::PathBuf {
+    std::env::temp_dir().join(VIRTUAL_OUTPUT_STATE_FILE_NAME)
+}
+
+/// `hyprctl output create` does not print the name it picked (HEADLESS-2, ...); diff instead.
+fn new_output_name(before: &[String], after: &[String]) -> Option<String> {
+    after.iter().find(|name| !before.contains(name)).cloned()
+}
+
+fn monitor_rule(name: &str, (width, height): (i32, i32), refresh: u32) -> String {
+    format!("{},{}x{}@{},auto,1", name, width, height, refresh)
+}
+
+fn monitor_names(verbose: bool) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(get_monitors(verbose)?
+        .into_iter()
+        .filter_map(|m| m.name)
+        .collect())
+}
+
+fn create_headless_output(
+    size: (i32, i32),
+    refresh: u32,
+    verbose: bool,
+) -> Result<String, Box<dyn Error>> {
+    let before = monitor_names(verbose)?;
+    execute_hyprctl(&["output", "create", "headless"], verbose)?;
+    let mut name = None;
+    for _ in 0..VIRTUAL_OUTPUT_WAIT_TICKS {
+        name = new_output_name(&before, &monitor_names(verbose)?);
+        if name.is_some() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let name =
+        name.ok_or_else(|| MyError("Hyprland did not report the new headless output.".into()))?;
+    execute_hyprctl(
+        &["keyword", "monitor", &monitor_rule(&name, size, refresh)],
+        verbose,
+    )?;
+    for _ in 0..VIRTUAL_OUTPUT_WAIT_TICKS {
+        let applied = get_monitors(verbose)?
+            .iter()
+            .any(|m| m.name.as_deref() == Some(name.as_str()) && (m.width, m.height) == size);
+        if applied {
+            return Ok(name);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let _ = execute_hyprctl(&["output", "remove", &name], verbose);
+    Err(MyError(format!(
+        "Headless output {} did not switch to {}x{}.",
+        name, size.0, size.1
+    ))
+    .into())
+}
+
+fn start_stream(server: StreamServer, output: &str, verbose: bool) -> Option<u32> {
+    let (program, args) = match server {
+        StreamServer::Wayvnc => ("wayvnc", vec!["-o".to_string(), output.to_string()]),
+        StreamServer::Sunshine => ("sunshine", vec![format!("output_name={}", output)]),
+    };
+    if !command_in_path(program) {
+        error(&trf(Msg::StreamerMissing, &[&program]));
+        return None;
+    }
+    if verbose {
+        debug(&format!("Executing {} with args: {:?}", program, args));
+    }
+    match Command::new(program)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => {
+            info(&trf(Msg::Streaming, &[&output, &program, &child.id()]));
+            Some(child.id())
+        }
+        Err(e) => {
+            error(&trf(Msg::StartFailed, &[&program, &e]));
+            None
+        }
+    }
+}
+
+/// Stops the stream and removes the headless output left by `virtual-up`, if any.
+pub(crate) fn teardown_virtual_output(verbose: bool) {
+    let path = virtual_output_state_path();
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    let _ = std::fs::remove_file(&path);
+    let state: VirtualOutputState = match serde_json::from_str(&text) {
+        Ok(state) => state,
+        Err(e) => {
+            error(&trf(Msg::VirtualStateUnreadable, &[&e]));
+            return;
+        }
+    };
+    if let Some(pid) = state.stream_pid {
+        let _ = Command::new("kill").arg(pid.to_string()).status();
+    }
+    match execute_hyprctl(&["output", "remove", &state.output], verbose) {
+        Ok(()) => info(&trf(Msg::RemovedHeadless, &[&state.output])),
+        Err(e) => error(&trf(Msg::RemoveHeadlessFailed, &[&state.output, &e])),
+    }
+}
+
+/// Creates a headless Hyprland output of `size`, runs a session spanning just that output,
+/// and optionally streams it. Everything is removed again when the session ends.
+pub(crate) fn virtual_up(
+    launch: &LaunchSettings,
+    size: (i32, i32),
+    refresh: u32,
+    stream: Option<StreamServer>,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    if compositor().name() != "hyprland" {
+        return Err(MyError(format!(
+            "virtual-up needs Hyprland's headless outputs; {} is not supported.",
+            compositor().name()
+        ))
+        .into());
+    }
+    // A crashed earlier run may have left its output behind.
+    teardown_virtual_output(verbose);
+    let output = create_headless_output(size, refresh, verbose)?;
+    info(&trf(
+        Msg::CreatedHeadless,
+        &[&output, &size.0, &size.1, &refresh],
+    ));
+    let stream_pid = stream.and_then(|server| start_stream(server, &output, verbose));
+    let state = VirtualOutputState {
+        output: output.clone(),
+        stream_pid,
+    };
+    std::fs::write(virtual_output_state_path(), serde_json::to_string(&state)?)?;
+
+    let result = execute_hyprctl(&["dispatch", "focusmonitor", &output], verbose).and_then(|_| {
+        let mut launch = launch.clone();
+        launch.span_target = SpanTarget::Focused;
+        launch.excluded_monitors = ExcludedMonitors::Keep;
+        launch.output_width = launch.output_width.or(Some(size.0));
+        launch.output_height = launch.output_height.or(Some(size.1));
+        launch_session(&launch, verbose)
+    });
+    teardown_virtual_output(verbose);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_output_is_found_by_diffing_monitor_names() {
+        let before = vec!["DP-1".to_string(), "HEADLESS-1".to_string()];
+        let after = vec![
+            "DP-1".to_string(),
+            "HEADLESS-1".to_string(),
+            "HEADLESS-2".to_string(),
+        ];
+        assert_eq!(
+            new_output_name(&before, &after).as_deref(),
+            Some("HEADLESS-2")
+        );
+        assert_eq!(new_output_name(&before, &before), None);
+        assert_eq!(
+            monitor_rule("HEADLESS-2", (3840, 1080), 60),
+            "HEADLESS-2,3840x1080@60,auto,1"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use crate::autotune::detect_auto_tune_profile;
+use crate::config::{
+    DEFAULT_MANGOHUD_CONFIG, default_config_values, print_config_table, prompt_yes_no,
+    render_config_template, resolve_config_path,
+};
+use crate::hyprland::get_monitors;
+use crate::i18n::{Msg, tr, trf};
+use crate::output::{error, info};
+use crate::picker::pick_desktop_app_command;
+use crate::recording::parse_hotkey;
+use crate::types::{DEFAULT_EXIT_HOTKEY, SpanTarget};
+use crate::util::stdio_is_interactive;
+use std::error::Error;
+use std::io::Write;
+
+fn prompt_line(prompt: &str, default: &str) -> Result<String, Box<dyn Error>> {
+    print!("{} [{}]: ", prompt, default);
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    let input = input.trim();
+    Ok(if input.is_empty() {
+        default.to_string()
+    } else {
+        input.to_string()
+    })
+}
+
+fn parse_span_choice(input: &str) -> Option<SpanTarget> {
+    match input.trim().to_lowercase().as_str() {
+        "a" | "all" => Some(SpanTarget::All),
+        "f" | "focused" => Some(SpanTarget::Focused),
+        _ => None,
+    }
+}
+
+fn parse_render_scale(input: &str) -> Option<f32> {
+    input
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|scale| (0.1..=1.0).contains(scale))
+}
+
+// MangoHud's overlay hint spells the bind as "SUPER+SHIFT+F12".
+fn overlay_hotkey_label(mods: &str, key: &str) -> String {
+    mods.split_whitespace()
+        .chain(std::iter::once(key))
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+pub(crate) fn should_run_first_run_wizard(path_override: &Option<String>) -> bool {
+    stdio_is_interactive() && resolve_config_path(path_override).is_ok_and(|path| !path.exists())
+}
+
+/// Walks a new user through the main settings and writes a config. Returns whether to
+/// launch a session right away.
+pub(crate) fn run_first_run_wizard(
+    path_override: &Option<String>,
+    verbose: bool,
+) -> Result<bool, Box<dyn Error>> {
+    let path = resolve_config_path(path_override)?;
+    info(tr(Msg::WizardStart));
+    let auto = detect_auto_tune_profile();
+    let mut config = default_config_values(&auto);
+
+    info(tr(Msg::WizardStepMonitors));
+    match get_monitors(verbose) {
+        Ok(monitors) => {
+            for monitor in &monitors {
+                info(&trf(
+                    Msg::WizardMonitor,
+                    &[
+                        &monitor
+                            .name
+                            .as_deref()
+                            .unwrap_or(tr(Msg::WizardMonitorUnnamed)),
+                        &monitor.width,
+                        &monitor.height,
+                        &monitor.x,
+                        &monitor.y,
+                        &if monitor.focused {
+                            tr(Msg::WizardMonitorFocused)
+                        } else {
+                            ""
+                        },
+                    ],
+                ));
+            }
+        }
+        Err(e) => error(&trf(Msg::WizardMonitorsFailed, &[&e])),
+    }
+    config.span_target = loop {
+        let answer = prompt_line(tr(Msg::WizardSpanPrompt), "all")?;
+        match parse_span_choice(&answer) {
+            Some(target) => break Some(target),
+            None => info(tr(Msg::WizardSpanRetry)),
+        }
+    };
+
+    info(tr(Msg::WizardStepRenderScale));
+    info(&trf(Msg::WizardAutoTune, &[&auto.reason]));
+    let suggested = format!("{:.2}", auto.render_scale);
+    config.render_scale = loop {
+        let answer = prompt_line(tr(Msg::WizardRenderScalePrompt), &suggested)?;
+        match parse_render_scale(&answer) {
+            Some(scale) => break Some(scale),
+            None => info(tr(Msg::WizardRenderScaleRetry)),
+        }
+    };
+
+    info(tr(Msg::WizardStepDefaultApp));
+    if prompt_yes_no(tr(Msg::WizardDefaultAppPrompt), true)? {
+        match pick_desktop_app_command(None) {
+            Ok(command) => {
+                info(&trf(Msg::WizardDefaultCommand, &[&command.join(" ")]));
+                config.default_command = Some(command);
+            }
+            Err(e) => error(&trf(Msg::WizardNoDefaultApp, &[&e])),
+        }
+    }
+
+    info(tr(Msg::WizardStepExitHotkey));
+    let (mods, key) = loop {
+        let answer = prompt_line(tr(Msg::WizardExitHotkeyPrompt), DEFAULT_EXIT_HOTKEY)?;
+        match parse_hotkey(&answer) {
+            Some(parsed) => break parsed,
+            None => info(tr(Msg::WizardExitHotkeyRetry)),
+        }
+    };
+    config.exit_hotkey = Some(format!("{}, {}", mods, key));
+    config.mangohud_config = Some(
+        DEFAULT_MANGOHUD_CONFIG.replace("SUPER+SHIFT+F12", &overlay_hotkey_label(&mods, &key)),
+    );
+
+    info(tr(Msg::WizardStepSave));
+    print_config_table(tr(Msg::WizardConfigTitle), &config);
+    if !prompt_yes_no(&trf(Msg::WizardWritePrompt, &[&path.display()]), true)? {
+        info(tr(Msg::WizardCancelled));
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, render_config_template(&config, &auto.reason))?;
+    info(&trf(Msg::WroteConfig, &[&path.display()]));
+
+    prompt_yes_no(tr(Msg::WizardLaunchPrompt), true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_wizard_answers() {
+        assert_eq!(parse_span_choice(" Focused "), Some(SpanTarget::Focused));
+        assert_eq!(parse_span_choice("a"), Some(SpanTarget::All));
+        assert_eq!(parse_span_choice("left"), None);
+        assert_eq!(parse_render_scale("0.75"), Some(0.75));
+        assert_eq!(parse_render_scale("1.5"), None);
+        assert_eq!(
+            overlay_hotkey_label("SUPER SHIFT", "F12"),
+            "SUPER+SHIFT+F12"
+        );
+        assert_eq!(overlay_hotkey_label("", "F9"), "F9");
+    }
+}
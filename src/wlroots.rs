@@ -0,0 +1,243 @@
+use crate::MyError;
+use crate::compositor::{Compositor, WindowRect, run_ipc, transform_index};
+use crate::debuglog::debug_log_line;
+use crate::output::debug;
+use crate::types::{Client, HotkeyBind, Monitor};
+use crate::util::command_in_path;
+use serde::Deserialize;
+use std::error::Error;
+use std::process::Command;
+
+/// Other wlroots compositors (river, labwc, Wayfire, ...) through `wlr-randr` for outputs and
+/// `wlrctl` for wlr-foreign-toplevel-management. That protocol can fullscreen a toplevel but
+/// not move or resize it, so a nested session covers the one output gamescope opens on
+/// (`span_target = "focused"`). It reports neither PIDs nor geometry, so the window is found
+/// by its `gamescope` app id; there is no runtime keybinding IPC either.
+pub(crate) struct WlrootsCompositor;
+
+/// The foreign-toplevel match for gamescope's nested window.
+const GAMESCOPE_TOPLEVEL: &str = "app_id:gamescope";
+
+#[derive(Debug, Deserialize)]
+struct WlrMode {
+    width: i32,
+    height: i32,
+    #[serde(default)]
+    refresh: Option<f32>,
+    #[serde(default)]
+    current: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct WlrPosition {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct WlrOutput {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    modes: Vec<WlrMode>,
+    #[serde(default)]
+    position: Option<WlrPosition>,
+    #[serde(default)]
+    transform: Option<String>,
+    #[serde(default)]
+    scale: Option<f32>,
+}
+
+fn wlr_randr_to_monitors(json: &str) -> serde_json::Result<Vec<Monitor>> {
+    let outputs: Vec<WlrOutput> = serde_json::from_str(json)?;
+    Ok(outputs
+        .into_iter()
+        .map(|output| {
+            let mode = output.modes.iter().find(|m| m.current);
+            let position = output.position.unwrap_or(WlrPosition { x: 0, y: 0 });
+            Monitor {
+                name: Some(output.name),
+                description: output.description,
+                model: output.model,
+                width: mode.map_or(0, |m| m.width),
+                height: mode.map_or(0, |m| m.height),
+                x: position.x,
+                y: position.y,
+                refresh_rate: mode.and_then(|m| m.refresh),
+                scale: output.scale,
+                transform: output.transform.as_deref().map(transform_index),
+                disabled: !output.enabled || mode.is_none(),
+                ..Monitor::default()
+            }
+        })
+        .collect())
+}
+
+/// The enabled output `rect` covers exactly, the only placement fullscreen can give.
+fn output_for_rect(monitors: &[Monitor], (x, y, width, height): WindowRect) -> Option<&Monitor> {
+    monitors
+        .iter()
+        .filter(|m| !m.disabled)
+        .find(|m| m.x == x && m.y == y && m.width == width && m.height == height)
+}
+
+/// Where a fullscreen window sits, when there is only one enabled output it can be on.
+fn fullscreen_output(monitors: &[Monitor]) -> Option<WindowRect> {
+    let mut enabled = monitors.iter().filter(|m| !m.disabled);
+    match (enabled.next(), enabled.next()) {
+        (Some(m), None) => Some((m.x, m.y, m.width, m.height)),
+        _ => None,
+    }
+}
+
+/// `wlrctl toplevel find` exits non-zero when nothing matches, so this reads the status
+/// instead of going through `run_ipc`.
+fn toplevel_exists(matches: &[&str], verbose: bool) -> Result<bool, Box<dyn Error>> {
+    let mut args = vec!["toplevel", "find"];
+    args.extend_from_slice(matches);
+    debug_log_line(&format!("wlrctl {:?}", args));
+    if verbose {
+        debug(&format!("Executing wlrctl with args: {:?}", args));
+    }
+    let output = Command::new("wlrctl")
+        .args(&args)
+        .output()
+        .map_err(|e| MyError(format!("Failed to run wlrctl: {}", e)))?;
+    Ok(output.status.success())
+}
+
+impl Compositor for WlrootsCompositor {
+    fn name(&self) -> &'static str {
+        "wlroots"
+    }
+
+    fn monitors(&self, verbose: bool) -> Result<Vec<Monitor>, Box<dyn Error>> {
+        let stdout = run_ipc("wlr-randr", &["--json"], verbose)?;
+        Ok(wlr_randr_to_monitors(&stdout)
+            .map_err(|e| MyError(format!("Failed to parse wlr-randr output: {}", e)))?)
+    }
+
+    fn clients(&self, verbose: bool) -> Result<Vec<Client>, Box<dyn Error>> {
+        if !toplevel_exists(&[GAMESCOPE_TOPLEVEL], verbose)? {
+            return Ok(Vec::new());
+        }
+        // A fullscreen window covers its output; with a single output, that is known.
+        let fullscreen_on = if toplevel_exists(&[GAMESCOPE_TOPLEVEL, "state:fullscreen"], verbose)?
+        {
+            fullscreen_output(&self.monitors(verbose)?)
+        } else {
+            None
+        };
+        // No PID: the startup probe matches it by class (`window_class`, "^gamescope$").
+        Ok(vec![Client {
+            pid: 0,
+            address: Some(GAMESCOPE_TOPLEVEL.to_string()),
+            at: fullscreen_on.map(|(x, y, _, _)| [x, y]),
+            size: fullscreen_on.map(|(_, _, w, h)| [w, h]),
+            class: Some("gamescope".to_string()),
+            ..Client::default()
+        }])
+    }
+
+    fn window_selector(&self, _pid: u32, client: Option<&Client>) -> String {
+        client
+            .and_then(|c| c.address.clone())
+            .unwrap_or_else(|| GAMESCOPE_TOPLEVEL.to_string())
+    }
+
+    fn place_window(
+        &self,
+        window: &str,
+        rect: WindowRect,
+        _float: bool,
+        _pin: bool,
+        verbose: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let monitors = self.monitors(verbose)?;
+        let Some(output) = output_for_rect(&monitors, rect) else {
+            return Err(MyError(format!(
+                "wlr-foreign-toplevel-management can only fullscreen a window on one output, \
+                 not place it at {},{} {}x{}; set span_target = \"focused\".",
+                rect.0, rect.1, rect.2, rect.3
+            ))
+            .into());
+        };
+        debug_log_line(&format!(
+            "fullscreening {} for output {:?}",
+            window, output.name
+        ));
+        run_ipc("wlrctl", &["toplevel", "fullscreen", window], verbose).map(|_| ())
+    }
+
+    fn bind_key(
+        &self,
+        mods: &str,
+        key: &str,
+        _bind: HotkeyBind,
+        command: &str,
+        _verbose: bool,
+    ) -> Result<bool, Box<dyn Error>> {
+        Err(MyError(format!(
+            "wlroots compositors have no common keybinding IPC; bind {}+{} to `{}` in your \
+             compositor config instead.",
+            mods, key, command
+        ))
+        .into())
+    }
+
+    fn unbind_key(&self, _mods: &str, _key: &str, _verbose: bool) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn window_control(&self) -> bool {
+        command_in_path("wlrctl")
+    }
+
+    fn chooses_output(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wlr_randr_outputs_map_to_monitors() {
+        let json = r#"[
+            {"name": "DP-1", "description": "Dell U2720Q", "enabled": true,
+             "modes": [{"width": 3840, "height": 2160, "refresh": 60.0, "preferred": true, "current": true}],
+             "position": {"x": 0, "y": 0}, "transform": "normal", "scale": 1.5},
+            {"name": "HDMI-A-1", "enabled": true,
+             "modes": [{"width": 1920, "height": 1080, "refresh": 60.0, "current": false},
+                       {"width": 1080, "height": 1920, "refresh": 75.0, "current": true}],
+             "position": {"x": 2560, "y": 0}, "transform": "90", "scale": 1.0},
+            {"name": "eDP-1", "enabled": false, "modes": [], "position": {"x": 0, "y": 0}}
+        ]"#;
+        let monitors = wlr_randr_to_monitors(json).unwrap();
+        assert_eq!(monitors.len(), 3);
+        assert_eq!(monitors[0].width, 3840);
+        assert_eq!(monitors[0].scale, Some(1.5));
+        assert_eq!(monitors[1].x, 2560);
+        assert_eq!(monitors[1].height, 1920);
+        assert_eq!(monitors[1].refresh_rate, Some(75.0));
+        assert_eq!(monitors[1].transform, Some(1));
+        assert!(monitors[2].disabled);
+
+        // Fullscreen covers one enabled output; a span across two has no placement.
+        assert_eq!(
+            output_for_rect(&monitors, (2560, 0, 1080, 1920)).and_then(|m| m.name.as_deref()),
+            Some("HDMI-A-1")
+        );
+        assert!(output_for_rect(&monitors, (0, 0, 3640, 2160)).is_none());
+        assert!(output_for_rect(&monitors, (0, 0, 0, 0)).is_none());
+        // Two enabled outputs: foreign-toplevel does not say which one is fullscreen.
+        assert_eq!(fullscreen_output(&monitors), None);
+        assert_eq!(fullscreen_output(&monitors[..1]), Some((0, 0, 3840, 2160)));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::MyError;
+use crate::compositor::{Compositor, WindowRect, run_ipc};
+use crate::types::{Client, HotkeyBind, Monitor};
+use std::error::Error;
+
+/// Plain X11 (or Hyprland's XWayland when testing) through `xrandr` and `wmctrl`. Window
+/// managers differ in how they treat `_NET_WM_STATE`, so "pin" maps to sticky + above.
+pub(crate) struct X11Compositor;
+
+fn parse_geometry(token: &str) -> Option<(i32, i32, i32, i32)> {
+    let (size, offsets) = token.split_once('+')?;
+    let (width, height) = size.split_once('x')?;
+    let (x, y) = offsets.split_once('+')?;
+    Some((
+        width.parse().ok()?,
+        height.parse().ok()?,
+        x.parse().ok()?,
+        y.parse().ok()?,
+    ))
+}
+
+/// Parses `xrandr --query`: connected outputs with a geometry are enabled; the mode line
+/// marked `*` carries the refresh rate.
+fn parse_xrandr(text: &str) -> Vec<Monitor> {
+    let mut monitors: Vec<Monitor> = Vec::new();
+    let mut current_is_output = false;
+    for line in text.lines() {
+        if line.starts_with(char::is_whitespace) {
+            if current_is_output
+                && let Some(monitor) = monitors.last_mut()
+                && monitor.refresh_rate.is_none()
+                && let Some(rate) = line
+                    .split_whitespace()
+                    .skip(1)
+                    .find(|token| token.contains('*'))
+            {
+                monitor.refresh_rate = rate.trim_end_matches(['*', '+']).parse().ok();
+            }
+            continue;
+        }
+        let mut tokens = line.split_whitespace();
+        let (Some(name), Some("connected")) = (tokens.next(), tokens.next()) else {
+            current_is_output = false;
+            continue;
+        };
+        let rest = tokens
+            .take_while(|t| !t.starts_with('('))
+            .collect::<Vec<_>>();
+        let geometry = rest.iter().find_map(|t| parse_geometry(t));
+        let transform = rest.iter().find_map(|t| match *t {
+            "left" => Some(1),
+            "inverted" => Some(2),
+            "right" => Some(3),
+            _ => None,
+        });
+        let (width, height, x, y) = geometry.unwrap_or((0, 0, 0, 0));
+        monitors.push(Monitor {
+            name: Some(name.to_string()),
+            width,
+            height,
+            x,
+            y,
+            transform: Some(transform.unwrap_or(0)),
+            focused: rest.contains(&"primary"),
+            disabled: geometry.is_none(),
+            ..Monitor::default()
+        });
+        current_is_output = true;
+    }
+    monitors
+}
+
+/// Parses `wmctrl -lpG`: `ID DESKTOP PID X Y W H HOST TITLE...`.
+fn parse_wmctrl(text: &str) -> Vec<Client> {
+    text.lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().take(7).collect::<Vec<_>>();
+            let [id, _, pid, x, y, w, h] = fields.as_slice() else {
+                return None;
+            };
+            Some(Client {
+                pid: pid.parse().ok().filter(|pid| *pid > 0)?,
+                address: Some(id.to_string()),
+                at: Some([x.parse().ok()?, y.parse().ok()?]),
+                size: Some([w.parse().ok()?, h.parse().ok()?]),
+                ..Client::default()
+            })
+        })
+        .collect()
+}
+
+impl Compositor for X11Compositor {
+    fn name(&self) -> &'static str {
+        "x11"
+    }
+
+    fn monitors(&self, verbose: bool) -> Result<Vec<Monitor>, Box<dyn Error>> {
+        Ok(parse_xrandr(&run_ipc("xrandr", &["--query"], verbose)?))
+    }
+
+    fn clients(&self, verbose: bool) -> Result<Vec<Client>, Box<dyn Error>> {
+        Ok(parse_wmctrl(&run_ipc("wmctrl", &["-lpG"], verbose)?))
+    }
+
+    fn window_selector(&self, pid: u32, client: Option<&Client>) -> String {
+        client
+            .and_then(|c| c.address.clone())
+            .unwrap_or_else(|| format!("pid:{}", pid))
+    }
+
+    fn place_window(
+        &self,
+        window: &str,
+        (x, y, width, height): WindowRect,
+        float: bool,
+        pin: bool,
+        verbose: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        if window.starts_with("pid:") {
+            return Err(
+                MyError(format!("wmctrl does not list a window for {} yet.", window)).into(),
+            );
+        }
+        let wmctrl = |args: &[&str]| {
+            let mut full = vec!["-i", "-r", window];
+            full.extend_from_slice(args);
+            run_ipc("wmctrl", &full, verbose).map(|_| ())
+        };
+        if float {
+            // A maximized window ignores move/resize requests.
+            wmctrl(&["-b", "remove,maximized_vert,maximized_horz"])?;
+            wmctrl(&["-b", "remove,fullscreen"])?;
+            if pin {
+                wmctrl(&["-b", "add,sticky,above"])?;
+            }
+        }
+        wmctrl(&["-e", &format!("0,{},{},{},{}", x, y, width, height)])
+    }
+
+    fn bind_key(
+        &self,
+        mods: &str,
+        key: &str,
+        _bind: HotkeyBind,
+        command: &str,
+        _verbose: bool,
+    ) -> Result<bool, Box<dyn Error>> {
+        Err(MyError(format!(
+            "X11 has no portable way to add global hotkeys; bind {}+{} to `{}` in your window manager.",
+            mods, key, command
+        ))
+        .into())
+    }
+
+    fn unbind_key(&self, _mods: &str, _key: &str, _verbose: bool) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_xrandr_reads_connected_outputs() {
+        let text = "\
+Screen 0: minimum 320 x 200, current 5760 x 1920, maximum 16384 x 16384
+DP-1 connected primary 1920x1080+0+0 (normal left inverted right x axis y axis) 527mm x 296mm
+   1920x1080     60.00 +  144.00*
+   1280x1024     60.02
+HDMI-1 connected 1080x1920+1920+0 left (normal left inverted right x axis y axis) 527mm x 296mm
+   1920x1080     60.00*+
+HDMI-2 disconnected (normal left inverted right x axis y axis)
+DP-2 connected (normal left inverted right x axis y axis)
+   2560x1440     59.95 +
+";
+        let monitors = parse_xrandr(text);
+        assert_eq!(monitors.len(), 3);
+        assert_eq!(monitors[0].name.as_deref(), Some("DP-1"));
+        assert!(monitors[0].focused);
+        assert_eq!(monitors[0].refresh_rate, Some(144.0));
+        assert_eq!(
+            (monitors[1].width, monitors[1].height, monitors[1].x),
+            (1080, 1920, 1920)
+        );
+        assert_eq!(monitors[1].transform, Some(1));
+        assert!(monitors[2].disabled);
+    }
+
+    #[test]
+    fn parse_wmctrl_reads_pid_and_geometry() {
+        let text = "\
+0x03a00003  0 4242   0    0    1280 800  host gamescope
+0x01200001 -1 0      0    0    3840 24   host panel
+";
+        let clients = parse_wmctrl(text);
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].pid, 4242);
+        assert_eq!(clients[0].address.as_deref(), Some("0x03a00003"));
+        assert_eq!(clients[0].size, Some([1280, 800]));
+    }
+}
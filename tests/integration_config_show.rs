@@ -16,6 +16,8 @@ startup_timeout_secs = 15
     fs::write(&config_path, config_contents).expect("write config");
 
     let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hyprfinity");
+    // The stderr check below expects the English catalog.
+    cmd.env("LC_ALL", "C");
     cmd.args([
         "--config",
         config_path.to_str().expect("config path"),
@@ -29,15 +31,66 @@ startup_timeout_secs = 15
         "120",
     ]);
 
-    cmd.assert().success().stdout(
-        predicate::str::contains("Hyprfinity: Config path:")
-            .and(predicate::str::contains(config_path.to_str().unwrap()))
-            .and(predicate::str::contains(
+    cmd.assert()
+        .success()
+        .stderr(
+            predicate::str::contains("Hyprfinity: Config path:")
+                .and(predicate::str::contains(config_path.to_str().unwrap())),
+        )
+        .stdout(
+            predicate::str::contains(
                 "[\"-r\", \"120\", \"--\", \"steam\", \"-applaunch\", \"620\"]",
-            ))
+            )
             .and(predicate::str::contains("startup_timeout_secs"))
             .and(predicate::str::contains("20"))
             .and(predicate::str::contains("render_scale"))
             .and(predicate::str::contains("1")),
+        );
+}
+
+#[test]
+fn show_alias_runs_config_show() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("config.toml");
+    fs::write(&config_path, "hide_waybar = true\n").expect("write config");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hyprfinity");
+    cmd.args([
+        "--config",
+        config_path.to_str().expect("config path"),
+        "show",
+        "--no-hide-waybar",
+    ]);
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("Effective Values").and(predicate::str::contains("hide_waybar")),
     );
 }
+
+#[test]
+fn config_show_diff_json_reports_value_sources() {
+    let dir = tempdir().expect("tempdir");
+    let config_path = dir.path().join("config.toml");
+    fs::write(&config_path, "hide_waybar = true\n").expect("write config");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hyprfinity");
+    cmd.args([
+        "--config",
+        config_path.to_str().expect("config path"),
+        "config-show",
+        "--diff",
+        "--json",
+        "--pick",
+    ]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let origins: Vec<serde_json::Value> = serde_json::from_slice(&output).expect("json");
+    let source = |key: &str| {
+        origins
+            .iter()
+            .find(|o| o["key"] == key)
+            .map(|o| o["source"].as_str().unwrap_or_default().to_string())
+    };
+    assert_eq!(source("hide_waybar").as_deref(), Some("config"));
+    assert_eq!(source("pick").as_deref(), Some("cli"));
+    assert_eq!(source("no_pin").as_deref(), Some("default"));
+}
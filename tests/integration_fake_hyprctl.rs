@@ -0,0 +1,252 @@
+// Runs the real hyprfinity binary against `fake-hyprctl` and a scripted `gamescope` found through
+// PATH, so sessions go through the same process calls as on Hyprland. Needs the fixture binary:
+// `cargo test --features fake-hyprctl`.
+#![cfg(feature = "fake-hyprctl")]
+
+use std::fs;
+use std::os::unix::fs::{PermissionsExt, symlink};
+use std::path::{Path, PathBuf};
+use tempfile::{TempDir, tempdir};
+
+// Registers a window under its own PID, then becomes the game command after `--`.
+const FAKE_GAMESCOPE: &str = r#"#!/bin/sh
+if [ "$1" = "--help" ] || [ "$1" = "--version" ]; then exit 0; fi
+echo "gamescope $*" >> "$FAKE_HYPRCTL_DIR/calls.log"
+printf '[{"pid": %d, "address": "0x1000", "class": "gamescope", "title": "gamescope", "at": [0, 0], "size": [1280, 800]}]' $$ > "$FAKE_HYPRCTL_DIR/clients.json"
+while [ "$#" -gt 0 ] && [ "$1" != "--" ]; do shift; done
+[ "$#" -gt 0 ] && shift
+[ "$#" -gt 0 ] && exec "$@"
+exit 0
+"#;
+
+const TWO_MONITORS: &str = r#"[
+    {"name": "DP-1", "width": 1920, "height": 1080, "x": 0, "y": 0, "refreshRate": 60.0},
+    {"name": "DP-2", "width": 1920, "height": 1080, "x": 1920, "y": 0, "refreshRate": 60.0, "focused": true}
+]"#;
+
+/// A temp dir with `bin/hyprctl`, `bin/gamescope` and the fake-hyprctl fixture files.
+fn fake_hyprland(monitors: &str) -> TempDir {
+    let dir = tempdir().expect("tempdir");
+    let bin = dir.path().join("bin");
+    fs::create_dir(&bin).expect("bin dir");
+    symlink(env!("CARGO_BIN_EXE_fake-hyprctl"), bin.join("hyprctl")).expect("hyprctl link");
+    let gamescope = bin.join("gamescope");
+    fs::write(&gamescope, FAKE_GAMESCOPE).expect("write gamescope");
+    fs::set_permissions(&gamescope, fs::Permissions::from_mode(0o755)).expect("chmod gamescope");
+    fs::write(dir.path().join("monitors.json"), monitors).expect("write monitors");
+    dir
+}
+
+fn hyprfinity(dir: &Path) -> assert_cmd::Command {
+    let path = std::env::join_paths(
+        std::iter::once(dir.join("bin")).chain(std::env::split_paths(
+            &std::env::var_os("PATH").unwrap_or_default(),
+        )),
+    )
+    .expect("PATH");
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hyprfinity");
+    cmd.env("PATH", path)
+        .env("FAKE_HYPRCTL_DIR", dir)
+        .env("HYPRLAND_INSTANCE_SIGNATURE", "fake")
+        .env_remove("HYPRFINITY_MOCK_COMPOSITOR")
+        .env("TMPDIR", dir)
+        .env("HOME", dir)
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .env("XDG_DATA_HOME", dir.join("data"))
+        .timeout(std::time::Duration::from_secs(30));
+    cmd
+}
+
+const SESSION_FLAGS: [&str; 4] = [
+    "gamescope-up",
+    "--no-idle-inhibit",
+    "--no-hide-waybar",
+    "--no-overlay",
+];
+
+fn calls(dir: &Path) -> String {
+    fs::read_to_string(dir.join("calls.log")).expect("calls log")
+}
+
+fn state_file(dir: &Path) -> PathBuf {
+    dir.join("hyprfinity_gamescope_state.json")
+}
+
+#[test]
+fn gamescope_up_spans_fits_and_tears_down_through_hyprctl() {
+    let dir = fake_hyprland(TWO_MONITORS);
+    hyprfinity(dir.path())
+        .args(SESSION_FLAGS)
+        .args(["--", "--", "sleep", "1"])
+        .assert()
+        .success();
+
+    let calls = calls(dir.path());
+    assert!(calls.contains("monitors -j"), "{}", calls);
+    assert!(calls.contains("-W 3840 -H 1080"), "{}", calls);
+    assert!(calls.contains("-- sleep 1"), "{}", calls);
+    assert!(calls.contains("dispatch setfloating"), "{}", calls);
+    assert!(
+        calls.contains("dispatch movewindowpixel exact 0 0"),
+        "{}",
+        calls
+    );
+    assert!(
+        calls.contains("dispatch resizewindowpixel exact 3840 1080"),
+        "{}",
+        calls
+    );
+    // The exit hotkey is bound for the session and released at teardown.
+    assert!(calls.contains("keyword bind"), "{}", calls);
+    assert!(calls.contains("keyword unbind"), "{}", calls);
+    let clients = fs::read_to_string(dir.path().join("clients.json")).expect("clients");
+    assert!(clients.contains(r#""size":[3840,1080]"#), "{}", clients);
+    assert!(
+        !state_file(dir.path()).exists(),
+        "state file should be removed"
+    );
+}
+
+#[test]
+fn focused_span_blanks_and_restores_the_other_monitor() {
+    let dir = fake_hyprland(TWO_MONITORS);
+    hyprfinity(dir.path())
+        .args([
+            "--set",
+            r#"span_target="focused""#,
+            "--set",
+            r#"excluded_monitors="blank""#,
+        ])
+        .args(SESSION_FLAGS)
+        .args(["--", "--", "sleep", "1"])
+        .assert()
+        .success();
+
+    let calls = calls(dir.path());
+    assert!(calls.contains("-W 1920 -H 1080"), "{}", calls);
+    assert!(
+        calls.contains("dispatch movewindowpixel exact 1920 0"),
+        "{}",
+        calls
+    );
+    let off = calls.find("dispatch dpms off DP-1").expect("DP-1 blanked");
+    let on = calls.find("dispatch dpms on DP-1").expect("DP-1 restored");
+    assert!(off < on, "{}", calls);
+    assert!(!calls.contains("dpms off DP-2"), "{}", calls);
+}
+
+#[test]
+fn saved_plan_runs_without_config_or_monitor_detection() {
+    let dir = fake_hyprland(TWO_MONITORS);
+    let output = hyprfinity(dir.path())
+        .args(SESSION_FLAGS)
+        .args(["--plan-json", "--", "--", "sleep", "1"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let plan = dir.path().join("plan.json");
+    fs::write(&plan, &output).expect("write plan");
+    let planned = calls(dir.path());
+    assert!(!planned.contains("gamescope -"), "{}", planned);
+    assert!(
+        String::from_utf8_lossy(&output).contains(r#""placement": "reflow""#),
+        "{}",
+        String::from_utf8_lossy(&output)
+    );
+
+    // Read by any launch that resolves the config: it would shrink the output to 1920 wide.
+    let config = dir.path().join("config").join("hyprfinity");
+    fs::create_dir_all(&config).expect("config dir");
+    fs::write(config.join("config.toml"), "output_width = 1920\n").expect("write config");
+    fs::write(dir.path().join("calls.log"), "").expect("reset calls");
+    hyprfinity(dir.path())
+        .args(["up", "--plan"])
+        .arg(&plan)
+        .assert()
+        .success();
+
+    let calls = calls(dir.path());
+    assert!(!calls.contains("monitors -j"), "{}", calls);
+    assert!(!calls.contains("plugin list"), "{}", calls);
+    assert!(calls.contains("-W 3840 -H 1080"), "{}", calls);
+    assert!(
+        calls.contains("dispatch resizewindowpixel exact 3840 1080"),
+        "{}",
+        calls
+    );
+    assert!(
+        !state_file(dir.path()).exists(),
+        "state file should be removed"
+    );
+}
+
+#[test]
+fn hold_exit_hotkey_registers_a_long_press_bind() {
+    let dir = fake_hyprland(TWO_MONITORS);
+    hyprfinity(dir.path())
+        .args([
+            "--set",
+            r#"exit_hotkey_bind="hold""#,
+            "--set",
+            "exit_hotkey_confirm=true",
+        ])
+        .args(SESSION_FLAGS)
+        .args(["--", "--", "sleep", "1"])
+        .assert()
+        .success();
+
+    let calls = calls(dir.path());
+    assert!(
+        calls.contains("keyword bindo SUPER SHIFT, F12, exec, hyprfinity gamescope-down --confirm"),
+        "{}",
+        calls
+    );
+    assert!(
+        calls.contains("keyword unbind SUPER SHIFT, F12"),
+        "{}",
+        calls
+    );
+}
+
+#[test]
+fn config_reload_rebinds_the_exit_hotkey() {
+    use std::io::Write;
+    use std::os::unix::net::UnixListener;
+
+    let dir = fake_hyprland(TWO_MONITORS);
+    let socket_dir = dir.path().join("hypr/fake");
+    fs::create_dir_all(&socket_dir).expect("socket dir");
+    let listener = UnixListener::bind(socket_dir.join(".socket2.sock")).expect("event socket");
+    let events = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("accept");
+        stream
+            .write_all(b"activewindow>>gamescope,gamescope\nconfigreloaded>>\n")
+            .expect("send events");
+        // Held open until the session hangs up, like Hyprland's socket.
+        let _ = std::io::copy(&mut stream, &mut std::io::sink());
+    });
+    hyprfinity(dir.path())
+        .env("XDG_RUNTIME_DIR", dir.path())
+        .args(SESSION_FLAGS)
+        .args(["--", "--", "sleep", "3"])
+        .assert()
+        .success();
+    events.join().expect("event thread");
+
+    let calls = calls(dir.path());
+    assert_eq!(
+        calls
+            .matches("keyword bind SUPER SHIFT, F12, exec, hyprfinity gamescope-down")
+            .count(),
+        2,
+        "{}",
+        calls
+    );
+    assert!(
+        calls.contains("keyword unbind SUPER SHIFT, F12"),
+        "{}",
+        calls
+    );
+}
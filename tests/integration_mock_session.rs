@@ -0,0 +1,139 @@
+use std::fs;
+use tempfile::tempdir;
+
+// Runs a full gamescope-up session against the fake compositor selected by
+// HYPRFINITY_MOCK_COMPOSITOR: the "game" is a short sleep standing in for gamescope.
+#[test]
+fn gamescope_up_fits_window_to_span_and_cleans_up_with_mock_compositor() {
+    let dir = tempdir().expect("tempdir");
+    let fixture = dir.path().join("compositor.json");
+    let log = dir.path().join("hyprctl.log");
+    fs::write(
+        &fixture,
+        r#"{"monitors": [
+            {"name": "DP-1", "width": 1920, "height": 1080, "x": 0, "y": 0, "refreshRate": 60.0},
+            {"name": "DP-2", "width": 1920, "height": 1080, "x": 1920, "y": 0, "refreshRate": 60.0}
+        ]}"#,
+    )
+    .expect("write fixture");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hyprfinity");
+    cmd.env("HYPRFINITY_MOCK_COMPOSITOR", &fixture)
+        .env("HYPRFINITY_MOCK_LOG", &log)
+        .env("TMPDIR", dir.path())
+        .env("HOME", dir.path())
+        .env("XDG_CONFIG_HOME", dir.path().join("config"))
+        .env("XDG_DATA_HOME", dir.path().join("data"))
+        .args([
+            "gamescope-up",
+            "--no-idle-inhibit",
+            "--no-hide-waybar",
+            "--no-overlay",
+            "--",
+            "--",
+            "sleep",
+            "1",
+        ]);
+    cmd.timeout(std::time::Duration::from_secs(30))
+        .assert()
+        .success();
+
+    let calls = fs::read_to_string(&log).expect("hyprctl log");
+    assert!(calls.contains("-W 3840 -H 1080"), "{}", calls);
+    assert!(calls.contains("movewindowpixel exact 0 0"), "{}", calls);
+    assert!(
+        calls.contains("resizewindowpixel exact 3840 1080"),
+        "{}",
+        calls
+    );
+    assert!(calls.contains("keyword unbind"), "{}", calls);
+    assert!(
+        !dir.path().join("hyprfinity_gamescope_state.json").exists(),
+        "state file should be removed after the session"
+    );
+}
+
+#[test]
+fn recorded_mock_session_replays_offline() {
+    let dir = tempdir().expect("tempdir");
+    let fixture = dir.path().join("compositor.json");
+    let trace = dir.path().join("trace.json");
+    fs::write(
+        &fixture,
+        r#"{"monitors": [
+            {"name": "DP-1", "width": 1920, "height": 1080, "x": 0, "y": 0, "refreshRate": 60.0},
+            {"name": "DP-2", "width": 1920, "height": 1080, "x": 1920, "y": 0, "refreshRate": 60.0}
+        ]}"#,
+    )
+    .expect("write fixture");
+    let envs = [
+        ("TMPDIR", dir.path().to_path_buf()),
+        ("HOME", dir.path().to_path_buf()),
+        ("XDG_CONFIG_HOME", dir.path().join("config")),
+        ("XDG_DATA_HOME", dir.path().join("data")),
+    ];
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hyprfinity");
+    cmd.envs(envs.clone())
+        .env("HYPRFINITY_MOCK_COMPOSITOR", &fixture)
+        .arg("--record")
+        .arg(&trace)
+        .args([
+            "gamescope-up",
+            "--no-idle-inhibit",
+            "--no-hide-waybar",
+            "--no-overlay",
+            "--",
+            "--",
+            "sleep",
+            "1",
+        ]);
+    cmd.timeout(std::time::Duration::from_secs(30))
+        .assert()
+        .success();
+
+    let recorded = fs::read_to_string(&trace).expect("trace");
+    assert!(recorded.contains(r#""kind": "gamescope""#), "{}", recorded);
+    assert!(
+        !recorded.contains(&dir.path().display().to_string()),
+        "home directory should be redacted"
+    );
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hyprfinity");
+    cmd.envs(envs).arg("replay").arg(&trace);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("3840x1080@0,0"))
+        .stdout(predicates::str::is_match(r"covers_span\s*\S*\s*yes").unwrap());
+}
+
+#[test]
+fn bugreport_includes_monitor_snapshot_and_redacts_home() {
+    let dir = tempdir().expect("tempdir");
+    let fixture = dir.path().join("compositor.json");
+    let config_dir = dir.path().join("config").join("hyprfinity");
+    fs::create_dir_all(&config_dir).expect("config dir");
+    fs::write(
+        config_dir.join("config.toml"),
+        format!("# games live in {}/games\n", dir.path().display()),
+    )
+    .expect("write config");
+    fs::write(
+        &fixture,
+        r#"{"monitors": [{"name": "DP-1", "width": 2560, "height": 1440, "x": 0, "y": 0, "refreshRate": 144.0}]}"#,
+    )
+    .expect("write fixture");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("hyprfinity");
+    cmd.env("HYPRFINITY_MOCK_COMPOSITOR", &fixture)
+        .env("TMPDIR", dir.path())
+        .env("HOME", dir.path())
+        .env("XDG_CONFIG_HOME", dir.path().join("config"))
+        .args(["bugreport", "--output", "-"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let report = String::from_utf8(output).expect("utf8 report");
+    assert!(report.contains("## Monitors"), "{}", report);
+    assert!(report.contains("\"DP-1\""), "{}", report);
+    assert!(report.contains("# games live in ~/games"), "{}", report);
+    assert!(report.contains("_No active session state._"), "{}", report);
+}
@@ -0,0 +1,30 @@
+// Uses the library the way another frontend would: only the public API, no compositor.
+use hyprfinity::{Config, LaunchPlan, LaunchSettings, Monitor, SpanTarget};
+
+#[test]
+fn plans_a_span_from_caller_supplied_monitors() {
+    let monitors = vec![
+        Monitor::new("DP-1", 0, 0, 2560, 1440).with_refresh_rate(144.0),
+        Monitor::new("DP-2", 2560, 0, 2560, 1440).with_focused(true),
+    ];
+    let settings = LaunchSettings::from_config(&Config::default());
+    assert!(settings.pin());
+
+    let plan = LaunchPlan::builder(settings.clone())
+        .monitors(monitors.clone())
+        .gamescope_args(vec!["--".into(), "steam".into()])
+        .build(false)
+        .expect("plan");
+    assert_eq!(plan.span(), (0, 0, 5120, 1440));
+    assert_eq!(plan.settings().gamescope_args(), ["--", "steam"]);
+
+    let focused = LaunchPlan::builder(settings)
+        .monitors(monitors)
+        .span_target(SpanTarget::Focused)
+        .build(false)
+        .expect("focused plan");
+    assert_eq!(focused.span(), (2560, 0, 2560, 1440));
+    assert_eq!(focused.monitors().len(), 1);
+    assert_eq!(focused.monitors()[0].name(), Some("DP-2"));
+    assert_eq!(focused.monitors()[0].position(), (2560, 0));
+}